@@ -135,6 +135,12 @@ impl UiAccount {
     }
 
     pub fn decode<T: WritableAccount>(&self) -> Option<T> {
+        self.decode_with_max_data_len(usize::MAX)
+    }
+
+    /// Like `decode`, but fails rather than decode more than `max_data_len` bytes of data, without
+    /// decompressing past the limit
+    pub fn decode_with_max_data_len<T: WritableAccount>(&self, max_data_len: usize) -> Option<T> {
         let data = match &self.data {
             UiAccountData::Json(_) => None,
             UiAccountData::LegacyBinary(blob) => bs58::decode(blob).into_vec().ok(),
@@ -144,13 +150,20 @@ impl UiAccount {
                 UiAccountEncoding::Base64Zstd => base64::decode(blob).ok().and_then(|zstd_data| {
                     let mut data = vec![];
                     zstd::stream::read::Decoder::new(zstd_data.as_slice())
-                        .and_then(|mut reader| reader.read_to_end(&mut data))
+                        .and_then(|reader| {
+                            reader
+                                .take((max_data_len as u64).saturating_add(1))
+                                .read_to_end(&mut data)
+                        })
                         .map(|_| data)
                         .ok()
                 }),
                 UiAccountEncoding::Binary | UiAccountEncoding::JsonParsed => None,
             },
         }?;
+        if data.len() > max_data_len {
+            return None;
+        }
         Some(T::create(
             self.lamports,
             data,
@@ -260,5 +273,13 @@ mod test {
         assert_eq!(decoded_account.data(), &vec![0; 1024]);
         let decoded_account = encoded_account.decode::<AccountSharedData>().unwrap();
         assert_eq!(decoded_account.data(), &vec![0; 1024]);
+
+        let decoded_account = encoded_account
+            .decode_with_max_data_len::<Account>(1024)
+            .unwrap();
+        assert_eq!(decoded_account.data(), &vec![0; 1024]);
+        assert!(encoded_account
+            .decode_with_max_data_len::<Account>(1023)
+            .is_none());
     }
 }
@@ -296,8 +296,15 @@ mod test {
         crate::parse_token_extension::{UiMemoTransfer, UiMintCloseAuthority},
         spl_token_2022::{
             extension::{
-                immutable_owner::ImmutableOwner, memo_transfer::MemoTransfer,
-                mint_close_authority::MintCloseAuthority, ExtensionType, StateWithExtensionsMut,
+                confidential_transfer::{ConfidentialTransferAccount, ConfidentialTransferMint},
+                default_account_state::DefaultAccountState,
+                immutable_owner::ImmutableOwner,
+                interest_bearing_mint::InterestBearingConfig,
+                memo_transfer::MemoTransfer,
+                mint_close_authority::MintCloseAuthority,
+                non_transferable::NonTransferable,
+                transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+                ExtensionType, StateWithExtensionsMut,
             },
             pod::OptionalNonZeroPubkey,
         },
@@ -646,4 +653,103 @@ mod test {
             }),
         );
     }
+    #[test]
+    fn test_parse_token_with_all_extensions() {
+        let owner_pubkey = SplTokenPubkey::new(&[3; 32]);
+
+        let mint_extensions = [
+            ExtensionType::TransferFeeConfig,
+            ExtensionType::MintCloseAuthority,
+            ExtensionType::ConfidentialTransferMint,
+            ExtensionType::DefaultAccountState,
+            ExtensionType::NonTransferable,
+            ExtensionType::InterestBearingConfig,
+        ];
+        let mut mint_data = vec![0; ExtensionType::get_account_len::<Mint>(&mint_extensions)];
+        let mut mint_state =
+            StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut mint_data).unwrap();
+        mint_state
+            .init_extension::<TransferFeeConfig>(true)
+            .unwrap();
+        mint_state
+            .init_extension::<MintCloseAuthority>(true)
+            .unwrap();
+        mint_state
+            .init_extension::<ConfidentialTransferMint>(true)
+            .unwrap();
+        mint_state
+            .init_extension::<DefaultAccountState>(true)
+            .unwrap();
+        mint_state.init_extension::<NonTransferable>(true).unwrap();
+        mint_state
+            .init_extension::<InterestBearingConfig>(true)
+            .unwrap();
+        mint_state.base = Mint {
+            mint_authority: COption::Some(owner_pubkey),
+            decimals: 3,
+            is_initialized: true,
+            ..Mint::default()
+        };
+        mint_state.pack_base();
+        mint_state.init_account_type().unwrap();
+
+        let extensions = match parse_token(&mint_data, None).unwrap() {
+            TokenAccountType::Mint(mint) => mint.extensions,
+            account_type => panic!("unexpected account type {:?}", account_type),
+        };
+        assert_eq!(extensions.len(), mint_extensions.len());
+        assert!(matches!(
+            extensions.as_slice(),
+            [
+                UiExtension::TransferFeeConfig(_),
+                UiExtension::MintCloseAuthority(_),
+                UiExtension::ConfidentialTransferMint(_),
+                UiExtension::DefaultAccountState(_),
+                UiExtension::NonTransferable,
+                UiExtension::InterestBearingConfig(_),
+            ]
+        ));
+
+        let account_extensions = [
+            ExtensionType::TransferFeeAmount,
+            ExtensionType::ConfidentialTransferAccount,
+            ExtensionType::ImmutableOwner,
+            ExtensionType::MemoTransfer,
+        ];
+        let mut account_data =
+            vec![0; ExtensionType::get_account_len::<Account>(&account_extensions)];
+        let mut account_state =
+            StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut account_data).unwrap();
+        account_state
+            .init_extension::<TransferFeeAmount>(true)
+            .unwrap();
+        account_state
+            .init_extension::<ConfidentialTransferAccount>(true)
+            .unwrap();
+        account_state
+            .init_extension::<ImmutableOwner>(true)
+            .unwrap();
+        account_state.init_extension::<MemoTransfer>(true).unwrap();
+        account_state.base = Account {
+            owner: owner_pubkey,
+            state: AccountState::Initialized,
+            ..Account::default()
+        };
+        account_state.pack_base();
+        account_state.init_account_type().unwrap();
+
+        let extensions = match parse_token(&account_data, Some(3)).unwrap() {
+            TokenAccountType::Account(account) => account.extensions,
+            account_type => panic!("unexpected account type {:?}", account_type),
+        };
+        assert!(matches!(
+            extensions.as_slice(),
+            [
+                UiExtension::TransferFeeAmount(_),
+                UiExtension::ConfidentialTransferAccount(_),
+                UiExtension::ImmutableOwner,
+                UiExtension::MemoTransfer(_),
+            ]
+        ));
+    }
 }
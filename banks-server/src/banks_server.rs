@@ -178,6 +178,7 @@ fn simulate_transaction(
         post_simulation_accounts: _,
         units_consumed,
         return_data,
+        execution_trace: _,
     } = bank.simulate_transaction_unchecked(sanitized_transaction);
     let simulation_details = TransactionSimulationDetails {
         logs,
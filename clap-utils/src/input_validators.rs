@@ -64,6 +64,14 @@ where
     is_parsable_generic::<Pubkey, _>(string)
 }
 
+// Return an error if a signature cannot be parsed.
+pub fn is_valid_signature<T>(string: T) -> Result<(), String>
+where
+    T: AsRef<str> + Display,
+{
+    is_parsable_generic::<Signature, _>(string)
+}
+
 // Return an error if a hash cannot be parsed.
 pub fn is_hash<T>(string: T) -> Result<(), String>
 where
@@ -34,7 +34,7 @@ use {
         system_program, system_transaction,
     },
     solana_streamer::socket::SocketAddrSpace,
-    solana_test_validator::TestValidator,
+    solana_test_validator::{TestValidator, TestValidatorGenesis},
     solana_transaction_status::{
         BlockEncodingOptions, ConfirmedBlock, TransactionDetails, UiTransactionEncoding,
     },
@@ -107,6 +107,56 @@ fn test_rpc_client() {
     );
 }
 
+#[test]
+fn test_rpc_client_over_websocket() {
+    solana_logger::setup();
+
+    let (test_validator, alice) = TestValidatorGenesis::default()
+        .pubsub_config(PubSubConfig {
+            enable_rpc_requests: true,
+            ..PubSubConfig::default()
+        })
+        .start();
+
+    let bob_pubkey = solana_sdk::pubkey::new_rand();
+
+    let client = RpcClient::new_websocket(test_validator.rpc_pubsub_url());
+
+    assert_eq!(
+        client.get_version().unwrap().solana_core,
+        solana_version::semver!()
+    );
+    assert_eq!(client.get_balance(&bob_pubkey).unwrap(), 0);
+
+    let blockhash = client.get_latest_blockhash().unwrap();
+    let tx = system_transaction::transfer(&alice, &bob_pubkey, sol_to_lamports(20.0), blockhash);
+    let signature = client.send_transaction(&tx).unwrap();
+
+    let mut confirmed_tx = false;
+    let now = Instant::now();
+    while now.elapsed().as_secs() <= 20 {
+        let response = client
+            .confirm_transaction_with_commitment(&signature, CommitmentConfig::default())
+            .unwrap();
+        if response.value {
+            confirmed_tx = true;
+            break;
+        }
+        sleep(Duration::from_millis(500));
+    }
+    assert!(confirmed_tx);
+    assert_eq!(
+        client.get_balance(&bob_pubkey).unwrap(),
+        sol_to_lamports(20.0)
+    );
+
+    // Nodes which don't enable RPC requests on the websocket only serve subscriptions
+    let test_validator =
+        TestValidator::with_no_fees(alice.pubkey(), None, SocketAddrSpace::Unspecified);
+    let client = RpcClient::new_websocket(test_validator.rpc_pubsub_url());
+    assert!(client.get_balance(&bob_pubkey).is_err());
+}
+
 #[test]
 #[serial]
 fn test_account_subscription() {
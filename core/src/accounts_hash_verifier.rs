@@ -27,7 +27,7 @@ use {
         collections::{HashMap, HashSet},
         sync::{
             atomic::{AtomicBool, Ordering},
-            Arc,
+            Arc, RwLock,
         },
         thread::{self, Builder, JoinHandle},
         time::Duration,
@@ -45,7 +45,7 @@ impl AccountsHashVerifier {
         pending_snapshot_package: Option<PendingSnapshotPackage>,
         exit: &Arc<AtomicBool>,
         cluster_info: &Arc<ClusterInfo>,
-        known_validators: Option<HashSet<Pubkey>>,
+        known_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
         halt_on_known_validators_accounts_hash_mismatch: bool,
         fault_injection_rate_slots: u64,
         snapshot_config: Option<SnapshotConfig>,
@@ -77,7 +77,7 @@ impl AccountsHashVerifier {
                         let (_, measure) = measure!(Self::process_accounts_package(
                             accounts_package,
                             &cluster_info,
-                            known_validators.as_ref(),
+                            known_validators.read().unwrap().as_ref(),
                             halt_on_known_validators_accounts_hash_mismatch,
                             pending_snapshot_package.as_ref(),
                             &mut hashes,
@@ -633,7 +633,7 @@ impl AncestorHashesService {
                     ancestor_hashes_request_socket,
                     &repair_info.cluster_slots,
                     serve_repair,
-                    &repair_info.repair_validators,
+                    &repair_info.repair_validators.read().unwrap(),
                     slot,
                     repair_stats,
                     outstanding_requests,
@@ -1058,7 +1058,7 @@ mod test {
                 cluster_slots: Arc::new(ClusterSlots::default()),
                 epoch_schedule,
                 duplicate_slots_reset_sender,
-                repair_validators: None,
+                repair_validators: Arc::default(),
             };
 
             let (ancestor_hashes_replay_update_sender, ancestor_hashes_replay_update_receiver) =
@@ -1167,7 +1167,7 @@ mod test {
             &ancestor_hashes_request_socket,
             &cluster_slots,
             &requester_serve_repair,
-            &repair_validators,
+            &repair_validators.read().unwrap(),
             dead_slot,
             &mut repair_stats,
             &outstanding_requests,
@@ -1185,7 +1185,7 @@ mod test {
             &ancestor_hashes_request_socket,
             &cluster_slots,
             &requester_serve_repair,
-            &repair_validators,
+            &repair_validators.read().unwrap(),
             dead_slot,
             &mut repair_stats,
             &outstanding_requests,
@@ -1360,7 +1360,8 @@ impl BankingStage {
                 transaction_status_sender.is_some(),
                 &mut execute_and_commit_timings.execute_timings,
                 None, // account_overrides
-                log_messages_bytes_limit
+                log_messages_bytes_limit,
+                None, // trace_config
             ),
             "load_execute",
         );
@@ -15,7 +15,7 @@ use {
     std::{
         string::ToString,
         sync::{
-            atomic::{AtomicBool, Ordering},
+            atomic::{AtomicBool, AtomicU64, Ordering},
             Arc,
         },
         thread::{self, Builder, JoinHandle},
@@ -48,7 +48,7 @@ impl LedgerCleanupService {
     pub fn new(
         new_root_receiver: Receiver<Slot>,
         blockstore: Arc<Blockstore>,
-        max_ledger_shreds: u64,
+        max_ledger_shreds: Arc<AtomicU64>,
         exit: &Arc<AtomicBool>,
     ) -> Self {
         let exit = exit.clone();
@@ -56,7 +56,7 @@ impl LedgerCleanupService {
 
         info!(
             "LedgerCleanupService active. max ledger shreds={}",
-            max_ledger_shreds
+            max_ledger_shreds.load(Ordering::Relaxed)
         );
 
         let t_cleanup = Builder::new()
@@ -68,7 +68,8 @@ impl LedgerCleanupService {
                 if let Err(e) = Self::cleanup_ledger(
                     &new_root_receiver,
                     &blockstore,
-                    max_ledger_shreds,
+                    // May be changed at runtime through the admin RPC
+                    max_ledger_shreds.load(Ordering::Relaxed),
                     &mut last_purge_slot,
                     DEFAULT_PURGE_SLOT_INTERVAL,
                 ) {
@@ -44,6 +44,7 @@ mod packet_hasher;
 pub mod packet_threshold;
 pub mod poh_timing_report_service;
 pub mod poh_timing_reporter;
+pub mod program_stats_service;
 pub mod progress_map;
 pub mod qos_service;
 pub mod repair_generic_traversal;
@@ -0,0 +1,103 @@
+//! The `program_stats_service` periodically scans the root bank and records the storage and
+//! access stats of each program that owns accounts. Access counts cover the time since the
+//! previous collection. The latest report is kept in the accounts db for the admin RPC, and the
+//! largest programs are reported as metrics.
+use {
+    solana_measure::measure::Measure,
+    solana_runtime::{bank_forks::BankForks, program_stats::ProgramStatsReport},
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, sleep, Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+const SLEEP_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How many of the largest programs are reported as metrics each interval
+const NUM_PROGRAMS_TO_REPORT: usize = 20;
+
+pub struct ProgramStatsService {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl ProgramStatsService {
+    pub fn new(
+        bank_forks: &Arc<RwLock<BankForks>>,
+        interval: Duration,
+        exit: &Arc<AtomicBool>,
+    ) -> Self {
+        let exit = exit.clone();
+        let bank_forks = bank_forks.clone();
+
+        info!("Starting ProgramStats service");
+        let thread_hdl = Builder::new()
+            .name("solProgramStats".to_string())
+            .spawn(move || {
+                Self::run(bank_forks, interval, exit);
+            })
+            .unwrap();
+
+        Self { thread_hdl }
+    }
+
+    fn run(bank_forks: Arc<RwLock<BankForks>>, interval: Duration, exit: Arc<AtomicBool>) {
+        let mut last_collection = Instant::now();
+        loop {
+            if exit.load(Ordering::Relaxed) {
+                break;
+            }
+
+            if last_collection.elapsed() >= interval {
+                last_collection = Instant::now();
+                let bank = bank_forks.read().unwrap().root_bank();
+
+                let mut measure = Measure::start("program_stats");
+                let report = bank.get_program_stats();
+                measure.stop();
+                Self::report_metrics(&report, measure.as_ms());
+                bank.rc
+                    .accounts
+                    .accounts_db
+                    .program_stats
+                    .set_latest_report(report);
+            }
+
+            sleep(SLEEP_INTERVAL);
+        }
+    }
+
+    fn report_metrics(report: &ProgramStatsReport, collection_time_ms: u64) {
+        datapoint_info!(
+            "program_stats",
+            ("slot", report.slot, i64),
+            ("num_programs", report.programs.len(), i64),
+            ("access_window_ms", report.access_window.as_millis(), i64),
+            ("collection_time_ms", collection_time_ms, i64),
+        );
+        for (program_id, stats) in report.programs.iter().take(NUM_PROGRAMS_TO_REPORT) {
+            datapoint_info!(
+                "program_stats-program",
+                ("program_id", program_id.to_string(), String),
+                ("accounts", stats.accounts, i64),
+                ("data_bytes", stats.data_bytes, i64),
+                ("rent_exempt_lamports", stats.rent_exempt_lamports, i64),
+                ("writes", stats.writes, i64),
+                (
+                    "writes_per_second",
+                    stats.writes_per_second(report.access_window),
+                    f64
+                ),
+                ("read_cache_hits", stats.read_cache_hits, i64),
+                ("read_cache_misses", stats.read_cache_misses, i64),
+            );
+        }
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
@@ -161,6 +161,7 @@ struct ServeRepairStats {
     handle_requests_unstaked: usize,
     processed: usize,
     self_repair: usize,
+    denylisted: usize,
     window_index: usize,
     highest_window_index: usize,
     orphan: usize,
@@ -274,6 +275,8 @@ impl RepairProtocol {
 pub struct ServeRepair {
     cluster_info: Arc<ClusterInfo>,
     bank_forks: Arc<RwLock<BankForks>>,
+    /// Peers whose repair requests are dropped; may be changed at runtime
+    repair_denylist: Arc<RwLock<HashSet<Pubkey>>>,
 }
 
 // Cache entry for repair peers for a slot.
@@ -311,9 +314,18 @@ impl RepairPeers {
 
 impl ServeRepair {
     pub fn new(cluster_info: Arc<ClusterInfo>, bank_forks: Arc<RwLock<BankForks>>) -> Self {
+        Self::new_with_repair_denylist(cluster_info, bank_forks, Arc::default())
+    }
+
+    pub fn new_with_repair_denylist(
+        cluster_info: Arc<ClusterInfo>,
+        bank_forks: Arc<RwLock<BankForks>>,
+        repair_denylist: Arc<RwLock<HashSet<Pubkey>>>,
+    ) -> Self {
         Self {
             cluster_info,
             bank_forks,
+            repair_denylist,
         }
     }
 
@@ -520,6 +532,7 @@ impl ServeRepair {
                 i64
             ),
             ("self_repair", stats.self_repair, i64),
+            ("denylisted", stats.denylisted, i64),
             ("window_index", stats.window_index, i64),
             (
                 "request-highest-window-index",
@@ -718,6 +731,7 @@ impl ServeRepair {
         let identity_keypair = self.cluster_info.keypair().clone();
         let my_id = identity_keypair.pubkey();
         let socket_addr_space = *self.cluster_info.socket_addr_space();
+        let repair_denylist = self.repair_denylist.read().unwrap();
         let mut pending_pings = Vec::default();
 
         // iter over the packets
@@ -757,6 +771,11 @@ impl ServeRepair {
                 stats.unsigned_requests += 1;
             }
 
+            if repair_denylist.contains(request.sender()) {
+                stats.denylisted += 1;
+                continue;
+            }
+
             if !matches!(&request, RepairProtocol::Pong(_)) {
                 let (check, ping_pkt) =
                     Self::check_ping_cache(ping_cache, &request, &from_addr, &identity_keypair);
@@ -1458,6 +1477,73 @@ mod tests {
         assert_eq!(stats.err_sig_verify, 1);
     }
 
+    #[test]
+    fn test_handle_packets_repair_denylist() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let me = ContactInfo::new_localhost(&solana_sdk::pubkey::new_rand(), timestamp());
+        let cluster_info = Arc::new(new_test_cluster_info(me));
+        let my_id = cluster_info.id();
+        let repair_denylist = Arc::<RwLock<HashSet<Pubkey>>>::default();
+        let serve_repair = ServeRepair::new_with_repair_denylist(
+            cluster_info,
+            bank_forks,
+            repair_denylist.clone(),
+        );
+        let ledger_path = get_tmp_ledger_path!();
+        let blockstore = Blockstore::open(&ledger_path).unwrap();
+        let mut ping_cache = PingCache::new(
+            REPAIR_PING_CACHE_TTL,
+            REPAIR_PING_CACHE_RATE_LIMIT_DELAY,
+            REPAIR_PING_CACHE_CAPACITY,
+        );
+        let (response_sender, _response_receiver) = crossbeam_channel::unbounded();
+        let data_budget = DataBudget::default();
+
+        let keypair = Keypair::new();
+        let from_addr = socketaddr!([127, 0, 0, 1], 1234);
+        let packet_batch = || {
+            let header = RepairRequestHeader::new(keypair.pubkey(), my_id, timestamp(), 678);
+            let request = RepairProtocol::WindowIndex {
+                header,
+                slot: 1,
+                shred_index: 0,
+            };
+            let mut packet = Packet::from_data(Some(&from_addr), &request).unwrap();
+            let payload = ServeRepair::repair_proto_to_bytes(&request, &keypair).unwrap();
+            packet.buffer_mut()[..payload.len()].copy_from_slice(&payload);
+            PacketBatch::new(vec![packet])
+        };
+        let mut handle_packets = |stats: &mut ServeRepairStats| {
+            serve_repair.handle_packets(
+                &mut ping_cache,
+                &PacketBatchRecycler::default(),
+                &blockstore,
+                packet_batch(),
+                &response_sender,
+                stats,
+                &data_budget,
+                &None,
+            )
+        };
+
+        // Requests from denylisted peers are dropped once verified
+        repair_denylist.write().unwrap().insert(keypair.pubkey());
+        let mut stats = ServeRepairStats::default();
+        handle_packets(&mut stats);
+        assert_eq!(stats.denylisted, 1);
+        assert_eq!(stats.err_sig_verify, 0);
+        assert_eq!(stats.processed, 0);
+
+        // and served again once removed from the denylist
+        repair_denylist.write().unwrap().clear();
+        let mut stats = ServeRepairStats::default();
+        handle_packets(&mut stats);
+        assert_eq!(stats.denylisted, 0);
+        assert_eq!(stats.processed, 1);
+    }
+
     #[test]
     fn test_run_highest_window_request() {
         run_highest_window_request(5, 3, 9);
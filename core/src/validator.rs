@@ -1,6 +1,7 @@
 //! The `validator` module hosts all the validator microservices.
 
 pub use solana_perf::report_target_features;
+pub use solana_rpc::rpc_health::ValidatorStartProgress;
 use {
     crate::{
         accounts_hash_verifier::AccountsHashVerifier,
@@ -11,6 +12,7 @@ use {
         consensus::{reconcile_blockstore_roots_with_external_source, ExternalRootSource, Tower},
         ledger_metric_report_service::LedgerMetricReportService,
         poh_timing_report_service::PohTimingReportService,
+        program_stats_service::ProgramStatsService,
         rewards_recorder_service::{RewardsRecorderSender, RewardsRecorderService},
         sample_performance_service::SamplePerformanceService,
         serve_repair::ServeRepair,
@@ -26,7 +28,9 @@ use {
     crossbeam_channel::{bounded, unbounded, Receiver},
     rand::{thread_rng, Rng},
     solana_entry::poh::compute_hash_time_ns,
-    solana_geyser_plugin_manager::geyser_plugin_service::GeyserPluginService,
+    solana_geyser_plugin_manager::{
+        geyser_plugin_manager::GeyserPluginManager, geyser_plugin_service::GeyserPluginService,
+    },
     solana_gossip::{
         cluster_info::{
             ClusterInfo, Node, DEFAULT_CONTACT_DEBUG_INTERVAL_MILLIS,
@@ -43,6 +47,7 @@ use {
         },
         blockstore_options::{BlockstoreOptions, BlockstoreRecoveryMode, LedgerColumnOptions},
         blockstore_processor::{self, TransactionStatusSender},
+        entry_notifier_service::{EntryNotifierSender, EntryNotifierService},
         leader_schedule::FixedSchedule,
         leader_schedule_cache::LeaderScheduleCache,
     },
@@ -127,6 +132,7 @@ pub struct ValidatorConfig {
     pub account_shrink_paths: Option<Vec<PathBuf>>,
     pub rpc_config: JsonRpcConfig,
     pub geyser_plugin_config_files: Option<Vec<PathBuf>>,
+    pub geyser_plugin_always_enabled: bool,
     pub rpc_addrs: Option<(SocketAddr, SocketAddr)>, // (JsonRpc, JsonRpcPubSub)
     pub pubsub_config: PubSubConfig,
     pub snapshot_config: Option<SnapshotConfig>,
@@ -175,6 +181,11 @@ pub struct ValidatorConfig {
     pub ledger_column_options: LedgerColumnOptions,
     pub runtime_config: RuntimeConfig,
     pub replay_slots_concurrently: bool,
+    /// How often to collect per-program accounts stats, if at all
+    pub program_stats_interval: Option<Duration>,
+    /// Take a full snapshot of the root bank on clean exit, to save the persisted accounts index
+    /// with. Delays the exit by the time it takes to archive the snapshot.
+    pub save_accounts_index_on_exit: bool,
 }
 
 impl Default for ValidatorConfig {
@@ -190,6 +201,7 @@ impl Default for ValidatorConfig {
             account_shrink_paths: None,
             rpc_config: JsonRpcConfig::default(),
             geyser_plugin_config_files: None,
+            geyser_plugin_always_enabled: false,
             rpc_addrs: None,
             pubsub_config: PubSubConfig::default(),
             snapshot_config: Some(SnapshotConfig::new_load_only()),
@@ -237,6 +249,8 @@ impl Default for ValidatorConfig {
             ledger_column_options: LedgerColumnOptions::default(),
             runtime_config: RuntimeConfig::default(),
             replay_slots_concurrently: false,
+            program_stats_interval: None,
+            save_accounts_index_on_exit: false,
         }
     }
 }
@@ -251,39 +265,32 @@ impl ValidatorConfig {
     }
 }
 
-// `ValidatorStartProgress` contains status information that is surfaced to the node operator over
-// the admin RPC channel to help them to follow the general progress of node startup without
-// having to watch log messages.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-pub enum ValidatorStartProgress {
-    Initializing, // Catch all, default state
-    SearchingForRpcService,
-    DownloadingSnapshot {
-        slot: Slot,
-        rpc_addr: SocketAddr,
-    },
-    CleaningBlockStore,
-    CleaningAccounts,
-    LoadingLedger,
-    ProcessingLedger {
-        slot: Slot,
-        max_slot: Slot,
-    },
-    StartingServices,
-    Halted, // Validator halted due to `--dev-halt-at-slot` argument
-    WaitingForSupermajority {
-        slot: Slot,
-        gossip_stake_percent: u64,
-    },
-
-    // `Running` is the terminal state once the validator fully starts and all services are
-    // operational
-    Running,
+/// The settings that the running validator's services read on every use, so they can be changed
+/// over the admin RPC channel without a restart
+#[derive(Clone)]
+pub struct AdjustableValidatorConfig {
+    pub send_transaction_service_config: Arc<RwLock<send_transaction_service::Config>>,
+    pub repair_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
+    /// Peers whose repair requests are not served, empty at startup
+    pub repair_denylist: Arc<RwLock<HashSet<Pubkey>>>,
+    pub known_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
+    /// `None` if the ledger cleanup service is disabled
+    pub max_ledger_shreds: Option<Arc<AtomicU64>>,
 }
 
-impl Default for ValidatorStartProgress {
-    fn default() -> Self {
-        Self::Initializing
+impl AdjustableValidatorConfig {
+    pub fn new(config: &ValidatorConfig) -> Self {
+        Self {
+            send_transaction_service_config: Arc::new(RwLock::new(
+                config.send_transaction_service_config.clone(),
+            )),
+            repair_validators: Arc::new(RwLock::new(config.repair_validators.clone())),
+            repair_denylist: Arc::default(),
+            known_validators: Arc::new(RwLock::new(config.known_validators.clone())),
+            max_ledger_shreds: config
+                .max_ledger_shreds
+                .map(|max_ledger_shreds| Arc::new(AtomicU64::new(max_ledger_shreds))),
+        }
     }
 }
 
@@ -342,6 +349,7 @@ pub struct Validator {
     cache_block_meta_service: Option<CacheBlockMetaService>,
     system_monitor_service: Option<SystemMonitorService>,
     sample_performance_service: Option<SamplePerformanceService>,
+    program_stats_service: Option<ProgramStatsService>,
     poh_timing_report_service: PohTimingReportService,
     stats_reporter_service: StatsReporterService,
     gossip_service: GossipService,
@@ -356,10 +364,14 @@ pub struct Validator {
     pub cluster_info: Arc<ClusterInfo>,
     pub bank_forks: Arc<RwLock<BankForks>>,
     pub blockstore: Arc<Blockstore>,
+    pub accounts_background_request_sender: AbsRequestSender,
+    pub adjustable_config: AdjustableValidatorConfig,
     geyser_plugin_service: Option<GeyserPluginService>,
+    entry_notifier_service: Option<EntryNotifierService>,
     ledger_metric_report_service: LedgerMetricReportService,
     accounts_background_service: AccountsBackgroundService,
     accounts_hash_verifier: AccountsHashVerifier,
+    save_accounts_index_on_exit: bool,
 }
 
 impl Validator {
@@ -397,11 +409,17 @@ impl Validator {
         let mut bank_notification_senders = Vec::new();
 
         let geyser_plugin_service =
-            if let Some(geyser_plugin_config_files) = &config.geyser_plugin_config_files {
+            if config.geyser_plugin_config_files.is_some() || config.geyser_plugin_always_enabled {
                 let (confirmed_bank_sender, confirmed_bank_receiver) = unbounded();
                 bank_notification_senders.push(confirmed_bank_sender);
-                let result =
-                    GeyserPluginService::new(confirmed_bank_receiver, geyser_plugin_config_files);
+                let result = GeyserPluginService::new(
+                    confirmed_bank_receiver,
+                    config
+                        .geyser_plugin_config_files
+                        .as_deref()
+                        .unwrap_or_default(),
+                    config.geyser_plugin_always_enabled,
+                );
                 match result {
                     Ok(geyser_plugin_service) => Some(geyser_plugin_service),
                     Err(err) => {
@@ -484,16 +502,26 @@ impl Validator {
             .as_ref()
             .and_then(|geyser_plugin_service| geyser_plugin_service.get_transaction_notifier());
 
+        let entry_notifier = geyser_plugin_service
+            .as_ref()
+            .and_then(|geyser_plugin_service| geyser_plugin_service.get_entry_notifier());
+
         let block_metadata_notifier = geyser_plugin_service
             .as_ref()
             .and_then(|geyser_plugin_service| geyser_plugin_service.get_block_metadata_notifier());
 
         info!(
-            "Geyser plugin: accounts_update_notifier: {} transaction_notifier: {}",
+            "Geyser plugin: accounts_update_notifier: {}, \
+            transaction_notifier: {}, \
+            entry_notifier: {}",
             accounts_update_notifier.is_some(),
-            transaction_notifier.is_some()
+            transaction_notifier.is_some(),
+            entry_notifier.is_some()
         );
 
+        let entry_notifier_service =
+            entry_notifier.map(|entry_notifier| EntryNotifierService::new(entry_notifier, &exit));
+
         let system_monitor_service = Some(SystemMonitorService::new(
             Arc::clone(&exit),
             !config.no_os_memory_stats_reporting,
@@ -607,6 +635,8 @@ impl Validator {
                 (None, None)
             };
 
+        let adjustable_config = AdjustableValidatorConfig::new(config);
+
         let (accounts_package_sender, accounts_package_receiver) = crossbeam_channel::unbounded();
         let accounts_hash_verifier = AccountsHashVerifier::new(
             accounts_package_sender.clone(),
@@ -614,7 +644,7 @@ impl Validator {
             pending_snapshot_package,
             &exit,
             &cluster_info,
-            config.known_validators.clone(),
+            adjustable_config.known_validators.clone(),
             config.halt_on_known_validators_accounts_hash_mismatch,
             config.accounts_hash_fault_injection_slots,
             config.snapshot_config.clone(),
@@ -657,6 +687,9 @@ impl Validator {
             &blockstore_process_options,
             transaction_status_sender.as_ref(),
             cache_block_meta_sender.clone(),
+            entry_notifier_service
+                .as_ref()
+                .map(|service| service.sender()),
             blockstore_root_scan,
             accounts_background_request_sender.clone(),
             config,
@@ -687,6 +720,10 @@ impl Validator {
                 None
             };
 
+        let program_stats_service = config
+            .program_stats_interval
+            .map(|interval| ProgramStatsService::new(&bank_forks, interval, &exit));
+
         let mut block_commitment_cache = BlockCommitmentCache::default();
         let bank_forks_guard = bank_forks.read().unwrap();
         block_commitment_cache.initialize_slots(
@@ -799,27 +836,39 @@ impl Validator {
                 genesis_config.hash(),
                 ledger_path,
                 config.validator_exit.clone(),
-                config.known_validators.clone(),
+                adjustable_config.known_validators.clone(),
                 rpc_override_health_check.clone(),
                 startup_verification_complete,
+                start_progress.clone(),
+                config
+                    .rpc_config
+                    .full_api
+                    .then(|| rpc_subscriptions.clone()),
                 optimistically_confirmed_bank.clone(),
-                config.send_transaction_service_config.clone(),
+                adjustable_config.send_transaction_service_config.clone(),
                 max_slots.clone(),
                 leader_schedule_cache.clone(),
                 connection_cache.clone(),
                 max_complete_transaction_status_slot,
                 prioritization_fee_cache.clone(),
             )?;
+            let pubsub_request_processor = config.pubsub_config.enable_rpc_requests.then(|| {
+                (
+                    json_rpc_service.request_processor().clone(),
+                    json_rpc_service.runtime().clone(),
+                )
+            });
 
             (
                 Some(json_rpc_service),
                 if !config.rpc_config.full_api {
                     None
                 } else {
-                    let (trigger, pubsub_service) = PubSubService::new(
+                    let (trigger, pubsub_service) = PubSubService::new_with_request_processor(
                         config.pubsub_config.clone(),
                         &rpc_subscriptions,
                         rpc_pubsub_addr,
+                        pubsub_request_processor,
                     );
                     config
                         .validator_exit
@@ -877,7 +926,11 @@ impl Validator {
             Some(stats_reporter_sender.clone()),
             &exit,
         );
-        let serve_repair = ServeRepair::new(cluster_info.clone(), bank_forks.clone());
+        let serve_repair = ServeRepair::new_with_repair_denylist(
+            cluster_info.clone(),
+            bank_forks.clone(),
+            adjustable_config.repair_denylist.clone(),
+        );
         let serve_repair_service = ServeRepairService::new(
             serve_repair,
             blockstore.clone(),
@@ -963,6 +1016,9 @@ impl Validator {
             transaction_status_sender.clone(),
             rewards_recorder_sender,
             cache_block_meta_sender,
+            entry_notifier_service
+                .as_ref()
+                .map(|service| service.sender().clone()),
             vote_tracker.clone(),
             retransmit_slots_sender,
             gossip_verified_vote_hash_receiver,
@@ -972,9 +1028,9 @@ impl Validator {
             bank_notification_sender.clone(),
             cluster_confirmed_slot_receiver,
             TvuConfig {
-                max_ledger_shreds: config.max_ledger_shreds,
+                max_ledger_shreds: adjustable_config.max_ledger_shreds.clone(),
                 shred_version: node.info.shred_version,
-                repair_validators: config.repair_validators.clone(),
+                repair_validators: adjustable_config.repair_validators.clone(),
                 wait_for_vote_to_start_leader,
                 replay_slots_concurrently: config.replay_slots_concurrently,
             },
@@ -982,7 +1038,7 @@ impl Validator {
             &cost_model,
             block_metadata_notifier,
             config.wait_to_vote_slot,
-            accounts_background_request_sender,
+            accounts_background_request_sender.clone(),
             config.runtime_config.log_messages_bytes_limit,
             &connection_cache,
             &prioritization_fee_cache,
@@ -1045,6 +1101,7 @@ impl Validator {
             cache_block_meta_service,
             system_monitor_service,
             sample_performance_service,
+            program_stats_service,
             poh_timing_report_service,
             snapshot_packager_service,
             completed_data_sets_service,
@@ -1057,10 +1114,14 @@ impl Validator {
             cluster_info,
             bank_forks,
             blockstore,
+            accounts_background_request_sender,
+            adjustable_config,
             geyser_plugin_service,
+            entry_notifier_service,
             ledger_metric_report_service,
             accounts_background_service,
             accounts_hash_verifier,
+            save_accounts_index_on_exit: config.save_accounts_index_on_exit,
         })
     }
 
@@ -1077,6 +1138,13 @@ impl Validator {
         self.join();
     }
 
+    /// The Geyser plugin manager, if the validator was started with Geyser plugins
+    pub fn geyser_plugin_manager(&self) -> Option<Arc<RwLock<GeyserPluginManager>>> {
+        self.geyser_plugin_service
+            .as_ref()
+            .map(|geyser_plugin_service| geyser_plugin_service.get_plugin_manager())
+    }
+
     fn print_node_info(node: &Node) {
         info!("{:?}", node.info);
         info!(
@@ -1103,6 +1171,8 @@ impl Validator {
     }
 
     pub fn join(self) {
+        let root_bank = self.bank_forks.read().unwrap().root_bank();
+        let snapshot_config = self.bank_forks.read().unwrap().snapshot_config.clone();
         drop(self.bank_forks);
         drop(self.cluster_info);
 
@@ -1159,6 +1229,10 @@ impl Validator {
                 .expect("sample_performance_service");
         }
 
+        if let Some(program_stats_service) = self.program_stats_service {
+            program_stats_service.join().expect("program_stats_service");
+        }
+
         if let Some(s) = self.snapshot_packager_service {
             s.join().expect("snapshot_packager_service");
         }
@@ -1188,6 +1262,12 @@ impl Validator {
             ip_echo_server.shutdown_background();
         }
 
+        if let Some(entry_notifier_service) = self.entry_notifier_service {
+            entry_notifier_service
+                .join()
+                .expect("entry_notifier_service");
+        }
+
         if let Some(geyser_plugin_service) = self.geyser_plugin_service {
             geyser_plugin_service.join().expect("geyser_plugin_service");
         }
@@ -1195,6 +1275,46 @@ impl Validator {
         self.poh_timing_report_service
             .join()
             .expect("poh_timing_report_service");
+
+        // Nothing stores or cleans accounts anymore
+        if self.save_accounts_index_on_exit {
+            save_accounts_index(&root_bank, snapshot_config.as_ref());
+        }
+    }
+}
+
+/// Saves the accounts index along with a full snapshot of the root bank, since the next start
+/// loads the storages of the latest snapshot
+fn save_accounts_index(root_bank: &Bank, snapshot_config: Option<&SnapshotConfig>) {
+    let accounts_db = &root_bank.rc.accounts.accounts_db;
+    if !accounts_db.is_accounts_index_persisted() {
+        warn!("Not saving the accounts index: it is not persisted");
+        return;
+    }
+    let snapshot_config = match snapshot_config
+        .filter(|snapshot_config| snapshot_config.should_generate_snapshots())
+    {
+        Some(snapshot_config) => snapshot_config,
+        None => {
+            warn!("Not saving the accounts index: snapshots are disabled");
+            return;
+        }
+    };
+    match snapshot_utils::bank_to_full_snapshot_archive_and_accounts_index(
+        &snapshot_config.bank_snapshots_dir,
+        root_bank,
+        Some(snapshot_config.snapshot_version),
+        &snapshot_config.full_snapshot_archives_dir,
+        &snapshot_config.incremental_snapshot_archives_dir,
+        snapshot_config.archive_format,
+        snapshot_config.maximum_full_snapshot_archives_to_retain,
+        snapshot_config.maximum_incremental_snapshot_archives_to_retain,
+    ) {
+        Ok(full_snapshot_archive_info) => info!(
+            "saved the accounts index with snapshot {}",
+            full_snapshot_archive_info.path().display()
+        ),
+        Err(err) => warn!("Failed to save the accounts index: {}", err),
     }
 }
 
@@ -1525,6 +1645,7 @@ pub struct ProcessBlockStore<'a> {
     process_options: &'a blockstore_processor::ProcessOptions,
     transaction_status_sender: Option<&'a TransactionStatusSender>,
     cache_block_meta_sender: Option<CacheBlockMetaSender>,
+    entry_notification_sender: Option<&'a EntryNotifierSender>,
     blockstore_root_scan: Option<BlockstoreRootScan>,
     accounts_background_request_sender: AbsRequestSender,
     config: &'a ValidatorConfig,
@@ -1544,6 +1665,7 @@ impl<'a> ProcessBlockStore<'a> {
         process_options: &'a blockstore_processor::ProcessOptions,
         transaction_status_sender: Option<&'a TransactionStatusSender>,
         cache_block_meta_sender: Option<CacheBlockMetaSender>,
+        entry_notification_sender: Option<&'a EntryNotifierSender>,
         blockstore_root_scan: BlockstoreRootScan,
         accounts_background_request_sender: AbsRequestSender,
         config: &'a ValidatorConfig,
@@ -1559,6 +1681,7 @@ impl<'a> ProcessBlockStore<'a> {
             process_options,
             transaction_status_sender,
             cache_block_meta_sender,
+            entry_notification_sender,
             blockstore_root_scan: Some(blockstore_root_scan),
             accounts_background_request_sender,
             config,
@@ -1596,6 +1719,7 @@ impl<'a> ProcessBlockStore<'a> {
                 self.process_options,
                 self.transaction_status_sender,
                 self.cache_block_meta_sender.as_ref(),
+                self.entry_notification_sender,
                 &self.accounts_background_request_sender,
             ) {
                 return Err(format!("Failed to load ledger: {:?}", e));
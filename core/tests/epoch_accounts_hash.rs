@@ -193,7 +193,7 @@ impl BackgroundServices {
             Some(pending_snapshot_package),
             &exit,
             &cluster_info,
-            None,
+            Arc::default(),
             false,
             0,
             Some(snapshot_config.clone()),
@@ -369,7 +369,7 @@ mod tests {
             Some(LedgerCleanupService::new(
                 receiver,
                 blockstore.clone(),
-                max_ledger_shreds,
+                Arc::new(AtomicU64::new(max_ledger_shreds)),
                 &exit,
             ))
         } else {
@@ -229,7 +229,7 @@ fn run_bank_forks_snapshot_n<F>(
         None,
         &exit,
         &cluster_info,
-        None,
+        Arc::default(),
         false,
         0,
         Some(snapshot_test_config.snapshot_config.clone()),
@@ -734,7 +734,7 @@ fn test_bank_forks_incremental_snapshot(
         None,
         &exit,
         &cluster_info,
-        None,
+        Arc::default(),
         false,
         0,
         Some(snapshot_test_config.snapshot_config.clone()),
@@ -1021,7 +1021,7 @@ fn test_snapshots_with_background_services(
         Some(pending_snapshot_package),
         &exit,
         &cluster_info,
-        None,
+        Arc::default(),
         false,
         0,
         Some(snapshot_test_config.snapshot_config.clone()),
@@ -3,9 +3,12 @@
 /// In addition, the dynamic library must export a "C" function _create_plugin which
 /// creates the implementation of the plugin.
 use {
-    solana_sdk::{clock::UnixTimestamp, signature::Signature, transaction::SanitizedTransaction},
+    solana_sdk::{
+        clock::UnixTimestamp, pubkey::Pubkey, signature::Signature,
+        transaction::SanitizedTransaction,
+    },
     solana_transaction_status::{Reward, TransactionStatusMeta},
-    std::{any::Any, error, io},
+    std::{any::Any, collections::HashSet, error, io},
     thiserror::Error,
 };
 
@@ -80,6 +83,77 @@ pub enum ReplicaAccountInfoVersions<'a> {
     V0_0_2(&'a ReplicaAccountInfoV2<'a>),
 }
 
+/// Selects the account updates a plugin is notified of. The filter is applied
+/// by the validator before any `ReplicaAccountInfoVersions` is built for the
+/// plugin, which saves both the plugin and the replay thread the work of
+/// handling unwanted updates.
+///
+/// When both `include_owners` and `include_pubkeys` are empty, every account
+/// is included; otherwise an account is included if its owner or its pubkey
+/// is in the corresponding set. Included accounts are then dropped if their
+/// owner or pubkey is excluded, if their data size is out of range, or if
+/// their data does not match every `memcmp` filter.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountFilter {
+    /// Include the accounts owned by these programs
+    pub include_owners: HashSet<Pubkey>,
+
+    /// Include these accounts
+    pub include_pubkeys: HashSet<Pubkey>,
+
+    /// Exclude the accounts owned by these programs
+    pub exclude_owners: HashSet<Pubkey>,
+
+    /// Exclude these accounts
+    pub exclude_pubkeys: HashSet<Pubkey>,
+
+    /// Exclude the accounts holding less data than this many bytes
+    pub min_data_size: Option<usize>,
+
+    /// Exclude the accounts holding more data than this many bytes
+    pub max_data_size: Option<usize>,
+
+    /// Exclude the accounts whose data does not match all of these
+    pub memcmp: Vec<AccountFilterMemcmp>,
+}
+
+/// Matches the accounts whose data holds `bytes` at `offset`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountFilterMemcmp {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+impl AccountFilterMemcmp {
+    pub fn matches(&self, data: &[u8]) -> bool {
+        self.offset
+            .checked_add(self.bytes.len())
+            .and_then(|end| data.get(self.offset..end))
+            .map_or(false, |data| data == self.bytes.as_slice())
+    }
+}
+
+impl AccountFilter {
+    /// Check if an update to the account with the given pubkey, owner and data
+    /// passes the filter
+    pub fn matches(&self, pubkey: &Pubkey, owner: &Pubkey, data: &[u8]) -> bool {
+        let data_size = data.len();
+        let included = (self.include_owners.is_empty() && self.include_pubkeys.is_empty())
+            || self.include_owners.contains(owner)
+            || self.include_pubkeys.contains(pubkey);
+        included
+            && !self.exclude_owners.contains(owner)
+            && !self.exclude_pubkeys.contains(pubkey)
+            && self
+                .min_data_size
+                .map_or(true, |min_data_size| data_size >= min_data_size)
+            && self
+                .max_data_size
+                .map_or(true, |max_data_size| data_size <= max_data_size)
+            && self.memcmp.iter().all(|memcmp| memcmp.matches(data))
+    }
+}
+
 /// Information about a transaction
 #[derive(Clone, Debug)]
 pub struct ReplicaTransactionInfo<'a> {
@@ -137,6 +211,38 @@ pub enum ReplicaBlockInfoVersions<'a> {
     V0_0_1(&'a ReplicaBlockInfo<'a>),
 }
 
+/// Information about an entry, as replayed from the ledger
+#[derive(Clone, Debug)]
+pub struct ReplicaEntryInfo<'a> {
+    /// The slot of the entry
+    pub slot: u64,
+
+    /// The entry's index in the block
+    pub index: usize,
+
+    /// The number of hashes since the previous entry
+    pub num_hashes: u64,
+
+    /// The entry's SHA-256 hash, generated from the previous entry's hash
+    /// with `solana_entry::entry::next_hash()`
+    pub hash: &'a [u8],
+
+    /// The number of executed transactions in the entry
+    pub executed_transaction_count: u64,
+
+    /// The index in the block of the first transaction of the entry.
+    /// Together with `executed_transaction_count` this maps the
+    /// transactions notified through `notify_transaction` to their entry.
+    pub starting_transaction_index: usize,
+}
+
+/// A wrapper to future-proof ReplicaEntryInfo handling. To make a change to the structure of
+/// ReplicaEntryInfo, add a new enum variant wrapping a newer version, which will force plugin
+/// implementations to handle the change.
+pub enum ReplicaEntryInfoVersions<'a> {
+    V0_0_1(&'a ReplicaEntryInfo<'a>),
+}
+
 /// Errors returned by plugin calls
 #[derive(Error, Debug)]
 pub enum GeyserPluginError {
@@ -260,6 +366,12 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called when an entry is executed.
+    #[allow(unused_variables)]
+    fn notify_entry(&mut self, entry: ReplicaEntryInfoVersions) -> Result<()> {
+        Ok(())
+    }
+
     /// Check if the plugin is interested in account data
     /// Default is true -- if the plugin is not interested in
     /// account data, please return false.
@@ -267,10 +379,182 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         true
     }
 
+    /// The filter selecting the account updates the plugin is notified of.
+    /// It is queried once, right after `on_load`, so a plugin can build it
+    /// from its config file. Default is None -- the plugin is notified of
+    /// every account update.
+    fn account_filter(&self) -> Option<AccountFilter> {
+        None
+    }
+
     /// Check if the plugin is interested in transaction data
     /// Default is false -- if the plugin is not interested in
     /// transaction data, please return false.
     fn transaction_notifications_enabled(&self) -> bool {
         false
     }
+
+    /// Check if the plugin is interested in entry data
+    /// Default is false -- if the plugin is interested in
+    /// entry data, return true.
+    fn entry_notifications_enabled(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_filter_default_matches_all() {
+        let filter = AccountFilter::default();
+        assert!(filter.matches(&Pubkey::new_unique(), &Pubkey::new_unique(), &[]));
+        assert!(filter.matches(&Pubkey::new_unique(), &Pubkey::new_unique(), &[1; 100]));
+    }
+
+    #[test]
+    fn test_account_filter_owners_and_pubkeys() {
+        let owner = Pubkey::new_unique();
+        let other_owner = Pubkey::new_unique();
+        let pubkey = Pubkey::new_unique();
+        let other_pubkey = Pubkey::new_unique();
+
+        let filter = AccountFilter {
+            include_owners: [owner].into(),
+            ..AccountFilter::default()
+        };
+        assert!(filter.matches(&other_pubkey, &owner, &[]));
+        assert!(!filter.matches(&other_pubkey, &other_owner, &[]));
+
+        // An account is included if either its owner or its pubkey is
+        let filter = AccountFilter {
+            include_owners: [owner].into(),
+            include_pubkeys: [pubkey].into(),
+            ..AccountFilter::default()
+        };
+        assert!(filter.matches(&other_pubkey, &owner, &[]));
+        assert!(filter.matches(&pubkey, &other_owner, &[]));
+        assert!(!filter.matches(&other_pubkey, &other_owner, &[]));
+
+        // Exclusions apply to included accounts
+        let filter = AccountFilter {
+            include_owners: [owner].into(),
+            exclude_pubkeys: [pubkey].into(),
+            ..AccountFilter::default()
+        };
+        assert!(filter.matches(&other_pubkey, &owner, &[]));
+        assert!(!filter.matches(&pubkey, &owner, &[]));
+
+        let filter = AccountFilter {
+            include_pubkeys: [pubkey].into(),
+            exclude_owners: [owner].into(),
+            ..AccountFilter::default()
+        };
+        assert!(filter.matches(&pubkey, &other_owner, &[]));
+        assert!(!filter.matches(&pubkey, &owner, &[]));
+
+        // Exclusions alone filter out of every account
+        let filter = AccountFilter {
+            exclude_owners: [owner].into(),
+            ..AccountFilter::default()
+        };
+        assert!(filter.matches(&pubkey, &other_owner, &[]));
+        assert!(!filter.matches(&pubkey, &owner, &[]));
+    }
+
+    #[test]
+    fn test_account_filter_data_size() {
+        let pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let filter = AccountFilter {
+            min_data_size: Some(10),
+            max_data_size: Some(20),
+            ..AccountFilter::default()
+        };
+        assert!(!filter.matches(&pubkey, &owner, &[0; 9]));
+        assert!(filter.matches(&pubkey, &owner, &[0; 10]));
+        assert!(filter.matches(&pubkey, &owner, &[0; 20]));
+        assert!(!filter.matches(&pubkey, &owner, &[0; 21]));
+
+        let filter = AccountFilter {
+            max_data_size: Some(0),
+            ..AccountFilter::default()
+        };
+        assert!(filter.matches(&pubkey, &owner, &[]));
+        assert!(!filter.matches(&pubkey, &owner, &[0]));
+    }
+
+    #[test]
+    fn test_account_filter_memcmp() {
+        let memcmp = AccountFilterMemcmp {
+            offset: 2,
+            bytes: vec![1, 2],
+        };
+        assert!(memcmp.matches(&[0, 0, 1, 2]));
+        assert!(memcmp.matches(&[0, 0, 1, 2, 3]));
+        assert!(!memcmp.matches(&[0, 0, 1, 3]));
+        assert!(!memcmp.matches(&[1, 2, 0, 0]));
+        // Data too short to hold the bytes at the offset
+        assert!(!memcmp.matches(&[0, 0, 1]));
+        assert!(!memcmp.matches(&[]));
+        assert!(!AccountFilterMemcmp {
+            offset: usize::MAX,
+            bytes: vec![1],
+        }
+        .matches(&[1]));
+        // Empty bytes match any data holding the offset
+        assert!(AccountFilterMemcmp {
+            offset: 1,
+            bytes: vec![],
+        }
+        .matches(&[0]));
+
+        let pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let filter = AccountFilter {
+            memcmp: vec![
+                memcmp,
+                AccountFilterMemcmp {
+                    offset: 0,
+                    bytes: vec![9],
+                },
+            ],
+            ..AccountFilter::default()
+        };
+        // Every memcmp filter must match
+        assert!(filter.matches(&pubkey, &owner, &[9, 0, 1, 2]));
+        assert!(!filter.matches(&pubkey, &owner, &[8, 0, 1, 2]));
+        assert!(!filter.matches(&pubkey, &owner, &[9, 0, 1, 3]));
+    }
+
+    #[test]
+    fn test_account_filter_combined() {
+        let token_program = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let excluded = Pubkey::new_unique();
+        let mut token_account = vec![0; 165];
+        token_account[..32].copy_from_slice(mint.as_ref());
+
+        // Token accounts of one mint, except one of them
+        let filter = AccountFilter {
+            include_owners: [token_program].into(),
+            exclude_pubkeys: [excluded].into(),
+            min_data_size: Some(165),
+            max_data_size: Some(165),
+            memcmp: vec![AccountFilterMemcmp {
+                offset: 0,
+                bytes: mint.to_bytes().to_vec(),
+            }],
+            ..AccountFilter::default()
+        };
+        let pubkey = Pubkey::new_unique();
+        assert!(filter.matches(&pubkey, &token_program, &token_account));
+        assert!(!filter.matches(&excluded, &token_program, &token_account));
+        assert!(!filter.matches(&pubkey, &Pubkey::new_unique(), &token_account));
+        assert!(!filter.matches(&pubkey, &token_program, &token_account[..164]));
+        let mut other_mint_account = token_account.clone();
+        other_mint_account[..32].copy_from_slice(Pubkey::new_unique().as_ref());
+        assert!(!filter.matches(&pubkey, &token_program, &other_mint_account));
+    }
 }
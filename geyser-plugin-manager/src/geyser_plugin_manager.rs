@@ -1,15 +1,34 @@
 /// Managing the Geyser plugins
 use {
+    crate::geyser_plugin_service::GeyserPluginServiceError,
     libloading::{Library, Symbol},
     log::*,
-    solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
-    std::error::Error,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{AccountFilter, GeyserPlugin},
+    solana_sdk::pubkey::Pubkey,
+    std::{
+        error::Error,
+        fs::File,
+        io::Read,
+        path::{Path, PathBuf},
+    },
 };
 
+/// The notifiers started by the `GeyserPluginService`. Plugins loaded at runtime
+/// can only be served the kinds of notifications there is a running notifier for.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct RunningNotifiers {
+    pub account_data: bool,
+    pub transaction: bool,
+    pub entry: bool,
+}
+
 #[derive(Default, Debug)]
 pub struct GeyserPluginManager {
     pub plugins: Vec<Box<dyn GeyserPlugin>>,
     libs: Vec<Library>,
+    config_files: Vec<PathBuf>,
+    account_filters: Vec<Option<AccountFilter>>,
+    running_notifiers: Option<RunningNotifiers>,
 }
 
 impl GeyserPluginManager {
@@ -17,6 +36,9 @@ impl GeyserPluginManager {
         GeyserPluginManager {
             plugins: Vec::default(),
             libs: Vec::default(),
+            config_files: Vec::default(),
+            account_filters: Vec::default(),
+            running_notifiers: None,
         }
     }
 
@@ -34,12 +56,134 @@ impl GeyserPluginManager {
         let constructor: Symbol<PluginConstructor> = lib.get(b"_create_plugin")?;
         let plugin_raw = constructor();
         let mut plugin = Box::from_raw(plugin_raw);
+        if self.plugin_position(plugin.name()).is_some() {
+            let name = plugin.name().to_string();
+            // The plugin must be dropped before the library it was created from.
+            drop(plugin);
+            drop(lib);
+            return Err(format!("A plugin named {} is already loaded", name).into());
+        }
         plugin.on_load(config_file)?;
+
+        if let Err(err) = self.check_running_notifiers(plugin.as_ref()) {
+            plugin.on_unload();
+            drop(plugin);
+            drop(lib);
+            return Err(err.into());
+        }
+
+        self.account_filters.push(plugin.account_filter());
         self.plugins.push(plugin);
         self.libs.push(lib);
+        self.config_files.push(PathBuf::from(config_file));
         Ok(())
     }
 
+    /// Load the plugin described by the given config file and return its name.
+    ///
+    /// The config file is in JSON5 format. Its `libpath` field points to the
+    /// dynamic shared library implementing the plugin; a relative path is
+    /// resolved against the directory of the config file.
+    pub fn load_plugin_from_config(
+        &mut self,
+        geyser_plugin_config_file: &Path,
+    ) -> Result<String, GeyserPluginServiceError> {
+        let mut file = match File::open(geyser_plugin_config_file) {
+            Ok(file) => file,
+            Err(err) => {
+                return Err(GeyserPluginServiceError::CannotOpenConfigFile(format!(
+                    "Failed to open the plugin config file {:?}, error: {:?}",
+                    geyser_plugin_config_file, err
+                )));
+            }
+        };
+
+        let mut contents = String::new();
+        if let Err(err) = file.read_to_string(&mut contents) {
+            return Err(GeyserPluginServiceError::CannotReadConfigFile(format!(
+                "Failed to read the plugin config file {:?}, error: {:?}",
+                geyser_plugin_config_file, err
+            )));
+        }
+
+        let result: serde_json::Value = match json5::from_str(&contents) {
+            Ok(value) => value,
+            Err(err) => {
+                return Err(GeyserPluginServiceError::InvalidConfigFileFormat(format!(
+                    "The config file {:?} is not in a valid Json5 format, error: {:?}",
+                    geyser_plugin_config_file, err
+                )));
+            }
+        };
+
+        let libpath = result["libpath"]
+            .as_str()
+            .ok_or(GeyserPluginServiceError::LibPathNotSet)?;
+        let mut libpath = PathBuf::from(libpath);
+        if libpath.is_relative() {
+            let config_dir = geyser_plugin_config_file.parent().ok_or_else(|| {
+                GeyserPluginServiceError::CannotOpenConfigFile(format!(
+                    "Failed to resolve parent of {:?}",
+                    geyser_plugin_config_file,
+                ))
+            })?;
+            libpath = config_dir.join(libpath);
+        }
+
+        let config_file = geyser_plugin_config_file
+            .as_os_str()
+            .to_str()
+            .ok_or(GeyserPluginServiceError::InvalidPluginPath)?;
+
+        unsafe {
+            let result = self.load_plugin(libpath.to_str().unwrap(), config_file);
+            if let Err(err) = result {
+                let msg = format!(
+                    "Failed to load the plugin library: {:?}, error: {:?}",
+                    libpath, err
+                );
+                return Err(GeyserPluginServiceError::PluginLoadError(msg));
+            }
+        }
+        let name = self.plugins.last().unwrap().name().to_string();
+        info!(
+            "Loaded plugin {} from {:?}",
+            name, geyser_plugin_config_file
+        );
+        Ok(name)
+    }
+
+    /// Names of the loaded plugins, in load order
+    pub fn list_plugins(&self) -> Vec<String> {
+        self.plugins
+            .iter()
+            .map(|plugin| plugin.name().to_string())
+            .collect()
+    }
+
+    /// Unload the plugin with the given name, firing its `on_unload()` method.
+    pub fn unload_plugin(&mut self, name: &str) -> Result<(), GeyserPluginServiceError> {
+        let index = self
+            .plugin_position(name)
+            .ok_or_else(|| GeyserPluginServiceError::PluginNotLoaded(name.to_string()))?;
+        self.drop_plugin(index);
+        Ok(())
+    }
+
+    /// Unload the plugin with the given name, then load it again from the
+    /// config file it was originally loaded from. The config file and the
+    /// library it points to are re-read, so this picks up upgraded plugins.
+    ///
+    /// The old instance is unloaded first in case the plugin needs exclusive
+    /// access to a resource, such as a port or a database connection.
+    pub fn reload_plugin(&mut self, name: &str) -> Result<String, GeyserPluginServiceError> {
+        let index = self
+            .plugin_position(name)
+            .ok_or_else(|| GeyserPluginServiceError::PluginNotLoaded(name.to_string()))?;
+        let config_file = self.drop_plugin(index);
+        self.load_plugin_from_config(&config_file)
+    }
+
     /// Unload all plugins and loaded plugin libraries, making sure to fire
     /// their `on_plugin_unload()` methods so they can do any necessary cleanup.
     pub fn unload(&mut self) {
@@ -51,6 +195,8 @@ impl GeyserPluginManager {
         for lib in self.libs.drain(..) {
             drop(lib);
         }
+        self.config_files.clear();
+        self.account_filters.clear();
     }
 
     /// Check if there is any plugin interested in account data
@@ -63,6 +209,34 @@ impl GeyserPluginManager {
         false
     }
 
+    /// Check if there is any plugin interested in an update to the account with
+    /// the given pubkey, owner and data
+    pub fn account_update_wanted(&self, pubkey: &Pubkey, owner: &Pubkey, data: &[u8]) -> bool {
+        self.plugins
+            .iter()
+            .zip(&self.account_filters)
+            .any(|(plugin, account_filter)| {
+                Self::plugin_wants_account_update(plugin, account_filter, pubkey, owner, data)
+            })
+    }
+
+    /// The plugins interested in an update to the account with the given pubkey,
+    /// owner and data
+    pub(crate) fn account_update_plugins<'a>(
+        &'a mut self,
+        pubkey: &'a Pubkey,
+        owner: &'a Pubkey,
+        data: &'a [u8],
+    ) -> impl Iterator<Item = &'a mut Box<dyn GeyserPlugin>> {
+        self.plugins
+            .iter_mut()
+            .zip(&self.account_filters)
+            .filter_map(move |(plugin, account_filter)| {
+                Self::plugin_wants_account_update(plugin, account_filter, pubkey, owner, data)
+                    .then_some(plugin)
+            })
+    }
+
     /// Check if there is any plugin interested in transaction data
     pub fn transaction_notifications_enabled(&self) -> bool {
         for plugin in &self.plugins {
@@ -72,4 +246,341 @@ impl GeyserPluginManager {
         }
         false
     }
+
+    /// Check if there is any plugin interested in entry data
+    pub fn entry_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.entry_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Record which notifiers were started, so plugins loaded afterwards can be
+    /// checked against them.
+    pub(crate) fn set_running_notifiers(&mut self, running_notifiers: RunningNotifiers) {
+        self.running_notifiers = Some(running_notifiers);
+    }
+
+    /// Check that the notifications wanted by a plugin loaded at runtime are
+    /// served by the running notifiers
+    fn check_running_notifiers(&self, plugin: &dyn GeyserPlugin) -> Result<(), String> {
+        let running_notifiers = match self.running_notifiers {
+            Some(running_notifiers) => running_notifiers,
+            None => return Ok(()),
+        };
+        let missing_notifier =
+            if plugin.account_data_notifications_enabled() && !running_notifiers.account_data {
+                Some("account data")
+            } else if plugin.transaction_notifications_enabled() && !running_notifiers.transaction {
+                Some("transaction")
+            } else if plugin.entry_notifications_enabled() && !running_notifiers.entry {
+                Some("entry")
+            } else {
+                None
+            };
+        match missing_notifier {
+            Some(missing_notifier) => Err(format!(
+                "Plugin {} requires {} notifications, which were not enabled when the \
+                 validator started. Restart the validator with --geyser-plugin-always-enabled \
+                 to load any plugin at runtime",
+                plugin.name(),
+                missing_notifier
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn plugin_wants_account_update(
+        plugin: &dyn GeyserPlugin,
+        account_filter: &Option<AccountFilter>,
+        pubkey: &Pubkey,
+        owner: &Pubkey,
+        data: &[u8],
+    ) -> bool {
+        plugin.account_data_notifications_enabled()
+            && account_filter.as_ref().map_or(true, |account_filter| {
+                account_filter.matches(pubkey, owner, data)
+            })
+    }
+
+    fn plugin_position(&self, name: &str) -> Option<usize> {
+        self.plugins.iter().position(|plugin| plugin.name() == name)
+    }
+
+    /// Unload the plugin at `index` and return the config file it was loaded from
+    fn drop_plugin(&mut self, index: usize) -> PathBuf {
+        let mut plugin = self.plugins.remove(index);
+        let lib = self.libs.remove(index);
+        let config_file = self.config_files.remove(index);
+        self.account_filters.remove(index);
+        info!("Unloading plugin for {:?}", plugin.name());
+        plugin.on_unload();
+        // The plugin must be dropped before the library it was created from.
+        drop(plugin);
+        drop(lib);
+        config_file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::{
+            fs,
+            sync::{
+                atomic::{AtomicBool, Ordering},
+                Arc,
+            },
+        },
+        tempfile::TempDir,
+    };
+
+    #[derive(Debug, Default)]
+    struct TestPlugin {
+        name: &'static str,
+        account_filter: Option<AccountFilter>,
+        transaction_notifications: bool,
+        unloaded: Arc<AtomicBool>,
+    }
+
+    impl GeyserPlugin for TestPlugin {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn on_unload(&mut self) {
+            self.unloaded.store(true, Ordering::Relaxed);
+        }
+
+        fn account_filter(&self) -> Option<AccountFilter> {
+            self.account_filter.clone()
+        }
+
+        fn transaction_notifications_enabled(&self) -> bool {
+            self.transaction_notifications
+        }
+    }
+
+    /// A library handle to stand in for the library a plugin was loaded from
+    fn dummy_library() -> Library {
+        #[cfg(unix)]
+        let library = libloading::os::unix::Library::this();
+        #[cfg(windows)]
+        let library = libloading::os::windows::Library::this().unwrap();
+        library.into()
+    }
+
+    /// Add a plugin the way `load_plugin` does, without loading a library
+    fn add_test_plugin(manager: &mut GeyserPluginManager, plugin: TestPlugin, config_file: &Path) {
+        manager.account_filters.push(plugin.account_filter());
+        manager.plugins.push(Box::new(plugin));
+        manager.libs.push(dummy_library());
+        manager.config_files.push(config_file.to_path_buf());
+    }
+
+    #[test]
+    fn test_unload_plugin() {
+        let mut manager = GeyserPluginManager::new();
+        let unloaded1 = Arc::new(AtomicBool::default());
+        let unloaded2 = Arc::new(AtomicBool::default());
+        add_test_plugin(
+            &mut manager,
+            TestPlugin {
+                name: "plugin1",
+                unloaded: unloaded1.clone(),
+                ..TestPlugin::default()
+            },
+            Path::new("plugin1.json"),
+        );
+        add_test_plugin(
+            &mut manager,
+            TestPlugin {
+                name: "plugin2",
+                unloaded: unloaded2.clone(),
+                ..TestPlugin::default()
+            },
+            Path::new("plugin2.json"),
+        );
+        assert_eq!(manager.list_plugins(), vec!["plugin1", "plugin2"]);
+
+        manager.unload_plugin("plugin1").unwrap();
+        assert!(unloaded1.load(Ordering::Relaxed));
+        assert!(!unloaded2.load(Ordering::Relaxed));
+        assert_eq!(manager.list_plugins(), vec!["plugin2"]);
+        assert_eq!(manager.config_files, vec![PathBuf::from("plugin2.json")]);
+        assert_eq!(manager.account_filters.len(), 1);
+        assert_eq!(manager.libs.len(), 1);
+
+        assert!(matches!(
+            manager.unload_plugin("plugin1"),
+            Err(GeyserPluginServiceError::PluginNotLoaded(name)) if name == "plugin1"
+        ));
+
+        manager.unload();
+        assert!(unloaded2.load(Ordering::Relaxed));
+        assert!(manager.list_plugins().is_empty());
+        assert!(manager.libs.is_empty());
+    }
+
+    #[test]
+    fn test_reload_plugin() {
+        let mut manager = GeyserPluginManager::new();
+        assert!(matches!(
+            manager.reload_plugin("plugin"),
+            Err(GeyserPluginServiceError::PluginNotLoaded(_))
+        ));
+
+        // The plugin is unloaded first, so it stays unloaded if loading it again fails
+        let tmp_dir = TempDir::new().unwrap();
+        let unloaded = Arc::new(AtomicBool::default());
+        add_test_plugin(
+            &mut manager,
+            TestPlugin {
+                name: "plugin",
+                unloaded: unloaded.clone(),
+                ..TestPlugin::default()
+            },
+            &tmp_dir.path().join("missing.json"),
+        );
+        assert!(matches!(
+            manager.reload_plugin("plugin"),
+            Err(GeyserPluginServiceError::CannotOpenConfigFile(_))
+        ));
+        assert!(unloaded.load(Ordering::Relaxed));
+        assert!(manager.list_plugins().is_empty());
+    }
+
+    #[test]
+    fn test_load_plugin_from_config_errors() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut manager = GeyserPluginManager::new();
+
+        assert!(matches!(
+            manager.load_plugin_from_config(&tmp_dir.path().join("missing.json")),
+            Err(GeyserPluginServiceError::CannotOpenConfigFile(_))
+        ));
+
+        let config_file = tmp_dir.path().join("plugin.json");
+        fs::write(&config_file, "{ libpath: ").unwrap();
+        assert!(matches!(
+            manager.load_plugin_from_config(&config_file),
+            Err(GeyserPluginServiceError::InvalidConfigFileFormat(_))
+        ));
+
+        fs::write(&config_file, "{ name: \"plugin\" }").unwrap();
+        assert!(matches!(
+            manager.load_plugin_from_config(&config_file),
+            Err(GeyserPluginServiceError::LibPathNotSet)
+        ));
+
+        // A relative libpath is resolved against the directory of the config file
+        fs::write(&config_file, "{ libpath: \"libmissing_plugin.so\" }").unwrap();
+        match manager.load_plugin_from_config(&config_file) {
+            Err(GeyserPluginServiceError::PluginLoadError(msg)) => {
+                let libpath = tmp_dir.path().join("libmissing_plugin.so");
+                assert!(msg.contains(&format!("{:?}", libpath)), "{}", msg);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert!(manager.list_plugins().is_empty());
+    }
+
+    #[test]
+    fn test_check_running_notifiers() {
+        let account_plugin = TestPlugin {
+            name: "accounts",
+            ..TestPlugin::default()
+        };
+        let transaction_plugin = TestPlugin {
+            name: "transactions",
+            transaction_notifications: true,
+            ..TestPlugin::default()
+        };
+
+        // Before the service starts, any plugin can be loaded
+        let mut manager = GeyserPluginManager::new();
+        assert!(manager.check_running_notifiers(&account_plugin).is_ok());
+        assert!(manager.check_running_notifiers(&transaction_plugin).is_ok());
+
+        manager.set_running_notifiers(RunningNotifiers {
+            account_data: true,
+            ..RunningNotifiers::default()
+        });
+        assert!(manager.check_running_notifiers(&account_plugin).is_ok());
+        let err = manager
+            .check_running_notifiers(&transaction_plugin)
+            .unwrap_err();
+        assert!(err.contains("transaction notifications"), "{}", err);
+
+        manager.set_running_notifiers(RunningNotifiers {
+            account_data: false,
+            transaction: true,
+            entry: true,
+        });
+        assert!(manager.check_running_notifiers(&account_plugin).is_err());
+        assert!(manager
+            .check_running_notifiers(&transaction_plugin)
+            .is_err());
+    }
+
+    #[test]
+    fn test_account_update_wanted() {
+        let owner = Pubkey::new_unique();
+        let pubkey = Pubkey::new_unique();
+        let small_data = [0u8; 10];
+        let large_data = [0u8; 100];
+        let mut manager = GeyserPluginManager::new();
+        assert!(!manager.account_update_wanted(&pubkey, &owner, &small_data));
+
+        add_test_plugin(
+            &mut manager,
+            TestPlugin {
+                name: "owner",
+                account_filter: Some(AccountFilter {
+                    include_owners: [owner].into(),
+                    ..AccountFilter::default()
+                }),
+                ..TestPlugin::default()
+            },
+            Path::new("owner.json"),
+        );
+        add_test_plugin(
+            &mut manager,
+            TestPlugin {
+                name: "large",
+                account_filter: Some(AccountFilter {
+                    min_data_size: Some(100),
+                    ..AccountFilter::default()
+                }),
+                ..TestPlugin::default()
+            },
+            Path::new("large.json"),
+        );
+
+        let wanted_by = |manager: &mut GeyserPluginManager, owner: &Pubkey, data: &[u8]| {
+            manager
+                .account_update_plugins(&pubkey, owner, data)
+                .map(|plugin| plugin.name())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(wanted_by(&mut manager, &owner, &small_data), vec!["owner"]);
+        assert_eq!(
+            wanted_by(&mut manager, &owner, &large_data),
+            vec!["owner", "large"]
+        );
+        assert_eq!(
+            wanted_by(&mut manager, &Pubkey::new_unique(), &large_data),
+            vec!["large"]
+        );
+        assert!(wanted_by(&mut manager, &Pubkey::new_unique(), &small_data).is_empty());
+        assert!(!manager.account_update_wanted(&pubkey, &Pubkey::new_unique(), &small_data));
+        assert!(manager.account_update_wanted(&pubkey, &owner, &small_data));
+
+        manager.unload_plugin("owner").unwrap();
+        assert_eq!(wanted_by(&mut manager, &owner, &large_data), vec!["large"]);
+        assert!(!manager.account_update_wanted(&pubkey, &owner, &small_data));
+    }
 }
@@ -3,20 +3,22 @@ use {
         accounts_update_notifier::AccountsUpdateNotifierImpl,
         block_metadata_notifier::BlockMetadataNotifierImpl,
         block_metadata_notifier_interface::BlockMetadataNotifierLock,
-        geyser_plugin_manager::GeyserPluginManager, slot_status_notifier::SlotStatusNotifierImpl,
-        slot_status_observer::SlotStatusObserver, transaction_notifier::TransactionNotifierImpl,
+        entry_notifier::EntryNotifierImpl,
+        geyser_plugin_manager::{GeyserPluginManager, RunningNotifiers},
+        slot_status_notifier::SlotStatusNotifierImpl,
+        slot_status_observer::SlotStatusObserver,
+        transaction_notifier::TransactionNotifierImpl,
     },
     crossbeam_channel::Receiver,
     log::*,
+    solana_ledger::entry_notifier_interface::EntryNotifierLock,
     solana_rpc::{
         optimistically_confirmed_bank_tracker::BankNotification,
         transaction_notifier_interface::TransactionNotifierLock,
     },
     solana_runtime::accounts_update_notifier_interface::AccountsUpdateNotifier,
     std::{
-        fs::File,
-        io::Read,
-        path::{Path, PathBuf},
+        path::PathBuf,
         sync::{Arc, RwLock},
         thread,
     },
@@ -42,6 +44,9 @@ pub enum GeyserPluginServiceError {
 
     #[error("Cannot load plugin shared library")]
     PluginLoadError(String),
+
+    #[error("No plugin with the name {0} is loaded")]
+    PluginNotLoaded(String),
 }
 
 /// The service managing the Geyser plugin workflow.
@@ -50,6 +55,7 @@ pub struct GeyserPluginService {
     plugin_manager: Arc<RwLock<GeyserPluginManager>>,
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     transaction_notifier: Option<TransactionNotifierLock>,
+    entry_notifier: Option<EntryNotifierLock>,
     block_metadata_notifier: Option<BlockMetadataNotifierLock>,
 }
 
@@ -66,10 +72,13 @@ impl GeyserPluginService {
     ///    shall create the implementation of `GeyserPlugin` and returns to the caller.
     ///    The rest of the JSON fields' definition is up to to the concrete plugin implementation
     ///    It is usually used to configure the connection information for the external data store.
+    /// * `geyser_plugin_always_enabled` - Start every notifier, even the ones no plugin loaded
+    ///    at startup wants, so that any plugin can be loaded at runtime.
 
     pub fn new(
         confirmed_bank_receiver: Receiver<BankNotification>,
         geyser_plugin_config_files: &[PathBuf],
+        geyser_plugin_always_enabled: bool,
     ) -> Result<Self, GeyserPluginServiceError> {
         info!(
             "Starting GeyserPluginService from config files: {:?}",
@@ -78,11 +87,19 @@ impl GeyserPluginService {
         let mut plugin_manager = GeyserPluginManager::new();
 
         for geyser_plugin_config_file in geyser_plugin_config_files {
-            Self::load_plugin(&mut plugin_manager, geyser_plugin_config_file)?;
+            plugin_manager.load_plugin_from_config(geyser_plugin_config_file)?;
         }
         let account_data_notifications_enabled =
-            plugin_manager.account_data_notifications_enabled();
-        let transaction_notifications_enabled = plugin_manager.transaction_notifications_enabled();
+            geyser_plugin_always_enabled || plugin_manager.account_data_notifications_enabled();
+        let transaction_notifications_enabled =
+            geyser_plugin_always_enabled || plugin_manager.transaction_notifications_enabled();
+        let entry_notifications_enabled =
+            geyser_plugin_always_enabled || plugin_manager.entry_notifications_enabled();
+        plugin_manager.set_running_notifiers(RunningNotifiers {
+            account_data: account_data_notifications_enabled,
+            transaction: transaction_notifications_enabled,
+            entry: entry_notifications_enabled,
+        });
 
         let plugin_manager = Arc::new(RwLock::new(plugin_manager));
 
@@ -103,10 +120,20 @@ impl GeyserPluginService {
                 None
             };
 
+        let entry_notifier: Option<EntryNotifierLock> = if entry_notifications_enabled {
+            let entry_notifier = EntryNotifierImpl::new(plugin_manager.clone());
+            Some(Arc::new(RwLock::new(entry_notifier)))
+        } else {
+            None
+        };
+
         let (slot_status_observer, block_metadata_notifier): (
             Option<SlotStatusObserver>,
             Option<BlockMetadataNotifierLock>,
-        ) = if account_data_notifications_enabled || transaction_notifications_enabled {
+        ) = if account_data_notifications_enabled
+            || transaction_notifications_enabled
+            || entry_notifications_enabled
+        {
             let slot_status_notifier = SlotStatusNotifierImpl::new(plugin_manager.clone());
             let slot_status_notifier = Arc::new(RwLock::new(slot_status_notifier));
             (
@@ -128,74 +155,11 @@ impl GeyserPluginService {
             plugin_manager,
             accounts_update_notifier,
             transaction_notifier,
+            entry_notifier,
             block_metadata_notifier,
         })
     }
 
-    fn load_plugin(
-        plugin_manager: &mut GeyserPluginManager,
-        geyser_plugin_config_file: &Path,
-    ) -> Result<(), GeyserPluginServiceError> {
-        let mut file = match File::open(geyser_plugin_config_file) {
-            Ok(file) => file,
-            Err(err) => {
-                return Err(GeyserPluginServiceError::CannotOpenConfigFile(format!(
-                    "Failed to open the plugin config file {:?}, error: {:?}",
-                    geyser_plugin_config_file, err
-                )));
-            }
-        };
-
-        let mut contents = String::new();
-        if let Err(err) = file.read_to_string(&mut contents) {
-            return Err(GeyserPluginServiceError::CannotReadConfigFile(format!(
-                "Failed to read the plugin config file {:?}, error: {:?}",
-                geyser_plugin_config_file, err
-            )));
-        }
-
-        let result: serde_json::Value = match json5::from_str(&contents) {
-            Ok(value) => value,
-            Err(err) => {
-                return Err(GeyserPluginServiceError::InvalidConfigFileFormat(format!(
-                    "The config file {:?} is not in a valid Json5 format, error: {:?}",
-                    geyser_plugin_config_file, err
-                )));
-            }
-        };
-
-        let libpath = result["libpath"]
-            .as_str()
-            .ok_or(GeyserPluginServiceError::LibPathNotSet)?;
-        let mut libpath = PathBuf::from(libpath);
-        if libpath.is_relative() {
-            let config_dir = geyser_plugin_config_file.parent().ok_or_else(|| {
-                GeyserPluginServiceError::CannotOpenConfigFile(format!(
-                    "Failed to resolve parent of {:?}",
-                    geyser_plugin_config_file,
-                ))
-            })?;
-            libpath = config_dir.join(libpath);
-        }
-
-        let config_file = geyser_plugin_config_file
-            .as_os_str()
-            .to_str()
-            .ok_or(GeyserPluginServiceError::InvalidPluginPath)?;
-
-        unsafe {
-            let result = plugin_manager.load_plugin(libpath.to_str().unwrap(), config_file);
-            if let Err(err) = result {
-                let msg = format!(
-                    "Failed to load the plugin library: {:?}, error: {:?}",
-                    libpath, err
-                );
-                return Err(GeyserPluginServiceError::PluginLoadError(msg));
-            }
-        }
-        Ok(())
-    }
-
     pub fn get_accounts_update_notifier(&self) -> Option<AccountsUpdateNotifier> {
         self.accounts_update_notifier.clone()
     }
@@ -204,10 +168,20 @@ impl GeyserPluginService {
         self.transaction_notifier.clone()
     }
 
+    pub fn get_entry_notifier(&self) -> Option<EntryNotifierLock> {
+        self.entry_notifier.clone()
+    }
+
     pub fn get_block_metadata_notifier(&self) -> Option<BlockMetadataNotifierLock> {
         self.block_metadata_notifier.clone()
     }
 
+    /// The plugin manager shared with the notifiers. Plugins can be loaded,
+    /// unloaded and reloaded through it while the notifiers keep running.
+    pub fn get_plugin_manager(&self) -> Arc<RwLock<GeyserPluginManager>> {
+        self.plugin_manager.clone()
+    }
+
     pub fn join(self) -> thread::Result<()> {
         if let Some(mut slot_status_observer) = self.slot_status_observer {
             slot_status_observer.join()?;
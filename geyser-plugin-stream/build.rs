@@ -0,0 +1,22 @@
+fn main() -> Result<(), std::io::Error> {
+    const PROTOC_ENVAR: &str = "PROTOC";
+    if std::env::var(PROTOC_ENVAR).is_err() {
+        #[cfg(not(windows))]
+        std::env::set_var(PROTOC_ENVAR, protobuf_src::protoc());
+    }
+
+    let proto_base_path = std::path::PathBuf::from("proto");
+    let storage_proto_base_path = std::path::PathBuf::from("../storage-proto/proto");
+    let proto = proto_base_path.join("geyser_stream.proto");
+    println!("cargo::rerun-if-changed={}", proto.display());
+
+    tonic_build::configure()
+        .build_client(false)
+        .build_server(false)
+        // Reuse the types generated by solana-storage-proto, so their conversions apply
+        .extern_path(
+            ".solana.storage.ConfirmedBlock",
+            "::solana_storage_proto::convert::generated",
+        )
+        .compile(&[proto], &[proto_base_path, storage_proto_base_path])
+}
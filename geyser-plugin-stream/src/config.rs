@@ -0,0 +1,207 @@
+/// The configuration of the stream plugin, read from the JSON5 config file
+/// the plugin is loaded with. For example:
+///
+/// ```json5
+/// {
+///     "libpath": "/path/to/libsolana_geyser_plugin_stream.so",
+///     "output": {
+///         "files": {
+///             "directory": "/path/to/stream",
+///             "max_file_size": 1073741824,
+///             "max_files": 16,
+///         },
+///     },
+///     "transaction_notifications": true,
+///     "account_filter": {
+///         "include_owners": ["TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"],
+///     },
+/// }
+/// ```
+use {
+    serde_derive::Deserialize,
+    solana_geyser_plugin_interface::geyser_plugin_interface::AccountFilter,
+    solana_sdk::pubkey::Pubkey,
+    std::{collections::HashSet, path::PathBuf, str::FromStr},
+};
+
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024 * 1024;
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 100_000;
+pub const DEFAULT_MAX_CLIENT_BUFFER_SIZE: usize = 64 * 1024 * 1024;
+
+/// Where the notifications are streamed to
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamOutput {
+    /// Append the records to files in `directory`, starting a new file once
+    /// the current one would grow over `max_file_size` bytes. When `max_files`
+    /// is set, the oldest files are deleted to keep at most that many.
+    Files {
+        directory: PathBuf,
+        #[serde(default = "default_max_file_size")]
+        max_file_size: u64,
+        #[serde(default)]
+        max_files: Option<usize>,
+    },
+
+    /// Listen on the Unix domain socket at `path` and write the records to
+    /// every connected client. A client only receives the records written
+    /// after it connected. A client is disconnected once it falls more than
+    /// `max_client_buffer_size` bytes behind. Only available on Unix.
+    #[cfg(unix)]
+    UnixSocket {
+        path: PathBuf,
+        #[serde(default = "default_max_client_buffer_size")]
+        max_client_buffer_size: usize,
+    },
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct AccountFilterConfig {
+    #[serde(default)]
+    pub include_owners: Vec<String>,
+    #[serde(default)]
+    pub include_pubkeys: Vec<String>,
+    #[serde(default)]
+    pub exclude_owners: Vec<String>,
+    #[serde(default)]
+    pub exclude_pubkeys: Vec<String>,
+    #[serde(default)]
+    pub min_data_size: Option<usize>,
+    #[serde(default)]
+    pub max_data_size: Option<usize>,
+}
+
+impl AccountFilterConfig {
+    pub fn to_account_filter(&self) -> Result<AccountFilter, String> {
+        Ok(AccountFilter {
+            include_owners: parse_pubkeys(&self.include_owners)?,
+            include_pubkeys: parse_pubkeys(&self.include_pubkeys)?,
+            exclude_owners: parse_pubkeys(&self.exclude_owners)?,
+            exclude_pubkeys: parse_pubkeys(&self.exclude_pubkeys)?,
+            min_data_size: self.min_data_size,
+            max_data_size: self.max_data_size,
+            ..AccountFilter::default()
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct GeyserPluginStreamConfig {
+    pub output: StreamOutput,
+
+    /// The number of encoded notifications buffered for the writer thread.
+    /// Notifications are dropped while the buffer is full.
+    #[serde(default = "default_channel_capacity")]
+    pub channel_capacity: usize,
+
+    #[serde(default = "default_true")]
+    pub account_data_notifications: bool,
+
+    #[serde(default)]
+    pub transaction_notifications: bool,
+
+    #[serde(default)]
+    pub entry_notifications: bool,
+
+    #[serde(default)]
+    pub account_filter: Option<AccountFilterConfig>,
+}
+
+fn parse_pubkeys(pubkeys: &[String]) -> Result<HashSet<Pubkey>, String> {
+    pubkeys
+        .iter()
+        .map(|pubkey| {
+            Pubkey::from_str(pubkey).map_err(|err| format!("Invalid pubkey {}: {}", pubkey, err))
+        })
+        .collect()
+}
+
+fn default_max_file_size() -> u64 {
+    DEFAULT_MAX_FILE_SIZE
+}
+
+fn default_channel_capacity() -> usize {
+    DEFAULT_CHANNEL_CAPACITY
+}
+
+#[cfg(unix)]
+fn default_max_client_buffer_size() -> usize {
+    DEFAULT_MAX_CLIENT_BUFFER_SIZE
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config: GeyserPluginStreamConfig = json5::from_str(
+            r#"{
+                libpath: "libsolana_geyser_plugin_stream.so",
+                output: { files: { directory: "/tmp/stream", max_files: 4 } },
+                transaction_notifications: true,
+                account_filter: { exclude_pubkeys: ["SysvarC1ock11111111111111111111111111111111"] },
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.output,
+            StreamOutput::Files {
+                directory: PathBuf::from("/tmp/stream"),
+                max_file_size: DEFAULT_MAX_FILE_SIZE,
+                max_files: Some(4),
+            }
+        );
+        assert_eq(config.channel_capacity, DEFAULT_CHANNEL_CAPACITY);
+        assert!(config.account_data_notifications);
+        assert!(config.transaction_notifications);
+        assert!(!config.entry_notifications);
+        let account_filter = config.account_filter.unwrap().to_account_filter().unwrap();
+        assert!(account_filter
+            .exclude_pubkeys
+            .contains(&solana_sdk::sysvar::clock::id()));
+
+        let config: GeyserPluginStreamConfig =
+            json5::from_str(r#"{ output: { files: { directory: "stream" } } }"#).unwrap();
+        assert_eq!(
+            config.output,
+            StreamOutput::Files {
+                directory: PathBuf::from("stream"),
+                max_file_size: DEFAULT_MAX_FILE_SIZE,
+                max_files: None,
+            }
+        );
+
+        // Unix sockets are not available on other platforms
+        #[cfg(not(unix))]
+        assert!(json5::from_str::<GeyserPluginStreamConfig>(
+            r#"{ output: { unix_socket: { path: "/tmp/geyser.sock" } } }"#,
+        )
+        .is_err());
+
+        let account_filter = AccountFilterConfig {
+            include_owners: vec!["not a pubkey".to_string()],
+            ..AccountFilterConfig::default()
+        };
+        assert!(account_filter.to_account_filter().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_unix_socket_config() {
+        let config: GeyserPluginStreamConfig =
+            json5::from_str(r#"{ output: { unix_socket: { path: "/tmp/geyser.sock" } } }"#)
+                .unwrap();
+        assert_eq!(
+            config.output,
+            StreamOutput::UnixSocket {
+                path: PathBuf::from("/tmp/geyser.sock"),
+                max_client_buffer_size: DEFAULT_MAX_CLIENT_BUFFER_SIZE,
+            }
+        );
+    }
+}
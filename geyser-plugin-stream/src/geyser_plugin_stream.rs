@@ -0,0 +1,247 @@
+/// The Geyser plugin streaming notifications to local files or a Unix socket
+use {
+    crate::{
+        config::{GeyserPluginStreamConfig, StreamOutput},
+        generated::{
+            notification, AccountUpdate, BlockMetadata, EndOfStartup, EntryUpdate, Notification,
+            SlotStatusUpdate, TransactionUpdate,
+        },
+        writer::{NotificationSink, RotatingFileSink, StreamWriter},
+    },
+    log::*,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        AccountFilter, GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions,
+        ReplicaBlockInfoVersions, ReplicaEntryInfoVersions, ReplicaTransactionInfoVersions, Result,
+        SlotStatus,
+    },
+    solana_storage_proto::convert::generated::{ConfirmedTransaction, Reward},
+    solana_transaction_status::VersionedTransactionWithStatusMeta,
+    std::fs,
+};
+
+#[derive(Default)]
+pub struct GeyserPluginStream {
+    config: Option<GeyserPluginStreamConfig>,
+    account_filter: Option<AccountFilter>,
+    writer: Option<StreamWriter>,
+}
+
+impl std::fmt::Debug for GeyserPluginStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GeyserPluginStream")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl GeyserPluginStream {
+    fn write(&self, notification: notification::Notification) -> Result<()> {
+        let writer = self.writer.as_ref().ok_or_else(|| {
+            GeyserPluginError::Custom("The stream plugin is not loaded".to_string().into())
+        })?;
+        writer
+            .write(&Notification {
+                notification: Some(notification),
+            })
+            .map_err(|err| GeyserPluginError::Custom(Box::new(err)))
+    }
+}
+
+impl GeyserPlugin for GeyserPluginStream {
+    fn name(&self) -> &'static str {
+        "GeyserPluginStream"
+    }
+
+    fn on_load(&mut self, config_file: &str) -> Result<()> {
+        solana_logger::setup_with_default("info");
+        let contents = fs::read_to_string(config_file)?;
+        let config: GeyserPluginStreamConfig =
+            json5::from_str(&contents).map_err(|err| GeyserPluginError::ConfigFileReadError {
+                msg: format!("The config file is not in the expected format: {}", err),
+            })?;
+        self.account_filter = config
+            .account_filter
+            .as_ref()
+            .map(|account_filter| account_filter.to_account_filter())
+            .transpose()
+            .map_err(|msg| GeyserPluginError::ConfigFileReadError { msg })?;
+
+        let sink: Box<dyn NotificationSink> = match &config.output {
+            StreamOutput::Files {
+                directory,
+                max_file_size,
+                max_files,
+            } => Box::new(
+                RotatingFileSink::new(directory, *max_file_size, *max_files)
+                    .map_err(|err| GeyserPluginError::Custom(Box::new(err)))?,
+            ),
+            #[cfg(unix)]
+            StreamOutput::UnixSocket {
+                path,
+                max_client_buffer_size,
+            } => Box::new(
+                crate::writer::UnixSocketSink::new(path, *max_client_buffer_size)
+                    .map_err(|err| GeyserPluginError::Custom(Box::new(err)))?,
+            ),
+        };
+        let writer = StreamWriter::new(sink, config.channel_capacity)
+            .map_err(|err| GeyserPluginError::Custom(Box::new(err)))?;
+        self.writer = Some(writer);
+        self.config = Some(config);
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        if let Some(mut writer) = self.writer.take() {
+            info!("Closing the Geyser stream");
+            writer.close();
+        }
+    }
+
+    fn update_account(
+        &mut self,
+        account: ReplicaAccountInfoVersions,
+        slot: u64,
+        is_startup: bool,
+    ) -> Result<()> {
+        let account_update = match account {
+            ReplicaAccountInfoVersions::V0_0_1(account) => AccountUpdate {
+                slot,
+                pubkey: account.pubkey.to_vec(),
+                lamports: account.lamports,
+                owner: account.owner.to_vec(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data.to_vec(),
+                write_version: account.write_version,
+                txn_signature: None,
+                is_startup,
+            },
+            ReplicaAccountInfoVersions::V0_0_2(account) => AccountUpdate {
+                slot,
+                pubkey: account.pubkey.to_vec(),
+                lamports: account.lamports,
+                owner: account.owner.to_vec(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data.to_vec(),
+                write_version: account.write_version,
+                txn_signature: account
+                    .txn_signature
+                    .map(|signature| signature.as_ref().to_vec()),
+                is_startup,
+            },
+        };
+        self.write(notification::Notification::Account(account_update))
+    }
+
+    fn notify_end_of_startup(&mut self) -> Result<()> {
+        self.write(notification::Notification::EndOfStartup(EndOfStartup {}))
+    }
+
+    fn update_slot_status(
+        &mut self,
+        slot: u64,
+        parent: Option<u64>,
+        status: SlotStatus,
+    ) -> Result<()> {
+        let status = match status {
+            SlotStatus::Processed => crate::generated::SlotStatus::Processed,
+            SlotStatus::Rooted => crate::generated::SlotStatus::Rooted,
+            SlotStatus::Confirmed => crate::generated::SlotStatus::Confirmed,
+        };
+        self.write(notification::Notification::SlotStatus(SlotStatusUpdate {
+            slot,
+            parent,
+            status: status.into(),
+        }))
+    }
+
+    fn notify_transaction(
+        &mut self,
+        transaction: ReplicaTransactionInfoVersions,
+        slot: u64,
+    ) -> Result<()> {
+        let (signature, is_vote, transaction, transaction_status_meta, index) = match transaction {
+            ReplicaTransactionInfoVersions::V0_0_1(info) => (
+                info.signature,
+                info.is_vote,
+                info.transaction,
+                info.transaction_status_meta,
+                None,
+            ),
+            ReplicaTransactionInfoVersions::V0_0_2(info) => (
+                info.signature,
+                info.is_vote,
+                info.transaction,
+                info.transaction_status_meta,
+                Some(info.index as u64),
+            ),
+        };
+        let confirmed_transaction =
+            ConfirmedTransaction::from(VersionedTransactionWithStatusMeta {
+                transaction: transaction.to_versioned_transaction(),
+                meta: transaction_status_meta.clone(),
+            });
+        self.write(notification::Notification::Transaction(TransactionUpdate {
+            slot,
+            signature: signature.as_ref().to_vec(),
+            is_vote,
+            index,
+            transaction: Some(confirmed_transaction),
+        }))
+    }
+
+    fn notify_block_metadata(&mut self, blockinfo: ReplicaBlockInfoVersions) -> Result<()> {
+        let ReplicaBlockInfoVersions::V0_0_1(blockinfo) = blockinfo;
+        self.write(notification::Notification::BlockMetadata(BlockMetadata {
+            slot: blockinfo.slot,
+            blockhash: blockinfo.blockhash.to_string(),
+            rewards: blockinfo
+                .rewards
+                .iter()
+                .cloned()
+                .map(Reward::from)
+                .collect(),
+            block_time: blockinfo.block_time,
+            block_height: blockinfo.block_height,
+        }))
+    }
+
+    fn notify_entry(&mut self, entry: ReplicaEntryInfoVersions) -> Result<()> {
+        let ReplicaEntryInfoVersions::V0_0_1(entry) = entry;
+        self.write(notification::Notification::Entry(EntryUpdate {
+            slot: entry.slot,
+            index: entry.index as u64,
+            num_hashes: entry.num_hashes,
+            hash: entry.hash.to_vec(),
+            executed_transaction_count: entry.executed_transaction_count,
+            starting_transaction_index: entry.starting_transaction_index as u64,
+        }))
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        self.config
+            .as_ref()
+            .map(|config| config.account_data_notifications)
+            .unwrap_or_default()
+    }
+
+    fn account_filter(&self) -> Option<AccountFilter> {
+        self.account_filter.clone()
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        self.config
+            .as_ref()
+            .map(|config| config.transaction_notifications)
+            .unwrap_or_default()
+    }
+
+    fn entry_notifications_enabled(&self) -> bool {
+        self.config
+            .as_ref()
+            .map(|config| config.entry_notifications)
+            .unwrap_or_default()
+    }
+}
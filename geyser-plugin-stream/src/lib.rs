@@ -0,0 +1,50 @@
+//! A Geyser plugin streaming the notifications it receives to rotating local
+//! files or to the clients of a Unix domain socket, together with the reader
+//! library to replay such a stream.
+//!
+//! A stream is a sequence of records, each of which is a protobuf encoded
+//! [`generated::Notification`] preceded by its length in bytes, encoded as a
+//! protobuf varint. The schema is defined in `proto/geyser_stream.proto`; it
+//! reuses the transaction and reward messages of `solana-storage-proto`.
+
+use {
+    crate::geyser_plugin_stream::GeyserPluginStream,
+    solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin, std::io,
+    thiserror::Error,
+};
+
+pub mod config;
+pub mod geyser_plugin_stream;
+pub mod reader;
+pub mod writer;
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+pub mod generated {
+    include!(concat!(env!("OUT_DIR"), "/solana.geyser.stream.rs"));
+}
+
+#[derive(Error, Debug)]
+pub enum StreamError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Failed to decode notification: {0}")]
+    Decode(#[from] prost::DecodeError),
+
+    #[error("Record of {0} bytes is longer than the maximum of {max}", max = reader::MAX_RECORD_LEN)]
+    RecordTooLong(u64),
+
+    #[error("The stream writer is closed")]
+    WriterClosed,
+}
+
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+/// # Safety
+///
+/// This function returns the GeyserPluginStream pointer as trait GeyserPlugin.
+pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
+    let plugin = GeyserPluginStream::default();
+    let plugin: Box<dyn GeyserPlugin> = Box::new(plugin);
+    Box::into_raw(plugin)
+}
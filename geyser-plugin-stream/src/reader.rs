@@ -0,0 +1,223 @@
+/// Reads back the notifications streamed by the plugin, from its files or
+/// from its Unix domain socket
+use {
+    crate::{generated::Notification, writer::stream_files, StreamError},
+    prost::Message,
+    std::{
+        collections::VecDeque,
+        fs::File,
+        io::{self, BufReader, Read},
+        path::{Path, PathBuf},
+    },
+};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+/// The longest encoding of a u64 varint
+const MAX_VARINT_LEN: usize = 10;
+
+/// The longest record the reader accepts. Notifications carry at most one
+/// account, whose data is limited to 10 MiB, or one transaction or block, so
+/// a longer length prefix means the stream is corrupt.
+pub const MAX_RECORD_LEN: usize = 64 * 1024 * 1024;
+
+/// Reads length-prefixed notifications from a byte stream
+pub struct StreamReader<R> {
+    reader: R,
+}
+
+impl<R: Read> StreamReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Read the next notification, or None at the end of the stream. A
+    /// stream ending in the middle of a record, or a record longer than
+    /// `MAX_RECORD_LEN`, is an error.
+    pub fn read_notification(&mut self) -> Result<Option<Notification>, StreamError> {
+        let record_len = match self.read_record_len()? {
+            Some(record_len) => record_len,
+            None => return Ok(None),
+        };
+        if record_len > MAX_RECORD_LEN as u64 {
+            return Err(StreamError::RecordTooLong(record_len));
+        }
+        let mut record = vec![0; record_len as usize];
+        self.reader.read_exact(&mut record)?;
+        Ok(Some(Notification::decode(record.as_slice())?))
+    }
+
+    fn read_record_len(&mut self) -> Result<Option<u64>, StreamError> {
+        let mut record_len = 0u64;
+        for i in 0..MAX_VARINT_LEN {
+            let mut byte = [0u8];
+            if let Err(err) = self.reader.read_exact(&mut byte) {
+                return if i == 0 && err.kind() == io::ErrorKind::UnexpectedEof {
+                    Ok(None)
+                } else {
+                    Err(err.into())
+                };
+            }
+            record_len |= u64::from(byte[0] & 0x7f) << (7 * i);
+            if byte[0] & 0x80 == 0 {
+                return Ok(Some(record_len));
+            }
+        }
+        Err(prost::DecodeError::new("invalid record length").into())
+    }
+}
+
+impl<R: Read> Iterator for StreamReader<R> {
+    type Item = Result<Notification, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_notification().transpose()
+    }
+}
+
+/// Connect to the Unix domain socket of a streaming plugin. The reader
+/// receives the notifications written after it connected.
+#[cfg(unix)]
+pub fn connect(path: &Path) -> io::Result<StreamReader<BufReader<UnixStream>>> {
+    let stream = UnixStream::connect(path)?;
+    Ok(StreamReader::new(BufReader::new(stream)))
+}
+
+/// Reads the notifications of all the stream files in a directory, oldest first
+pub struct FileStreamReader {
+    paths: VecDeque<PathBuf>,
+    reader: Option<StreamReader<BufReader<File>>>,
+}
+
+impl FileStreamReader {
+    /// Open the stream files currently in `directory`
+    pub fn open(directory: &Path) -> io::Result<Self> {
+        Ok(Self {
+            paths: stream_files(directory)?.into(),
+            reader: None,
+        })
+    }
+
+    pub fn read_notification(&mut self) -> Result<Option<Notification>, StreamError> {
+        loop {
+            if let Some(reader) = self.reader.as_mut() {
+                if let Some(notification) = reader.read_notification()? {
+                    return Ok(Some(notification));
+                }
+            }
+            match self.paths.pop_front() {
+                Some(path) => {
+                    self.reader = Some(StreamReader::new(BufReader::new(File::open(path)?)));
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+impl Iterator for FileStreamReader {
+    type Item = Result<Notification, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_notification().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            generated::{notification, SlotStatusUpdate},
+            writer::{RotatingFileSink, StreamWriter},
+        },
+        tempfile::TempDir,
+    };
+
+    fn slot_status_notification(slot: u64) -> Notification {
+        Notification {
+            notification: Some(notification::Notification::SlotStatus(SlotStatusUpdate {
+                slot,
+                parent: slot.checked_sub(1),
+                status: 0,
+            })),
+        }
+    }
+
+    #[test]
+    fn test_file_stream() {
+        let directory = TempDir::new().unwrap();
+        let notifications: Vec<_> = (0..100).map(slot_status_notification).collect();
+        let sink = RotatingFileSink::new(directory.path(), 64, None).unwrap();
+        let mut writer = StreamWriter::new(Box::new(sink), notifications.len()).unwrap();
+        for notification in &notifications {
+            writer.write(notification).unwrap();
+        }
+        writer.close();
+        assert_eq!(writer.dropped_records(), 0);
+        assert!(writer.write(&notifications[0]).is_err());
+        assert!(stream_files(directory.path()).unwrap().len() > 1);
+
+        let read_notifications = FileStreamReader::open(directory.path())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(read_notifications, notifications);
+    }
+
+    #[test]
+    fn test_truncated_stream() {
+        let record = slot_status_notification(u64::MAX).encode_length_delimited_to_vec();
+        let mut reader = StreamReader::new(&record[..record.len() - 1]);
+        assert!(reader.read_notification().is_err());
+
+        let mut reader = StreamReader::new(&record[..0]);
+        assert!(reader.read_notification().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_record_too_long() {
+        let mut stream = vec![];
+        prost::encoding::encode_varint(MAX_RECORD_LEN as u64 + 1, &mut stream);
+        let mut reader = StreamReader::new(stream.as_slice());
+        assert!(matches!(
+            reader.read_notification(),
+            Err(StreamError::RecordTooLong(len)) if len == MAX_RECORD_LEN as u64 + 1
+        ));
+
+        let mut stream = vec![];
+        prost::encoding::encode_varint(u64::MAX, &mut stream);
+        let mut reader = StreamReader::new(stream.as_slice());
+        assert!(matches!(
+            reader.read_notification(),
+            Err(StreamError::RecordTooLong(u64::MAX))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_stream() {
+        use crate::{generated::EndOfStartup, writer::UnixSocketSink};
+
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("geyser.sock");
+        let sink = UnixSocketSink::new(&path, 1024).unwrap();
+        let mut writer = StreamWriter::new(Box::new(sink), 16).unwrap();
+        let mut reader = connect(&path).unwrap();
+
+        // The client is accepted before the next record is written
+        let notification = Notification {
+            notification: Some(notification::Notification::EndOfStartup(EndOfStartup {})),
+        };
+        writer.write(&notification).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap(), notification);
+        writer.write(&slot_status_notification(1)).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap(), slot_status_notification(1));
+
+        // Closing the writer disconnects the clients
+        writer.close();
+        assert!(reader.next().is_none());
+        assert!(!path.exists());
+    }
+}
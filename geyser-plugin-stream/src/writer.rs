@@ -0,0 +1,513 @@
+/// Writes the encoded notifications to their destination, off the threads
+/// notifying the plugin
+use {
+    crate::{generated::Notification, StreamError},
+    crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError},
+    log::*,
+    prost::Message,
+    std::{
+        fs::{self, File},
+        io::{self, BufWriter, Write},
+        path::{Path, PathBuf},
+        sync::atomic::{AtomicU64, Ordering},
+        thread::{Builder, JoinHandle},
+        time::Duration,
+    },
+};
+
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+
+const STREAM_FILE_PREFIX: &str = "geyser-stream-";
+const STREAM_FILE_EXTENSION: &str = ".pb";
+
+/// How often the writer thread flushes the sink while no records come in
+const IDLE_FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Log the number of dropped records every time this many more were dropped
+const DROPPED_RECORDS_LOG_INTERVAL: u64 = 10_000;
+
+/// A destination of length-prefixed records
+pub trait NotificationSink: Send {
+    fn write_record(&mut self, record: &[u8]) -> io::Result<()>;
+
+    fn flush(&mut self) -> io::Result<()>;
+}
+
+/// The stream files in `directory`, in the order they were written
+pub fn stream_files(directory: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut stream_files = vec![];
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if let Some(sequence) = stream_file_sequence(&path) {
+            stream_files.push((sequence, path));
+        }
+    }
+    stream_files.sort_unstable();
+    Ok(stream_files.into_iter().map(|(_, path)| path).collect())
+}
+
+fn stream_file_sequence(path: &Path) -> Option<u64> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix(STREAM_FILE_PREFIX)?
+        .strip_suffix(STREAM_FILE_EXTENSION)?
+        .parse()
+        .ok()
+}
+
+fn stream_file_path(directory: &Path, sequence: u64) -> PathBuf {
+    directory.join(format!(
+        "{}{:020}{}",
+        STREAM_FILE_PREFIX, sequence, STREAM_FILE_EXTENSION
+    ))
+}
+
+/// Appends the records to numbered files in a directory, starting a new file
+/// once the current one would grow over the size limit. A record is never
+/// split across files. Files left over from an earlier run are kept, the new
+/// files are numbered after them.
+pub struct RotatingFileSink {
+    directory: PathBuf,
+    max_file_size: u64,
+    max_files: Option<usize>,
+    sequence: u64,
+    file: BufWriter<File>,
+    file_size: u64,
+}
+
+impl RotatingFileSink {
+    pub fn new(directory: &Path, max_file_size: u64, max_files: Option<usize>) -> io::Result<Self> {
+        fs::create_dir_all(directory)?;
+        let sequence = stream_files(directory)?
+            .last()
+            .and_then(|path| stream_file_sequence(path))
+            .map(|sequence| sequence + 1)
+            .unwrap_or_default();
+        let file = Self::create_file(directory, sequence)?;
+        let mut sink = Self {
+            directory: directory.to_path_buf(),
+            max_file_size,
+            max_files,
+            sequence,
+            file,
+            file_size: 0,
+        };
+        sink.remove_old_files()?;
+        Ok(sink)
+    }
+
+    fn create_file(directory: &Path, sequence: u64) -> io::Result<BufWriter<File>> {
+        let path = stream_file_path(directory, sequence);
+        info!("Streaming Geyser notifications to {:?}", path);
+        Ok(BufWriter::new(File::create(path)?))
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.sequence += 1;
+        self.file = Self::create_file(&self.directory, self.sequence)?;
+        self.file_size = 0;
+        self.remove_old_files()
+    }
+
+    fn remove_old_files(&self) -> io::Result<()> {
+        if let Some(max_files) = self.max_files {
+            let stream_files = stream_files(&self.directory)?;
+            let num_old_files = stream_files.len().saturating_sub(max_files.max(1));
+            for path in &stream_files[..num_old_files] {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl NotificationSink for RotatingFileSink {
+    fn write_record(&mut self, record: &[u8]) -> io::Result<()> {
+        let record_size = record.len() as u64;
+        if self.file_size > 0 && self.file_size + record_size > self.max_file_size {
+            self.rotate()?;
+        }
+        self.file.write_all(record)?;
+        self.file_size += record_size;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// A client of the `UnixSocketSink`, with the records it has yet to be sent
+#[cfg(unix)]
+struct SocketClient {
+    stream: UnixStream,
+    buffer: Vec<u8>,
+    written: usize,
+}
+
+#[cfg(unix)]
+impl SocketClient {
+    fn buffered(&self) -> usize {
+        self.buffer.len() - self.written
+    }
+
+    /// Send as much of the buffer as the socket takes without blocking
+    fn send_buffered(&mut self) -> io::Result<()> {
+        while self.written < self.buffer.len() {
+            match self.stream.write(&self.buffer[self.written..]) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(size) => self.written += size,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        if self.written == self.buffer.len() {
+            self.buffer.clear();
+            self.written = 0;
+        } else if self.written >= self.buffer.len() / 2 {
+            self.buffer.drain(..self.written);
+            self.written = 0;
+        }
+        Ok(())
+    }
+}
+
+/// Listens on a Unix domain socket and writes the records to all of its
+/// clients. Clients are accepted before each record is written, and written
+/// to without blocking. The records a client does not take right away are
+/// buffered for it; a client is dropped once writing to it fails, or once more
+/// than `max_client_buffer_size` bytes are buffered for it, so a slow client
+/// never holds up the stream for the others.
+#[cfg(unix)]
+pub struct UnixSocketSink {
+    path: PathBuf,
+    listener: UnixListener,
+    clients: Vec<SocketClient>,
+    max_client_buffer_size: usize,
+}
+
+#[cfg(unix)]
+impl UnixSocketSink {
+    pub fn new(path: &Path, max_client_buffer_size: usize) -> io::Result<Self> {
+        // Remove the socket file left behind by an earlier run
+        match fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+        let listener = UnixListener::bind(path)?;
+        listener.set_nonblocking(true)?;
+        info!(
+            "Streaming Geyser notifications to the clients of {:?}",
+            path
+        );
+        Ok(Self {
+            path: path.to_path_buf(),
+            listener,
+            clients: vec![],
+            max_client_buffer_size,
+        })
+    }
+
+    fn accept_clients(&mut self) -> io::Result<()> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    stream.set_nonblocking(true)?;
+                    self.clients.push(SocketClient {
+                        stream,
+                        buffer: vec![],
+                        written: 0,
+                    });
+                }
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl NotificationSink for UnixSocketSink {
+    fn write_record(&mut self, record: &[u8]) -> io::Result<()> {
+        self.accept_clients()?;
+        let max_client_buffer_size = self.max_client_buffer_size;
+        self.clients.retain_mut(|client| {
+            let buffered = client.buffered();
+            if buffered > 0 && buffered + record.len() > max_client_buffer_size {
+                warn!(
+                    "Dropping Geyser stream client that is {} bytes behind",
+                    buffered
+                );
+                return false;
+            }
+            client.buffer.extend_from_slice(record);
+            match client.send_buffered() {
+                Ok(()) => true,
+                Err(err) => {
+                    info!("Dropping Geyser stream client: {}", err);
+                    false
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.clients
+            .retain_mut(|client| match client.send_buffered() {
+                Ok(()) => true,
+                Err(err) => {
+                    info!("Dropping Geyser stream client: {}", err);
+                    false
+                }
+            });
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+impl Drop for UnixSocketSink {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Encodes notifications on the calling thread and hands the records over to
+/// a thread writing them to the sink. Notifying never blocks on the sink: the
+/// records that do not fit in the channel to the writer thread are dropped
+/// and counted.
+pub struct StreamWriter {
+    sender: Option<Sender<Vec<u8>>>,
+    thread_hdl: Option<JoinHandle<()>>,
+    dropped_records: AtomicU64,
+}
+
+impl StreamWriter {
+    pub fn new(sink: Box<dyn NotificationSink>, channel_capacity: usize) -> io::Result<Self> {
+        let (sender, receiver) = bounded(channel_capacity);
+        let thread_hdl = Builder::new()
+            .name("solGeyserStream".to_string())
+            .spawn(move || Self::write_records(sink, receiver))?;
+        Ok(Self {
+            sender: Some(sender),
+            thread_hdl: Some(thread_hdl),
+            dropped_records: AtomicU64::default(),
+        })
+    }
+
+    fn write_records(mut sink: Box<dyn NotificationSink>, receiver: Receiver<Vec<u8>>) {
+        loop {
+            match receiver.recv_timeout(IDLE_FLUSH_INTERVAL) {
+                Ok(record) => {
+                    if let Err(err) = sink.write_record(&record) {
+                        error!("Failed to write Geyser notification: {}", err);
+                    }
+                    // Make the records visible to readers as soon as the writer caught up
+                    if !receiver.is_empty() {
+                        continue;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            if let Err(err) = sink.flush() {
+                error!("Failed to flush Geyser notifications: {}", err);
+            }
+        }
+        if let Err(err) = sink.flush() {
+            error!("Failed to flush Geyser notifications: {}", err);
+        }
+    }
+
+    /// Queue a notification for the writer thread, or drop it if the writer
+    /// thread is too far behind
+    pub fn write(&self, notification: &Notification) -> Result<(), StreamError> {
+        let record = notification.encode_length_delimited_to_vec();
+        match self
+            .sender
+            .as_ref()
+            .ok_or(StreamError::WriterClosed)?
+            .try_send(record)
+        {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => {
+                let dropped_records = self.dropped_records.fetch_add(1, Ordering::Relaxed) + 1;
+                if dropped_records == 1 || dropped_records % DROPPED_RECORDS_LOG_INTERVAL == 0 {
+                    warn!(
+                        "The Geyser stream writer is falling behind, dropped {} notifications",
+                        dropped_records
+                    );
+                }
+                Ok(())
+            }
+            Err(TrySendError::Disconnected(_)) => Err(StreamError::WriterClosed),
+        }
+    }
+
+    /// The number of notifications dropped because the writer thread was behind
+    pub fn dropped_records(&self) -> u64 {
+        self.dropped_records.load(Ordering::Relaxed)
+    }
+
+    /// Write out the buffered notifications and stop the writer thread
+    pub fn close(&mut self) {
+        drop(self.sender.take());
+        if let Some(thread_hdl) = self.thread_hdl.take() {
+            if thread_hdl.join().is_err() {
+                error!("The Geyser stream writer thread panicked");
+            }
+            let dropped_records = self.dropped_records();
+            if dropped_records > 0 {
+                warn!(
+                    "The Geyser stream writer dropped {} notifications",
+                    dropped_records
+                );
+            }
+        }
+    }
+}
+
+impl Drop for StreamWriter {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::generated::{notification, SlotStatusUpdate},
+        crossbeam_channel::unbounded,
+        std::sync::{Arc, Mutex},
+        tempfile::TempDir,
+    };
+
+    fn slot_status_notification(slot: u64) -> Notification {
+        Notification {
+            notification: Some(notification::Notification::SlotStatus(SlotStatusUpdate {
+                slot,
+                parent: None,
+                status: 0,
+            })),
+        }
+    }
+
+    /// A sink blocking in `write_record` until it is allowed to go on
+    struct BlockingSink {
+        started: Sender<()>,
+        unblock: Receiver<()>,
+        records: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    impl NotificationSink for BlockingSink {
+        fn write_record(&mut self, record: &[u8]) -> io::Result<()> {
+            let _ = self.started.send(());
+            let _ = self.unblock.recv();
+            self.records.lock().unwrap().push(record.to_vec());
+            Ok(())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_stream_writer_drops_records_when_full() {
+        let (started_sender, started_receiver) = unbounded();
+        let (unblock_sender, unblock_receiver) = unbounded();
+        let records = Arc::<Mutex<Vec<Vec<u8>>>>::default();
+        let sink = BlockingSink {
+            started: started_sender,
+            unblock: unblock_receiver,
+            records: records.clone(),
+        };
+        let mut writer = StreamWriter::new(Box::new(sink), 1).unwrap();
+
+        // The writer thread is stuck writing the first record
+        writer.write(&slot_status_notification(0)).unwrap();
+        started_receiver.recv().unwrap();
+        // The second record fills the channel, the third one is dropped
+        writer.write(&slot_status_notification(1)).unwrap();
+        writer.write(&slot_status_notification(2)).unwrap();
+        assert_eq!(writer.dropped_records(), 1);
+
+        drop(unblock_sender);
+        writer.close();
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                slot_status_notification(0).encode_length_delimited_to_vec(),
+                slot_status_notification(1).encode_length_delimited_to_vec(),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_sink_drops_slow_clients() {
+        use std::io::Read;
+
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("geyser.sock");
+        let mut sink = UnixSocketSink::new(&path, 1024 * 1024).unwrap();
+
+        // A client that never reads is dropped once it falls too far behind,
+        // without blocking the sink
+        let _stalled_client = UnixStream::connect(&path).unwrap();
+        let record = vec![1; 64 * 1024];
+        sink.write_record(&record).unwrap();
+        assert_eq!(sink.clients.len(), 1);
+        for _ in 0..100 {
+            sink.write_record(&record).unwrap();
+        }
+        assert!(sink.clients.is_empty());
+
+        // Clients that keep up still get every record
+        let mut client = UnixStream::connect(&path).unwrap();
+        sink.write_record(&[2; 16]).unwrap();
+        sink.flush().unwrap();
+        let mut received = [0; 16];
+        client.read_exact(&mut received).unwrap();
+        assert_eq!(received, [2; 16]);
+        assert_eq!(sink.clients.len(), 1);
+    }
+
+    #[test]
+    fn test_rotating_file_sink() {
+        let directory = TempDir::new().unwrap();
+        let mut sink = RotatingFileSink::new(directory.path(), 10, Some(2)).unwrap();
+        sink.write_record(&[0; 6]).unwrap();
+        sink.write_record(&[1; 4]).unwrap();
+        // Does not fit in the first file
+        sink.write_record(&[2; 6]).unwrap();
+        // Larger than a file, but still written whole
+        sink.write_record(&[3; 12]).unwrap();
+        sink.flush().unwrap();
+
+        // The first file was removed to keep two
+        let files = stream_files(directory.path()).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                stream_file_path(directory.path(), 1),
+                stream_file_path(directory.path(), 2)
+            ]
+        );
+        assert_eq!(fs::read(&files[0]).unwrap(), vec![2; 6]);
+        assert_eq!(fs::read(&files[1]).unwrap(), vec![3; 12]);
+        drop(sink);
+
+        // A new sink carries on numbering after the existing files
+        let sink = RotatingFileSink::new(directory.path(), 10, None).unwrap();
+        drop(sink);
+        assert_eq!(
+            stream_files(directory.path()).unwrap().last().unwrap(),
+            &stream_file_path(directory.path(), 3)
+        );
+    }
+}
@@ -8,7 +8,7 @@ use {
     serde_json::json,
     solana_clap_utils::{
         input_parsers::pubkey_of,
-        input_validators::{is_slot, is_valid_pubkey},
+        input_validators::{is_parsable, is_slot, is_valid_pubkey},
     },
     solana_cli_output::{
         display::println_transaction, CliBlock, CliTransaction, CliTransactionConfirmation,
@@ -28,7 +28,7 @@ use {
     std::{
         cmp::min,
         collections::HashSet,
-        path::Path,
+        path::{Path, PathBuf},
         process::exit,
         result::Result,
         str::FromStr,
@@ -100,6 +100,22 @@ async fn first_available_block(
     Ok(())
 }
 
+async fn train_dictionary(
+    starting_slot: Slot,
+    limit: usize,
+    max_dictionary_size: usize,
+    config: solana_storage_bigtable::LedgerStorageConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
+        .await
+        .map_err(|err| format!("Failed to connect to storage: {:?}", err))?;
+    let id = bigtable
+        .train_compression_dictionary(starting_slot, limit, max_dictionary_size)
+        .await?;
+    println!("Stored compression dictionary {}", id);
+    Ok(())
+}
+
 async fn block(
     slot: Slot,
     output_format: OutputFormat,
@@ -365,6 +381,15 @@ impl BigTableSubCommand for App<'_, '_> {
                         .default_value(solana_storage_bigtable::DEFAULT_APP_PROFILE_ID)
                         .help("Bigtable application profile id to use in requests")
                 )
+                .arg(
+                    Arg::with_name("local_storage")
+                        .global(true)
+                        .long("local-storage")
+                        .takes_value(true)
+                        .value_name("PATH")
+                        .help("Use the local RocksDB ledger storage at PATH \
+                               instead of a Bigtable instance")
+                )
                 .subcommand(
                     SubCommand::with_name("upload")
                         .about("Upload the ledger to BigTable")
@@ -425,6 +450,43 @@ impl BigTableSubCommand for App<'_, '_> {
                     SubCommand::with_name("first-available-block")
                         .about("Get the first available block in the storage"),
                 )
+                .subcommand(
+                    SubCommand::with_name("train-dictionary")
+                        .about("Train a compression dictionary on a sample of the stored \
+                                blocks. Running uploaders start compressing new cells with it \
+                                within 10 minutes")
+                        .arg(
+                            Arg::with_name("starting_slot")
+                                .long("starting-slot")
+                                .validator(is_slot)
+                                .value_name("SLOT")
+                                .takes_value(true)
+                                .index(1)
+                                .required(true)
+                                .default_value("0")
+                                .help("Sample the blocks starting at this slot"),
+                        )
+                        .arg(
+                            Arg::with_name("limit")
+                                .long("limit")
+                                .validator(is_slot)
+                                .value_name("LIMIT")
+                                .takes_value(true)
+                                .index(2)
+                                .required(true)
+                                .default_value("10000")
+                                .help("Maximum number of blocks to sample"),
+                        )
+                        .arg(
+                            Arg::with_name("max_dictionary_size")
+                                .long("max-dictionary-size")
+                                .validator(is_parsable::<usize>)
+                                .value_name("BYTES")
+                                .takes_value(true)
+                                .default_value("112640")
+                                .help("Maximum size of the dictionary"),
+                        ),
+                )
                 .subcommand(
                     SubCommand::with_name("blocks")
                         .about("Get a list of slots with confirmed blocks for the given range")
@@ -640,6 +702,10 @@ pub fn bigtable_process_command(
         "rpc_bigtable_app_profile_id",
         solana_storage_bigtable::DEFAULT_APP_PROFILE_ID,
     );
+    let local_storage_path = matches
+        .value_of("local_storage")
+        .or_else(|| sub_matches.and_then(|sub_matches| sub_matches.value_of("local_storage")))
+        .map(PathBuf::from);
 
     let future = match (subcommand, sub_matches) {
         ("upload", Some(arg_matches)) => {
@@ -657,6 +723,7 @@ pub fn bigtable_process_command(
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(upload(
@@ -673,6 +740,7 @@ pub fn bigtable_process_command(
                 read_only: !arg_matches.is_present("force"),
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(delete_slots(slots, config))
@@ -682,16 +750,36 @@ pub fn bigtable_process_command(
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(first_available_block(config))
         }
+        ("train-dictionary", Some(arg_matches)) => {
+            let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
+            let limit = value_t_or_exit!(arg_matches, "limit", usize);
+            let max_dictionary_size = value_t_or_exit!(arg_matches, "max_dictionary_size", usize);
+            let config = solana_storage_bigtable::LedgerStorageConfig {
+                read_only: false,
+                instance_name,
+                app_profile_id,
+                local_storage_path,
+                ..solana_storage_bigtable::LedgerStorageConfig::default()
+            };
+            runtime.block_on(train_dictionary(
+                starting_slot,
+                limit,
+                max_dictionary_size,
+                config,
+            ))
+        }
         ("block", Some(arg_matches)) => {
             let slot = value_t_or_exit!(arg_matches, "slot", Slot);
             let config = solana_storage_bigtable::LedgerStorageConfig {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(block(slot, output_format, config))
@@ -703,6 +791,7 @@ pub fn bigtable_process_command(
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -715,6 +804,7 @@ pub fn bigtable_process_command(
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -748,6 +838,7 @@ pub fn bigtable_process_command(
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -768,6 +859,7 @@ pub fn bigtable_process_command(
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
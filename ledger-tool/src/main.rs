@@ -15,9 +15,10 @@ use {
     serde_json::json,
     solana_account_decoder::{UiAccount, UiAccountData, UiAccountEncoding},
     solana_clap_utils::{
-        input_parsers::{cluster_type_of, pubkey_of, pubkeys_of},
+        input_parsers::{cluster_type_of, pubkey_of, pubkeys_of, value_of},
         input_validators::{
             is_parsable, is_pow2, is_pubkey, is_pubkey_or_keypair, is_slot, is_valid_percentage,
+            is_valid_signature,
         },
     },
     solana_core::{
@@ -35,6 +36,8 @@ use {
             ShredStorageType, BLOCKSTORE_DIRECTORY_ROCKS_FIFO,
         },
         blockstore_processor::{self, BlockstoreProcessorError, ProcessOptions},
+        entry_notifier_interface::EntryNotifierLock,
+        entry_notifier_service::EntryNotifierService,
         shred::Shred,
     },
     solana_measure::{measure, measure::Measure},
@@ -1097,6 +1100,7 @@ fn load_bank_forks(
     };
 
     let mut accounts_update_notifier = Option::<AccountsUpdateNotifier>::default();
+    let mut entry_notifier = Option::<EntryNotifierLock>::default();
     if arg_matches.is_present("geyser_plugin_config") {
         let geyser_config_files = values_t_or_exit!(arg_matches, "geyser_plugin_config", String)
             .into_iter()
@@ -1106,13 +1110,13 @@ fn load_bank_forks(
         let (confirmed_bank_sender, confirmed_bank_receiver) = unbounded();
         drop(confirmed_bank_sender);
         let geyser_service =
-            GeyserPluginService::new(confirmed_bank_receiver, &geyser_config_files).unwrap_or_else(
-                |err| {
+            GeyserPluginService::new(confirmed_bank_receiver, &geyser_config_files, false)
+                .unwrap_or_else(|err| {
                     eprintln!("Failed to setup Geyser service: {:?}", err);
                     exit(1);
-                },
-            );
+                });
         accounts_update_notifier = geyser_service.get_accounts_update_notifier();
+        entry_notifier = geyser_service.get_entry_notifier();
     }
 
     let (bank_forks, leader_schedule_cache, starting_snapshot_hashes, ..) =
@@ -1147,6 +1151,8 @@ fn load_bank_forks(
         pruned_banks_request_handler,
     };
     let exit = Arc::new(AtomicBool::new(false));
+    let entry_notifier_service =
+        entry_notifier.map(|entry_notifier| EntryNotifierService::new(entry_notifier, &exit));
     let accounts_background_service = AccountsBackgroundService::new(
         bank_forks.clone(),
         &exit,
@@ -1163,12 +1169,18 @@ fn load_bank_forks(
         &process_options,
         None,
         None,
+        entry_notifier_service
+            .as_ref()
+            .map(|service| service.sender()),
         &accounts_background_request_sender,
     )
     .map(|_| (bank_forks, starting_snapshot_hashes));
 
     exit.store(true, Ordering::Relaxed);
     accounts_background_service.join().unwrap();
+    if let Some(service) = entry_notifier_service {
+        service.join().unwrap();
+    }
 
     result
 }
@@ -1572,7 +1584,7 @@ fn main() {
                 .takes_value(true)
                 .possible_values(&["json", "json-compact"])
                 .help("Return information in specified output format, \
-                       currently only available for bigtable subcommands"),
+                       currently only available for bigtable and program-stats subcommands"),
         )
         .arg(
             Arg::with_name("verbose")
@@ -2041,6 +2053,22 @@ fn main() {
                 .help("Do not print account data when printing account contents."),
             )
             .arg(&max_genesis_archive_unpacked_size_arg)
+        ).subcommand(
+            SubCommand::with_name("program-stats")
+            .about("Print per-program account storage stats after processing the ledger")
+            .arg(&no_snapshot_arg)
+            .arg(&account_paths_arg)
+            .arg(&halt_at_slot_arg)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+            .arg(
+                Arg::with_name("limit")
+                    .long("limit")
+                    .takes_value(true)
+                    .value_name("NUM")
+                    .validator(is_parsable::<usize>)
+                    .help("Only print this many programs, largest first [default: all]"),
+            )
         ).subcommand(
             SubCommand::with_name("capitalization")
             .about("Print capitalization (aka, total supply) while checksumming it")
@@ -2181,6 +2209,44 @@ fn main() {
                         .help("Number of slots in the output"),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("account-history")
+                .about("Show the balance changes of the given address, from newest to oldest \
+                        rooted transaction")
+                .arg(
+                    Arg::with_name("address")
+                        .index(1)
+                        .value_name("ADDRESS")
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("Account address"),
+                )
+                .arg(
+                    Arg::with_name("limit")
+                        .long("limit")
+                        .value_name("LIMIT")
+                        .takes_value(true)
+                        .validator(is_slot)
+                        .default_value("1000")
+                        .help("Maximum number of transactions to show"),
+                )
+                .arg(
+                    Arg::with_name("before")
+                        .long("before")
+                        .value_name("TRANSACTION_SIGNATURE")
+                        .takes_value(true)
+                        .validator(is_valid_signature)
+                        .help("Start with the first transaction older than this one"),
+                )
+                .arg(
+                    Arg::with_name("until")
+                        .long("until")
+                        .value_name("TRANSACTION_SIGNATURE")
+                        .takes_value(true)
+                        .validator(is_valid_signature)
+                        .help("End with the last transaction newer than this one"),
+                )
+        )
         .subcommand(
             SubCommand::with_name("repair-roots")
                 .about("Traverses the AncestorIterator backward from a last known root \
@@ -3424,6 +3490,101 @@ fn main() {
 
                 println!("{:#?}", total_accounts_stats);
             }
+            ("program-stats", Some(arg_matches)) => {
+                let halt_at_slot = value_t!(arg_matches, "halt_at_slot", Slot).ok();
+                let process_options = ProcessOptions {
+                    new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                    halt_at_slot,
+                    poh_verify: false,
+                    accounts_db_config: Some(AccountsDbConfig {
+                        program_access_stats: true,
+                        ..AccountsDbConfig::default()
+                    }),
+                    ..ProcessOptions::default()
+                };
+                let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                let blockstore = open_blockstore(
+                    &ledger_path,
+                    AccessType::Secondary,
+                    wal_recovery_mode,
+                    &shred_storage_type,
+                    force_update_to_open,
+                );
+                let (bank_forks, ..) = load_bank_forks(
+                    arg_matches,
+                    &genesis_config,
+                    &blockstore,
+                    process_options,
+                    snapshot_archive_path,
+                    incremental_snapshot_archive_path,
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("Failed to load ledger: {:?}", err);
+                    exit(1);
+                });
+
+                let bank = bank_forks.read().unwrap().working_bank();
+                let mut measure = Measure::start("collecting program stats");
+                let report = bank.get_program_stats();
+                measure.stop();
+                info!("{}", measure);
+
+                let limit = value_t!(arg_matches, "limit", usize).unwrap_or(usize::MAX);
+                let programs = report.programs.iter().take(limit);
+                match arg_matches.value_of("output_format") {
+                    Some(output_format) => {
+                        let programs = programs
+                            .map(|(program_id, stats)| {
+                                json!({
+                                    "programId": program_id.to_string(),
+                                    "accounts": stats.accounts,
+                                    "dataBytes": stats.data_bytes,
+                                    "rentExemptLamports": stats.rent_exempt_lamports,
+                                    "writes": stats.writes,
+                                    "readCacheHits": stats.read_cache_hits,
+                                    "readCacheMisses": stats.read_cache_misses,
+                                    "readCacheHitRate": stats.read_cache_hit_rate(),
+                                })
+                            })
+                            .collect::<Vec<_>>();
+                        let output = json!({
+                            "slot": report.slot,
+                            "accessWindowSecs": report.access_window.as_secs_f64(),
+                            "programs": programs,
+                        });
+                        if output_format == "json-compact" {
+                            println!("{}", serde_json::to_string(&output).unwrap());
+                        } else {
+                            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+                        }
+                    }
+                    None => {
+                        println!(
+                            "Program stats at slot {}, writes over the last {:.1}s",
+                            report.slot,
+                            report.access_window.as_secs_f64()
+                        );
+                        println!(
+                            "{:<44} {:>12} {:>16} {:>22} {:>12}",
+                            "Program Id",
+                            "Accounts",
+                            "Data Bytes",
+                            "Rent Exempt Lamports",
+                            "Writes"
+                        );
+                        for (program_id, stats) in programs {
+                            println!(
+                                "{:<44} {:>12} {:>16} {:>22} {:>12}",
+                                program_id.to_string(),
+                                stats.accounts,
+                                stats.data_bytes,
+                                stats.rent_exempt_lamports,
+                                stats.writes,
+                            );
+                        }
+                    }
+                }
+            }
             ("capitalization", Some(arg_matches)) => {
                 let halt_at_slot = value_t!(arg_matches, "halt_at_slot", Slot).ok();
                 let process_options = ProcessOptions {
@@ -4138,6 +4299,58 @@ fn main() {
                     println!("{:>20} {:>44} {:>32}", slot, &hash_str, &time_str);
                 }
             }
+            ("account-history", Some(arg_matches)) => {
+                let blockstore = open_blockstore(
+                    &ledger_path,
+                    AccessType::Secondary,
+                    wal_recovery_mode,
+                    &shred_storage_type,
+                    force_update_to_open,
+                );
+                let address = pubkey_of(arg_matches, "address").unwrap();
+                let limit = value_t_or_exit!(arg_matches, "limit", usize);
+                let before = value_of(arg_matches, "before");
+                let until = value_of(arg_matches, "until");
+                let changes = blockstore
+                    .get_account_history(address, blockstore.max_root(), before, until, limit)
+                    .unwrap_or_else(|err| {
+                        eprintln!("Failed to get account history: {:?}", err);
+                        exit(1);
+                    });
+                for change in changes {
+                    let lamports = match (change.pre_balance, change.post_balance) {
+                        (Some(pre_balance), Some(post_balance)) => {
+                            format!("{} -> {}", Sol(pre_balance), Sol(post_balance))
+                        }
+                        _ => "unavailable".to_string(),
+                    };
+                    println!(
+                        "{}, slot={}, block_time={:?}, status={}, balance={}",
+                        change.signature,
+                        change.slot,
+                        change.block_time,
+                        match change.err {
+                            None => "Confirmed".to_string(),
+                            Some(err) => format!("Failed: {:?}", err),
+                        },
+                        lamports,
+                    );
+                    let pre_token_balance = change.pre_token_balance.as_ref();
+                    let post_token_balance = change.post_token_balance.as_ref();
+                    if let Some(token_balance) = pre_token_balance.or(post_token_balance) {
+                        println!(
+                            "  token balance of mint {}: {} -> {}",
+                            token_balance.mint,
+                            pre_token_balance.map_or("none", |token_balance| {
+                                &token_balance.ui_token_amount.ui_amount_string
+                            }),
+                            post_token_balance.map_or("none", |token_balance| {
+                                &token_balance.ui_token_amount.ui_amount_string
+                            }),
+                        );
+                    }
+                }
+            }
             ("repair-roots", Some(arg_matches)) => {
                 let blockstore = open_blockstore(
                     &ledger_path,
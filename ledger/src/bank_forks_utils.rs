@@ -5,6 +5,7 @@ use {
             self, BlockstoreProcessorError, CacheBlockMetaSender, ProcessOptions,
             TransactionStatusSender,
         },
+        entry_notifier_service::EntryNotifierSender,
         leader_schedule_cache::LeaderScheduleCache,
     },
     log::*,
@@ -49,6 +50,7 @@ pub fn load(
     process_options: ProcessOptions,
     transaction_status_sender: Option<&TransactionStatusSender>,
     cache_block_meta_sender: Option<&CacheBlockMetaSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     exit: &Arc<AtomicBool>,
 ) -> LoadResult {
@@ -71,6 +73,7 @@ pub fn load(
         &process_options,
         transaction_status_sender,
         cache_block_meta_sender,
+        entry_notification_sender,
         &AbsRequestSender::default(),
     )
     .map(|_| (bank_forks, leader_schedule_cache, starting_snapshot_hashes))
@@ -47,13 +47,13 @@ use {
         pubkey::Pubkey,
         signature::{Keypair, Signature, Signer},
         timing::timestamp,
-        transaction::VersionedTransaction,
+        transaction::{TransactionError, VersionedTransaction},
     },
     solana_storage_proto::{StoredExtendedRewards, StoredTransactionStatusMeta},
     solana_transaction_status::{
         ConfirmedTransactionStatusWithSignature, ConfirmedTransactionWithStatusMeta, Rewards,
-        TransactionStatusMeta, TransactionWithStatusMeta, VersionedConfirmedBlock,
-        VersionedTransactionWithStatusMeta,
+        TransactionStatusMeta, TransactionTokenBalance, TransactionWithStatusMeta,
+        VersionedConfirmedBlock, VersionedTransactionWithStatusMeta,
     },
     std::{
         borrow::Cow,
@@ -121,6 +121,59 @@ pub struct SignatureInfosForAddress {
     pub found_before: bool,
 }
 
+/// The balances of an account before and after one of the transactions referencing it. The
+/// balances are `None` when the transaction status metadata does not record them
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountBalanceChange {
+    pub signature: Signature,
+    pub slot: Slot,
+    pub block_time: Option<UnixTimestamp>,
+    pub err: Option<TransactionError>,
+    pub pre_balance: Option<u64>,
+    pub post_balance: Option<u64>,
+    pub pre_token_balance: Option<TransactionTokenBalance>,
+    pub post_token_balance: Option<TransactionTokenBalance>,
+}
+
+impl AccountBalanceChange {
+    /// Reads the balances of `address` out of the transaction listed by `info`, if available
+    pub fn new(
+        address: &Pubkey,
+        info: ConfirmedTransactionStatusWithSignature,
+        tx_with_meta: Option<VersionedTransactionWithStatusMeta>,
+    ) -> Self {
+        let mut change = Self {
+            signature: info.signature,
+            slot: info.slot,
+            block_time: info.block_time,
+            err: info.err,
+            pre_balance: None,
+            post_balance: None,
+            pre_token_balance: None,
+            post_token_balance: None,
+        };
+        let account_index = tx_with_meta.as_ref().and_then(|tx_with_meta| {
+            tx_with_meta
+                .account_keys()
+                .iter()
+                .position(|key| key == address)
+        });
+        if let (Some(tx_with_meta), Some(account_index)) = (tx_with_meta, account_index) {
+            let meta = tx_with_meta.meta;
+            let token_balance = |token_balances: Option<Vec<TransactionTokenBalance>>| {
+                token_balances?
+                    .into_iter()
+                    .find(|token_balance| usize::from(token_balance.account_index) == account_index)
+            };
+            change.pre_balance = meta.pre_balances.get(account_index).copied();
+            change.post_balance = meta.post_balances.get(account_index).copied();
+            change.pre_token_balance = token_balance(meta.pre_token_balances);
+            change.post_token_balance = token_balance(meta.post_token_balances);
+        }
+        change
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum InsertDataShredError {
     Exists,
@@ -2637,21 +2690,7 @@ impl Blockstore {
 
         // Fill in the status information for each found transaction
         let mut get_status_info_timer = Measure::start("get_status_info_timer");
-        let mut infos = vec![];
-        for (slot, signature) in address_signatures.into_iter() {
-            let transaction_status =
-                self.get_transaction_status(signature, &confirmed_unrooted_slots)?;
-            let err = transaction_status.and_then(|(_slot, status)| status.status.err());
-            let memo = self.read_transaction_memos(signature)?;
-            let block_time = self.get_block_time(slot)?;
-            infos.push(ConfirmedTransactionStatusWithSignature {
-                signature,
-                slot,
-                err,
-                memo,
-                block_time,
-            });
-        }
+        let infos = self.get_signature_infos(address_signatures, &confirmed_unrooted_slots)?;
         get_status_info_timer.stop();
 
         datapoint_info!(
@@ -2694,6 +2733,174 @@ impl Blockstore {
         })
     }
 
+    /// Like `get_confirmed_signatures_for_address2`, but oldest first: lists the signatures from
+    /// after the `after` signature, or from `lowest_slot`, up to the `before` signature, or
+    /// through `highest_slot`
+    pub fn get_confirmed_signatures_for_address_ascending(
+        &self,
+        address: Pubkey,
+        lowest_slot: Slot,
+        highest_slot: Slot, // highest_confirmed_root or highest_confirmed_slot
+        after: Option<Signature>,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> Result<SignatureInfosForAddress> {
+        datapoint_info!(
+            "blockstore-rpc-api",
+            (
+                "method",
+                "get_confirmed_signatures_for_address_ascending",
+                String
+            )
+        );
+        let last_root = self.last_root();
+        let confirmed_unrooted_slots: Vec<_> = AncestorIterator::new_inclusive(highest_slot, self)
+            .take_while(|&slot| slot > last_root)
+            .collect();
+
+        // Within a slot, the AddressSignatures column is ordered by signature, so (slot,
+        // signature) pairs order the signatures consistently with `get_sorted_block_signatures`
+        let find_slot = |signature: Signature| -> Result<(Slot, Signature)> {
+            match self.get_transaction_status(signature, &confirmed_unrooted_slots)? {
+                Some((slot, _)) => Ok((slot, signature)),
+                None => Err(BlockstoreError::SignatureNotFound(signature)),
+            }
+        };
+        let after = after.map(find_slot).transpose()?;
+        let before = before.map(find_slot).transpose()?;
+        let first_available_block = self.get_first_available_block()?;
+        let start_slot = after
+            .map_or(lowest_slot, |(slot, _)| slot.max(lowest_slot))
+            .max(first_available_block);
+
+        let (lock, lowest_available_slot) = self.ensure_lowest_cleanup_slot();
+        let mut address_signatures = vec![];
+        for transaction_status_cf_primary_index in 0..=1 {
+            let index_iterator = self.address_signatures_cf.iter(IteratorMode::From(
+                (
+                    transaction_status_cf_primary_index,
+                    address,
+                    start_slot.max(lowest_available_slot),
+                    Signature::default(),
+                ),
+                IteratorDirection::Forward,
+            ))?;
+            // Each primary index is sorted, so the oldest `limit` signatures are among the first
+            // `limit` ones of each index
+            let mut num_found = 0;
+            for ((i, key_address, slot, signature), _) in index_iterator {
+                if i != transaction_status_cf_primary_index
+                    || key_address != address
+                    || slot > highest_slot
+                    || num_found == limit
+                    || matches!(before, Some(before) if (slot, signature) >= before)
+                {
+                    break;
+                }
+                if matches!(after, Some(after) if (slot, signature) <= after) {
+                    continue;
+                }
+                if self.is_root(slot) || confirmed_unrooted_slots.contains(&slot) {
+                    address_signatures.push((slot, signature));
+                    num_found += 1;
+                }
+            }
+        }
+        drop(lock);
+        address_signatures.sort_unstable();
+        address_signatures.dedup();
+        address_signatures.truncate(limit);
+
+        Ok(SignatureInfosForAddress {
+            infos: self.get_signature_infos(address_signatures, &confirmed_unrooted_slots)?,
+            found_before: true, // if `before` signature was not found, this method errored
+        })
+    }
+
+    /// Lists the balance changes of `address`, one per transaction and newest first, paging
+    /// through the transactions like `get_confirmed_signatures_for_address2`
+    pub fn get_account_history(
+        &self,
+        address: Pubkey,
+        highest_slot: Slot, // highest_confirmed_root or highest_confirmed_slot
+        before: Option<Signature>,
+        until: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<AccountBalanceChange>> {
+        datapoint_info!(
+            "blockstore-rpc-api",
+            ("method", "get_account_history", String)
+        );
+        let SignatureInfosForAddress { infos, .. } = self.get_confirmed_signatures_for_address2(
+            address,
+            highest_slot,
+            before,
+            until,
+            limit,
+        )?;
+        let last_root = self.last_root();
+        let confirmed_unrooted_slots: Vec<_> = AncestorIterator::new_inclusive(highest_slot, self)
+            .take_while(|&slot| slot > last_root)
+            .collect();
+
+        // Read each block once for all of its listed transactions
+        let mut signatures_by_slot: HashMap<Slot, HashSet<Signature>> = HashMap::new();
+        for info in &infos {
+            signatures_by_slot
+                .entry(info.slot)
+                .or_default()
+                .insert(info.signature);
+        }
+        let mut transactions = HashMap::new();
+        for (slot, signatures) in signatures_by_slot {
+            transactions.extend(
+                self.get_slot_entries(slot, 0)?
+                    .into_iter()
+                    .flat_map(|entry| entry.transactions)
+                    .filter(|transaction| signatures.contains(&transaction.signatures[0]))
+                    .map(|transaction| (transaction.signatures[0], transaction)),
+            );
+        }
+
+        let mut changes = Vec::with_capacity(infos.len());
+        for info in infos {
+            let transaction = transactions.remove(&info.signature);
+            let status = self.get_transaction_status(info.signature, &confirmed_unrooted_slots)?;
+            let tx_with_meta = match (transaction, status) {
+                (Some(transaction), Some((_slot, meta))) => {
+                    Some(VersionedTransactionWithStatusMeta { transaction, meta })
+                }
+                _ => None,
+            };
+            changes.push(AccountBalanceChange::new(&address, info, tx_with_meta));
+        }
+        Ok(changes)
+    }
+
+    /// Fill in the status information of each transaction signature
+    fn get_signature_infos(
+        &self,
+        address_signatures: Vec<(Slot, Signature)>,
+        confirmed_unrooted_slots: &[Slot],
+    ) -> Result<Vec<ConfirmedTransactionStatusWithSignature>> {
+        let mut infos = vec![];
+        for (slot, signature) in address_signatures.into_iter() {
+            let transaction_status =
+                self.get_transaction_status(signature, confirmed_unrooted_slots)?;
+            let err = transaction_status.and_then(|(_slot, status)| status.status.err());
+            let memo = self.read_transaction_memos(signature)?;
+            let block_time = self.get_block_time(slot)?;
+            infos.push(ConfirmedTransactionStatusWithSignature {
+                signature,
+                slot,
+                err,
+                memo,
+                block_time,
+            });
+        }
+        Ok(infos)
+    }
+
     pub fn read_rewards(&self, index: Slot) -> Result<Option<Rewards>> {
         self.rewards_cf
             .get_protobuf_or_bincode::<Rewards>(index)
@@ -7973,6 +8180,116 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_get_account_history() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let (shreds, _) = make_slot_entries(1, 0, 4, /*merkle_variant:*/ true);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+
+        let address = solana_sdk::pubkey::new_rand();
+        let mint = solana_sdk::pubkey::new_rand().to_string();
+        let token_balance = |amount: u64| TransactionTokenBalance {
+            account_index: 1,
+            mint: mint.clone(),
+            ui_token_amount: UiTokenAmount {
+                ui_amount: Some(amount as f64),
+                decimals: 0,
+                amount: amount.to_string(),
+                ui_amount_string: amount.to_string(),
+            },
+            owner: String::new(),
+            program_id: String::new(),
+        };
+
+        let slot = 2;
+        let mut entries: Vec<Entry> = Vec::new();
+        for x in 0..3u64 {
+            let transaction = Transaction::new_with_compiled_instructions(
+                &[&Keypair::new()],
+                &[address],
+                Hash::default(),
+                vec![solana_sdk::pubkey::new_rand()],
+                vec![CompiledInstruction::new(1, &(), vec![0])],
+            );
+            entries.push(next_entry_mut(&mut Hash::default(), 0, vec![transaction]));
+            let mut tick = create_ticks(1, 0, hash(&serialize(&x).unwrap()));
+            entries.append(&mut tick);
+        }
+        let shreds = entries_to_test_shreds(
+            &entries,
+            slot,
+            slot - 1, // parent_slot
+            true,     // is_full_slot
+            0,        // version
+            true,     // merkle_variant
+        );
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+        blockstore.set_roots(vec![0, 1, 2].iter()).unwrap();
+
+        let mut expected_balances = HashMap::new();
+        let transactions = entries.into_iter().flat_map(|entry| entry.transactions);
+        for (x, transaction) in transactions.enumerate() {
+            let x = x as u64;
+            let signature = transaction.signatures[0];
+            blockstore
+                .write_transaction_status(
+                    slot,
+                    signature,
+                    transaction.message.static_account_keys().iter().collect(),
+                    vec![],
+                    TransactionStatusMeta {
+                        pre_balances: vec![10, 100 + x, 1],
+                        post_balances: vec![5, 200 + x, 1],
+                        pre_token_balances: Some(vec![token_balance(x)]),
+                        post_token_balances: Some(vec![token_balance(x + 1)]),
+                        ..TransactionStatusMeta::default()
+                    },
+                )
+                .unwrap();
+            expected_balances.insert(signature, x);
+        }
+
+        let history = blockstore
+            .get_account_history(address, slot, None, None, usize::MAX)
+            .unwrap();
+        assert_eq!(history.len(), 3);
+        for change in &history {
+            let x = expected_balances[&change.signature];
+            assert_eq!(change.slot, slot);
+            assert_eq!(change.err, None);
+            assert_eq!(change.pre_balance, Some(100 + x));
+            assert_eq!(change.post_balance, Some(200 + x));
+            assert_eq!(change.pre_token_balance, Some(token_balance(x)));
+            assert_eq!(change.post_token_balance, Some(token_balance(x + 1)));
+        }
+
+        // Paging follows `get_confirmed_signatures_for_address2`
+        let signatures = blockstore
+            .get_confirmed_signatures_for_address2(address, slot, None, None, usize::MAX)
+            .unwrap()
+            .infos
+            .into_iter()
+            .map(|info| info.signature)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            history
+                .iter()
+                .map(|change| change.signature)
+                .collect::<Vec<_>>(),
+            signatures
+        );
+        let page = blockstore
+            .get_account_history(address, slot, Some(signatures[0]), None, 1)
+            .unwrap();
+        assert_eq!(page, vec![history[1].clone()]);
+        let page = blockstore
+            .get_account_history(address, slot, None, Some(signatures[1]), usize::MAX)
+            .unwrap();
+        assert_eq!(page, vec![history[0].clone()]);
+    }
+
     #[test]
     fn test_get_confirmed_signatures_for_address2() {
         let ledger_path = get_tmp_ledger_path_auto_delete!();
@@ -8089,6 +8406,63 @@ pub mod tests {
             .infos;
         assert_eq!(all1.len(), 12);
 
+        // Fetch all rooted signatures for address 0 oldest first, 5 at a time
+        let mut ascending0 = vec![];
+        loop {
+            let sig_infos = blockstore
+                .get_confirmed_signatures_for_address_ascending(
+                    address0,
+                    0,
+                    highest_confirmed_root,
+                    ascending0
+                        .last()
+                        .map(|info: &ConfirmedTransactionStatusWithSignature| info.signature),
+                    None,
+                    5,
+                )
+                .unwrap()
+                .infos;
+            if sig_infos.is_empty() {
+                break;
+            }
+            ascending0.extend(sig_infos);
+        }
+        assert_eq!(ascending0, all0.iter().rev().cloned().collect::<Vec<_>>());
+
+        // Fetch the signatures for address 0 of slots 5 and 6 oldest first, up to a `before`
+        // signature
+        let sig_infos = blockstore
+            .get_confirmed_signatures_for_address_ascending(
+                address0,
+                5,
+                6,
+                None,
+                Some(all0[5].signature),
+                usize::MAX,
+            )
+            .unwrap()
+            .infos;
+        assert_eq!(sig_infos, vec![all0[7].clone(), all0[6].clone()]);
+
+        // Paging from a signature that is not in the Blockstore is an error rather than a
+        // silently unbounded or empty listing
+        for (after, before) in [
+            (Some(Signature::new_unique()), None),
+            (None, Some(Signature::new_unique())),
+        ] {
+            assert_matches!(
+                blockstore.get_confirmed_signatures_for_address_ascending(
+                    address0,
+                    0,
+                    highest_confirmed_root,
+                    after,
+                    before,
+                    usize::MAX,
+                ),
+                Err(BlockstoreError::SignatureNotFound(_))
+            );
+        }
+
         // Fetch all signatures for address 0 individually
         for i in 0..all0.len() {
             let sig_infos = blockstore
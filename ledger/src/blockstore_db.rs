@@ -117,6 +117,7 @@ pub enum BlockstoreError {
     SlotUnavailable,
     UnsupportedTransactionVersion,
     MissingTransactionMetadata,
+    SignatureNotFound(Signature),
 }
 pub type Result<T> = std::result::Result<T, BlockstoreError>;
 
@@ -1,7 +1,11 @@
 use {
     crate::{
-        block_error::BlockError, blockstore::Blockstore, blockstore_db::BlockstoreError,
-        blockstore_meta::SlotMeta, leader_schedule_cache::LeaderScheduleCache,
+        block_error::BlockError,
+        blockstore::Blockstore,
+        blockstore_db::BlockstoreError,
+        blockstore_meta::SlotMeta,
+        entry_notifier_service::{EntryNotification, EntryNotifierSender},
+        leader_schedule_cache::LeaderScheduleCache,
         token_balances::collect_token_balances,
     },
     chrono_humanize::{Accuracy, HumanTime, Tense},
@@ -11,7 +15,8 @@ use {
     rand::{seq::SliceRandom, thread_rng},
     rayon::{prelude::*, ThreadPool},
     solana_entry::entry::{
-        self, create_ticks, Entry, EntrySlice, EntryType, EntryVerificationStatus, VerifyRecyclers,
+        self, create_ticks, Entry, EntrySlice, EntrySummary, EntryType, EntryVerificationStatus,
+        VerifyRecyclers,
     },
     solana_measure::{measure, measure::Measure},
     solana_metrics::{datapoint_error, inc_new_counter_debug},
@@ -778,6 +783,7 @@ pub fn test_process_blockstore(
         opts,
         None,
         None,
+        None,
         &abs_request_sender,
     )
     .unwrap();
@@ -834,6 +840,7 @@ pub fn process_blockstore_from_root(
     opts: &ProcessOptions,
     transaction_status_sender: Option<&TransactionStatusSender>,
     cache_block_meta_sender: Option<&CacheBlockMetaSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     accounts_background_request_sender: &AbsRequestSender,
 ) -> result::Result<(), BlockstoreProcessorError> {
     // Starting slot must be a root, and thus has no parents
@@ -881,6 +888,7 @@ pub fn process_blockstore_from_root(
             opts,
             transaction_status_sender,
             cache_block_meta_sender,
+            entry_notification_sender,
             &mut timing,
             accounts_background_request_sender,
         )?;
@@ -984,6 +992,7 @@ fn confirm_full_slot(
     recyclers: &VerifyRecyclers,
     progress: &mut ConfirmationProgress,
     transaction_status_sender: Option<&TransactionStatusSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     replay_vote_sender: Option<&ReplayVoteSender>,
     timing: &mut ExecuteTimings,
 ) -> result::Result<(), BlockstoreProcessorError> {
@@ -998,6 +1007,7 @@ fn confirm_full_slot(
         progress,
         skip_verification,
         transaction_status_sender,
+        entry_notification_sender,
         replay_vote_sender,
         opts.entry_callback.as_ref(),
         recyclers,
@@ -1125,6 +1135,7 @@ pub fn confirm_slot(
     progress: &mut ConfirmationProgress,
     skip_verification: bool,
     transaction_status_sender: Option<&TransactionStatusSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     replay_vote_sender: Option<&ReplayVoteSender>,
     entry_callback: Option<&ProcessCallback>,
     recyclers: &VerifyRecyclers,
@@ -1155,6 +1166,7 @@ pub fn confirm_slot(
         progress,
         skip_verification,
         transaction_status_sender,
+        entry_notification_sender,
         replay_vote_sender,
         entry_callback,
         recyclers,
@@ -1171,6 +1183,7 @@ fn confirm_slot_entries(
     progress: &mut ConfirmationProgress,
     skip_verification: bool,
     transaction_status_sender: Option<&TransactionStatusSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     replay_vote_sender: Option<&ReplayVoteSender>,
     entry_callback: Option<&ProcessCallback>,
     recyclers: &VerifyRecyclers,
@@ -1220,6 +1233,15 @@ fn confirm_slot_entries(
     }
 
     let last_entry_hash = entries.last().map(|e| e.hash);
+    // Entries are moved into the verification below, so keep what the entry
+    // notifier needs until the slot's entries have been replayed successfully.
+    let entry_summaries = entry_notification_sender.map(|_| {
+        entries
+            .iter()
+            .map(EntrySummary::from)
+            .zip(entry_starting_indexes.iter().copied())
+            .collect::<Vec<_>>()
+    });
     let verifier = if !skip_verification {
         datapoint_debug!("verify-batch-size", ("size", num_entries as i64, i64));
         let entry_state = entries.start_verify(&progress.last_entry, recyclers.clone());
@@ -1308,6 +1330,27 @@ fn confirm_slot_entries(
 
             process_result?;
 
+            if let (Some(entry_notification_sender), Some(entry_summaries)) =
+                (entry_notification_sender, entry_summaries)
+            {
+                for (index, (entry, starting_transaction_index)) in
+                    entry_summaries.into_iter().enumerate()
+                {
+                    let index = progress.num_entries.saturating_add(index);
+                    if let Err(err) = entry_notification_sender.send(EntryNotification {
+                        slot,
+                        index,
+                        entry,
+                        starting_transaction_index,
+                    }) {
+                        warn!(
+                            "Slot {}, entry {} entry_notification_sender send failed: {:?}",
+                            slot, index, err
+                        );
+                    }
+                }
+            }
+
             progress.num_shreds += num_shreds;
             progress.num_entries += num_entries;
             progress.num_txs += num_txs;
@@ -1342,6 +1385,7 @@ fn process_bank_0(
         &mut progress,
         None,
         None,
+        None,
         &mut ExecuteTimings::default(),
     )
     .expect("Failed to process bank 0 from ledger. Did you forget to provide a snapshot?");
@@ -1419,6 +1463,7 @@ fn load_frozen_forks(
     opts: &ProcessOptions,
     transaction_status_sender: Option<&TransactionStatusSender>,
     cache_block_meta_sender: Option<&CacheBlockMetaSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     timing: &mut ExecuteTimings,
     accounts_background_request_sender: &AbsRequestSender,
 ) -> result::Result<u64, BlockstoreProcessorError> {
@@ -1490,6 +1535,7 @@ fn load_frozen_forks(
                 &mut progress,
                 transaction_status_sender,
                 cache_block_meta_sender,
+                entry_notification_sender,
                 None,
                 timing,
             )
@@ -1701,6 +1747,7 @@ fn process_single_slot(
     progress: &mut ConfirmationProgress,
     transaction_status_sender: Option<&TransactionStatusSender>,
     cache_block_meta_sender: Option<&CacheBlockMetaSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     replay_vote_sender: Option<&ReplayVoteSender>,
     timing: &mut ExecuteTimings,
 ) -> result::Result<(), BlockstoreProcessorError> {
@@ -1713,6 +1760,7 @@ fn process_single_slot(
         recyclers,
         progress,
         transaction_status_sender,
+        entry_notification_sender,
         replay_vote_sender,
         timing,
     )
@@ -3524,6 +3572,7 @@ pub mod tests {
             &mut ConfirmationProgress::new(bank0.last_blockhash()),
             None,
             None,
+            None,
             &mut ExecuteTimings::default(),
         )
         .unwrap();
@@ -3544,6 +3593,7 @@ pub mod tests {
             &opts,
             None,
             None,
+            None,
             &AbsRequestSender::default(),
         )
         .unwrap();
@@ -4168,6 +4218,7 @@ pub mod tests {
             None,
             None,
             None,
+            None,
             &VerifyRecyclers::default(),
             None,
             &PrioritizationFeeCache::new(0u64),
@@ -4286,6 +4337,8 @@ pub mod tests {
         let transaction_status_sender = TransactionStatusSender {
             sender: transaction_status_sender,
         };
+        let (entry_notification_sender, entry_notification_receiver) =
+            crossbeam_channel::unbounded();
 
         let blockhash = bank.last_blockhash();
         let tx1 = system_transaction::transfer(
@@ -4310,6 +4363,7 @@ pub mod tests {
             &mut progress,
             false,
             Some(&transaction_status_sender),
+            Some(&entry_notification_sender),
             None,
             None,
             &VerifyRecyclers::default(),
@@ -4318,6 +4372,12 @@ pub mod tests {
         )
         .unwrap();
         assert_eq!(progress.num_txs, 2);
+        let entry_notification = entry_notification_receiver.recv().unwrap();
+        assert_eq!(entry_notification.slot, bank.slot());
+        assert_eq!(entry_notification.index, 0);
+        assert_eq!(entry_notification.entry.hash, new_hash);
+        assert_eq!(entry_notification.entry.num_transactions, 2);
+        assert_eq!(entry_notification.starting_transaction_index, 0);
         let batch = transaction_status_receiver.recv().unwrap();
         if let TransactionStatusMessage::Batch(batch) = batch {
             assert_eq!(batch.transactions.len(), 2);
@@ -4348,6 +4408,7 @@ pub mod tests {
             bank.last_blockhash(),
         );
         let entry = next_entry(&new_hash, 1, vec![tx1, tx2, tx3]);
+        let new_hash = entry.hash;
 
         confirm_slot_entries(
             &bank,
@@ -4356,6 +4417,7 @@ pub mod tests {
             &mut progress,
             false,
             Some(&transaction_status_sender),
+            Some(&entry_notification_sender),
             None,
             None,
             &VerifyRecyclers::default(),
@@ -4364,6 +4426,11 @@ pub mod tests {
         )
         .unwrap();
         assert_eq!(progress.num_txs, 5);
+        let entry_notification = entry_notification_receiver.recv().unwrap();
+        assert_eq!(entry_notification.index, 1);
+        assert_eq!(entry_notification.entry.hash, new_hash);
+        assert_eq!(entry_notification.entry.num_transactions, 3);
+        assert_eq!(entry_notification.starting_transaction_index, 2);
         let batch = transaction_status_receiver.recv().unwrap();
         if let TransactionStatusMessage::Batch(batch) = batch {
             assert_eq!(batch.transactions.len(), 3);
@@ -0,0 +1,92 @@
+use {
+    crate::entry_notifier_interface::EntryNotifierLock,
+    crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender},
+    solana_entry::entry::EntrySummary,
+    solana_measure::measure::Measure,
+    solana_sdk::clock::Slot,
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::Duration,
+    },
+};
+
+pub struct EntryNotification {
+    pub slot: Slot,
+    pub index: usize,
+    pub entry: EntrySummary,
+    pub starting_transaction_index: usize,
+}
+
+pub type EntryNotifierSender = Sender<EntryNotification>;
+pub type EntryNotifierReceiver = Receiver<EntryNotification>;
+
+/// Forwards the entries replayed by `blockstore_processor` to the entry notifier,
+/// off the replay thread.
+pub struct EntryNotifierService {
+    sender: EntryNotifierSender,
+    thread_hdl: JoinHandle<()>,
+}
+
+impl EntryNotifierService {
+    pub fn new(entry_notifier: EntryNotifierLock, exit: &Arc<AtomicBool>) -> Self {
+        let exit = exit.clone();
+        let (entry_notification_sender, entry_notification_receiver) = unbounded();
+        let thread_hdl = Builder::new()
+            .name("solEntryNotif".to_string())
+            .spawn(move || loop {
+                if exit.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                if let Err(RecvTimeoutError::Disconnected) =
+                    Self::notify_entry(&entry_notification_receiver, entry_notifier.clone())
+                {
+                    break;
+                }
+            })
+            .unwrap();
+        Self {
+            sender: entry_notification_sender,
+            thread_hdl,
+        }
+    }
+
+    fn notify_entry(
+        entry_notification_receiver: &EntryNotifierReceiver,
+        entry_notifier: EntryNotifierLock,
+    ) -> Result<(), RecvTimeoutError> {
+        let EntryNotification {
+            slot,
+            index,
+            entry,
+            starting_transaction_index,
+        } = entry_notification_receiver.recv_timeout(Duration::from_secs(1))?;
+        let mut measure = Measure::start("entry_notification-us");
+        entry_notifier.write().unwrap().notify_entry(
+            slot,
+            index,
+            &entry,
+            starting_transaction_index,
+        );
+        measure.stop();
+        inc_new_counter_debug!(
+            "entry_notification-us",
+            measure.as_us() as usize,
+            10000,
+            10000
+        );
+        Ok(())
+    }
+
+    pub fn sender(&self) -> &EntryNotifierSender {
+        &self.sender
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
@@ -2179,6 +2179,7 @@ fn create_snapshot_to_hard_fork(
         None,
         None,
         None,
+        None,
         &Arc::default(),
     )
     .unwrap();
@@ -9,6 +9,7 @@ use {
         stable_log,
         sysvar_cache::SysvarCache,
         timings::{ExecuteDetailsTimings, ExecuteTimings},
+        trace_collector::TraceCollector,
     },
     solana_measure::measure::Measure,
     solana_sdk::{
@@ -117,6 +118,7 @@ pub struct InvokeContext<'a> {
     builtin_programs: &'a [BuiltinProgram],
     pub sysvar_cache: Cow<'a, SysvarCache>,
     log_collector: Option<Rc<RefCell<LogCollector>>>,
+    trace_collector: Option<Rc<RefCell<TraceCollector>>>,
     compute_budget: ComputeBudget,
     current_compute_budget: ComputeBudget,
     compute_meter: Rc<RefCell<ComputeMeter>>,
@@ -137,6 +139,7 @@ impl<'a> InvokeContext<'a> {
         builtin_programs: &'a [BuiltinProgram],
         sysvar_cache: Cow<'a, SysvarCache>,
         log_collector: Option<Rc<RefCell<LogCollector>>>,
+        trace_collector: Option<Rc<RefCell<TraceCollector>>>,
         compute_budget: ComputeBudget,
         tx_executor_cache: Rc<RefCell<TransactionExecutorCache>>,
         feature_set: Arc<FeatureSet>,
@@ -151,6 +154,7 @@ impl<'a> InvokeContext<'a> {
             builtin_programs,
             sysvar_cache,
             log_collector,
+            trace_collector,
             current_compute_budget: compute_budget,
             compute_budget,
             compute_meter: ComputeMeter::new_ref(compute_budget.compute_unit_limit),
@@ -192,6 +196,7 @@ impl<'a> InvokeContext<'a> {
             builtin_programs,
             Cow::Owned(sysvar_cache),
             Some(LogCollector::new_ref()),
+            None,
             ComputeBudget::default(),
             Rc::new(RefCell::new(TransactionExecutorCache::default())),
             Arc::new(FeatureSet::all_enabled()),
@@ -687,7 +692,25 @@ impl<'a> InvokeContext<'a> {
             .get_next_instruction_context()?
             .configure(program_indices, instruction_accounts, instruction_data);
         self.push()?;
-        self.process_executable_chain(compute_units_consumed, timings)
+        let trace_collector = self.get_trace_collector();
+        if let Some(trace_collector) = trace_collector.as_ref() {
+            let program_id = self
+                .transaction_context
+                .get_current_instruction_context()
+                .and_then(|instruction_context| {
+                    instruction_context
+                        .get_last_program_key(self.transaction_context)
+                        .copied()
+                })
+                .unwrap_or_default();
+            trace_collector
+                .borrow_mut()
+                .begin_frame(program_id, self.get_stack_height(), || {
+                    self.get_instruction_accounts_for_trace()
+                });
+        }
+        let result = self
+            .process_executable_chain(compute_units_consumed, timings)
             .and_then(|_| {
                 if self
                     .feature_set
@@ -712,10 +735,51 @@ impl<'a> InvokeContext<'a> {
                     );
                     result
                 }
+            });
+        if let Some(trace_collector) = trace_collector {
+            trace_collector
+                .borrow_mut()
+                .end_frame(*compute_units_consumed, &result, || {
+                    self.get_instruction_accounts_for_trace()
+                });
+        }
+        // MUST pop if and only if `push` succeeded, independent of `result`.
+        // Thus, the `.and()` instead of an `.and_then()`.
+        result.and(self.pop())
+    }
+
+    /// Snapshot of the current instruction's accounts, recorded by the `TraceCollector`
+    fn get_instruction_accounts_for_trace(&self) -> Vec<TransactionAccount> {
+        let instruction_context = match self.transaction_context.get_current_instruction_context() {
+            Ok(instruction_context) => instruction_context,
+            Err(_) => return Vec::new(),
+        };
+        (0..instruction_context.get_number_of_instruction_accounts())
+            .filter(|instruction_account_index| {
+                matches!(
+                    instruction_context
+                        .is_instruction_account_duplicate(*instruction_account_index),
+                    Ok(None)
+                )
             })
-            // MUST pop if and only if `push` succeeded, independent of `result`.
-            // Thus, the `.and()` instead of an `.and_then()`.
-            .and(self.pop())
+            .filter_map(|instruction_account_index| {
+                let index_in_transaction = instruction_context
+                    .get_index_of_instruction_account_in_transaction(instruction_account_index)
+                    .ok()?;
+                let key = self
+                    .transaction_context
+                    .get_key_of_account_at_index(index_in_transaction)
+                    .ok()?;
+                let account = self
+                    .transaction_context
+                    .get_account_at_index(index_in_transaction)
+                    .ok()?
+                    .try_borrow()
+                    .ok()?
+                    .clone();
+                Some((*key, account))
+            })
+            .collect()
     }
 
     /// Calls the instruction's program entrypoint method
@@ -784,6 +848,11 @@ impl<'a> InvokeContext<'a> {
         self.log_collector.clone()
     }
 
+    /// Get this invocation's TraceCollector
+    pub fn get_trace_collector(&self) -> Option<Rc<RefCell<TraceCollector>>> {
+        self.trace_collector.clone()
+    }
+
     /// Get this invocation's ComputeMeter
     pub fn get_compute_meter(&self) -> Rc<RefCell<ComputeMeter>> {
         self.compute_meter.clone()
@@ -20,3 +20,4 @@ pub mod prioritization_fee;
 pub mod stable_log;
 pub mod sysvar_cache;
 pub mod timings;
+pub mod trace_collector;
@@ -0,0 +1,317 @@
+use {
+    solana_sdk::{
+        account::ReadableAccount, instruction::InstructionError, pubkey::Pubkey,
+        transaction_context::TransactionAccount,
+    },
+    std::{cell::RefCell, rc::Rc},
+};
+
+/// Selects what is recorded by a `TraceCollector`, and how much of it
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TraceConfig {
+    /// Maximum number of instruction frames to record, unlimited if `None`
+    pub max_frames: Option<usize>,
+    /// Record the BPF instruction trace of every invocation of this program
+    pub bpf_trace_program_id: Option<Pubkey>,
+    /// Maximum number of BPF instructions to record per invocation, unlimited if `None`
+    pub max_bpf_trace_length: Option<usize>,
+}
+
+/// A contiguous range of account data written by an instruction
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountDataChange {
+    pub offset: usize,
+    /// The account data in this range after the instruction
+    pub data: Vec<u8>,
+}
+
+/// The changes an instruction made to one of its accounts
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountDiff {
+    pub pubkey: Pubkey,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+    pub pre_owner: Pubkey,
+    pub post_owner: Pubkey,
+    pub pre_data_len: usize,
+    pub post_data_len: usize,
+    pub data_changes: Vec<AccountDataChange>,
+}
+
+impl AccountDiff {
+    fn new(pre: &TransactionAccount, post: &TransactionAccount) -> Option<Self> {
+        let (pubkey, pre_account) = pre;
+        let (_, post_account) = post;
+        let data_changes = data_changes(pre_account.data(), post_account.data());
+        if pre_account.lamports() == post_account.lamports()
+            && pre_account.owner() == post_account.owner()
+            && pre_account.data().len() == post_account.data().len()
+            && data_changes.is_empty()
+        {
+            return None;
+        }
+        Some(Self {
+            pubkey: *pubkey,
+            pre_lamports: pre_account.lamports(),
+            post_lamports: post_account.lamports(),
+            pre_owner: *pre_account.owner(),
+            post_owner: *post_account.owner(),
+            pre_data_len: pre_account.data().len(),
+            post_data_len: post_account.data().len(),
+            data_changes,
+        })
+    }
+}
+
+/// Splits the bytes of `post` which differ from `pre` into contiguous ranges
+fn data_changes(pre: &[u8], post: &[u8]) -> Vec<AccountDataChange> {
+    let mut changes = Vec::new();
+    let mut current: Option<AccountDataChange> = None;
+    for (offset, post_byte) in post.iter().enumerate() {
+        if pre.get(offset) == Some(post_byte) {
+            changes.extend(current.take());
+        } else {
+            current
+                .get_or_insert_with(|| AccountDataChange {
+                    offset,
+                    data: Vec::new(),
+                })
+                .data
+                .push(*post_byte);
+        }
+    }
+    changes.extend(current);
+    changes
+}
+
+/// One invocation of a program, and the invocations it made in turn
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstructionFrame {
+    pub program_id: Pubkey,
+    pub stack_height: usize,
+    /// Compute units consumed by this frame, including its inner frames
+    pub compute_units_consumed: u64,
+    pub error: Option<InstructionError>,
+    pub account_diffs: Vec<AccountDiff>,
+    /// The BPF instruction trace, if this program was selected for instruction tracing
+    pub bpf_trace: Option<Vec<String>>,
+    pub inner_frames: Vec<InstructionFrame>,
+}
+
+/// The call tree of a transaction's execution
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionTrace {
+    /// One frame per top-level instruction
+    pub frames: Vec<InstructionFrame>,
+    /// Set if frames or BPF instructions were dropped because of the `TraceConfig` limits
+    pub truncated: bool,
+}
+
+#[derive(Default)]
+pub struct TraceCollector {
+    config: TraceConfig,
+    frames: Vec<InstructionFrame>,
+    open_frames: Vec<(InstructionFrame, Vec<TransactionAccount>)>,
+    recorded_frames: usize,
+    /// Number of open frames which are not recorded because `max_frames` was reached
+    skipped_depth: usize,
+    truncated: bool,
+}
+
+impl TraceCollector {
+    pub fn new_ref(config: TraceConfig) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            config,
+            ..Self::default()
+        }))
+    }
+
+    /// Opens a frame for an invocation of `program_id`. `pre_accounts` is only called if the
+    /// frame is recorded.
+    pub fn begin_frame<F>(&mut self, program_id: Pubkey, stack_height: usize, pre_accounts: F)
+    where
+        F: FnOnce() -> Vec<TransactionAccount>,
+    {
+        let limit_reached = self
+            .config
+            .max_frames
+            .map(|max_frames| self.recorded_frames >= max_frames)
+            .unwrap_or(false);
+        if self.skipped_depth > 0 || limit_reached {
+            self.skipped_depth = self.skipped_depth.saturating_add(1);
+            self.truncated = true;
+            return;
+        }
+        self.recorded_frames = self.recorded_frames.saturating_add(1);
+        let frame = InstructionFrame {
+            program_id,
+            stack_height,
+            ..InstructionFrame::default()
+        };
+        self.open_frames.push((frame, pre_accounts()));
+    }
+
+    /// Closes the innermost open frame. `post_accounts` is only called if the frame is recorded.
+    pub fn end_frame<F>(
+        &mut self,
+        compute_units_consumed: u64,
+        result: &Result<(), InstructionError>,
+        post_accounts: F,
+    ) where
+        F: FnOnce() -> Vec<TransactionAccount>,
+    {
+        if self.skipped_depth > 0 {
+            self.skipped_depth = self.skipped_depth.saturating_sub(1);
+            return;
+        }
+        let (mut frame, pre_accounts) = match self.open_frames.pop() {
+            Some(open_frame) => open_frame,
+            None => return,
+        };
+        frame.compute_units_consumed = compute_units_consumed;
+        frame.error = result.as_ref().err().cloned();
+        frame.account_diffs = pre_accounts
+            .iter()
+            .zip(post_accounts().iter())
+            .filter_map(|(pre, post)| AccountDiff::new(pre, post))
+            .collect();
+        match self.open_frames.last_mut() {
+            Some((parent, _)) => parent.inner_frames.push(frame),
+            None => self.frames.push(frame),
+        }
+    }
+
+    /// Whether the BPF instructions executed by `program_id` should be recorded
+    pub fn is_bpf_trace_enabled(&self, program_id: &Pubkey) -> bool {
+        self.skipped_depth == 0 && self.config.bpf_trace_program_id.as_ref() == Some(program_id)
+    }
+
+    /// Maximum number of BPF instructions recorded per invocation
+    pub fn max_bpf_trace_length(&self) -> usize {
+        self.config.max_bpf_trace_length.unwrap_or(usize::MAX)
+    }
+
+    /// Attaches a BPF instruction trace, one instruction per line, to the innermost open frame
+    pub fn set_bpf_trace(&mut self, bpf_trace: &str) {
+        let max_length = self.max_bpf_trace_length();
+        if let Some((frame, _)) = self.open_frames.last_mut() {
+            let mut lines = bpf_trace.lines();
+            let trace = lines
+                .by_ref()
+                .take(max_length)
+                .map(str::to_string)
+                .collect();
+            if lines.next().is_some() {
+                self.truncated = true;
+            }
+            frame.bpf_trace = Some(trace);
+        }
+    }
+}
+
+impl From<TraceCollector> for ExecutionTrace {
+    fn from(trace_collector: TraceCollector) -> Self {
+        Self {
+            frames: trace_collector.frames,
+            truncated: trace_collector.truncated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::account::{AccountSharedData, WritableAccount},
+    };
+
+    #[test]
+    fn test_data_changes() {
+        assert!(data_changes(&[1, 2, 3], &[1, 2, 3]).is_empty());
+        assert!(data_changes(&[1, 2, 3], &[1, 2]).is_empty());
+        assert_eq!(
+            data_changes(&[1, 2, 3, 4, 5], &[0, 2, 9, 9, 5, 6]),
+            vec![
+                AccountDataChange {
+                    offset: 0,
+                    data: vec![0],
+                },
+                AccountDataChange {
+                    offset: 2,
+                    data: vec![9, 9],
+                },
+                AccountDataChange {
+                    offset: 5,
+                    data: vec![6],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trace_collector() {
+        let program_a = Pubkey::new_unique();
+        let program_b = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let pre_account = AccountSharedData::new(100, 2, &owner);
+        let mut post_account = pre_account.clone();
+        post_account.set_lamports(90);
+        post_account.set_data(vec![0, 7]);
+
+        let mut trace_collector = TraceCollector {
+            config: TraceConfig {
+                max_frames: Some(2),
+                bpf_trace_program_id: Some(program_b),
+                max_bpf_trace_length: Some(1),
+            },
+            ..TraceCollector::default()
+        };
+        trace_collector.begin_frame(program_a, 1, || vec![(key, pre_account.clone())]);
+        assert!(!trace_collector.is_bpf_trace_enabled(&program_a));
+        trace_collector.begin_frame(program_b, 2, Vec::new);
+        assert!(trace_collector.is_bpf_trace_enabled(&program_b));
+        trace_collector.set_bpf_trace("insn 0\ninsn 1\n");
+        // Exceeds max_frames
+        trace_collector.begin_frame(program_b, 3, || panic!("frame is not recorded"));
+        assert!(!trace_collector.is_bpf_trace_enabled(&program_b));
+        trace_collector.end_frame(1, &Ok(()), || panic!("frame is not recorded"));
+        trace_collector.end_frame(10, &Err(InstructionError::Custom(1)), Vec::new);
+        trace_collector.end_frame(100, &Ok(()), || vec![(key, post_account.clone())]);
+
+        let trace = ExecutionTrace::from(trace_collector);
+        assert!(trace.truncated);
+        assert_eq!(
+            trace.frames,
+            vec![InstructionFrame {
+                program_id: program_a,
+                stack_height: 1,
+                compute_units_consumed: 100,
+                error: None,
+                account_diffs: vec![AccountDiff {
+                    pubkey: key,
+                    pre_lamports: 100,
+                    post_lamports: 90,
+                    pre_owner: owner,
+                    post_owner: owner,
+                    pre_data_len: 2,
+                    post_data_len: 2,
+                    data_changes: vec![AccountDataChange {
+                        offset: 1,
+                        data: vec![7],
+                    }],
+                }],
+                bpf_trace: None,
+                inner_frames: vec![InstructionFrame {
+                    program_id: program_b,
+                    stack_height: 2,
+                    compute_units_consumed: 10,
+                    error: Some(InstructionError::Custom(1)),
+                    account_diffs: vec![],
+                    bpf_trace: Some(vec!["insn 0".to_string()]),
+                    inner_frames: vec![],
+                }],
+            }]
+        );
+    }
+}
@@ -35,7 +35,7 @@ use {
         memory_region::MemoryRegion,
         static_analysis::Analysis,
         verifier::{RequisiteVerifier, VerifierError},
-        vm::{Config, EbpfVm, InstructionMeter, ProgramResult, VerifiedExecutable},
+        vm::{Config, EbpfVm, InstructionMeter, ProgramResult, Tracer, VerifiedExecutable},
     },
     solana_sdk::{
         bpf_loader, bpf_loader_deprecated,
@@ -130,6 +130,7 @@ fn create_executor_from_bytes(
     programdata: &[u8],
     use_jit: bool,
     reject_deployment_of_broken_elfs: bool,
+    enable_instruction_tracing: bool,
 ) -> Result<Arc<BpfExecutor>, InstructionError> {
     let mut register_syscalls_time = Measure::start("register_syscalls_time");
     let disable_deploy_of_alloc_free_syscall = reject_deployment_of_broken_elfs
@@ -148,7 +149,7 @@ fn create_executor_from_bytes(
         enable_stack_frame_gaps: true,
         instruction_meter_checkpoint_distance: 10000,
         enable_instruction_meter: true,
-        enable_instruction_tracing: log_enabled!(Trace),
+        enable_instruction_tracing: enable_instruction_tracing || log_enabled!(Trace),
         enable_symbol_and_section_labels: false,
         reject_broken_elfs: reject_deployment_of_broken_elfs,
         noop_instruction_rate: 256,
@@ -210,6 +211,7 @@ pub fn create_executor_from_account(
     program: &BorrowedAccount,
     programdata: &BorrowedAccount,
     use_jit: bool,
+    enable_instruction_tracing: bool,
 ) -> Result<(Arc<dyn Executor>, Option<CreateMetrics>), InstructionError> {
     if !check_loader_id(program.get_owner()) {
         ic_logger_msg!(
@@ -271,6 +273,7 @@ pub fn create_executor_from_account(
             .ok_or(InstructionError::AccountDataTooSmall)?,
         use_jit,
         false, /* reject_deployment_of_broken_elfs */
+        enable_instruction_tracing,
     )?;
     if let Some(mut tx_executor_cache) = tx_executor_cache {
         tx_executor_cache.set(*program.get_key(), executor.clone(), false);
@@ -432,15 +435,25 @@ fn process_instruction_common(
                 first_instruction_account,
             )?)
         };
+        let enable_instruction_tracing = invoke_context
+            .get_trace_collector()
+            .map(|trace_collector| {
+                trace_collector
+                    .borrow()
+                    .is_bpf_trace_enabled(program.get_key())
+            })
+            .unwrap_or(false);
         let mut get_or_create_executor_time = Measure::start("get_or_create_executor_time");
+        // Traced executors are interpreted, and kept out of the executor cache
         let (executor, create_executor_metrics) = create_executor_from_account(
             &invoke_context.feature_set,
             invoke_context.get_compute_budget(),
             log_collector,
-            Some(invoke_context.tx_executor_cache.borrow_mut()),
+            (!enable_instruction_tracing).then(|| invoke_context.tx_executor_cache.borrow_mut()),
             &program,
             programdata.as_ref().unwrap_or(&program),
-            use_jit,
+            use_jit && !enable_instruction_tracing,
+            enable_instruction_tracing,
         )?;
         drop(program);
         drop(programdata);
@@ -677,7 +690,8 @@ fn process_loader_upgradeable_instruction(
                     .get(buffer_data_offset..)
                     .ok_or(InstructionError::AccountDataTooSmall)?,
                 use_jit,
-                true,
+                true,  /* reject_deployment_of_broken_elfs */
+                false, /* enable_instruction_tracing */
             )?;
             drop(buffer);
             create_executor_metrics.program_id = new_program_id.to_string();
@@ -856,7 +870,8 @@ fn process_loader_upgradeable_instruction(
                     .get(buffer_data_offset..)
                     .ok_or(InstructionError::AccountDataTooSmall)?,
                 use_jit,
-                true,
+                true,  /* reject_deployment_of_broken_elfs */
+                false, /* enable_instruction_tracing */
             )?;
             drop(buffer);
             create_executor_metrics.program_id = new_program_id.to_string();
@@ -1350,7 +1365,8 @@ fn process_loader_instruction(
                 &mut create_executor_metrics,
                 program.get_data(),
                 use_jit,
-                true,
+                true,  /* reject_deployment_of_broken_elfs */
+                false, /* enable_instruction_tracing */
             )?;
             create_executor_metrics.program_id = program.get_key().to_string();
             create_executor_metrics.submit_datapoint(&mut invoke_context.timings);
@@ -1402,6 +1418,7 @@ impl Debug for BpfExecutor {
 impl Executor for BpfExecutor {
     fn execute(&self, invoke_context: &mut InvokeContext) -> Result<(), InstructionError> {
         let log_collector = invoke_context.get_log_collector();
+        let trace_collector = invoke_context.get_trace_collector();
         let compute_meter = invoke_context.get_compute_meter();
         let stack_height = invoke_context.get_stack_height();
         let transaction_context = &invoke_context.transaction_context;
@@ -1463,6 +1480,31 @@ impl Executor for BpfExecutor {
                 let trace_string = String::from_utf8(trace_buffer).unwrap();
                 trace!("SBF Program Instruction Trace:\n{}", trace_string);
             }
+            if let Some(trace_collector) = trace_collector.as_ref().filter(|trace_collector| {
+                trace_collector.borrow().is_bpf_trace_enabled(&program_id)
+            }) {
+                // Only render the instructions that fit in the trace, and one more so that it is
+                // marked as truncated
+                let log = &vm.get_program_environment().tracer.log;
+                let rendered_length = log.len().min(
+                    trace_collector
+                        .borrow()
+                        .max_bpf_trace_length()
+                        .saturating_add(1),
+                );
+                let mut tracer = Tracer::default();
+                tracer.log.extend_from_slice(&log[..rendered_length]);
+                let mut trace_buffer = Vec::<u8>::new();
+                if let Ok(analysis) =
+                    Analysis::from_executable(self.verified_executable.get_executable())
+                {
+                    if tracer.write(&mut trace_buffer, &analysis).is_ok() {
+                        trace_collector
+                            .borrow_mut()
+                            .set_bpf_trace(&String::from_utf8_lossy(&trace_buffer));
+                    }
+                }
+            }
             drop(vm);
             let (_returned_from_program_id, return_data) =
                 invoke_context.transaction_context.get_return_data();
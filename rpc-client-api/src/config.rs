@@ -2,10 +2,11 @@ use {
     crate::filter::RpcFilterType,
     solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig},
     solana_sdk::{
-        clock::{Epoch, Slot},
+        clock::{Epoch, Slot, UnixTimestamp},
         commitment_config::{CommitmentConfig, CommitmentLevel},
     },
     solana_transaction_status::{TransactionDetails, UiTransactionEncoding},
+    std::collections::HashMap,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -44,6 +45,43 @@ pub struct RpcSimulateTransactionConfig {
     pub encoding: Option<UiTransactionEncoding>,
     pub accounts: Option<RpcSimulateTransactionAccountsConfig>,
     pub min_context_slot: Option<Slot>,
+    /// Accounts to simulate with in place of their state in the bank, by base-58 address
+    pub account_overrides: Option<HashMap<String, RpcAccountOverride>>,
+    /// Record the invoked instructions, and return them as an execution trace
+    pub trace: Option<RpcSimulateTransactionTraceConfig>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateTransactionTraceConfig {
+    /// Also record the BPF instructions executed by this program, as a base-58 address
+    pub bpf_trace_program: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountOverride {
+    pub lamports: u64,
+    pub owner: String, // Pubkey as base-58 string
+    /// Account data as `[data, encoding]`, with encoding either base64 or base64+zstd
+    pub data: Option<(String, UiAccountEncoding)>,
+    #[serde(default)]
+    pub executable: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleConfig {
+    #[serde(default)]
+    pub sig_verify: bool,
+    #[serde(default)]
+    pub replace_recent_blockhash: bool,
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub encoding: Option<UiTransactionEncoding>,
+    /// The accounts to return after each transaction, one entry per transaction
+    pub accounts: Option<Vec<Option<RpcSimulateTransactionAccountsConfig>>>,
+    pub min_context_slot: Option<Slot>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -156,6 +194,17 @@ pub struct RpcProgramAccountsConfig {
     pub with_context: Option<bool>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProgramAccountsPageConfig {
+    pub filters: Option<Vec<RpcFilterType>>,
+    #[serde(flatten)]
+    pub account_config: RpcAccountInfoConfig,
+    pub limit: Option<usize>,
+    /// The `next` cursor of the previous page
+    pub after: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcTransactionLogsFilter {
@@ -204,6 +253,26 @@ pub struct RpcBlockSubscribeConfig {
     pub max_supported_transaction_version: Option<u8>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionSubscribeFilter {
+    /// Only notify transactions mentioning at least one of these accounts
+    pub account_include: Option<Vec<String>>, // base58-encoded list of addresses
+    /// Never notify transactions mentioning any of these accounts
+    pub account_exclude: Option<Vec<String>>, // base58-encoded list of addresses
+    /// Only notify failed transactions when true, or successful transactions when false
+    pub failed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionSubscribeConfig {
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub encoding: Option<UiTransactionEncoding>,
+    pub max_supported_transaction_version: Option<u8>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcSignaturesForAddressConfig {
@@ -213,6 +282,39 @@ pub struct RpcSignaturesForAddressConfig {
     #[serde(flatten)]
     pub commitment: Option<CommitmentConfig>,
     pub min_context_slot: Option<Slot>,
+    pub min_slot: Option<Slot>,
+    pub max_slot: Option<Slot>,
+    pub min_block_time: Option<UnixTimestamp>,
+    pub max_block_time: Option<UnixTimestamp>,
+    pub errors_only: Option<bool>,
+    pub success_only: Option<bool>,
+    pub order: Option<RpcSignaturesOrder>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountHistoryConfig {
+    pub before: Option<String>, // Signature as base-58 string
+    pub until: Option<String>,  // Signature as base-58 string
+    pub limit: Option<usize>,
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub min_context_slot: Option<Slot>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcSignaturesOrder {
+    /// Newest signatures first, paging backwards with `before`
+    Descending,
+    /// Oldest signatures first, paging forwards with `until`
+    Ascending,
+}
+
+impl Default for RpcSignaturesOrder {
+    fn default() -> Self {
+        Self::Descending
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -24,6 +24,8 @@ pub const JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_LEN_MISMATCH: i64 = -32013
 pub const JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET: i64 = -32014;
 pub const JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION: i64 = -32015;
 pub const JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED: i64 = -32016;
+pub const JSON_RPC_SERVER_ERROR_RATE_LIMITED: i64 = -32017;
+pub const JSON_RPC_SERVER_ERROR_SIGNATURE_SCAN_LIMIT_REACHED: i64 = -32018;
 
 #[derive(Error, Debug)]
 pub enum RpcCustomError {
@@ -65,6 +67,10 @@ pub enum RpcCustomError {
     UnsupportedTransactionVersion(u8),
     #[error("MinContextSlotNotReached")]
     MinContextSlotNotReached { context_slot: Slot },
+    #[error("RateLimited")]
+    RateLimited { retry_after_secs: u64 },
+    #[error("SignatureScanLimitReached")]
+    SignatureScanLimitReached { cursor: String },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -79,6 +85,18 @@ pub struct MinContextSlotNotReachedErrorData {
     pub context_slot: Slot,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitedErrorData {
+    pub retry_after_secs: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureScanLimitReachedErrorData {
+    pub cursor: String,
+}
+
 impl From<EncodeError> for RpcCustomError {
     fn from(err: EncodeError) -> Self {
         match err {
@@ -210,6 +228,24 @@ impl From<RpcCustomError> for Error {
                     context_slot,
                 })),
             },
+            RpcCustomError::RateLimited { retry_after_secs } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_RATE_LIMITED),
+                message: format!(
+                    "Rate limit exceeded, retry after {} seconds",
+                    retry_after_secs
+                ),
+                data: Some(serde_json::json!(RateLimitedErrorData { retry_after_secs })),
+            },
+            RpcCustomError::SignatureScanLimitReached { cursor } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_SIGNATURE_SCAN_LIMIT_REACHED),
+                message: format!(
+                    "No signature passed the filters within the scan limit, resume from {}",
+                    cursor
+                ),
+                data: Some(serde_json::json!(SignatureScanLimitReachedErrorData {
+                    cursor
+                })),
+            },
         }
     }
 }
@@ -1,6 +1,6 @@
 #![allow(deprecated)]
 use {
-    crate::version_req::VersionReq,
+    crate::{request::MAX_GET_PROGRAM_ACCOUNT_FILTERS, version_req::VersionReq},
     solana_sdk::account::{AccountSharedData, ReadableAccount},
     spl_token_2022::{generic_token_account::GenericTokenAccount, state::Account},
     std::borrow::Cow,
@@ -17,13 +17,23 @@ pub enum RpcFilterType {
     DataSize(u64),
     Memcmp(Memcmp),
     TokenAccountState,
+    /// Matches accounts whose data does not match the `Memcmp`
+    NotMemcmp(Memcmp),
+    /// Matches accounts whose data length is within the range
+    DataSizeRange(ValueRange),
+    /// Matches accounts whose balance is within the range
+    Lamports(ValueRange),
+    /// Matches accounts holding a little-endian integer within the range at the given offset
+    IntRange(IntRange),
+    /// Matches accounts allowed by any of the filters
+    Or(Vec<RpcFilterType>),
 }
 
 impl RpcFilterType {
     pub fn verify(&self) -> Result<(), RpcFilterError> {
         match self {
             RpcFilterType::DataSize(_) => Ok(()),
-            RpcFilterType::Memcmp(compare) => {
+            RpcFilterType::Memcmp(compare) | RpcFilterType::NotMemcmp(compare) => {
                 let encoding = compare.encoding.as_ref().unwrap_or(&MemcmpEncoding::Binary);
                 match encoding {
                     MemcmpEncoding::Binary => {
@@ -76,6 +86,20 @@ impl RpcFilterType {
                 }
             }
             RpcFilterType::TokenAccountState => Ok(()),
+            RpcFilterType::DataSizeRange(range) | RpcFilterType::Lamports(range) => range.verify(),
+            RpcFilterType::IntRange(range) => range.verify(),
+            RpcFilterType::Or(filters) => {
+                if filters.is_empty() {
+                    return Err(RpcFilterError::EmptyOr);
+                }
+                if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
+                    return Err(RpcFilterError::TooManyOrFilters);
+                }
+                filters.iter().try_for_each(|filter| match filter {
+                    RpcFilterType::Or(_) => Err(RpcFilterError::NestedOr),
+                    filter => filter.verify(),
+                })
+            }
         }
     }
 
@@ -84,6 +108,11 @@ impl RpcFilterType {
             RpcFilterType::DataSize(size) => account.data().len() as u64 == *size,
             RpcFilterType::Memcmp(compare) => compare.bytes_match(account.data()),
             RpcFilterType::TokenAccountState => Account::valid_account_data(account.data()),
+            RpcFilterType::NotMemcmp(compare) => !compare.bytes_match(account.data()),
+            RpcFilterType::DataSizeRange(range) => range.contains(account.data().len() as u64),
+            RpcFilterType::Lamports(range) => range.contains(account.lamports()),
+            RpcFilterType::IntRange(range) => range.matches(account.data()),
+            RpcFilterType::Or(filters) => filters.iter().any(|filter| filter.allows(account)),
         }
     }
 }
@@ -108,6 +137,136 @@ pub enum RpcFilterError {
     Base58DecodeError(#[from] bs58::decode::Error),
     #[error("base64 decode error")]
     Base64DecodeError(#[from] base64::DecodeError),
+    #[error("range min should not be greater than max")]
+    InvalidRange,
+    #[error("range bound does not fit the integer type")]
+    IntRangeBoundOutOfBounds,
+    #[error("OR group should contain at least one filter")]
+    EmptyOr,
+    #[error("OR group should contain no more than 4 filters")]
+    TooManyOrFilters,
+    #[error("OR groups cannot be nested")]
+    NestedOr,
+}
+
+/// An inclusive range, unbounded on the sides without a bound
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueRange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<u64>,
+}
+
+impl ValueRange {
+    pub fn verify(&self) -> Result<(), RpcFilterError> {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min > max => Err(RpcFilterError::InvalidRange),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        self.min.map(|min| value >= min).unwrap_or(true)
+            && self.max.map(|max| value <= max).unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IntType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+}
+
+impl IntType {
+    fn size(&self) -> usize {
+        match self {
+            IntType::U8 | IntType::I8 => 1,
+            IntType::U16 | IntType::I16 => 2,
+            IntType::U32 | IntType::I32 => 4,
+            IntType::U64 | IntType::I64 => 8,
+        }
+    }
+
+    fn bounds(&self) -> (i128, i128) {
+        match self {
+            IntType::U8 => (u8::MIN.into(), u8::MAX.into()),
+            IntType::U16 => (u16::MIN.into(), u16::MAX.into()),
+            IntType::U32 => (u32::MIN.into(), u32::MAX.into()),
+            IntType::U64 => (u64::MIN.into(), u64::MAX.into()),
+            IntType::I8 => (i8::MIN.into(), i8::MAX.into()),
+            IntType::I16 => (i16::MIN.into(), i16::MAX.into()),
+            IntType::I32 => (i32::MIN.into(), i32::MAX.into()),
+            IntType::I64 => (i64::MIN.into(), i64::MAX.into()),
+        }
+    }
+
+    /// Read a little-endian integer of this type from the start of `bytes`
+    fn read(&self, bytes: &[u8]) -> Option<i128> {
+        let bytes = bytes.get(..self.size())?;
+        Some(match self {
+            IntType::U8 => bytes[0].into(),
+            IntType::U16 => u16::from_le_bytes(bytes.try_into().ok()?).into(),
+            IntType::U32 => u32::from_le_bytes(bytes.try_into().ok()?).into(),
+            IntType::U64 => u64::from_le_bytes(bytes.try_into().ok()?).into(),
+            IntType::I8 => (bytes[0] as i8).into(),
+            IntType::I16 => i16::from_le_bytes(bytes.try_into().ok()?).into(),
+            IntType::I32 => i32::from_le_bytes(bytes.try_into().ok()?).into(),
+            IntType::I64 => i64::from_le_bytes(bytes.try_into().ok()?).into(),
+        })
+    }
+}
+
+/// An inclusive range of values of a little-endian integer in the account data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntRange {
+    /// Data offset of the integer
+    pub offset: usize,
+    pub int_type: IntType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<i128>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<i128>,
+}
+
+impl IntRange {
+    pub fn verify(&self) -> Result<(), RpcFilterError> {
+        let (type_min, type_max) = self.int_type.bounds();
+        let in_bounds = |bound: Option<i128>| {
+            bound
+                .map(|bound| (type_min..=type_max).contains(&bound))
+                .unwrap_or(true)
+        };
+        if !in_bounds(self.min) || !in_bounds(self.max) {
+            return Err(RpcFilterError::IntRangeBoundOutOfBounds);
+        }
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min > max => Err(RpcFilterError::InvalidRange),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn matches(&self, data: &[u8]) -> bool {
+        match data
+            .get(self.offset..)
+            .and_then(|bytes| self.int_type.read(bytes))
+        {
+            Some(value) => {
+                self.min.map(|min| value >= min).unwrap_or(true)
+                    && self.max.map(|max| value <= max).unwrap_or(true)
+            }
+            None => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -290,7 +449,7 @@ pub fn maybe_map_filters(
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {super::*, solana_sdk::pubkey::Pubkey};
 
     #[test]
     fn test_worst_case_encoded_tx_goldens() {
@@ -393,4 +552,124 @@ mod tests {
             Err(RpcFilterError::DataTooLarge)
         );
     }
+
+    #[test]
+    fn test_range_filters() {
+        let mut data = vec![0u8; 16];
+        data[4..12].copy_from_slice(&(-5i64).to_le_bytes());
+        data[12..14].copy_from_slice(&300u16.to_le_bytes());
+        let mut account = AccountSharedData::new(42, data.len(), &Pubkey::default());
+        account.set_data_from_slice(&data);
+
+        let int_range = |offset, int_type, min, max| {
+            RpcFilterType::IntRange(IntRange {
+                offset,
+                int_type,
+                min,
+                max,
+            })
+        };
+        assert!(int_range(4, IntType::I64, Some(-10), Some(0)).allows(&account));
+        assert!(!int_range(4, IntType::I64, Some(0), None).allows(&account));
+        assert!(int_range(12, IntType::U16, Some(300), Some(300)).allows(&account));
+        assert!(!int_range(12, IntType::U16, None, Some(299)).allows(&account));
+        // Integer overruns data
+        assert!(!int_range(12, IntType::U64, None, None).allows(&account));
+        assert!(!int_range(17, IntType::U8, None, None).allows(&account));
+
+        let range = |min, max| ValueRange { min, max };
+        assert!(RpcFilterType::Lamports(range(Some(42), None)).allows(&account));
+        assert!(!RpcFilterType::Lamports(range(None, Some(41))).allows(&account));
+        assert!(RpcFilterType::DataSizeRange(range(Some(10), Some(20))).allows(&account));
+        assert!(!RpcFilterType::DataSizeRange(range(Some(17), None)).allows(&account));
+
+        assert!(!RpcFilterType::NotMemcmp(Memcmp::new_raw_bytes(0, vec![0, 0])).allows(&account));
+        assert!(RpcFilterType::NotMemcmp(Memcmp::new_raw_bytes(0, vec![1])).allows(&account));
+
+        assert!(RpcFilterType::Or(vec![
+            RpcFilterType::DataSize(1),
+            RpcFilterType::Lamports(range(Some(40), None)),
+        ])
+        .allows(&account));
+        assert!(!RpcFilterType::Or(vec![
+            RpcFilterType::DataSize(1),
+            RpcFilterType::Lamports(range(None, Some(40))),
+        ])
+        .allows(&account));
+    }
+
+    #[test]
+    fn test_verify_range_filters() {
+        let range = |min, max| ValueRange { min, max };
+        assert_eq!(
+            RpcFilterType::Lamports(range(Some(1), Some(1))).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::DataSizeRange(range(Some(2), Some(1))).verify(),
+            Err(RpcFilterError::InvalidRange)
+        );
+
+        let int_range = |int_type, min, max| {
+            RpcFilterType::IntRange(IntRange {
+                offset: 0,
+                int_type,
+                min,
+                max,
+            })
+        };
+        assert_eq!(int_range(IntType::U64, Some(0), None).verify(), Ok(()));
+        assert_eq!(
+            int_range(IntType::U8, None, Some(256)).verify(),
+            Err(RpcFilterError::IntRangeBoundOutOfBounds)
+        );
+        assert_eq!(
+            int_range(IntType::U32, Some(-1), None).verify(),
+            Err(RpcFilterError::IntRangeBoundOutOfBounds)
+        );
+        assert_eq!(
+            int_range(IntType::I16, Some(1), Some(-1)).verify(),
+            Err(RpcFilterError::InvalidRange)
+        );
+
+        assert_eq!(
+            RpcFilterType::Or(vec![]).verify(),
+            Err(RpcFilterError::EmptyOr)
+        );
+        assert_eq!(
+            RpcFilterType::Or(vec![RpcFilterType::DataSize(1); 5]).verify(),
+            Err(RpcFilterError::TooManyOrFilters)
+        );
+        assert_eq!(
+            RpcFilterType::Or(vec![RpcFilterType::Or(vec![RpcFilterType::DataSize(1)])]).verify(),
+            Err(RpcFilterError::NestedOr)
+        );
+        assert_eq!(
+            RpcFilterType::Or(vec![RpcFilterType::DataSizeRange(range(Some(2), Some(1)))]).verify(),
+            Err(RpcFilterError::InvalidRange)
+        );
+    }
+
+    #[test]
+    fn test_range_filter_serde() {
+        let filter: RpcFilterType = serde_json::from_str(
+            r#"{"or":[{"intRange":{"offset":8,"intType":"u64","min":1}},{"lamports":{"max":10}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            filter,
+            RpcFilterType::Or(vec![
+                RpcFilterType::IntRange(IntRange {
+                    offset: 8,
+                    int_type: IntType::U64,
+                    min: Some(1),
+                    max: None,
+                }),
+                RpcFilterType::Lamports(ValueRange {
+                    min: None,
+                    max: Some(10),
+                }),
+            ])
+        );
+    }
 }
@@ -12,6 +12,7 @@ pub enum RpcRequest {
         method: &'static str,
     },
     DeregisterNode,
+    GetAccountHistory,
     GetAccountInfo,
     GetBalance,
     GetBlock,
@@ -73,6 +74,7 @@ pub enum RpcRequest {
     GetMinimumBalanceForRentExemption,
     GetMultipleAccounts,
     GetProgramAccounts,
+    GetProgramAccountsPage,
     #[deprecated(
         since = "1.9.0",
         note = "Please use RpcRequest::GetLatestBlockhash instead"
@@ -111,6 +113,7 @@ pub enum RpcRequest {
     RegisterNode,
     RequestAirdrop,
     SendTransaction,
+    SimulateBundle,
     SimulateTransaction,
     SignVote,
 }
@@ -121,6 +124,7 @@ impl fmt::Display for RpcRequest {
         let method = match self {
             RpcRequest::Custom { method } => method,
             RpcRequest::DeregisterNode => "deregisterNode",
+            RpcRequest::GetAccountHistory => "getAccountHistory",
             RpcRequest::GetAccountInfo => "getAccountInfo",
             RpcRequest::GetBalance => "getBalance",
             RpcRequest::GetBlock => "getBlock",
@@ -156,6 +160,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetMinimumBalanceForRentExemption => "getMinimumBalanceForRentExemption",
             RpcRequest::GetMultipleAccounts => "getMultipleAccounts",
             RpcRequest::GetProgramAccounts => "getProgramAccounts",
+            RpcRequest::GetProgramAccountsPage => "getProgramAccountsPage",
             RpcRequest::GetRecentBlockhash => "getRecentBlockhash",
             RpcRequest::GetRecentPerformanceSamples => "getRecentPerformanceSamples",
             RpcRequest::GetHighestSnapshotSlot => "getHighestSnapshotSlot",
@@ -186,6 +191,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::RegisterNode => "registerNode",
             RpcRequest::RequestAirdrop => "requestAirdrop",
             RpcRequest::SendTransaction => "sendTransaction",
+            RpcRequest::SimulateBundle => "simulateBundle",
             RpcRequest::SimulateTransaction => "simulateTransaction",
             RpcRequest::SignVote => "signVote",
         };
@@ -199,9 +205,14 @@ pub const MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS: usize = 256;
 pub const MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE: u64 = 10_000;
 pub const MAX_GET_CONFIRMED_BLOCKS_RANGE: u64 = 500_000;
 pub const MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT: usize = 1_000;
+pub const MAX_GET_SIGNATURES_FOR_ADDRESS_SCANNED_SIGNATURES: usize = 10_000;
 pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
 pub const NUM_LARGEST_ACCOUNTS: usize = 20;
 pub const MAX_GET_PROGRAM_ACCOUNT_FILTERS: usize = 4;
+pub const MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT: usize = 1_000;
+pub const MAX_TRANSACTION_SUBSCRIBE_FILTER_ACCOUNTS: usize = 256;
+pub const MAX_SIMULATE_BUNDLE_TRANSACTIONS: usize = 16;
+pub const MAX_SIMULATE_ACCOUNT_OVERRIDES: usize = 64;
 pub const MAX_GET_SLOT_LEADERS: usize = 5000;
 
 // Limit the length of the `epoch_credits` array for each validator in a `get_vote_accounts`
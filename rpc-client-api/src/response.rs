@@ -7,11 +7,13 @@ use {
         fee_calculator::{FeeCalculator, FeeRateGovernor},
         hash::Hash,
         inflation::Inflation,
+        instruction::InstructionError,
         transaction::{Result, TransactionError},
     },
     solana_transaction_status::{
-        ConfirmedTransactionStatusWithSignature, TransactionConfirmationStatus, UiConfirmedBlock,
-        UiTransactionReturnData,
+        ConfirmedTransactionStatusWithSignature, EncodedTransactionWithStatusMeta,
+        TransactionConfirmationStatus, UiConfirmedBlock, UiTransactionReturnData,
+        UiTransactionTokenBalance,
     },
     std::{collections::HashMap, fmt, net::SocketAddr, str::FromStr},
     thiserror::Error,
@@ -175,6 +177,17 @@ pub struct RpcKeyedAccount {
     pub account: UiAccount,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProgramAccountsPage {
+    pub accounts: Vec<RpcKeyedAccount>,
+    /// Cursor for the next page, `None` once all the accounts were returned
+    pub next: Option<String>,
+    /// True if the bank the previous pages were read from is gone, so this page was read from
+    /// the bank at the context slot instead
+    pub slot_changed: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SlotInfo {
     pub slot: Slot,
@@ -400,6 +413,60 @@ pub struct RpcSimulateTransactionResult {
     pub accounts: Option<Vec<Option<UiAccount>>>,
     pub units_consumed: Option<u64>,
     pub return_data: Option<UiTransactionReturnData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<RpcExecutionTrace>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcExecutionTrace {
+    /// One frame per top-level instruction
+    pub frames: Vec<RpcInstructionFrame>,
+    /// Set if frames or BPF instructions were dropped because of the node's trace limits
+    pub truncated: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInstructionFrame {
+    pub program_id: String,
+    pub stack_height: usize,
+    /// Compute units consumed by this frame, including its inner frames
+    pub compute_units_consumed: u64,
+    pub err: Option<InstructionError>,
+    pub account_diffs: Vec<RpcAccountDiff>,
+    pub bpf_trace: Option<Vec<String>>,
+    pub inner_frames: Vec<RpcInstructionFrame>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountDiff {
+    pub pubkey: String,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+    pub pre_owner: String,
+    pub post_owner: String,
+    pub pre_data_len: usize,
+    pub post_data_len: usize,
+    pub data_changes: Vec<RpcAccountDataChange>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountDataChange {
+    pub offset: usize,
+    /// The account data written at `offset`, base-64 encoded
+    pub data: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleResult {
+    /// The error of the transaction the bundle stopped at, if any
+    pub err: Option<TransactionError>,
+    /// The results of the transactions run, in order
+    pub transaction_results: Vec<RpcSimulateTransactionResult>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -461,6 +528,20 @@ pub struct RpcConfirmedTransactionStatusWithSignature {
     pub confirmation_status: Option<TransactionConfirmationStatus>,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountBalanceChange {
+    pub signature: String,
+    pub slot: Slot,
+    pub err: Option<TransactionError>,
+    pub block_time: Option<UnixTimestamp>,
+    pub confirmation_status: Option<TransactionConfirmationStatus>,
+    pub pre_balance: Option<u64>,  // lamports
+    pub post_balance: Option<u64>, // lamports
+    pub pre_token_balance: Option<UiTransactionTokenBalance>,
+    pub post_token_balance: Option<UiTransactionTokenBalance>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcPerfSample {
@@ -497,6 +578,21 @@ pub struct RpcBlockUpdate {
     pub err: Option<RpcBlockUpdateError>,
 }
 
+#[derive(Clone, Deserialize, Serialize, Debug, Error, Eq, PartialEq)]
+pub enum RpcTransactionUpdateError {
+    #[error("unsupported transaction version ({0})")]
+    UnsupportedTransactionVersion(u8),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionUpdate {
+    pub signature: String, // Signature as base58 string
+    pub slot: Slot,
+    pub transaction: Option<EncodedTransactionWithStatusMeta>,
+    pub err: Option<RpcTransactionUpdateError>,
+}
+
 impl From<ConfirmedTransactionStatusWithSignature> for RpcConfirmedTransactionStatusWithSignature {
     fn from(value: ConfirmedTransactionStatusWithSignature) -> Self {
         let ConfirmedTransactionStatusWithSignature {
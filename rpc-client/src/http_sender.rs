@@ -74,14 +74,14 @@ impl HttpSender {
     }
 }
 
-struct StatsUpdater<'a> {
+pub(crate) struct StatsUpdater<'a> {
     stats: &'a RwLock<RpcTransportStats>,
     request_start_time: Instant,
     rate_limited_time: Duration,
 }
 
 impl<'a> StatsUpdater<'a> {
-    fn new(stats: &'a RwLock<RpcTransportStats>) -> Self {
+    pub(crate) fn new(stats: &'a RwLock<RpcTransportStats>) -> Self {
         Self {
             stats,
             request_start_time: Instant::now(),
@@ -103,6 +103,57 @@ impl<'a> Drop for StatsUpdater<'a> {
     }
 }
 
+/// Extracts the result of a JSON RPC response, or the error it carries
+pub(crate) fn parse_response_json(mut json: serde_json::Value) -> Result<serde_json::Value> {
+    if json["error"].is_object() {
+        return match serde_json::from_value::<RpcErrorObject>(json["error"].clone()) {
+            Ok(rpc_error_object) => {
+                let data = match rpc_error_object.code {
+                    custom_error::JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE => {
+                        match serde_json::from_value::<RpcSimulateTransactionResult>(
+                            json["error"]["data"].clone(),
+                        ) {
+                            Ok(data) => RpcResponseErrorData::SendTransactionPreflightFailure(data),
+                            Err(err) => {
+                                debug!(
+                                    "Failed to deserialize RpcSimulateTransactionResult: {:?}",
+                                    err
+                                );
+                                RpcResponseErrorData::Empty
+                            }
+                        }
+                    }
+                    custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY => {
+                        match serde_json::from_value::<custom_error::NodeUnhealthyErrorData>(
+                            json["error"]["data"].clone(),
+                        ) {
+                            Ok(custom_error::NodeUnhealthyErrorData { num_slots_behind }) => {
+                                RpcResponseErrorData::NodeUnhealthy { num_slots_behind }
+                            }
+                            Err(_err) => RpcResponseErrorData::Empty,
+                        }
+                    }
+                    _ => RpcResponseErrorData::Empty,
+                };
+
+                Err(RpcError::RpcResponseError {
+                    code: rpc_error_object.code,
+                    message: rpc_error_object.message,
+                    data,
+                }
+                .into())
+            }
+            Err(err) => Err(RpcError::RpcRequestError(format!(
+                "Failed to deserialize RPC error response: {} [{}]",
+                serde_json::to_string(&json["error"]).unwrap(),
+                err
+            ))
+            .into()),
+        };
+    }
+    Ok(json["result"].take())
+}
+
 #[async_trait]
 impl RpcSender for HttpSender {
     fn get_transport_stats(&self) -> RpcTransportStats {
@@ -160,47 +211,8 @@ impl RpcSender for HttpSender {
                 return Err(response.error_for_status().unwrap_err().into());
             }
 
-            let mut json = response.json::<serde_json::Value>().await?;
-            if json["error"].is_object() {
-                return match serde_json::from_value::<RpcErrorObject>(json["error"].clone()) {
-                    Ok(rpc_error_object) => {
-                        let data = match rpc_error_object.code {
-                                    custom_error::JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE => {
-                                        match serde_json::from_value::<RpcSimulateTransactionResult>(json["error"]["data"].clone()) {
-                                            Ok(data) => RpcResponseErrorData::SendTransactionPreflightFailure(data),
-                                            Err(err) => {
-                                                debug!("Failed to deserialize RpcSimulateTransactionResult: {:?}", err);
-                                                RpcResponseErrorData::Empty
-                                            }
-                                        }
-                                    },
-                                    custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY => {
-                                        match serde_json::from_value::<custom_error::NodeUnhealthyErrorData>(json["error"]["data"].clone()) {
-                                            Ok(custom_error::NodeUnhealthyErrorData {num_slots_behind}) => RpcResponseErrorData::NodeUnhealthy {num_slots_behind},
-                                            Err(_err) => {
-                                                RpcResponseErrorData::Empty
-                                            }
-                                        }
-                                    },
-                                    _ => RpcResponseErrorData::Empty
-                                };
-
-                        Err(RpcError::RpcResponseError {
-                            code: rpc_error_object.code,
-                            message: rpc_error_object.message,
-                            data,
-                        }
-                        .into())
-                    }
-                    Err(err) => Err(RpcError::RpcRequestError(format!(
-                        "Failed to deserialize RPC error response: {} [{}]",
-                        serde_json::to_string(&json["error"]).unwrap(),
-                        err
-                    ))
-                    .into()),
-                };
-            }
-            return Ok(json["result"].take());
+            let json = response.json::<serde_json::Value>().await?;
+            return parse_response_json(json);
         }
     }
 
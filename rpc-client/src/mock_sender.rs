@@ -349,6 +349,7 @@ impl RpcSender for MockSender {
                     accounts: None,
                     units_consumed: None,
                     return_data: None,
+                    trace: None,
                 },
             })?,
             "getMinimumBalanceForRentExemption" => json![20],
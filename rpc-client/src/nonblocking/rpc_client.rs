@@ -23,6 +23,7 @@ use {
             SerializableTransaction,
         },
         rpc_sender::*,
+        ws_sender::WsSender,
     },
     bincode::serialize,
     log::*,
@@ -509,6 +510,53 @@ impl RpcClient {
         Self::new_with_timeout(url, timeout)
     }
 
+    /// Create a WebSocket `RpcClient`.
+    ///
+    /// The URL is a WebSocket URL of a node which serves RPC requests on its
+    /// PubSub endpoint, usually for port 8900, as in "ws://localhost:8900".
+    ///
+    /// The client has a default timeout of 30 seconds, and a default [commitment
+    /// level][cl] of [`Finalized`](CommitmentLevel::Finalized).
+    ///
+    /// [cl]: https://docs.solana.com/developing/clients/jsonrpc-api#configuring-state-commitment
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// let url = "ws://localhost:8900".to_string();
+    /// let client = RpcClient::new_websocket(url);
+    /// ```
+    pub fn new_websocket(url: String) -> Self {
+        Self::new_websocket_with_commitment(url, CommitmentConfig::default())
+    }
+
+    /// Create a WebSocket `RpcClient` with specified [commitment level][cl].
+    ///
+    /// [cl]: https://docs.solana.com/developing/clients/jsonrpc-api#configuring-state-commitment
+    ///
+    /// The URL is a WebSocket URL of a node which serves RPC requests on its
+    /// PubSub endpoint, usually for port 8900, as in "ws://localhost:8900".
+    ///
+    /// The client has a default timeout of 30 seconds, and a user-specified
+    /// [`CommitmentLevel`] via [`CommitmentConfig`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_sdk::commitment_config::CommitmentConfig;
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// let url = "ws://localhost:8900".to_string();
+    /// let commitment_config = CommitmentConfig::processed();
+    /// let client = RpcClient::new_websocket_with_commitment(url, commitment_config);
+    /// ```
+    pub fn new_websocket_with_commitment(url: String, commitment_config: CommitmentConfig) -> Self {
+        Self::new_sender(
+            WsSender::new(url),
+            RpcClientConfig::with_commitment(commitment_config),
+        )
+    }
+
     /// Get the configured url of the client's sender
     pub fn url(&self) -> String {
         self.sender.url()
@@ -3045,7 +3093,7 @@ impl RpcClient {
             until: config.until.map(|signature| signature.to_string()),
             limit: config.limit,
             commitment: config.commitment,
-            min_context_slot: None,
+            ..RpcSignaturesForAddressConfig::default()
         };
 
         let result: Vec<RpcConfirmedTransactionStatusWithSignature> = self
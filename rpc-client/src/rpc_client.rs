@@ -20,6 +20,7 @@ use {
         mock_sender::MockSender,
         nonblocking::{self, rpc_client::get_rpc_request_str},
         rpc_sender::*,
+        ws_sender::WsSender,
     },
     serde::Serialize,
     serde_json::Value,
@@ -581,6 +582,59 @@ impl RpcClient {
         Self::new_with_timeout(url, timeout)
     }
 
+    /// Create a WebSocket `RpcClient`.
+    ///
+    /// The URL is a WebSocket URL of a node which serves RPC requests on its
+    /// PubSub endpoint, usually for port 8900, as in "ws://localhost:8900".
+    ///
+    /// The client has a default timeout of 30 seconds, and a default [commitment
+    /// level][cl] of [`Finalized`].
+    ///
+    /// [cl]: https://docs.solana.com/developing/clients/jsonrpc-api#configuring-state-commitment
+    /// [`Finalized`]: solana_sdk::commitment_config::CommitmentLevel::Finalized
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// let url = "ws://localhost:8900".to_string();
+    /// let client = RpcClient::new_websocket(url);
+    /// ```
+    pub fn new_websocket<U: ToString>(url: U) -> Self {
+        Self::new_websocket_with_commitment(url, CommitmentConfig::default())
+    }
+
+    /// Create a WebSocket `RpcClient` with specified [commitment level][cl].
+    ///
+    /// [cl]: https://docs.solana.com/developing/clients/jsonrpc-api#configuring-state-commitment
+    ///
+    /// The URL is a WebSocket URL of a node which serves RPC requests on its
+    /// PubSub endpoint, usually for port 8900, as in "ws://localhost:8900".
+    ///
+    /// The client has a default timeout of 30 seconds, and a user-specified
+    /// [`CommitmentLevel`] via [`CommitmentConfig`].
+    ///
+    /// [`CommitmentLevel`]: solana_sdk::commitment_config::CommitmentLevel
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_sdk::commitment_config::CommitmentConfig;
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// let url = "ws://localhost:8900".to_string();
+    /// let commitment_config = CommitmentConfig::processed();
+    /// let client = RpcClient::new_websocket_with_commitment(url, commitment_config);
+    /// ```
+    pub fn new_websocket_with_commitment<U: ToString>(
+        url: U,
+        commitment_config: CommitmentConfig,
+    ) -> Self {
+        Self::new_sender(
+            WsSender::new(url),
+            RpcClientConfig::with_commitment(commitment_config),
+        )
+    }
+
     /// Get the configured url of the client's sender
     pub fn url(&self) -> String {
         (self.rpc_client.as_ref()).url()
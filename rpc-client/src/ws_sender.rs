@@ -0,0 +1,173 @@
+//! Nonblocking [`RpcSender`] over a WebSocket.
+
+use {
+    crate::{
+        http_sender::{parse_response_json, StatsUpdater},
+        rpc_sender::*,
+    },
+    async_trait::async_trait,
+    futures_util::{SinkExt, StreamExt},
+    solana_rpc_client_api::{
+        client_error::{ErrorKind as ClientErrorKind, Result},
+        request::RpcRequest,
+    },
+    std::{
+        io,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            RwLock,
+        },
+        time::Duration,
+    },
+    tokio::{net::TcpStream, sync::Mutex, time::timeout},
+    tokio_tungstenite::{
+        connect_async,
+        tungstenite::{self, Message},
+        MaybeTlsStream, WebSocketStream,
+    },
+};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+pub struct WsSender {
+    url: String,
+    timeout: Duration,
+    stream: Mutex<Option<WsStream>>,
+    request_id: AtomicU64,
+    stats: RwLock<RpcTransportStats>,
+}
+
+/// Nonblocking [`RpcSender`] over a WebSocket.
+///
+/// The connection is opened on the first request, and reopened after a request fails. Requests
+/// are sent one at a time over the connection.
+impl WsSender {
+    /// Create a WebSocket RPC sender.
+    ///
+    /// The URL is a WebSocket URL, usually for port 8900, as in
+    /// "ws://localhost:8900". The sender has a default timeout of 30 seconds.
+    pub fn new<U: ToString>(url: U) -> Self {
+        Self::new_with_timeout(url, Duration::from_secs(30))
+    }
+
+    /// Create a WebSocket RPC sender.
+    ///
+    /// The URL is a WebSocket URL, usually for port 8900.
+    pub fn new_with_timeout<U: ToString>(url: U, timeout: Duration) -> Self {
+        Self {
+            url: url.to_string(),
+            timeout,
+            stream: Mutex::new(None),
+            request_id: AtomicU64::new(0),
+            stats: RwLock::new(RpcTransportStats::default()),
+        }
+    }
+
+    /// Sends a request and waits for the matching response, opening the connection if needed
+    async fn send_request(
+        &self,
+        stream: &mut Option<WsStream>,
+        request_id: u64,
+        request_json: String,
+    ) -> Result<serde_json::Value> {
+        if stream.is_none() {
+            let (ws, _response) = connect_async(self.url.as_str())
+                .await
+                .map_err(websocket_error)?;
+            *stream = Some(ws);
+        }
+        let ws = stream.as_mut().unwrap();
+        ws.send(Message::Text(request_json))
+            .await
+            .map_err(websocket_error)?;
+
+        loop {
+            let text = match ws.next().await {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Ping(data))) => {
+                    ws.send(Message::Pong(data))
+                        .await
+                        .map_err(websocket_error)?;
+                    continue;
+                }
+                Some(Ok(Message::Close(_frame))) | None => {
+                    return Err(websocket_error(tungstenite::Error::ConnectionClosed).into())
+                }
+                Some(Ok(_message)) => continue,
+                Some(Err(err)) => return Err(websocket_error(err).into()),
+            };
+            let json: serde_json::Value = serde_json::from_str(&text)?;
+            // Skip subscription notifications, and responses to requests which timed out.
+            // Errors about unparsable requests carry a null id.
+            if json["id"].as_u64() == Some(request_id)
+                || (json["id"].is_null() && json["error"].is_object())
+            {
+                return Ok(json);
+            }
+        }
+    }
+}
+
+fn websocket_error(err: tungstenite::Error) -> ClientErrorKind {
+    match err {
+        tungstenite::Error::Io(err) => ClientErrorKind::Io(err),
+        err => ClientErrorKind::Custom(format!("WebSocket error: {}", err)),
+    }
+}
+
+#[async_trait]
+impl RpcSender for WsSender {
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.stats.read().unwrap().clone()
+    }
+
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let _stats_updater = StatsUpdater::new(&self.stats);
+
+        let request_id = self.request_id.fetch_add(1, Ordering::Relaxed);
+        let request_json = request.build_request_json(request_id, params).to_string();
+
+        let mut stream = self.stream.lock().await;
+        let json = match timeout(
+            self.timeout,
+            self.send_request(&mut stream, request_id, request_json),
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(_elapsed) => Err(ClientErrorKind::Io(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "WebSocket request timed out",
+            ))
+            .into()),
+        };
+        if json.is_err() {
+            // The connection may be left with a pending response, so start over with a new one
+            *stream = None;
+        }
+        parse_response_json(json?)
+    }
+
+    fn url(&self) -> String {
+        self.url.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn ws_sender_connection_refused() {
+        let ws_sender = WsSender::new("ws://localhost:1234".to_string());
+        assert!(ws_sender
+            .send(RpcRequest::GetVersion, serde_json::Value::Null)
+            .await
+            .is_err());
+        assert_eq!(ws_sender.get_transport_stats().request_count, 1);
+    }
+}
@@ -2,8 +2,12 @@
 
 use {
     crate::{
-        max_slots::MaxSlots, optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
-        parsed_token_accounts::*, rpc_cache::LargestAccountsCache, rpc_health::*,
+        max_slots::MaxSlots,
+        optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
+        parsed_token_accounts::*,
+        rpc_cache::LargestAccountsCache,
+        rpc_health::*,
+        rpc_service::{RpcClientId, RpcRateLimitConfig, RpcRateLimiter},
     },
     bincode::{config::Options, serialize},
     crossbeam_channel::{unbounded, Receiver, Sender},
@@ -11,20 +15,23 @@ use {
     jsonrpc_derive::rpc,
     serde::{Deserialize, Serialize},
     solana_account_decoder::{
-        parse_token::{is_known_spl_token_id, token_amount_to_ui_amount, UiTokenAmount},
-        UiAccount, UiAccountEncoding, UiDataSliceConfig, MAX_BASE58_BYTES,
+        parse_token::{
+            is_known_spl_token_id, spl_token_ids, token_amount_to_ui_amount, UiTokenAmount,
+        },
+        UiAccount, UiAccountData, UiAccountEncoding, UiDataSliceConfig, MAX_BASE58_BYTES,
     },
     solana_entry::entry::Entry,
     solana_faucet::faucet::request_airdrop_transaction,
     solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
     solana_ledger::{
-        blockstore::{Blockstore, SignatureInfosForAddress},
+        blockstore::{AccountBalanceChange, Blockstore, SignatureInfosForAddress},
         blockstore_db::BlockstoreError,
         get_tmp_ledger_path,
         leader_schedule_cache::LeaderScheduleCache,
     },
     solana_metrics::inc_new_counter_info,
     solana_perf::packet::PACKET_DATA_SIZE,
+    solana_program_runtime::trace_collector::{ExecutionTrace, InstructionFrame, TraceConfig},
     solana_rpc_client_api::{
         config::*,
         custom_error::RpcCustomError,
@@ -33,19 +40,26 @@ use {
         request::{
             TokenAccountsFilter, DELINQUENT_VALIDATOR_SLOT_DISTANCE,
             MAX_GET_CONFIRMED_BLOCKS_RANGE, MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT,
-            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
+            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE,
+            MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
+            MAX_GET_SIGNATURES_FOR_ADDRESS_SCANNED_SIGNATURES,
             MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
-            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, NUM_LARGEST_ACCOUNTS,
+            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, MAX_SIMULATE_ACCOUNT_OVERRIDES,
+            MAX_SIMULATE_BUNDLE_TRANSACTIONS, NUM_LARGEST_ACCOUNTS,
         },
         response::{Response as RpcResponse, *},
     },
     solana_runtime::{
+        account_overrides::AccountOverrides,
         accounts::AccountAddressFilter,
-        accounts_index::{AccountIndex, AccountSecondaryIndexes, IndexKey, ScanConfig},
+        accounts_index::{AccountIndex, AccountSecondaryIndexes, IndexKey, ScanConfig, ScanResult},
         bank::{Bank, TransactionSimulationResult},
         bank_forks::BankForks,
         commitment::{BlockCommitmentArray, BlockCommitmentCache, CommitmentSlots},
-        inline_spl_token::{SPL_TOKEN_ACCOUNT_MINT_OFFSET, SPL_TOKEN_ACCOUNT_OWNER_OFFSET},
+        inline_spl_token::{
+            SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET, SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET,
+            SPL_TOKEN_ACCOUNT_MINT_OFFSET, SPL_TOKEN_ACCOUNT_OWNER_OFFSET,
+        },
         inline_spl_token_2022::{self, ACCOUNTTYPE_ACCOUNT},
         non_circulating_supply::calculate_non_circulating_supply,
         prioritization_fee_cache::PrioritizationFeeCache,
@@ -87,7 +101,7 @@ use {
         BlockEncodingOptions, ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
         ConfirmedTransactionWithStatusMeta, EncodedConfirmedTransactionWithStatusMeta, Reward,
         RewardType, TransactionBinaryEncoding, TransactionConfirmationStatus, TransactionStatus,
-        UiConfirmedBlock, UiTransactionEncoding,
+        TransactionWithStatusMeta, UiConfirmedBlock, UiTransactionEncoding,
     },
     solana_vote_program::vote_state::{VoteState, MAX_LOCKOUT_HISTORY},
     spl_token_2022::{
@@ -101,6 +115,7 @@ use {
         collections::{HashMap, HashSet},
         convert::TryFrom,
         net::SocketAddr,
+        path::PathBuf,
         str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
@@ -148,6 +163,11 @@ pub struct JsonRpcConfig {
     pub enable_extended_tx_metadata_storage: bool,
     pub faucet_addr: Option<SocketAddr>,
     pub health_check_slot_distance: u64,
+    /// `/health/ready` fails once more pubsub notifications than this are queued
+    pub health_check_max_pubsub_backlog: Option<usize>,
+    /// `/health/ready` fails once the transaction statuses written to the blockstore trail the
+    /// highest bank by more than this many slots
+    pub health_check_max_blockstore_write_lag: Option<u64>,
     pub rpc_bigtable_config: Option<RpcBigtableConfig>,
     pub max_multiple_accounts: Option<usize>,
     pub account_indexes: AccountSecondaryIndexes,
@@ -157,6 +177,14 @@ pub struct JsonRpcConfig {
     pub obsolete_v1_7_api: bool,
     pub rpc_scan_and_fix_roots: bool,
     pub max_request_body_size: Option<usize>,
+    /// Maximum number of instruction frames recorded for a `simulateTransaction` trace; traces
+    /// are disabled if `None`
+    pub max_simulation_trace_frames: Option<usize>,
+    /// Maximum number of BPF instructions recorded per invocation of the program selected for
+    /// instruction tracing; BPF instruction traces are disabled if `None`
+    pub max_simulation_bpf_trace_length: Option<usize>,
+    /// Per-client rate limits of the RPC methods, unlimited if `None`
+    pub rate_limit: Option<RpcRateLimitConfig>,
 }
 
 impl JsonRpcConfig {
@@ -174,6 +202,8 @@ pub struct RpcBigtableConfig {
     pub bigtable_instance_name: String,
     pub bigtable_app_profile_id: String,
     pub timeout: Option<Duration>,
+    /// Use a local RocksDB database at this path instead of the BigTable instance
+    pub local_storage_path: Option<PathBuf>,
 }
 
 impl Default for RpcBigtableConfig {
@@ -185,6 +215,7 @@ impl Default for RpcBigtableConfig {
             bigtable_instance_name,
             bigtable_app_profile_id,
             timeout: None,
+            local_storage_path: None,
         }
     }
 }
@@ -209,6 +240,9 @@ pub struct JsonRpcRequestProcessor {
     leader_schedule_cache: Arc<LeaderScheduleCache>,
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+    rate_limiter: Option<Arc<RpcRateLimiter>>,
+    /// The client of the request being processed
+    rate_limit_client: RpcClientId,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
@@ -297,6 +331,29 @@ impl JsonRpcRequestProcessor {
         self.bank(None).genesis_creation_time()
     }
 
+    pub(crate) fn config(&self) -> &JsonRpcConfig {
+        &self.config
+    }
+
+    pub(crate) fn rate_limiter(&self) -> Option<&Arc<RpcRateLimiter>> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// The rate limiter and the client charged for the request being processed
+    pub(crate) fn rate_limit(&self) -> Option<(&RpcRateLimiter, &RpcClientId)> {
+        self.rate_limiter
+            .as_deref()
+            .map(|rate_limiter| (rate_limiter, &self.rate_limit_client))
+    }
+
+    /// A processor for the requests of `client`
+    pub(crate) fn with_rate_limit_client(&self, client: RpcClientId) -> Self {
+        Self {
+            rate_limit_client: client,
+            ..self.clone()
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: JsonRpcConfig,
@@ -317,6 +374,10 @@ impl JsonRpcRequestProcessor {
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
     ) -> (Self, Receiver<TransactionInfo>) {
         let (sender, receiver) = unbounded();
+        let rate_limiter = config
+            .rate_limit
+            .clone()
+            .map(|rate_limit| Arc::new(RpcRateLimiter::new(rate_limit)));
         (
             Self {
                 config,
@@ -336,6 +397,8 @@ impl JsonRpcRequestProcessor {
                 leader_schedule_cache,
                 max_complete_transaction_status_slot,
                 prioritization_fee_cache,
+                rate_limiter,
+                rate_limit_client: RpcClientId::default(),
             },
             receiver,
         )
@@ -384,10 +447,16 @@ impl JsonRpcRequestProcessor {
             validator_exit: create_validator_exit(&exit),
             health: Arc::new(RpcHealth::new(
                 cluster_info.clone(),
-                None,
+                Arc::default(),
                 0,
                 exit.clone(),
                 Arc::clone(bank.get_startup_verification_complete()),
+                Arc::new(RwLock::new(ValidatorStartProgress::Running)),
+                bank_forks.clone(),
+                Arc::default(),
+                None,
+                None,
+                None,
             )),
             cluster_info,
             genesis_hash,
@@ -401,6 +470,8 @@ impl JsonRpcRequestProcessor {
             leader_schedule_cache: Arc::new(LeaderScheduleCache::new_from_bank(bank)),
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Arc::new(PrioritizationFeeCache::default()),
+            rate_limiter: None,
+            rate_limit_client: RpcClientId::default(),
         }
     }
 
@@ -477,36 +548,103 @@ impl JsonRpcRequestProcessor {
         })?;
         let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
         optimize_filters(&mut filters);
-        let keyed_accounts = {
-            if let Some(owner) = get_spl_token_owner_filter(program_id, &filters) {
-                self.get_filtered_spl_token_accounts_by_owner(&bank, program_id, &owner, filters)?
-            } else if let Some(mint) = get_spl_token_mint_filter(program_id, &filters) {
-                self.get_filtered_spl_token_accounts_by_mint(&bank, program_id, &mint, filters)?
-            } else {
-                self.get_filtered_program_accounts(&bank, program_id, filters)?
-            }
-        };
-        let accounts = if is_known_spl_token_id(program_id)
-            && encoding == UiAccountEncoding::JsonParsed
-        {
-            get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
-        } else {
-            keyed_accounts
-                .into_iter()
-                .map(|(pubkey, account)| {
-                    Ok(RpcKeyedAccount {
-                        pubkey: pubkey.to_string(),
-                        account: encode_account(&account, &pubkey, encoding, data_slice_config)?,
-                    })
-                })
-                .collect::<Result<Vec<_>>>()?
-        };
+        let keyed_accounts = self.get_keyed_program_accounts(&bank, program_id, filters, None)?;
+        let accounts = encode_keyed_accounts(
+            &bank,
+            program_id,
+            keyed_accounts,
+            encoding,
+            data_slice_config,
+        )?;
         Ok(match with_context {
             true => OptionalContext::Context(new_response(&bank, accounts)),
             false => OptionalContext::NoContext(accounts),
         })
     }
 
+    pub fn get_program_accounts_page(
+        &self,
+        program_id: &Pubkey,
+        config: Option<RpcAccountInfoConfig>,
+        mut filters: Vec<RpcFilterType>,
+        limit: usize,
+        after: Option<ProgramAccountsCursor>,
+    ) -> Result<RpcResponse<RpcProgramAccountsPage>> {
+        let RpcAccountInfoConfig {
+            encoding,
+            data_slice: data_slice_config,
+            commitment,
+            min_context_slot,
+        } = config.unwrap_or_default();
+        // Keep reading from the bank of the first page for as long as it is around
+        let cursor_bank = after.and_then(|after| self.bank_forks.read().unwrap().get(after.slot));
+        let slot_changed = after.is_some() && cursor_bank.is_none();
+        let bank = match cursor_bank {
+            Some(bank) => bank,
+            None => self.get_bank_with_config(RpcContextConfig {
+                commitment,
+                min_context_slot,
+            })?,
+        };
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+        optimize_filters(&mut filters);
+        // Ask for one more account than the limit, to know whether there is a next page
+        let mut keyed_accounts = self.get_keyed_program_accounts(
+            &bank,
+            program_id,
+            filters,
+            Some(AccountsPage {
+                start_after: after.map(|after| after.pubkey),
+                limit: limit.saturating_add(1),
+            }),
+        )?;
+        let next = if keyed_accounts.len() > limit {
+            keyed_accounts.truncate(limit);
+            keyed_accounts.last().map(|(pubkey, _)| {
+                ProgramAccountsCursor {
+                    slot: bank.slot(),
+                    pubkey: *pubkey,
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
+        let accounts = encode_keyed_accounts(
+            &bank,
+            program_id,
+            keyed_accounts,
+            encoding,
+            data_slice_config,
+        )?;
+        Ok(new_response(
+            &bank,
+            RpcProgramAccountsPage {
+                accounts,
+                next,
+                slot_changed,
+            },
+        ))
+    }
+
+    /// Get the program accounts that pass `filters`, through a secondary index when the filters
+    /// allow it
+    fn get_keyed_program_accounts(
+        &self,
+        bank: &Arc<Bank>,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+        page: Option<AccountsPage>,
+    ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+        if let Some(owner) = get_spl_token_owner_filter(program_id, &filters) {
+            self.get_filtered_spl_token_accounts_by_owner(bank, program_id, &owner, filters, page)
+        } else if let Some(mint) = get_spl_token_mint_filter(program_id, &filters) {
+            self.get_filtered_spl_token_accounts_by_mint(bank, program_id, &mint, filters, page)
+        } else {
+            self.get_filtered_program_accounts(bank, program_id, filters, page)
+        }
+    }
+
     pub async fn get_inflation_reward(
         &self,
         addresses: Vec<Pubkey>,
@@ -1568,39 +1706,33 @@ impl JsonRpcRequestProcessor {
         address: Pubkey,
         before: Option<Signature>,
         until: Option<Signature>,
-        mut limit: usize,
+        limit: usize,
         config: RpcContextConfig,
+        filter: SignaturesForAddressFilter,
     ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
         let commitment = config.commitment.unwrap_or_default();
         check_is_at_least_confirmed(commitment)?;
 
         if self.config.enable_rpc_transaction_history {
-            let highest_confirmed_root = self
-                .block_commitment_cache
-                .read()
-                .unwrap()
-                .highest_confirmed_root();
-            let highest_slot = if commitment.is_confirmed() {
-                let confirmed_bank = self.get_bank_with_config(config)?;
-                confirmed_bank.slot()
-            } else {
-                let min_context_slot = config.min_context_slot.unwrap_or_default();
-                if highest_confirmed_root < min_context_slot {
-                    return Err(RpcCustomError::MinContextSlotNotReached {
-                        context_slot: highest_confirmed_root,
-                    }
-                    .into());
-                }
-                highest_confirmed_root
-            };
+            let (highest_slot, highest_confirmed_root) = self.get_history_highest_slots(config)?;
+            let highest_slot = filter
+                .max_slot
+                .map_or(highest_slot, |max_slot| max_slot.min(highest_slot));
 
-            let SignatureInfosForAddress {
+            let FilteredSignatures {
                 infos: mut results,
                 found_before,
-            } = self
-                .blockstore
-                .get_confirmed_signatures_for_address2(address, highest_slot, before, until, limit)
-                .map_err(|err| Error::invalid_params(format!("{}", err)))?;
+                last_signature,
+                past_range,
+                mut num_scanned,
+            } = self.get_filtered_blockstore_signatures(
+                address,
+                highest_slot,
+                before,
+                until,
+                limit,
+                &filter,
+            )?;
 
             let map_results = |results: Vec<ConfirmedTransactionStatusWithSignature>| {
                 results
@@ -1625,13 +1757,27 @@ impl JsonRpcRequestProcessor {
                     .collect()
             };
 
-            if results.len() < limit {
+            // The signature to resume the listing from if the scan limit is reached before
+            // `limit` signatures pass the filter
+            let mut scan_cursor = if results.len() < limit
+                && !past_range
+                && num_scanned >= MAX_GET_SIGNATURES_FOR_ADDRESS_SCANNED_SIGNATURES
+            {
+                last_signature
+            } else {
+                None
+            };
+
+            // Long-term storage can only be read newest first, so only the Blockstore history is
+            // listed oldest first
+            if results.len() < limit
+                && !past_range
+                && scan_cursor.is_none()
+                && filter.order == RpcSignaturesOrder::Descending
+            {
                 if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                    let mut bigtable_before = before;
-                    if !results.is_empty() {
-                        limit -= results.len();
-                        bigtable_before = results.last().map(|x| x.signature);
-                    }
+                    let bigtable_limit = limit - results.len();
+                    let mut bigtable_before = last_signature.or(before);
 
                     // If the oldest address-signature found in Blockstore has not yet been
                     // uploaded to long-term storage, modify the storage query to return all latest
@@ -1652,40 +1798,316 @@ impl JsonRpcRequestProcessor {
                         }
                     }
 
-                    let bigtable_results = bigtable_ledger_storage
-                        .get_confirmed_signatures_for_address(
-                            &address,
-                            bigtable_before.as_ref(),
-                            until.as_ref(),
-                            limit,
-                        )
-                        .await;
-                    match bigtable_results {
-                        Ok(bigtable_results) => {
-                            let results_set: HashSet<_> =
-                                results.iter().map(|result| result.signature).collect();
-                            for (bigtable_result, _) in bigtable_results {
-                                // In the upload race condition, latest address-signatures in
-                                // long-term storage may include original `before` signature...
-                                if before != Some(bigtable_result.signature)
-                                    // ...or earlier Blockstore signatures
-                                    && !results_set.contains(&bigtable_result.signature)
+                    let results_set: HashSet<_> =
+                        results.iter().map(|result| result.signature).collect();
+                    loop {
+                        let bigtable_results = bigtable_ledger_storage
+                            .get_confirmed_signatures_for_address(
+                                &address,
+                                bigtable_before.as_ref(),
+                                until.as_ref(),
+                                bigtable_limit,
+                            )
+                            .await;
+                        match bigtable_results {
+                            Ok(bigtable_results) => {
+                                let num_read = bigtable_results.len();
+                                num_scanned += num_read;
+                                if let Some((last_result, _)) = bigtable_results.last() {
+                                    bigtable_before = Some(last_result.signature);
+                                }
+                                let mut past_range = false;
+                                for (bigtable_result, _) in bigtable_results {
+                                    if filter.is_past_range(&bigtable_result) {
+                                        past_range = true;
+                                        break;
+                                    }
+                                    // In the upload race condition, latest address-signatures in
+                                    // long-term storage may include original `before` signature...
+                                    if before != Some(bigtable_result.signature)
+                                        // ...or earlier Blockstore signatures
+                                        && !results_set.contains(&bigtable_result.signature)
+                                        && filter.allows(&bigtable_result)
+                                    {
+                                        results.push(bigtable_result);
+                                    }
+                                }
+                                if filter.is_empty()
+                                    || past_range
+                                    || results.len() >= limit
+                                    || num_read < bigtable_limit
                                 {
-                                    results.push(bigtable_result);
+                                    break;
                                 }
+                                if num_scanned >= MAX_GET_SIGNATURES_FOR_ADDRESS_SCANNED_SIGNATURES
+                                {
+                                    scan_cursor = bigtable_before;
+                                    break;
+                                }
+                            }
+                            Err(err) => {
+                                warn!("{:?}", err);
+                                break;
                             }
                         }
+                    }
+                    results.truncate(limit);
+                }
+            }
+
+            // Listing fewer than `limit` signatures does not mean the history is exhausted, but an
+            // empty listing does, so a scan that found nothing hands the client a cursor instead
+            match scan_cursor {
+                Some(cursor) if results.is_empty() => {
+                    Err(RpcCustomError::SignatureScanLimitReached {
+                        cursor: cursor.to_string(),
+                    }
+                    .into())
+                }
+                _ => Ok(map_results(results)),
+            }
+        } else {
+            Err(RpcCustomError::TransactionHistoryNotAvailable.into())
+        }
+    }
+
+    /// Returns the highest slot address history may be listed through at the requested
+    /// commitment, along with the highest confirmed root
+    fn get_history_highest_slots(&self, config: RpcContextConfig) -> Result<(Slot, Slot)> {
+        let commitment = config.commitment.unwrap_or_default();
+        let highest_confirmed_root = self
+            .block_commitment_cache
+            .read()
+            .unwrap()
+            .highest_confirmed_root();
+        let highest_slot = if commitment.is_confirmed() {
+            let confirmed_bank = self.get_bank_with_config(config)?;
+            confirmed_bank.slot()
+        } else {
+            let min_context_slot = config.min_context_slot.unwrap_or_default();
+            if highest_confirmed_root < min_context_slot {
+                return Err(RpcCustomError::MinContextSlotNotReached {
+                    context_slot: highest_confirmed_root,
+                }
+                .into());
+            }
+            highest_confirmed_root
+        };
+        Ok((highest_slot, highest_confirmed_root))
+    }
+
+    pub async fn get_account_history(
+        &self,
+        address: Pubkey,
+        before: Option<Signature>,
+        until: Option<Signature>,
+        limit: usize,
+        config: RpcContextConfig,
+    ) -> Result<Vec<RpcAccountBalanceChange>> {
+        let commitment = config.commitment.unwrap_or_default();
+        check_is_at_least_confirmed(commitment)?;
+
+        if !self.config.enable_rpc_transaction_history {
+            return Err(RpcCustomError::TransactionHistoryNotAvailable.into());
+        }
+        let (highest_slot, highest_confirmed_root) = self.get_history_highest_slots(config)?;
+        let mut changes = self
+            .blockstore
+            .get_account_history(address, highest_slot, before, until, limit)
+            .map_err(|err| Error::invalid_params(format!("{}", err)))?;
+
+        if changes.len() < limit {
+            if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
+                let mut bigtable_before = changes.last().map(|change| change.signature).or(before);
+
+                // As in `get_signatures_for_address`, list the latest signatures of long-term
+                // storage if the oldest Blockstore transaction has not been uploaded yet
+                if let Some(oldest_change) = changes.last() {
+                    match bigtable_ledger_storage
+                        .get_signature_status(&oldest_change.signature)
+                        .await
+                    {
+                        Err(StorageError::SignatureNotFound) => {
+                            bigtable_before = None;
+                        }
                         Err(err) => {
                             warn!("{:?}", err);
+                            return Ok(self.map_account_history(changes, highest_confirmed_root));
                         }
+                        Ok(_) => {}
                     }
                 }
+
+                let listed: HashSet<_> = changes.iter().map(|change| change.signature).collect();
+                let bigtable_changes = async {
+                    let infos: Vec<_> = bigtable_ledger_storage
+                        .get_confirmed_signatures_for_address(
+                            &address,
+                            bigtable_before.as_ref(),
+                            until.as_ref(),
+                            limit - changes.len(),
+                        )
+                        .await?
+                        .into_iter()
+                        .map(|(info, _)| info)
+                        .filter(|info| {
+                            before != Some(info.signature) && !listed.contains(&info.signature)
+                        })
+                        .collect();
+                    if infos.is_empty() {
+                        return Ok(vec![]);
+                    }
+                    let signatures: Vec<_> = infos.iter().map(|info| info.signature).collect();
+                    let mut transactions: HashMap<_, _> = bigtable_ledger_storage
+                        .get_confirmed_transactions(&signatures)
+                        .await?
+                        .into_iter()
+                        .filter_map(|confirmed_transaction| {
+                            match confirmed_transaction.tx_with_meta {
+                                TransactionWithStatusMeta::Complete(tx_with_meta) => {
+                                    Some((tx_with_meta.transaction.signatures[0], tx_with_meta))
+                                }
+                                TransactionWithStatusMeta::MissingMetadata(_) => None,
+                            }
+                        })
+                        .collect();
+                    Ok::<_, StorageError>(
+                        infos
+                            .into_iter()
+                            .map(|info| {
+                                let tx_with_meta = transactions.remove(&info.signature);
+                                AccountBalanceChange::new(&address, info, tx_with_meta)
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                }
+                .await;
+                match bigtable_changes {
+                    Ok(bigtable_changes) => {
+                        changes.extend(bigtable_changes);
+                        changes.truncate(limit);
+                    }
+                    Err(err) => warn!("{:?}", err),
+                }
             }
+        }
 
-            Ok(map_results(results))
-        } else {
-            Err(RpcCustomError::TransactionHistoryNotAvailable.into())
+        Ok(self.map_account_history(changes, highest_confirmed_root))
+    }
+
+    fn map_account_history(
+        &self,
+        changes: Vec<AccountBalanceChange>,
+        highest_confirmed_root: Slot,
+    ) -> Vec<RpcAccountBalanceChange> {
+        changes
+            .into_iter()
+            .map(|change| {
+                let (confirmation_status, block_time) = if change.slot <= highest_confirmed_root {
+                    (TransactionConfirmationStatus::Finalized, change.block_time)
+                } else {
+                    let block_time = change.block_time.or_else(|| {
+                        let r_bank_forks = self.bank_forks.read().unwrap();
+                        r_bank_forks
+                            .get(change.slot)
+                            .map(|bank| bank.clock().unix_timestamp)
+                    });
+                    (TransactionConfirmationStatus::Confirmed, block_time)
+                };
+                RpcAccountBalanceChange {
+                    signature: change.signature.to_string(),
+                    slot: change.slot,
+                    err: change.err,
+                    block_time,
+                    confirmation_status: Some(confirmation_status),
+                    pre_balance: change.pre_balance,
+                    post_balance: change.post_balance,
+                    pre_token_balance: change.pre_token_balance.map(Into::into),
+                    post_token_balance: change.post_token_balance.map(Into::into),
+                }
+            })
+            .collect()
+    }
+
+    /// Page through the Blockstore signatures of `address` until `limit` of them pass `filter`,
+    /// reading at most `MAX_GET_SIGNATURES_FOR_ADDRESS_SCANNED_SIGNATURES` of them
+    fn get_filtered_blockstore_signatures(
+        &self,
+        address: Pubkey,
+        highest_slot: Slot,
+        mut before: Option<Signature>,
+        mut until: Option<Signature>,
+        limit: usize,
+        filter: &SignaturesForAddressFilter,
+    ) -> Result<FilteredSignatures> {
+        let mut filtered = FilteredSignatures {
+            infos: vec![],
+            found_before: true,
+            last_signature: None,
+            past_range: false,
+            num_scanned: 0,
+        };
+        loop {
+            let SignatureInfosForAddress {
+                infos,
+                found_before,
+            } = match filter.order {
+                RpcSignaturesOrder::Descending => {
+                    self.blockstore.get_confirmed_signatures_for_address2(
+                        address,
+                        highest_slot,
+                        before,
+                        until,
+                        limit,
+                    )
+                }
+                RpcSignaturesOrder::Ascending => self
+                    .blockstore
+                    .get_confirmed_signatures_for_address_ascending(
+                        address,
+                        filter.min_slot.unwrap_or_default(),
+                        highest_slot,
+                        until,
+                        before,
+                        limit,
+                    ),
+            }
+            .map_err(|err| match err {
+                BlockstoreError::SignatureNotFound(signature) => {
+                    Error::invalid_params(format!("Signature not found: {}", signature))
+                }
+                err => Error::invalid_params(format!("{}", err)),
+            })?;
+            filtered.found_before &= found_before;
+
+            let num_read = infos.len();
+            filtered.num_scanned += num_read;
+            if let Some(last_info) = infos.last() {
+                filtered.last_signature = Some(last_info.signature);
+                match filter.order {
+                    RpcSignaturesOrder::Descending => before = Some(last_info.signature),
+                    RpcSignaturesOrder::Ascending => until = Some(last_info.signature),
+                }
+            }
+            for info in infos {
+                if filter.is_past_range(&info) {
+                    filtered.past_range = true;
+                    break;
+                }
+                if filter.allows(&info) {
+                    filtered.infos.push(info);
+                }
+            }
+            if filtered.past_range
+                || filtered.infos.len() >= limit
+                || num_read < limit
+                || filtered.num_scanned >= MAX_GET_SIGNATURES_FOR_ADDRESS_SCANNED_SIGNATURES
+            {
+                break;
+            }
         }
+        filtered.infos.truncate(limit);
+        Ok(filtered)
     }
 
     pub async fn get_first_available_block(&self) -> Slot {
@@ -1855,7 +2277,7 @@ impl JsonRpcRequestProcessor {
             ));
         }
         let mut token_balances: Vec<RpcTokenAccountBalance> = self
-            .get_filtered_spl_token_accounts_by_mint(&bank, &mint_owner, mint, vec![])?
+            .get_filtered_spl_token_accounts_by_mint(&bank, &mint_owner, mint, vec![], None)?
             .into_iter()
             .map(|(address, account)| {
                 let amount = StateWithExtensions::<TokenAccount>::unpack(account.data())
@@ -1883,7 +2305,7 @@ impl JsonRpcRequestProcessor {
     pub fn get_token_accounts_by_owner(
         &self,
         owner: &Pubkey,
-        token_account_filter: TokenAccountsFilter,
+        token_account_filter: Option<TokenAccountsFilter>,
         config: Option<RpcAccountInfoConfig>,
     ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>> {
         let RpcAccountInfoConfig {
@@ -1897,7 +2319,8 @@ impl JsonRpcRequestProcessor {
             min_context_slot,
         })?;
         let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
-        let (token_program_id, mint) = get_token_program_id_and_mint(&bank, token_account_filter)?;
+        let (token_program_ids, mint) =
+            get_token_program_ids_and_mint(&bank, token_account_filter)?;
 
         let mut filters = vec![];
         if let Some(mint) = mint {
@@ -1908,12 +2331,16 @@ impl JsonRpcRequestProcessor {
             )));
         }
 
-        let keyed_accounts = self.get_filtered_spl_token_accounts_by_owner(
-            &bank,
-            &token_program_id,
-            owner,
-            filters,
-        )?;
+        let mut keyed_accounts = vec![];
+        for token_program_id in &token_program_ids {
+            keyed_accounts.extend(self.get_filtered_spl_token_accounts_by_owner(
+                &bank,
+                token_program_id,
+                owner,
+                filters.clone(),
+                None,
+            )?);
+        }
         let accounts = if encoding == UiAccountEncoding::JsonParsed {
             get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
         } else {
@@ -1933,7 +2360,7 @@ impl JsonRpcRequestProcessor {
     pub fn get_token_accounts_by_delegate(
         &self,
         delegate: &Pubkey,
-        token_account_filter: TokenAccountsFilter,
+        token_account_filter: Option<TokenAccountsFilter>,
         config: Option<RpcAccountInfoConfig>,
     ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>> {
         let RpcAccountInfoConfig {
@@ -1947,25 +2374,42 @@ impl JsonRpcRequestProcessor {
             min_context_slot,
         })?;
         let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
-        let (token_program_id, mint) = get_token_program_id_and_mint(&bank, token_account_filter)?;
+        let (token_program_ids, mint) =
+            get_token_program_ids_and_mint(&bank, token_account_filter)?;
 
-        let mut filters = vec![
+        let filters = vec![
             // Filter on Delegate is_some()
             RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
-                72,
+                SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET,
                 bincode::serialize(&1u32).unwrap(),
             )),
             // Filter on Delegate address
-            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(76, delegate.to_bytes().into())),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET,
+                delegate.to_bytes().into(),
+            )),
         ];
-        // Optional filter on Mint address, uses mint account index for scan
-        let keyed_accounts = if let Some(mint) = mint {
-            self.get_filtered_spl_token_accounts_by_mint(&bank, &token_program_id, &mint, filters)?
-        } else {
-            // Filter on Token Account state
-            filters.push(RpcFilterType::TokenAccountState);
-            self.get_filtered_program_accounts(&bank, &token_program_id, filters)?
-        };
+        let mut keyed_accounts = vec![];
+        for token_program_id in &token_program_ids {
+            // Optional filter on Mint address, uses mint account index for scan
+            keyed_accounts.extend(if let Some(mint) = mint {
+                self.get_filtered_spl_token_accounts_by_mint(
+                    &bank,
+                    token_program_id,
+                    &mint,
+                    filters.clone(),
+                    None,
+                )?
+            } else {
+                self.get_filtered_spl_token_accounts_by_delegate(
+                    &bank,
+                    token_program_id,
+                    delegate,
+                    filters.clone(),
+                    None,
+                )?
+            });
+        }
         let accounts = if encoding == UiAccountEncoding::JsonParsed {
             get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
         } else {
@@ -1988,6 +2432,7 @@ impl JsonRpcRequestProcessor {
         bank: &Arc<Bank>,
         program_id: &Pubkey,
         mut filters: Vec<RpcFilterType>,
+        page: Option<AccountsPage>,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         optimize_filters(&mut filters);
         let filter_closure = |account: &AccountSharedData| {
@@ -1995,7 +2440,19 @@ impl JsonRpcRequestProcessor {
                 .iter()
                 .all(|filter_type| filter_type.allows(account))
         };
-        if self
+        if let Some(index_key) =
+            data_offset_index_key(&self.config.account_indexes, program_id, &filters)
+        {
+            Ok(get_filtered_indexed_accounts(
+                bank,
+                &index_key,
+                |account| account.owner() == program_id && filter_closure(account),
+                page,
+            )
+            .map_err(|e| RpcCustomError::ScanError {
+                message: e.to_string(),
+            })?)
+        } else if self
             .config
             .account_indexes
             .contains(&AccountIndex::ProgramId)
@@ -2005,30 +2462,40 @@ impl JsonRpcRequestProcessor {
                     index_key: program_id.to_string(),
                 });
             }
-            Ok(bank
-                .get_filtered_indexed_accounts(
-                    &IndexKey::ProgramId(*program_id),
-                    |account| {
-                        // The program-id account index checks for Account owner on inclusion. However, due
-                        // to the current AccountsDb implementation, an account may remain in storage as a
-                        // zero-lamport AccountSharedData::Default() after being wiped and reinitialized in later
-                        // updates. We include the redundant filters here to avoid returning these
-                        // accounts.
-                        account.owner() == program_id && filter_closure(account)
-                    },
-                    &ScanConfig::default(),
-                    bank.byte_limit_for_scans(),
-                )
-                .map_err(|e| RpcCustomError::ScanError {
-                    message: e.to_string(),
-                })?)
+            Ok(get_filtered_indexed_accounts(
+                bank,
+                &IndexKey::ProgramId(*program_id),
+                |account| {
+                    // The program-id account index checks for Account owner on inclusion. However, due
+                    // to the current AccountsDb implementation, an account may remain in storage as a
+                    // zero-lamport AccountSharedData::Default() after being wiped and reinitialized in later
+                    // updates. We include the redundant filters here to avoid returning these
+                    // accounts.
+                    account.owner() == program_id && filter_closure(account)
+                },
+                page,
+            )
+            .map_err(|e| RpcCustomError::ScanError {
+                message: e.to_string(),
+            })?)
         } else {
             // this path does not need to provide a mb limit because we only want to support secondary indexes
-            Ok(bank
-                .get_filtered_program_accounts(program_id, filter_closure, &ScanConfig::default())
-                .map_err(|e| RpcCustomError::ScanError {
-                    message: e.to_string(),
-                })?)
+            Ok(match page {
+                Some(page) => bank.get_filtered_program_accounts_page(
+                    program_id,
+                    filter_closure,
+                    &page.scan_config(),
+                    page.limit,
+                ),
+                None => bank.get_filtered_program_accounts(
+                    program_id,
+                    filter_closure,
+                    &ScanConfig::default(),
+                ),
+            }
+            .map_err(|e| RpcCustomError::ScanError {
+                message: e.to_string(),
+            })?)
         }
     }
 
@@ -2039,6 +2506,7 @@ impl JsonRpcRequestProcessor {
         program_id: &Pubkey,
         owner_key: &Pubkey,
         mut filters: Vec<RpcFilterType>,
+        page: Option<AccountsPage>,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         // The by-owner accounts index checks for Token Account state and Owner address on
         // inclusion. However, due to the current AccountsDb implementation, an account may remain
@@ -2063,23 +2531,64 @@ impl JsonRpcRequestProcessor {
                     index_key: owner_key.to_string(),
                 });
             }
-            Ok(bank
-                .get_filtered_indexed_accounts(
-                    &IndexKey::SplTokenOwner(*owner_key),
-                    |account| {
-                        account.owner() == program_id
-                            && filters
-                                .iter()
-                                .all(|filter_type| filter_type.allows(account))
-                    },
-                    &ScanConfig::default(),
-                    bank.byte_limit_for_scans(),
-                )
-                .map_err(|e| RpcCustomError::ScanError {
-                    message: e.to_string(),
-                })?)
+            Ok(get_filtered_indexed_accounts(
+                bank,
+                &IndexKey::SplTokenOwner(*owner_key),
+                |account| {
+                    account.owner() == program_id
+                        && filters
+                            .iter()
+                            .all(|filter_type| filter_type.allows(account))
+                },
+                page,
+            )
+            .map_err(|e| RpcCustomError::ScanError {
+                message: e.to_string(),
+            })?)
         } else {
-            self.get_filtered_program_accounts(bank, program_id, filters)
+            self.get_filtered_program_accounts(bank, program_id, filters, page)
+        }
+    }
+
+    /// Get an iterator of spl-token accounts by delegate address. `filters` must include the
+    /// delegate filters.
+    fn get_filtered_spl_token_accounts_by_delegate(
+        &self,
+        bank: &Arc<Bank>,
+        program_id: &Pubkey,
+        delegate_key: &Pubkey,
+        mut filters: Vec<RpcFilterType>,
+        page: Option<AccountsPage>,
+    ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+        // The by-delegate accounts index is not updated when a delegate is revoked, so the
+        // delegate filters are needed as well as a filter on Token Account state
+        filters.push(RpcFilterType::TokenAccountState);
+        if self
+            .config
+            .account_indexes
+            .contains(&AccountIndex::SplTokenDelegate)
+        {
+            if !self.config.account_indexes.include_key(delegate_key) {
+                return Err(RpcCustomError::KeyExcludedFromSecondaryIndex {
+                    index_key: delegate_key.to_string(),
+                });
+            }
+            Ok(get_filtered_indexed_accounts(
+                bank,
+                &IndexKey::SplTokenDelegate(*delegate_key),
+                |account| {
+                    account.owner() == program_id
+                        && filters
+                            .iter()
+                            .all(|filter_type| filter_type.allows(account))
+                },
+                page,
+            )
+            .map_err(|e| RpcCustomError::ScanError {
+                message: e.to_string(),
+            })?)
+        } else {
+            self.get_filtered_program_accounts(bank, program_id, filters, page)
         }
     }
 
@@ -2090,6 +2599,7 @@ impl JsonRpcRequestProcessor {
         program_id: &Pubkey,
         mint_key: &Pubkey,
         mut filters: Vec<RpcFilterType>,
+        page: Option<AccountsPage>,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         // The by-mint accounts index checks for Token Account state and Mint address on inclusion.
         // However, due to the current AccountsDb implementation, an account may remain in storage
@@ -2113,23 +2623,22 @@ impl JsonRpcRequestProcessor {
                     index_key: mint_key.to_string(),
                 });
             }
-            Ok(bank
-                .get_filtered_indexed_accounts(
-                    &IndexKey::SplTokenMint(*mint_key),
-                    |account| {
-                        account.owner() == program_id
-                            && filters
-                                .iter()
-                                .all(|filter_type| filter_type.allows(account))
-                    },
-                    &ScanConfig::default(),
-                    bank.byte_limit_for_scans(),
-                )
-                .map_err(|e| RpcCustomError::ScanError {
-                    message: e.to_string(),
-                })?)
+            Ok(get_filtered_indexed_accounts(
+                bank,
+                &IndexKey::SplTokenMint(*mint_key),
+                |account| {
+                    account.owner() == program_id
+                        && filters
+                            .iter()
+                            .all(|filter_type| filter_type.allows(account))
+                },
+                page,
+            )
+            .map_err(|e| RpcCustomError::ScanError {
+                message: e.to_string(),
+            })?)
         } else {
-            self.get_filtered_program_accounts(bank, program_id, filters)
+            self.get_filtered_program_accounts(bank, program_id, filters, page)
         }
     }
 
@@ -2181,22 +2690,121 @@ impl JsonRpcRequestProcessor {
     }
 }
 
-fn optimize_filters(filters: &mut [RpcFilterType]) {
-    filters.iter_mut().for_each(|filter_type| {
-        if let RpcFilterType::Memcmp(compare) = filter_type {
-            use MemcmpEncodedBytes::*;
-            match &compare.bytes {
-                #[allow(deprecated)]
-                Binary(bytes) | Base58(bytes) => {
-                    compare.bytes = Bytes(bs58::decode(bytes).into_vec().unwrap());
-                }
-                Base64(bytes) => {
-                    compare.bytes = Bytes(base64::decode(bytes).unwrap());
+/// Limits a program accounts scan to one page of accounts, in key order
+#[derive(Debug, Clone, Copy)]
+struct AccountsPage {
+    start_after: Option<Pubkey>,
+    limit: usize,
+}
+
+impl AccountsPage {
+    fn scan_config(&self) -> ScanConfig {
+        ScanConfig {
+            start_after: self.start_after,
+            ..ScanConfig::default()
+        }
+    }
+}
+
+/// The opaque `getProgramAccountsPage` cursor: the slot of the bank the pages are read from and
+/// the last account returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramAccountsCursor {
+    slot: Slot,
+    pubkey: Pubkey,
+}
+
+impl ProgramAccountsCursor {
+    fn encode(&self) -> String {
+        let mut bytes = self.slot.to_le_bytes().to_vec();
+        bytes.extend_from_slice(self.pubkey.as_ref());
+        bs58::encode(bytes).into_string()
+    }
+}
+
+impl FromStr for ProgramAccountsCursor {
+    type Err = Error;
+
+    fn from_str(cursor: &str) -> Result<Self> {
+        let invalid_cursor = || Error::invalid_params("Invalid param: cursor");
+        let bytes = bs58::decode(cursor)
+            .into_vec()
+            .map_err(|_| invalid_cursor())?;
+        if bytes.len() != std::mem::size_of::<Slot>() + PUBKEY_BYTES {
+            return Err(invalid_cursor());
+        }
+        let (slot, pubkey) = bytes.split_at(std::mem::size_of::<Slot>());
+        Ok(Self {
+            slot: Slot::from_le_bytes(slot.try_into().unwrap()),
+            pubkey: Pubkey::new(pubkey),
+        })
+    }
+}
+
+fn get_filtered_indexed_accounts<F: Fn(&AccountSharedData) -> bool>(
+    bank: &Bank,
+    index_key: &IndexKey,
+    filter: F,
+    page: Option<AccountsPage>,
+) -> ScanResult<Vec<(Pubkey, AccountSharedData)>> {
+    match page {
+        Some(page) => bank.get_filtered_indexed_accounts_page(
+            index_key,
+            filter,
+            &page.scan_config(),
+            page.limit,
+        ),
+        None => bank.get_filtered_indexed_accounts(
+            index_key,
+            filter,
+            &ScanConfig::default(),
+            bank.byte_limit_for_scans(),
+        ),
+    }
+}
+
+fn encode_keyed_accounts(
+    bank: &Arc<Bank>,
+    program_id: &Pubkey,
+    keyed_accounts: Vec<(Pubkey, AccountSharedData)>,
+    encoding: UiAccountEncoding,
+    data_slice_config: Option<UiDataSliceConfig>,
+) -> Result<Vec<RpcKeyedAccount>> {
+    if is_known_spl_token_id(program_id) && encoding == UiAccountEncoding::JsonParsed {
+        Ok(get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect())
+    } else {
+        keyed_accounts
+            .into_iter()
+            .map(|(pubkey, account)| {
+                Ok(RpcKeyedAccount {
+                    pubkey: pubkey.to_string(),
+                    account: encode_account(&account, &pubkey, encoding, data_slice_config)?,
+                })
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn optimize_filters(filters: &mut [RpcFilterType]) {
+    filters
+        .iter_mut()
+        .for_each(|filter_type| match filter_type {
+            RpcFilterType::Memcmp(compare) | RpcFilterType::NotMemcmp(compare) => {
+                use MemcmpEncodedBytes::*;
+                match &compare.bytes {
+                    #[allow(deprecated)]
+                    Binary(bytes) | Base58(bytes) => {
+                        compare.bytes = Bytes(bs58::decode(bytes).into_vec().unwrap());
+                    }
+                    Base64(bytes) => {
+                        compare.bytes = Bytes(base64::decode(bytes).unwrap());
+                    }
+                    _ => {}
                 }
-                _ => {}
             }
-        }
-    })
+            RpcFilterType::Or(filters) => optimize_filters(filters),
+            _ => {}
+        })
 }
 
 fn verify_transaction(
@@ -2215,7 +2823,7 @@ fn verify_transaction(
     Ok(())
 }
 
-fn verify_filter(input: &RpcFilterType) -> Result<()> {
+pub(crate) fn verify_filter(input: &RpcFilterType) -> Result<()> {
     input
         .verify()
         .map_err(|e| Error::invalid_params(format!("Invalid param: {:?}", e)))
@@ -2254,6 +2862,106 @@ fn verify_token_account_filter(
     }
 }
 
+/// Narrows down the signatures listed by `getSignaturesForAddress`, and sets their order.
+///
+/// Block times are assumed not to decrease from one slot to the next, so that the listing can
+/// stop at the first signature past the block time range.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SignaturesForAddressFilter {
+    pub min_slot: Option<Slot>,
+    pub max_slot: Option<Slot>,
+    pub min_block_time: Option<UnixTimestamp>,
+    pub max_block_time: Option<UnixTimestamp>,
+    pub errors_only: bool,
+    pub success_only: bool,
+    pub order: RpcSignaturesOrder,
+}
+
+impl SignaturesForAddressFilter {
+    /// Whether every signature passes the filter
+    fn is_empty(&self) -> bool {
+        *self
+            == Self {
+                order: self.order,
+                ..Self::default()
+            }
+    }
+
+    fn allows(&self, info: &ConfirmedTransactionStatusWithSignature) -> bool {
+        fn in_range<T: PartialOrd>(value: T, min: Option<T>, max: Option<T>) -> bool {
+            min.map_or(true, |min| value >= min) && max.map_or(true, |max| value <= max)
+        }
+        let has_block_time_range = self.min_block_time.is_some() || self.max_block_time.is_some();
+        in_range(info.slot, self.min_slot, self.max_slot)
+            && (!has_block_time_range
+                || info.block_time.map_or(false, |block_time| {
+                    in_range(block_time, self.min_block_time, self.max_block_time)
+                }))
+            && !(self.errors_only && info.err.is_none())
+            && !(self.success_only && info.err.is_some())
+    }
+
+    /// Whether `info`, and so every signature listed after it, is past the slot or block time
+    /// range
+    fn is_past_range(&self, info: &ConfirmedTransactionStatusWithSignature) -> bool {
+        match self.order {
+            RpcSignaturesOrder::Descending => {
+                matches!(self.min_slot, Some(min_slot) if info.slot < min_slot)
+                    || matches!(
+                        (self.min_block_time, info.block_time),
+                        (Some(min_block_time), Some(block_time)) if block_time < min_block_time
+                    )
+            }
+            RpcSignaturesOrder::Ascending => {
+                matches!(self.max_slot, Some(max_slot) if info.slot > max_slot)
+                    || matches!(
+                        (self.max_block_time, info.block_time),
+                        (Some(max_block_time), Some(block_time)) if block_time > max_block_time
+                    )
+            }
+        }
+    }
+}
+
+struct FilteredSignatures {
+    infos: Vec<ConfirmedTransactionStatusWithSignature>,
+    found_before: bool,
+    /// The last signature read, whether or not it passed the filter
+    last_signature: Option<Signature>,
+    /// Whether the listing went past the slot or block time range of the filter
+    past_range: bool,
+    /// The number of signatures read, whether or not they passed the filter
+    num_scanned: usize,
+}
+
+fn verify_signatures_for_address_filter(
+    config: &RpcSignaturesForAddressConfig,
+) -> Result<SignaturesForAddressFilter> {
+    let filter = SignaturesForAddressFilter {
+        min_slot: config.min_slot,
+        max_slot: config.max_slot,
+        min_block_time: config.min_block_time,
+        max_block_time: config.max_block_time,
+        errors_only: config.errors_only.unwrap_or_default(),
+        success_only: config.success_only.unwrap_or_default(),
+        order: config.order.unwrap_or_default(),
+    };
+    if filter.errors_only && filter.success_only {
+        return Err(Error::invalid_params(
+            "Only one of errorsOnly and successOnly can be set",
+        ));
+    }
+    if matches!((filter.min_slot, filter.max_slot), (Some(min), Some(max)) if min > max)
+        || matches!(
+            (filter.min_block_time, filter.max_block_time),
+            (Some(min), Some(max)) if min > max
+        )
+    {
+        return Err(Error::invalid_params("Invalid range"));
+    }
+    Ok(filter)
+}
+
 fn verify_and_parse_signatures_for_address_params(
     address: String,
     before: Option<String>,
@@ -2328,6 +3036,27 @@ fn encode_account<T: ReadableAccount>(
     }
 }
 
+/// The key of a data offset index which covers one of the memcmp `filters`, if any
+fn data_offset_index_key(
+    account_indexes: &AccountSecondaryIndexes,
+    program_id: &Pubkey,
+    filters: &[RpcFilterType],
+) -> Option<IndexKey> {
+    account_indexes
+        .data_offset_indexes()
+        .filter(|index| index.program_id == *program_id)
+        .find_map(|index| {
+            filters.iter().find_map(|filter| match filter {
+                RpcFilterType::Memcmp(memcmp) if memcmp.offset == index.offset => {
+                    let bytes = memcmp.bytes()?;
+                    let key = bytes.get(..index.length)?;
+                    Some(IndexKey::DataOffset(*index, key.to_vec()))
+                }
+                _ => None,
+            })
+        })
+}
+
 /// Analyze custom filters to determine if the result will be a subset of spl-token accounts by
 /// owner.
 /// NOTE: `optimize_filters()` should almost always be called before using this method because of
@@ -2441,11 +3170,15 @@ fn get_spl_token_mint_filter(program_id: &Pubkey, filters: &[RpcFilterType]) ->
 }
 
 /// Analyze a passed Pubkey that may be a Token program id or Mint address to determine the program
-/// id and optional Mint
-fn get_token_program_id_and_mint(
+/// ids and optional Mint. Without a filter, all of the known Token programs are returned.
+fn get_token_program_ids_and_mint(
     bank: &Arc<Bank>,
-    token_account_filter: TokenAccountsFilter,
-) -> Result<(Pubkey, Option<Pubkey>)> {
+    token_account_filter: Option<TokenAccountsFilter>,
+) -> Result<(Vec<Pubkey>, Option<Pubkey>)> {
+    let token_account_filter = match token_account_filter {
+        Some(token_account_filter) => token_account_filter,
+        None => return Ok((spl_token_ids(), None)),
+    };
     match token_account_filter {
         TokenAccountsFilter::Mint(mint) => {
             let (mint_owner, _) = get_mint_owner_and_decimals(bank, &mint)?;
@@ -2454,11 +3187,11 @@ fn get_token_program_id_and_mint(
                     "Invalid param: not a Token mint".to_string(),
                 ));
             }
-            Ok((mint_owner, Some(mint)))
+            Ok((vec![mint_owner], Some(mint)))
         }
         TokenAccountsFilter::ProgramId(program_id) => {
             if is_known_spl_token_id(&program_id) {
-                Ok((program_id, None))
+                Ok((vec![program_id], None))
             } else {
                 Err(Error::invalid_params(
                     "Invalid param: unrecognized Token program id".to_string(),
@@ -2981,6 +3714,14 @@ pub mod rpc_accounts {
             config: Option<RpcProgramAccountsConfig>,
         ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>>;
 
+        #[rpc(meta, name = "getProgramAccountsPage")]
+        fn get_program_accounts_page(
+            &self,
+            meta: Self::Metadata,
+            program_id_str: String,
+            config: Option<RpcProgramAccountsPageConfig>,
+        ) -> Result<RpcResponse<RpcProgramAccountsPage>>;
+
         #[rpc(meta, name = "getBlockCommitment")]
         fn get_block_commitment(
             &self,
@@ -3043,7 +3784,7 @@ pub mod rpc_accounts {
             &self,
             meta: Self::Metadata,
             owner_str: String,
-            token_account_filter: RpcTokenAccountsFilter,
+            token_account_filter: Option<RpcTokenAccountsFilter>,
             config: Option<RpcAccountInfoConfig>,
         ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>>;
 
@@ -3052,7 +3793,7 @@ pub mod rpc_accounts {
             &self,
             meta: Self::Metadata,
             delegate_str: String,
-            token_account_filter: RpcTokenAccountsFilter,
+            token_account_filter: Option<RpcTokenAccountsFilter>,
             config: Option<RpcAccountInfoConfig>,
         ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>>;
     }
@@ -3129,7 +3870,47 @@ pub mod rpc_accounts {
             for filter in &filters {
                 verify_filter(filter)?;
             }
-            meta.get_program_accounts(&program_id, config, filters, with_context)
+            meta.get_program_accounts(&program_id, config, filters, with_context)
+        }
+
+        fn get_program_accounts_page(
+            &self,
+            meta: Self::Metadata,
+            program_id_str: String,
+            config: Option<RpcProgramAccountsPageConfig>,
+        ) -> Result<RpcResponse<RpcProgramAccountsPage>> {
+            debug!(
+                "get_program_accounts_page rpc request received: {:?}",
+                program_id_str
+            );
+            let program_id = verify_pubkey(&program_id_str)?;
+            let RpcProgramAccountsPageConfig {
+                filters,
+                account_config,
+                limit,
+                after,
+            } = config.unwrap_or_default();
+            let filters = filters.unwrap_or_default();
+            if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
+                return Err(Error::invalid_params(format!(
+                    "Too many filters provided; max {}",
+                    MAX_GET_PROGRAM_ACCOUNT_FILTERS
+                )));
+            }
+            for filter in &filters {
+                verify_filter(filter)?;
+            }
+            let limit = limit.unwrap_or(MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT);
+            if limit == 0 || limit > MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT {
+                return Err(Error::invalid_params(format!(
+                    "Invalid limit; max {}",
+                    MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT
+                )));
+            }
+            let after = after
+                .map(|after| after.parse::<ProgramAccountsCursor>())
+                .transpose()?;
+            meta.get_program_accounts_page(&program_id, Some(account_config), filters, limit, after)
         }
 
         fn get_block_commitment(
@@ -3216,7 +3997,7 @@ pub mod rpc_accounts {
             &self,
             meta: Self::Metadata,
             owner_str: String,
-            token_account_filter: RpcTokenAccountsFilter,
+            token_account_filter: Option<RpcTokenAccountsFilter>,
             config: Option<RpcAccountInfoConfig>,
         ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>> {
             debug!(
@@ -3224,7 +4005,9 @@ pub mod rpc_accounts {
                 owner_str
             );
             let owner = verify_pubkey(&owner_str)?;
-            let token_account_filter = verify_token_account_filter(token_account_filter)?;
+            let token_account_filter = token_account_filter
+                .map(verify_token_account_filter)
+                .transpose()?;
             meta.get_token_accounts_by_owner(&owner, token_account_filter, config)
         }
 
@@ -3232,7 +4015,7 @@ pub mod rpc_accounts {
             &self,
             meta: Self::Metadata,
             delegate_str: String,
-            token_account_filter: RpcTokenAccountsFilter,
+            token_account_filter: Option<RpcTokenAccountsFilter>,
             config: Option<RpcAccountInfoConfig>,
         ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>> {
             debug!(
@@ -3240,7 +4023,9 @@ pub mod rpc_accounts {
                 delegate_str
             );
             let delegate = verify_pubkey(&delegate_str)?;
-            let token_account_filter = verify_token_account_filter(token_account_filter)?;
+            let token_account_filter = token_account_filter
+                .map(verify_token_account_filter)
+                .transpose()?;
             meta.get_token_accounts_by_delegate(&delegate, token_account_filter, config)
         }
     }
@@ -3314,6 +4099,14 @@ pub mod rpc_full {
             config: Option<RpcSimulateTransactionConfig>,
         ) -> Result<RpcResponse<RpcSimulateTransactionResult>>;
 
+        #[rpc(meta, name = "simulateBundle")]
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<RpcSimulateBundleResult>>;
+
         #[rpc(meta, name = "minimumLedgerSlot")]
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot>;
 
@@ -3366,6 +4159,14 @@ pub mod rpc_full {
             config: Option<RpcSignaturesForAddressConfig>,
         ) -> BoxFuture<Result<Vec<RpcConfirmedTransactionStatusWithSignature>>>;
 
+        #[rpc(meta, name = "getAccountHistory")]
+        fn get_account_history(
+            &self,
+            meta: Self::Metadata,
+            address: String,
+            config: Option<RpcAccountHistoryConfig>,
+        ) -> BoxFuture<Result<Vec<RpcAccountBalanceChange>>>;
+
         #[rpc(meta, name = "getFirstAvailableBlock")]
         fn get_first_available_block(&self, meta: Self::Metadata) -> BoxFuture<Result<Slot>>;
 
@@ -3660,6 +4461,7 @@ pub mod rpc_full {
                     post_simulation_accounts: _,
                     units_consumed,
                     return_data,
+                    execution_trace: _,
                 } = preflight_bank.simulate_transaction(transaction)
                 {
                     match err {
@@ -3678,6 +4480,7 @@ pub mod rpc_full {
                             accounts: None,
                             units_consumed: Some(units_consumed),
                             return_data: return_data.map(|return_data| return_data.into()),
+                            trace: None,
                         },
                     }
                     .into());
@@ -3708,7 +4511,15 @@ pub mod rpc_full {
                 encoding,
                 accounts: config_accounts,
                 min_context_slot,
+                account_overrides,
+                trace,
             } = config.unwrap_or_default();
+            let account_overrides = account_overrides
+                .map(verify_account_overrides)
+                .transpose()?;
+            let trace_config = trace
+                .map(|trace| verify_trace_config(trace, &meta.config))
+                .transpose()?;
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
                 Error::invalid_params(format!(
@@ -3716,88 +4527,126 @@ pub mod rpc_full {
                     tx_encoding
                 ))
             })?;
-            let (_, mut unsanitized_tx) =
+            let (_, unsanitized_tx) =
                 decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
 
             let bank = &*meta.get_bank_with_config(RpcContextConfig {
                 commitment,
                 min_context_slot,
             })?;
-            if replace_recent_blockhash {
-                if sig_verify {
-                    return Err(Error::invalid_params(
-                        "sigVerify may not be used with replaceRecentBlockhash",
-                    ));
-                }
-                unsanitized_tx
-                    .message
-                    .set_recent_blockhash(bank.last_blockhash());
-            }
-
-            let transaction = sanitize_transaction(unsanitized_tx, bank)?;
-            if sig_verify {
-                verify_transaction(&transaction, &bank.feature_set)?;
-            }
+            let transaction = sanitize_simulation_transaction(
+                unsanitized_tx,
+                bank,
+                sig_verify,
+                replace_recent_blockhash,
+            )?;
             let number_of_accounts = transaction.message().account_keys().len();
 
-            let TransactionSimulationResult {
-                result,
-                logs,
-                post_simulation_accounts,
-                units_consumed,
-                return_data,
-            } = bank.simulate_transaction(transaction);
-
-            let accounts = if let Some(config_accounts) = config_accounts {
-                let accounts_encoding = config_accounts
-                    .encoding
-                    .unwrap_or(UiAccountEncoding::Base64);
-
-                if accounts_encoding == UiAccountEncoding::Binary
-                    || accounts_encoding == UiAccountEncoding::Base58
-                {
-                    return Err(Error::invalid_params("base58 encoding not supported"));
+            let simulation_result = match (account_overrides, trace_config) {
+                (account_overrides, Some(trace_config)) => bank.simulate_transaction_with_trace(
+                    transaction,
+                    account_overrides.unwrap_or_default(),
+                    &trace_config,
+                ),
+                (Some(account_overrides), None) => {
+                    bank.simulate_transaction_with_account_overrides(transaction, account_overrides)
                 }
+                (None, None) => bank.simulate_transaction(transaction),
+            };
+            Ok(new_response(
+                bank,
+                encode_simulation_result(simulation_result, number_of_accounts, config_accounts)?,
+            ))
+        }
 
-                if config_accounts.addresses.len() > number_of_accounts {
-                    return Err(Error::invalid_params(format!(
-                        "Too many accounts provided; max {}",
-                        number_of_accounts
-                    )));
-                }
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<RpcSimulateBundleResult>> {
+            debug!(
+                "simulate_bundle rpc request received: {:?} transactions",
+                data.len()
+            );
+            let RpcSimulateBundleConfig {
+                sig_verify,
+                replace_recent_blockhash,
+                commitment,
+                encoding,
+                accounts: config_accounts,
+                min_context_slot,
+            } = config.unwrap_or_default();
+            if data.is_empty() || data.len() > MAX_SIMULATE_BUNDLE_TRANSACTIONS {
+                return Err(Error::invalid_params(format!(
+                    "Invalid number of transactions; expected 1 to {}",
+                    MAX_SIMULATE_BUNDLE_TRANSACTIONS
+                )));
+            }
+            let config_accounts = config_accounts.unwrap_or_else(|| vec![None; data.len()]);
+            if config_accounts.len() != data.len() {
+                return Err(Error::invalid_params(format!(
+                    "Expected {} accounts configs, one per transaction",
+                    data.len()
+                )));
+            }
+            let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                Error::invalid_params(format!(
+                    "unsupported encoding: {}. Supported encodings: base58, base64",
+                    tx_encoding
+                ))
+            })?;
+            let unsanitized_txs = data
+                .into_iter()
+                .map(|data| {
+                    decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)
+                        .map(|(_, unsanitized_tx)| unsanitized_tx)
+                })
+                .collect::<Result<Vec<_>>>()?;
 
-                if result.is_err() {
-                    Some(vec![None; config_accounts.addresses.len()])
-                } else {
-                    Some(
-                        config_accounts
-                            .addresses
-                            .iter()
-                            .map(|address_str| {
-                                let address = verify_pubkey(address_str)?;
-                                post_simulation_accounts
-                                    .iter()
-                                    .find(|(key, _account)| key == &address)
-                                    .map(|(pubkey, account)| {
-                                        encode_account(account, pubkey, accounts_encoding, None)
-                                    })
-                                    .transpose()
-                            })
-                            .collect::<Result<Vec<_>>>()?,
+            let bank = &*meta.get_bank_with_config(RpcContextConfig {
+                commitment,
+                min_context_slot,
+            })?;
+            let transactions = unsanitized_txs
+                .into_iter()
+                .map(|unsanitized_tx| {
+                    sanitize_simulation_transaction(
+                        unsanitized_tx,
+                        bank,
+                        sig_verify,
+                        replace_recent_blockhash,
                     )
-                }
-            } else {
-                None
-            };
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let numbers_of_accounts: Vec<_> = transactions
+                .iter()
+                .map(|transaction| transaction.message().account_keys().len())
+                .collect();
 
+            let transaction_results = bank
+                .simulate_bundle(transactions)
+                .into_iter()
+                .zip(numbers_of_accounts)
+                .zip(config_accounts)
+                .map(
+                    |((simulation_result, number_of_accounts), config_accounts)| {
+                        encode_simulation_result(
+                            simulation_result,
+                            number_of_accounts,
+                            config_accounts,
+                        )
+                    },
+                )
+                .collect::<Result<Vec<_>>>()?;
             Ok(new_response(
                 bank,
-                RpcSimulateTransactionResult {
-                    err: result.err(),
-                    logs: Some(logs),
-                    accounts,
-                    units_consumed: Some(units_consumed),
-                    return_data: return_data.map(|return_data| return_data.into()),
+                RpcSimulateBundleResult {
+                    err: transaction_results
+                        .last()
+                        .and_then(|transaction_result| transaction_result.err.clone()),
+                    transaction_results,
                 },
             ))
         }
@@ -3881,13 +4730,19 @@ pub mod rpc_full {
             address: String,
             config: Option<RpcSignaturesForAddressConfig>,
         ) -> BoxFuture<Result<Vec<RpcConfirmedTransactionStatusWithSignature>>> {
+            let config = config.unwrap_or_default();
+            let filter = match verify_signatures_for_address_filter(&config) {
+                Ok(filter) => filter,
+                Err(err) => return Box::pin(future::err(err)),
+            };
             let RpcSignaturesForAddressConfig {
                 before,
                 until,
                 limit,
                 commitment,
                 min_context_slot,
-            } = config.unwrap_or_default();
+                ..
+            } = config;
             let verification =
                 verify_and_parse_signatures_for_address_params(address, before, until, limit);
 
@@ -3903,6 +4758,42 @@ pub mod rpc_full {
                             commitment,
                             min_context_slot,
                         },
+                        filter,
+                    )
+                    .await
+                }),
+            }
+        }
+
+        fn get_account_history(
+            &self,
+            meta: Self::Metadata,
+            address: String,
+            config: Option<RpcAccountHistoryConfig>,
+        ) -> BoxFuture<Result<Vec<RpcAccountBalanceChange>>> {
+            debug!("get_account_history rpc request received: {:?}", address);
+            let RpcAccountHistoryConfig {
+                before,
+                until,
+                limit,
+                commitment,
+                min_context_slot,
+            } = config.unwrap_or_default();
+            let verification =
+                verify_and_parse_signatures_for_address_params(address, before, until, limit);
+
+            match verification {
+                Err(err) => Box::pin(future::err(err)),
+                Ok((address, before, until, limit)) => Box::pin(async move {
+                    meta.get_account_history(
+                        address,
+                        before,
+                        until,
+                        limit,
+                        RpcContextConfig {
+                            commitment,
+                            min_context_slot,
+                        },
                     )
                     .await
                 }),
@@ -4268,6 +5159,7 @@ pub mod rpc_deprecated_v1_7 {
                             commitment,
                             min_context_slot: None,
                         },
+                        SignaturesForAddressFilter::default(),
                     )
                     .await
                 }),
@@ -4491,6 +5383,213 @@ fn sanitize_transaction(
     .map_err(|err| Error::invalid_params(format!("invalid transaction: {}", err)))
 }
 
+fn verify_account_overrides(
+    account_overrides: HashMap<String, RpcAccountOverride>,
+) -> Result<AccountOverrides> {
+    if account_overrides.len() > MAX_SIMULATE_ACCOUNT_OVERRIDES {
+        return Err(Error::invalid_params(format!(
+            "Too many account overrides provided; max {}",
+            MAX_SIMULATE_ACCOUNT_OVERRIDES
+        )));
+    }
+    let mut overrides = AccountOverrides::default();
+    for (address, account_override) in account_overrides {
+        let pubkey = verify_pubkey(&address)?;
+        let (data, encoding) = account_override
+            .data
+            .unwrap_or_else(|| (String::new(), UiAccountEncoding::Base64));
+        if encoding != UiAccountEncoding::Base64 && encoding != UiAccountEncoding::Base64Zstd {
+            return Err(Error::invalid_params(format!(
+                "unsupported account override encoding: {:?}. Supported encodings: base64, \
+                 base64+zstd",
+                encoding
+            )));
+        }
+        let account = UiAccount {
+            lamports: account_override.lamports,
+            data: UiAccountData::Binary(data, encoding),
+            owner: verify_pubkey(&account_override.owner)?.to_string(),
+            executable: account_override.executable,
+            rent_epoch: 0,
+            space: None,
+        }
+        .decode_with_max_data_len::<AccountSharedData>(
+            system_instruction::MAX_PERMITTED_DATA_LENGTH as usize,
+        )
+        .ok_or_else(|| {
+            Error::invalid_params(format!(
+                "Invalid account override data for {}; max {} bytes",
+                pubkey,
+                system_instruction::MAX_PERMITTED_DATA_LENGTH
+            ))
+        })?;
+        overrides.set_account(&pubkey, Some(account));
+    }
+    Ok(overrides)
+}
+
+fn verify_trace_config(
+    trace: RpcSimulateTransactionTraceConfig,
+    config: &JsonRpcConfig,
+) -> Result<TraceConfig> {
+    let max_frames = config.max_simulation_trace_frames.ok_or_else(|| {
+        Error::invalid_params("Transaction simulation traces are not enabled on this node")
+    })?;
+    let bpf_trace_program_id = trace
+        .bpf_trace_program
+        .as_deref()
+        .map(verify_pubkey)
+        .transpose()?;
+    if bpf_trace_program_id.is_some() && config.max_simulation_bpf_trace_length.is_none() {
+        return Err(Error::invalid_params(
+            "BPF instruction traces are not enabled on this node",
+        ));
+    }
+    Ok(TraceConfig {
+        max_frames: Some(max_frames),
+        bpf_trace_program_id,
+        max_bpf_trace_length: config.max_simulation_bpf_trace_length,
+    })
+}
+
+/// Prepare a decoded transaction for simulation against `bank`
+fn sanitize_simulation_transaction(
+    mut unsanitized_tx: VersionedTransaction,
+    bank: &Bank,
+    sig_verify: bool,
+    replace_recent_blockhash: bool,
+) -> Result<SanitizedTransaction> {
+    if replace_recent_blockhash {
+        if sig_verify {
+            return Err(Error::invalid_params(
+                "sigVerify may not be used with replaceRecentBlockhash",
+            ));
+        }
+        unsanitized_tx
+            .message
+            .set_recent_blockhash(bank.last_blockhash());
+    }
+
+    let transaction = sanitize_transaction(unsanitized_tx, bank)?;
+    if sig_verify {
+        verify_transaction(&transaction, &bank.feature_set)?;
+    }
+    Ok(transaction)
+}
+
+fn encode_simulation_result(
+    simulation_result: TransactionSimulationResult,
+    number_of_accounts: usize,
+    config_accounts: Option<RpcSimulateTransactionAccountsConfig>,
+) -> Result<RpcSimulateTransactionResult> {
+    let TransactionSimulationResult {
+        result,
+        logs,
+        post_simulation_accounts,
+        units_consumed,
+        return_data,
+        execution_trace,
+    } = simulation_result;
+
+    let accounts = if let Some(config_accounts) = config_accounts {
+        let accounts_encoding = config_accounts
+            .encoding
+            .unwrap_or(UiAccountEncoding::Base64);
+
+        if accounts_encoding == UiAccountEncoding::Binary
+            || accounts_encoding == UiAccountEncoding::Base58
+        {
+            return Err(Error::invalid_params("base58 encoding not supported"));
+        }
+
+        if config_accounts.addresses.len() > number_of_accounts {
+            return Err(Error::invalid_params(format!(
+                "Too many accounts provided; max {}",
+                number_of_accounts
+            )));
+        }
+
+        if result.is_err() {
+            Some(vec![None; config_accounts.addresses.len()])
+        } else {
+            Some(
+                config_accounts
+                    .addresses
+                    .iter()
+                    .map(|address_str| {
+                        let address = verify_pubkey(address_str)?;
+                        post_simulation_accounts
+                            .iter()
+                            .find(|(key, _account)| key == &address)
+                            .map(|(pubkey, account)| {
+                                encode_account(account, pubkey, accounts_encoding, None)
+                            })
+                            .transpose()
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            )
+        }
+    } else {
+        None
+    };
+
+    Ok(RpcSimulateTransactionResult {
+        err: result.err(),
+        logs: Some(logs),
+        accounts,
+        units_consumed: Some(units_consumed),
+        return_data: return_data.map(|return_data| return_data.into()),
+        trace: execution_trace.map(encode_execution_trace),
+    })
+}
+
+fn encode_execution_trace(execution_trace: ExecutionTrace) -> RpcExecutionTrace {
+    RpcExecutionTrace {
+        frames: execution_trace
+            .frames
+            .into_iter()
+            .map(encode_instruction_frame)
+            .collect(),
+        truncated: execution_trace.truncated,
+    }
+}
+
+fn encode_instruction_frame(frame: InstructionFrame) -> RpcInstructionFrame {
+    RpcInstructionFrame {
+        program_id: frame.program_id.to_string(),
+        stack_height: frame.stack_height,
+        compute_units_consumed: frame.compute_units_consumed,
+        err: frame.error,
+        account_diffs: frame
+            .account_diffs
+            .into_iter()
+            .map(|account_diff| RpcAccountDiff {
+                pubkey: account_diff.pubkey.to_string(),
+                pre_lamports: account_diff.pre_lamports,
+                post_lamports: account_diff.post_lamports,
+                pre_owner: account_diff.pre_owner.to_string(),
+                post_owner: account_diff.post_owner.to_string(),
+                pre_data_len: account_diff.pre_data_len,
+                post_data_len: account_diff.post_data_len,
+                data_changes: account_diff
+                    .data_changes
+                    .into_iter()
+                    .map(|data_change| RpcAccountDataChange {
+                        offset: data_change.offset,
+                        data: base64::encode(data_change.data),
+                    })
+                    .collect(),
+            })
+            .collect(),
+        bpf_trace: frame.bpf_trace,
+        inner_frames: frame
+            .inner_frames
+            .into_iter()
+            .map(encode_instruction_frame)
+            .collect(),
+    }
+}
+
 pub(crate) fn create_validator_exit(exit: &Arc<AtomicBool>) -> Arc<RwLock<Exit>> {
     let mut validator_exit = Exit::default();
     let exit_ = exit.clone();
@@ -4617,8 +5716,9 @@ pub mod tests {
             filter::{Memcmp, MemcmpEncodedBytes},
         },
         solana_runtime::{
-            accounts_background_service::AbsRequestSender, commitment::BlockCommitment,
-            inline_spl_token, non_circulating_supply::non_circulating_accounts,
+            accounts_background_service::AbsRequestSender, accounts_index::DataOffsetIndex,
+            commitment::BlockCommitment, inline_spl_token,
+            non_circulating_supply::non_circulating_accounts,
         },
         solana_sdk::{
             account::{Account, WritableAccount},
@@ -5739,43 +6839,269 @@ pub mod tests {
         let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
         assert_eq!(result.len(), 0);
 
-        // Test multiple filters
+        // Test multiple filters
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{
+                    "memcmp": {
+                        "offset": 4,
+                        "bytes": bs58::encode(vec![1, 0, 0, 0]).into_string(),
+                    },
+                }, {
+                    "memcmp": {
+                        "offset": 8,
+                        "bytes": nonce_authorities[0].to_string(),
+                    },
+                }]}, // Filter on Initialized and Nonce authority
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 1);
+
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{
+                    "memcmp": {
+                        "offset": 4,
+                        "bytes": bs58::encode(vec![1, 0, 0, 0]).into_string(),
+                    },
+                }, {
+                    "dataSize": 1,
+                }]}, // Filter on Initialized and non-matching data size
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 0);
+    }
+
+    #[test]
+    fn test_signatures_for_address_filter() {
+        let info = |slot, block_time, err| ConfirmedTransactionStatusWithSignature {
+            signature: Signature::default(),
+            slot,
+            err,
+            memo: None,
+            block_time,
+        };
+        let failed = Some(TransactionError::AccountInUse);
+
+        let filter = verify_signatures_for_address_filter(&RpcSignaturesForAddressConfig {
+            min_slot: Some(10),
+            max_slot: Some(20),
+            min_block_time: Some(1_000),
+            errors_only: Some(true),
+            ..RpcSignaturesForAddressConfig::default()
+        })
+        .unwrap();
+        assert!(!filter.is_empty());
+        assert!(filter.allows(&info(15, Some(1_000), failed.clone())));
+        assert!(!filter.allows(&info(15, Some(1_000), None)));
+        assert!(!filter.allows(&info(15, None, failed.clone())));
+        assert!(!filter.allows(&info(21, Some(1_000), failed.clone())));
+        // Newest first, the listing is over once past the lower bounds
+        assert!(filter.is_past_range(&info(9, Some(1_000), None)));
+        assert!(filter.is_past_range(&info(15, Some(999), None)));
+        assert!(!filter.is_past_range(&info(21, Some(1_000), None)));
+
+        let filter = verify_signatures_for_address_filter(&RpcSignaturesForAddressConfig {
+            max_slot: Some(20),
+            order: Some(RpcSignaturesOrder::Ascending),
+            ..RpcSignaturesForAddressConfig::default()
+        })
+        .unwrap();
+        assert!(filter.allows(&info(15, None, failed)));
+        // Oldest first, the listing is over once past the upper bounds
+        assert!(filter.is_past_range(&info(21, None, None)));
+        assert!(!filter.is_past_range(&info(9, None, None)));
+
+        assert!(
+            verify_signatures_for_address_filter(&RpcSignaturesForAddressConfig {
+                order: Some(RpcSignaturesOrder::Ascending),
+                ..RpcSignaturesForAddressConfig::default()
+            })
+            .unwrap()
+            .is_empty()
+        );
+        assert!(
+            verify_signatures_for_address_filter(&RpcSignaturesForAddressConfig {
+                errors_only: Some(true),
+                success_only: Some(true),
+                ..RpcSignaturesForAddressConfig::default()
+            })
+            .is_err()
+        );
+        assert!(
+            verify_signatures_for_address_filter(&RpcSignaturesForAddressConfig {
+                min_block_time: Some(2),
+                max_block_time: Some(1),
+                ..RpcSignaturesForAddressConfig::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rpc_get_signatures_for_address_ascending_unknown_signature() {
+        let rpc = RpcHandler::start();
+        rpc.create_test_transactions_and_populate_blockstore();
+        let mint_pubkey = rpc.mint_keypair.pubkey();
+
+        let unknown_signature = Signature::new_unique();
+        for bound in ["before", "until"] {
+            let request = create_test_request(
+                "getSignaturesForAddress",
+                Some(json!([
+                    mint_pubkey.to_string(),
+                    {"order": "ascending", bound: unknown_signature.to_string()},
+                ])),
+            );
+            let response = parse_failure_response(rpc.handle_request_sync(request));
+            let expected = (
+                ErrorCode::InvalidParams.code(),
+                format!("Signature not found: {}", unknown_signature),
+            );
+            assert_eq!(response, expected);
+        }
+    }
+
+    #[test]
+    fn test_rpc_get_account_history() {
+        let rpc = RpcHandler::start();
+        let confirmed_block_signatures = rpc.create_test_transactions_and_populate_blockstore();
+        let rent_exempt_amount = rpc.working_bank().get_minimum_balance_for_rent_exemption(0);
+        let mint_pubkey = rpc.mint_keypair.pubkey();
+
+        let request =
+            create_test_request("getAccountHistory", Some(json!([mint_pubkey.to_string()])));
+        let result: Vec<RpcAccountBalanceChange> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 1);
+        let change = &result[0];
+        assert_eq!(change.signature, confirmed_block_signatures[0].to_string());
+        assert_eq!(change.err, None);
+        assert_eq!(
+            change.confirmation_status,
+            Some(TransactionConfirmationStatus::Finalized)
+        );
+        assert_eq!(
+            change.pre_balance.unwrap() - change.post_balance.unwrap(),
+            rent_exempt_amount + TEST_SIGNATURE_FEE
+        );
+        assert_eq!(change.pre_token_balance, None);
+        assert_eq!(change.post_token_balance, None);
+
+        // Paging past the only transaction
+        let request = create_test_request(
+            "getAccountHistory",
+            Some(json!([
+                mint_pubkey.to_string(),
+                {"before": confirmed_block_signatures[0].to_string()},
+            ])),
+        );
+        let result: Vec<RpcAccountBalanceChange> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert!(result.is_empty());
+
         let request = create_test_request(
-            "getProgramAccounts",
+            "getAccountHistory",
             Some(json!([
-                system_program::id().to_string(),
-                {"filters": [{
-                    "memcmp": {
-                        "offset": 4,
-                        "bytes": bs58::encode(vec![1, 0, 0, 0]).into_string(),
-                    },
-                }, {
-                    "memcmp": {
-                        "offset": 8,
-                        "bytes": nonce_authorities[0].to_string(),
-                    },
-                }]}, // Filter on Initialized and Nonce authority
+                mint_pubkey.to_string(),
+                {"limit": MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT + 1},
             ])),
         );
-        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
-        assert_eq!(result.len(), 1);
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            format!(
+                "Invalid limit; max {}",
+                MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT
+            ),
+        );
+        assert_eq!(response, expected);
+    }
+
+    #[test]
+    fn test_rpc_get_program_accounts_page() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+
+        let program_id = Pubkey::new_unique();
+        let mut account_keys = (0..5)
+            .map(|_| {
+                let pubkey = Pubkey::new_unique();
+                bank.store_account(&pubkey, &AccountSharedData::new(42, 0, &program_id));
+                pubkey
+            })
+            .collect::<Vec<_>>();
+        account_keys.sort();
+
+        // Walk the accounts two at a time, in key order
+        let mut pages = vec![];
+        let mut after: Option<String> = None;
+        loop {
+            let request = create_test_request(
+                "getProgramAccountsPage",
+                Some(json!([
+                    program_id.to_string(),
+                    {"limit": 2, "after": after},
+                ])),
+            );
+            let result: RpcResponse<RpcProgramAccountsPage> =
+                parse_success_result(rpc.handle_request_sync(request));
+            assert!(!result.value.slot_changed);
+            pages.push(
+                result
+                    .value
+                    .accounts
+                    .into_iter()
+                    .map(|keyed_account| keyed_account.pubkey)
+                    .collect::<Vec<_>>(),
+            );
+            after = result.value.next;
+            if after.is_none() {
+                break;
+            }
+        }
+        assert_eq!(
+            pages,
+            account_keys
+                .chunks(2)
+                .map(|chunk| chunk.iter().map(|pubkey| pubkey.to_string()).collect())
+                .collect::<Vec<Vec<_>>>()
+        );
 
+        // A cursor whose bank is gone restarts from the current bank
+        let cursor = ProgramAccountsCursor {
+            slot: 42,
+            pubkey: account_keys[2],
+        };
+        assert_eq!(cursor.encode().parse::<ProgramAccountsCursor>(), Ok(cursor));
         let request = create_test_request(
-            "getProgramAccounts",
+            "getProgramAccountsPage",
             Some(json!([
-                system_program::id().to_string(),
-                {"filters": [{
-                    "memcmp": {
-                        "offset": 4,
-                        "bytes": bs58::encode(vec![1, 0, 0, 0]).into_string(),
-                    },
-                }, {
-                    "dataSize": 1,
-                }]}, // Filter on Initialized and non-matching data size
+                program_id.to_string(),
+                {"after": cursor.encode()},
             ])),
         );
-        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
-        assert_eq!(result.len(), 0);
+        let result: RpcResponse<RpcProgramAccountsPage> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert!(result.value.slot_changed);
+        assert_eq!(result.value.accounts.len(), 2);
+        assert_eq!(result.value.next, None);
+
+        // Bad limits and cursors are rejected
+        for config in [json!({"limit": 0}), json!({"after": "notACursor"})] {
+            let request = create_test_request(
+                "getProgramAccountsPage",
+                Some(json!([program_id.to_string(), config])),
+            );
+            let response = parse_failure_response(rpc.handle_request_sync(request));
+            assert_eq!(response.0, ErrorCode::InvalidParams.code());
+        }
     }
 
     #[test]
@@ -6056,6 +7382,286 @@ pub mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_rpc_simulate_bundle() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        bank.freeze();
+
+        // The second transfer spends lamports only the first one provides
+        let keypair = Keypair::new();
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+        let fund_tx = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &keypair.pubkey(),
+            3 * rent_exempt_amount,
+            recent_blockhash,
+        );
+        let spend_tx = system_transaction::transfer(
+            &keypair,
+            &bob_pubkey,
+            rent_exempt_amount,
+            recent_blockhash,
+        );
+        let encode = |tx: &Transaction| base64::encode(serialize(tx).unwrap());
+
+        let request = create_test_request(
+            "simulateBundle",
+            Some(json!([
+                [encode(&fund_tx), encode(&spend_tx)],
+                {
+                    "encoding": "base64",
+                    "accounts": [null, {"addresses": [bob_pubkey.to_string()]}],
+                },
+            ])),
+        );
+        let result: RpcResponse<RpcSimulateBundleResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.err, None);
+        assert_eq!(result.value.transaction_results.len(), 2);
+        assert_eq!(result.value.transaction_results[0].accounts, None);
+        let bob_account = result.value.transaction_results[1]
+            .accounts
+            .as_ref()
+            .unwrap()[0]
+            .as_ref()
+            .unwrap();
+        assert_eq!(bob_account.lamports, rent_exempt_amount);
+        assert_eq!(bank.get_balance(&bob_pubkey), 0);
+
+        // Out of order, the bundle stops at the first transaction
+        let request = create_test_request(
+            "simulateBundle",
+            Some(json!([
+                [encode(&spend_tx), encode(&fund_tx)],
+                {"encoding": "base64"},
+            ])),
+        );
+        let result: RpcResponse<RpcSimulateBundleResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.err, Some(TransactionError::AccountNotFound));
+        assert_eq!(result.value.transaction_results.len(), 1);
+
+        let request = create_test_request(
+            "simulateBundle",
+            Some(json!([
+                [encode(&fund_tx), encode(&spend_tx)],
+                {"encoding": "base64", "accounts": [null]},
+            ])),
+        );
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            String::from("Expected 2 accounts configs, one per transaction"),
+        );
+        assert_eq!(response, expected);
+
+        let request = create_test_request("simulateBundle", Some(json!([[]])));
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            format!(
+                "Invalid number of transactions; expected 1 to {}",
+                MAX_SIMULATE_BUNDLE_TRANSACTIONS
+            ),
+        );
+        assert_eq!(response, expected);
+    }
+
+    #[test]
+    fn test_rpc_simulate_transaction_account_overrides() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        bank.freeze();
+
+        let keypair = Keypair::new();
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+        let tx = system_transaction::transfer(
+            &keypair,
+            &bob_pubkey,
+            rent_exempt_amount,
+            recent_blockhash,
+        );
+        let tx_encoded = base64::encode(serialize(&tx).unwrap());
+        let simulate = |account_overrides: Value| {
+            create_test_request(
+                "simulateTransaction",
+                Some(json!([
+                    tx_encoded,
+                    {
+                        "encoding": "base64",
+                        "accounts": {"addresses": [bob_pubkey.to_string()]},
+                        "accountOverrides": account_overrides,
+                    },
+                ])),
+            )
+        };
+
+        // The payer only exists in the overrides
+        let request = simulate(json!({
+            keypair.pubkey().to_string(): {
+                "lamports": 3 * rent_exempt_amount,
+                "owner": system_program::id().to_string(),
+            },
+        }));
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.err, None);
+        let bob_account = result.value.accounts.unwrap()[0].clone().unwrap();
+        assert_eq!(bob_account.lamports, rent_exempt_amount);
+        assert_eq!(bank.get_balance(&keypair.pubkey()), 0);
+
+        // An account with data can not pay fees
+        let request = simulate(json!({
+            keypair.pubkey().to_string(): {
+                "lamports": 3 * rent_exempt_amount,
+                "owner": system_program::id().to_string(),
+                "data": [base64::encode([1, 2, 3]), "base64"],
+            },
+        }));
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            result.value.err,
+            Some(TransactionError::InvalidAccountForFee)
+        );
+
+        let request = simulate(json!({
+            keypair.pubkey().to_string(): {
+                "lamports": 3 * rent_exempt_amount,
+                "owner": system_program::id().to_string(),
+                "data": ["not base64", "base64"],
+            },
+        }));
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            format!(
+                "Invalid account override data for {}; max {} bytes",
+                keypair.pubkey(),
+                system_instruction::MAX_PERMITTED_DATA_LENGTH
+            ),
+        );
+        assert_eq!(response, expected);
+
+        // Compressed data may not decompress past the account data size limit
+        let oversized_account = UiAccount::encode(
+            &keypair.pubkey(),
+            &AccountSharedData::from(Account {
+                data: vec![0; system_instruction::MAX_PERMITTED_DATA_LENGTH as usize + 1],
+                ..Account::default()
+            }),
+            UiAccountEncoding::Base64Zstd,
+            None,
+            None,
+        );
+        let request = simulate(json!({
+            keypair.pubkey().to_string(): {
+                "lamports": 3 * rent_exempt_amount,
+                "owner": system_program::id().to_string(),
+                "data": oversized_account.data,
+            },
+        }));
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(response, expected);
+
+        let account_overrides: serde_json::Map<_, _> = (0..=MAX_SIMULATE_ACCOUNT_OVERRIDES)
+            .map(|_| {
+                (
+                    solana_sdk::pubkey::new_rand().to_string(),
+                    json!({
+                        "lamports": rent_exempt_amount,
+                        "owner": system_program::id().to_string(),
+                    }),
+                )
+            })
+            .collect();
+        let request = simulate(Value::Object(account_overrides));
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            format!(
+                "Too many account overrides provided; max {}",
+                MAX_SIMULATE_ACCOUNT_OVERRIDES
+            ),
+        );
+        assert_eq!(response, expected);
+    }
+
+    #[test]
+    fn test_rpc_simulate_transaction_trace() {
+        let mut rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        bank.freeze();
+
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+        let tx = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &bob_pubkey,
+            rent_exempt_amount,
+            recent_blockhash,
+        );
+        let tx_encoded = base64::encode(serialize(&tx).unwrap());
+        let simulate = |trace: Value| {
+            create_test_request(
+                "simulateTransaction",
+                Some(json!([tx_encoded, {"encoding": "base64", "trace": trace}])),
+            )
+        };
+
+        // Traces are disabled by default
+        let response = parse_failure_response(rpc.handle_request_sync(simulate(json!({}))));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            String::from("Transaction simulation traces are not enabled on this node"),
+        );
+        assert_eq!(response, expected);
+
+        rpc.meta.config.max_simulation_trace_frames = Some(8);
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(rpc.handle_request_sync(simulate(json!({}))));
+        assert_eq!(result.value.err, None);
+        let trace = result.value.trace.unwrap();
+        assert!(!trace.truncated);
+        assert_eq!(trace.frames.len(), 1);
+        let frame = &trace.frames[0];
+        assert_eq!(frame.program_id, system_program::id().to_string());
+        assert_eq!(frame.stack_height, 1);
+        assert_eq!(frame.err, None);
+        assert!(frame.inner_frames.is_empty());
+        let bob_diff = frame
+            .account_diffs
+            .iter()
+            .find(|account_diff| account_diff.pubkey == bob_pubkey.to_string())
+            .unwrap();
+        assert_eq!(bob_diff.pre_lamports, 0);
+        assert_eq!(bob_diff.post_lamports, rent_exempt_amount);
+        assert!(bob_diff.data_changes.is_empty());
+
+        // Untraced simulations leave the trace out of the response
+        let request = create_test_request(
+            "simulateTransaction",
+            Some(json!([tx_encoded, {"encoding": "base64"}])),
+        );
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        assert!(result["value"].get("trace").is_none());
+
+        let response = parse_failure_response(rpc.handle_request_sync(simulate(
+            json!({"bpfTraceProgram": system_program::id().to_string()}),
+        )));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            String::from("BPF instruction traces are not enabled on this node"),
+        );
+        assert_eq!(response, expected);
+    }
+
     #[test]
     #[should_panic(expected = "simulation bank must be frozen")]
     fn test_rpc_simulate_transaction_panic_on_unfrozen_bank() {
@@ -7593,6 +9199,26 @@ pub mod tests {
                 serde_json::from_value(result["result"]["value"].clone()).unwrap();
             assert_eq!(accounts.len(), 3);
 
+            // Test getTokenAccountsByOwner without a filter returns accounts of all Token programs
+            let req = format!(
+                r#"{{
+                    "jsonrpc":"2.0",
+                    "id":1,
+                    "method":"getTokenAccountsByOwner",
+                    "params":["{}", null, {{"encoding":"base64"}}]
+                }}"#,
+                owner,
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            let accounts: Vec<RpcKeyedAccount> =
+                serde_json::from_value(result["result"]["value"].clone()).unwrap();
+            assert_eq!(accounts.len(), 3);
+            assert!(accounts
+                .iter()
+                .all(|account| account.account.owner == program_id.to_string()));
+
             // Test getTokenAccountsByOwner with jsonParsed encoding doesn't return accounts with invalid mints
             let req = format!(
                 r#"{{
@@ -7713,6 +9339,23 @@ pub mod tests {
                 serde_json::from_value(result["result"]["value"].clone()).unwrap();
             assert_eq!(accounts.len(), 3);
 
+            // Test getTokenAccountsByDelegate without a filter returns accounts of all Token programs
+            let req = format!(
+                r#"{{
+                    "jsonrpc":"2.0",
+                    "id":1,
+                    "method":"getTokenAccountsByDelegate",
+                    "params":["{}"]
+                }}"#,
+                delegate,
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            let accounts: Vec<RpcKeyedAccount> =
+                serde_json::from_value(result["result"]["value"].clone()).unwrap();
+            assert_eq!(accounts.len(), 3);
+
             // Test returns only mint accounts
             let req = format!(
                 r#"{{
@@ -8069,6 +9712,67 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_data_offset_index_key() {
+        let program_id = Pubkey::new_unique();
+        let index = DataOffsetIndex {
+            program_id,
+            offset: 8,
+            length: 4,
+        };
+        let account_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([AccountIndex::DataOffset(index)]),
+        };
+
+        let index_key = data_offset_index_key(
+            &account_indexes,
+            &program_id,
+            &[
+                RpcFilterType::DataSize(64),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(8, vec![1, 2, 3, 4, 5])),
+            ],
+        );
+        match index_key {
+            Some(IndexKey::DataOffset(key_index, bytes)) => {
+                assert_eq!(key_index, index);
+                assert_eq!(bytes, vec![1, 2, 3, 4]);
+            }
+            index_key => panic!("unexpected index key {:?}", index_key),
+        }
+
+        // Too few bytes to use the index
+        assert!(data_offset_index_key(
+            &account_indexes,
+            &program_id,
+            &[RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                8,
+                vec![1, 2, 3]
+            ))],
+        )
+        .is_none());
+        // Different offset
+        assert!(data_offset_index_key(
+            &account_indexes,
+            &program_id,
+            &[RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                vec![1, 2, 3, 4]
+            ))],
+        )
+        .is_none());
+        // Different program
+        assert!(data_offset_index_key(
+            &account_indexes,
+            &Pubkey::new_unique(),
+            &[RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                8,
+                vec![1, 2, 3, 4]
+            ))],
+        )
+        .is_none());
+    }
+
     #[test]
     fn test_get_spl_token_owner_filter() {
         // Filtering on token-v3 length
@@ -1,11 +1,14 @@
 use {
+    crate::rpc_subscriptions::RpcSubscriptions,
     solana_gossip::cluster_info::ClusterInfo,
+    solana_runtime::bank_forks::BankForks,
     solana_sdk::{clock::Slot, pubkey::Pubkey},
     std::{
         collections::HashSet,
+        net::SocketAddr,
         sync::{
-            atomic::{AtomicBool, Ordering},
-            Arc,
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc, RwLock,
         },
     },
 };
@@ -17,23 +20,141 @@ pub enum RpcHealthStatus {
     Unknown,
 }
 
+// `ValidatorStartProgress` contains status information that is surfaced to the node operator over
+// the admin RPC channel to help them to follow the general progress of node startup without
+// having to watch log messages.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ValidatorStartProgress {
+    Initializing, // Catch all, default state
+    SearchingForRpcService,
+    DownloadingSnapshot {
+        slot: Slot,
+        rpc_addr: SocketAddr,
+    },
+    CleaningBlockStore,
+    CleaningAccounts,
+    LoadingLedger,
+    ProcessingLedger {
+        slot: Slot,
+        max_slot: Slot,
+    },
+    StartingServices,
+    Halted, // Validator halted due to `--dev-halt-at-slot` argument
+    WaitingForSupermajority {
+        slot: Slot,
+        gossip_stake_percent: u64,
+    },
+
+    // `Running` is the terminal state once the validator fully starts and all services are
+    // operational
+    Running,
+}
+
+impl Default for ValidatorStartProgress {
+    fn default() -> Self {
+        Self::Initializing
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RpcHealthCheckStatus {
+    Pass,
+    Fail,
+}
+
+impl From<bool> for RpcHealthCheckStatus {
+    fn from(pass: bool) -> Self {
+        if pass {
+            Self::Pass
+        } else {
+            Self::Fail
+        }
+    }
+}
+
+/// The outcome of a single readiness check
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcHealthCheck {
+    pub name: &'static str,
+    pub status: RpcHealthCheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub observed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<&'static str>,
+}
+
+impl RpcHealthCheck {
+    fn new(name: &'static str, pass: bool) -> Self {
+        Self {
+            name,
+            status: pass.into(),
+            observed: None,
+            threshold: None,
+            detail: None,
+        }
+    }
+
+    /// Pass if `observed` does not exceed `threshold`
+    fn with_threshold(name: &'static str, observed: u64, threshold: u64) -> Self {
+        Self {
+            observed: Some(observed),
+            threshold: Some(threshold),
+            ..Self::new(name, observed <= threshold)
+        }
+    }
+
+    fn detail(self, detail: &'static str) -> Self {
+        Self {
+            detail: Some(detail),
+            ..self
+        }
+    }
+}
+
+/// The body of the `/health/live` and `/health/ready` responses
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcHealthReport {
+    pub status: RpcHealthCheckStatus,
+    pub start_progress: ValidatorStartProgress,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<RpcHealthCheck>,
+}
+
 pub struct RpcHealth {
     cluster_info: Arc<ClusterInfo>,
-    known_validators: Option<HashSet<Pubkey>>,
+    known_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
     health_check_slot_distance: u64,
     override_health_check: Arc<AtomicBool>,
     startup_verification_complete: Arc<AtomicBool>,
+    start_progress: Arc<RwLock<ValidatorStartProgress>>,
+    bank_forks: Arc<RwLock<BankForks>>,
+    max_complete_transaction_status_slot: Arc<AtomicU64>,
+    rpc_subscriptions: Option<Arc<RpcSubscriptions>>,
+    max_pubsub_backlog: Option<usize>,
+    max_blockstore_write_lag: Option<u64>,
     #[cfg(test)]
     stub_health_status: std::sync::RwLock<Option<RpcHealthStatus>>,
 }
 
 impl RpcHealth {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         cluster_info: Arc<ClusterInfo>,
-        known_validators: Option<HashSet<Pubkey>>,
+        known_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
         health_check_slot_distance: u64,
         override_health_check: Arc<AtomicBool>,
         startup_verification_complete: Arc<AtomicBool>,
+        start_progress: Arc<RwLock<ValidatorStartProgress>>,
+        bank_forks: Arc<RwLock<BankForks>>,
+        max_complete_transaction_status_slot: Arc<AtomicU64>,
+        rpc_subscriptions: Option<Arc<RpcSubscriptions>>,
+        max_pubsub_backlog: Option<usize>,
+        max_blockstore_write_lag: Option<u64>,
     ) -> Self {
         Self {
             cluster_info,
@@ -41,6 +162,12 @@ impl RpcHealth {
             health_check_slot_distance,
             override_health_check,
             startup_verification_complete,
+            start_progress,
+            bank_forks,
+            max_complete_transaction_status_slot,
+            rpc_subscriptions,
+            max_pubsub_backlog,
+            max_blockstore_write_lag,
             #[cfg(test)]
             stub_health_status: std::sync::RwLock::new(None),
         }
@@ -60,30 +187,8 @@ impl RpcHealth {
 
         if self.override_health_check.load(Ordering::Relaxed) {
             RpcHealthStatus::Ok
-        } else if let Some(known_validators) = &self.known_validators {
-            match (
-                self.cluster_info
-                    .get_accounts_hash_for_node(&self.cluster_info.id(), |hashes| {
-                        hashes
-                            .iter()
-                            .max_by(|a, b| a.0.cmp(&b.0))
-                            .map(|slot_hash| slot_hash.0)
-                    })
-                    .flatten(),
-                known_validators
-                    .iter()
-                    .filter_map(|known_validator| {
-                        self.cluster_info
-                            .get_accounts_hash_for_node(known_validator, |hashes| {
-                                hashes
-                                    .iter()
-                                    .max_by(|a, b| a.0.cmp(&b.0))
-                                    .map(|slot_hash| slot_hash.0)
-                            })
-                            .flatten()
-                    })
-                    .max(),
-            ) {
+        } else if let Some(known_validators) = self.known_validators.read().unwrap().as_ref() {
+            match self.latest_account_hash_slots(known_validators) {
                 (
                     Some(latest_account_hash_slot),
                     Some(latest_known_validator_account_hash_slot),
@@ -126,22 +231,136 @@ impl RpcHealth {
         }
     }
 
+    /// Liveness only requires the RPC service to answer, so this always passes and just reports
+    /// how far along startup is
+    pub fn check_live(&self) -> RpcHealthReport {
+        RpcHealthReport {
+            status: RpcHealthCheckStatus::Pass,
+            start_progress: *self.start_progress.read().unwrap(),
+            checks: vec![],
+        }
+    }
+
+    /// Readiness requires every check to pass
+    pub fn check_ready(&self) -> RpcHealthReport {
+        let start_progress = *self.start_progress.read().unwrap();
+        let mut checks = vec![
+            RpcHealthCheck::new(
+                "startProgress",
+                start_progress == ValidatorStartProgress::Running,
+            ),
+            RpcHealthCheck::new(
+                "snapshotVerification",
+                self.startup_verification_complete.load(Ordering::Acquire),
+            ),
+            self.check_slot_distance(),
+        ];
+        if let (Some(rpc_subscriptions), Some(max_pubsub_backlog)) =
+            (&self.rpc_subscriptions, self.max_pubsub_backlog)
+        {
+            checks.push(RpcHealthCheck::with_threshold(
+                "pubsubBacklog",
+                rpc_subscriptions.notification_backlog() as u64,
+                max_pubsub_backlog as u64,
+            ));
+        }
+        if let Some(max_blockstore_write_lag) = self.max_blockstore_write_lag {
+            // How far the transaction statuses written to the blockstore trail the banks being
+            // replayed
+            let highest_slot = self.bank_forks.read().unwrap().highest_slot();
+            let max_complete_transaction_status_slot = self
+                .max_complete_transaction_status_slot
+                .load(Ordering::SeqCst);
+            checks.push(RpcHealthCheck::with_threshold(
+                "blockstoreWriteLag",
+                highest_slot.saturating_sub(max_complete_transaction_status_slot),
+                max_blockstore_write_lag,
+            ));
+        }
+
+        let pass = checks
+            .iter()
+            .all(|check| check.status == RpcHealthCheckStatus::Pass);
+        RpcHealthReport {
+            status: pass.into(),
+            start_progress,
+            checks,
+        }
+    }
+
+    fn check_slot_distance(&self) -> RpcHealthCheck {
+        const NAME: &str = "slotDistance";
+        if self.override_health_check.load(Ordering::Relaxed) {
+            return RpcHealthCheck::new(NAME, true).detail("health check overridden");
+        }
+        let known_validators = self.known_validators.read().unwrap();
+        let known_validators = match known_validators.as_ref() {
+            Some(known_validators) => known_validators,
+            None => return RpcHealthCheck::new(NAME, true).detail("no known validators"),
+        };
+        match self.latest_account_hash_slots(known_validators) {
+            (Some(latest_account_hash_slot), Some(latest_known_validator_account_hash_slot)) => {
+                let num_slots = latest_known_validator_account_hash_slot
+                    .saturating_sub(latest_account_hash_slot);
+                RpcHealthCheck {
+                    observed: Some(num_slots),
+                    threshold: Some(self.health_check_slot_distance),
+                    ..RpcHealthCheck::new(NAME, num_slots < self.health_check_slot_distance)
+                }
+            }
+            _ => RpcHealthCheck::new(NAME, false).detail("account hashes not available"),
+        }
+    }
+
+    /// The latest account hash slots of this validator and of the known validators
+    fn latest_account_hash_slots(
+        &self,
+        known_validators: &HashSet<Pubkey>,
+    ) -> (Option<Slot>, Option<Slot>) {
+        let latest_account_hash_slot = |node: &Pubkey| {
+            self.cluster_info
+                .get_accounts_hash_for_node(node, |hashes| {
+                    hashes
+                        .iter()
+                        .max_by(|a, b| a.0.cmp(&b.0))
+                        .map(|slot_hash| slot_hash.0)
+                })
+                .flatten()
+        };
+        (
+            latest_account_hash_slot(&self.cluster_info.id()),
+            known_validators
+                .iter()
+                .filter_map(latest_account_hash_slot)
+                .max(),
+        )
+    }
+
     #[cfg(test)]
     pub(crate) fn stub() -> Arc<Self> {
         use {
-            solana_gossip::contact_info::ContactInfo, solana_sdk::signer::keypair::Keypair,
+            solana_gossip::contact_info::ContactInfo,
+            solana_runtime::{bank::Bank, genesis_utils::create_genesis_config},
+            solana_sdk::signer::keypair::Keypair,
             solana_streamer::socket::SocketAddrSpace,
         };
+        let bank = Bank::new_for_tests(&create_genesis_config(100).genesis_config);
         Arc::new(Self::new(
             Arc::new(ClusterInfo::new(
                 ContactInfo::default(),
                 Arc::new(Keypair::new()),
                 SocketAddrSpace::Unspecified,
             )),
-            None,
+            Arc::default(),
             42,
             Arc::new(AtomicBool::new(false)),
             Arc::new(AtomicBool::new(true)),
+            Arc::new(RwLock::new(ValidatorStartProgress::Running)),
+            Arc::new(RwLock::new(BankForks::new(bank))),
+            Arc::default(),
+            None,
+            None,
+            None,
         ))
     }
 
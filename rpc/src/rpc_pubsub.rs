@@ -2,13 +2,13 @@
 
 use {
     crate::{
-        rpc::check_is_at_least_confirmed,
-        rpc_pubsub_service::PubSubConfig,
+        rpc::{check_is_at_least_confirmed, optimize_filters, verify_filter},
+        rpc_pubsub_service::{PubSubConfig, SubscriptionQuota},
         rpc_subscription_tracker::{
             AccountSubscriptionParams, BlockSubscriptionKind, BlockSubscriptionParams,
             LogsSubscriptionKind, LogsSubscriptionParams, ProgramSubscriptionParams,
             SignatureSubscriptionParams, SubscriptionControl, SubscriptionId, SubscriptionParams,
-            SubscriptionToken,
+            SubscriptionToken, TransactionSubscriptionParams,
         },
     },
     dashmap::DashMap,
@@ -20,11 +20,13 @@ use {
         config::{
             RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
             RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcTransactionLogsFilter, RpcTransactionSubscribeConfig, RpcTransactionSubscribeFilter,
         },
+        request::MAX_TRANSACTION_SUBSCRIBE_FILTER_ACCOUNTS,
         response::{
             Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse,
-            RpcSignatureResult, RpcVersionInfo, RpcVote, SlotInfo, SlotUpdate,
+            RpcSignatureResult, RpcTransactionUpdate, RpcVersionInfo, RpcVote, SlotInfo,
+            SlotUpdate,
         },
     },
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
@@ -213,6 +215,32 @@ pub trait RpcSolPubSub {
         id: PubSubSubscriptionId,
     ) -> Result<bool>;
 
+    // Subscribe to processed transactions, with their status
+    #[pubsub(
+        subscription = "transactionNotification",
+        subscribe,
+        name = "transactionSubscribe"
+    )]
+    fn transaction_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<Arc<RpcTransactionUpdate>>,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    );
+
+    // Unsubscribe from transaction notification subscription.
+    #[pubsub(
+        subscription = "transactionNotification",
+        unsubscribe,
+        name = "transactionUnsubscribe"
+    )]
+    fn transaction_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        id: PubSubSubscriptionId,
+    ) -> Result<bool>;
+
     // Get notification when vote is encountered
     #[pubsub(subscription = "voteNotification", subscribe, name = "voteSubscribe")]
     fn vote_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<RpcVote>);
@@ -333,6 +361,18 @@ mod internal {
         #[rpc(name = "blockUnsubscribe")]
         fn block_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
 
+        // Subscribe to processed transactions, with their status
+        #[rpc(name = "transactionSubscribe")]
+        fn transaction_subscribe(
+            &self,
+            filter: RpcTransactionSubscribeFilter,
+            config: Option<RpcTransactionSubscribeConfig>,
+        ) -> Result<SubscriptionId>;
+
+        // Unsubscribe from transaction notification subscription.
+        #[rpc(name = "transactionUnsubscribe")]
+        fn transaction_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
+
         // Get notification when vote is encountered
         #[rpc(name = "voteSubscribe")]
         fn vote_subscribe(&self) -> Result<SubscriptionId>;
@@ -359,6 +399,7 @@ pub struct RpcSolPubSubImpl {
     config: PubSubConfig,
     subscription_control: SubscriptionControl,
     current_subscriptions: Arc<DashMap<SubscriptionId, SubscriptionToken>>,
+    subscription_quota: Option<SubscriptionQuota>,
 }
 
 impl RpcSolPubSubImpl {
@@ -366,27 +407,47 @@ impl RpcSolPubSubImpl {
         config: PubSubConfig,
         subscription_control: SubscriptionControl,
         current_subscriptions: Arc<DashMap<SubscriptionId, SubscriptionToken>>,
+        subscription_quota: Option<SubscriptionQuota>,
     ) -> Self {
         Self {
             config,
             subscription_control,
             current_subscriptions,
+            subscription_quota,
         }
     }
 
     fn subscribe(&self, params: SubscriptionParams) -> Result<SubscriptionId> {
-        let token = self
-            .subscription_control
-            .subscribe(params)
-            .map_err(|_| Error {
-                code: ErrorCode::InternalError,
-                message: "Internal Error: Subscription refused. Node subscription limit reached"
-                    .into(),
-                data: None,
-            })?;
-        let id = token.id();
-        self.current_subscriptions.insert(id, token);
-        Ok(id)
+        let subscribe = || {
+            let token = self
+                .subscription_control
+                .subscribe(params)
+                .map_err(|_| Error {
+                    code: ErrorCode::InternalError,
+                    message:
+                        "Internal Error: Subscription refused. Node subscription limit reached"
+                            .into(),
+                    data: None,
+                })?;
+            let id = token.id();
+            self.current_subscriptions.insert(id, token);
+            Ok(id)
+        };
+        match &self.subscription_quota {
+            Some(subscription_quota) => {
+                subscription_quota
+                    .try_subscribe(subscribe)
+                    .unwrap_or_else(|| {
+                        Err(Error {
+                            code: ErrorCode::InvalidRequest,
+                            message: "Subscription refused. IP address subscription limit reached"
+                                .into(),
+                            data: None,
+                        })
+                    })
+            }
+            None => subscribe(),
+        }
     }
 
     fn unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
@@ -441,9 +502,14 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
         config: Option<RpcProgramAccountsConfig>,
     ) -> Result<SubscriptionId> {
         let config = config.unwrap_or_default();
+        let mut filters = config.filters.unwrap_or_default();
+        for filter in &filters {
+            verify_filter(filter)?;
+        }
+        optimize_filters(&mut filters);
         let params = ProgramSubscriptionParams {
             pubkey: param::<Pubkey>(&pubkey_str, "pubkey")?,
-            filters: config.filters.unwrap_or_default(),
+            filters,
             encoding: config
                 .account_config
                 .encoding
@@ -559,6 +625,54 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
         self.unsubscribe(id)
     }
 
+    fn transaction_subscribe(
+        &self,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    ) -> Result<SubscriptionId> {
+        if !self.config.enable_transaction_subscription {
+            return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
+        }
+        let config = config.unwrap_or_default();
+        let commitment = config.commitment.unwrap_or_default();
+        let parse_accounts = |keys: Option<Vec<String>>, thing| -> Result<Vec<Pubkey>> {
+            let keys = keys.unwrap_or_default();
+            if keys.len() > MAX_TRANSACTION_SUBSCRIBE_FILTER_ACCOUNTS {
+                return Err(Error {
+                    code: ErrorCode::InvalidParams,
+                    message: format!(
+                        "Invalid Request: Too many {} provided; max {}",
+                        thing, MAX_TRANSACTION_SUBSCRIBE_FILTER_ACCOUNTS
+                    ),
+                    data: None,
+                });
+            }
+            let mut keys = keys
+                .iter()
+                .map(|key| param::<Pubkey>(key, thing))
+                .collect::<Result<Vec<_>>>()?;
+            keys.sort();
+            keys.dedup();
+            Ok(keys)
+        };
+        let params = TransactionSubscriptionParams {
+            commitment,
+            encoding: config.encoding.unwrap_or(UiTransactionEncoding::Base64),
+            max_supported_transaction_version: config.max_supported_transaction_version,
+            account_include: parse_accounts(filter.account_include, "accountInclude")?,
+            account_exclude: parse_accounts(filter.account_exclude, "accountExclude")?,
+            failed: filter.failed,
+        };
+        self.subscribe(SubscriptionParams::Transaction(params))
+    }
+
+    fn transaction_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
+        if !self.config.enable_transaction_subscription {
+            return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
+        }
+        self.unsubscribe(id)
+    }
+
     fn vote_subscribe(&self) -> Result<SubscriptionId> {
         if !self.config.enable_vote_subscription {
             return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
@@ -2,26 +2,29 @@
 
 use {
     crate::{
+        rpc::{JsonRpcRequestProcessor, MAX_REQUEST_BODY_SIZE},
         rpc_pubsub::{RpcSolPubSubImpl, RpcSolPubSubInternal},
+        rpc_service::{rpc_io_handler, RpcRateLimitMiddleware},
         rpc_subscription_tracker::{
             SubscriptionControl, SubscriptionId, SubscriptionParams, SubscriptionToken,
         },
         rpc_subscriptions::{RpcNotification, RpcSubscriptions},
     },
     dashmap::{mapref::entry::Entry, DashMap},
-    jsonrpc_core::IoHandler,
+    jsonrpc_core::{IoHandler, MetaIoHandler},
     soketto::handshake::{server, Server},
     solana_metrics::TokenCounter,
     std::{
+        collections::HashMap,
         io,
-        net::SocketAddr,
+        net::{IpAddr, SocketAddr},
         str,
-        sync::Arc,
+        sync::{Arc, Mutex, Weak},
         thread::{self, Builder, JoinHandle},
     },
     stream_cancel::{Trigger, Tripwire},
     thiserror::Error,
-    tokio::{net::TcpStream, pin, select, sync::broadcast},
+    tokio::{net::TcpStream, pin, runtime::Handle, select, sync::broadcast},
     tokio_util::compat::TokioAsyncReadCompatExt,
 };
 
@@ -34,24 +37,33 @@ pub const DEFAULT_WORKER_THREADS: usize = 1;
 #[derive(Debug, Clone)]
 pub struct PubSubConfig {
     pub enable_block_subscription: bool,
+    pub enable_transaction_subscription: bool,
     pub enable_vote_subscription: bool,
     pub max_active_subscriptions: usize,
     pub queue_capacity_items: usize,
     pub queue_capacity_bytes: usize,
     pub worker_threads: usize,
     pub notification_threads: Option<usize>,
+    /// Also serve the JSON RPC methods of the HTTP endpoint on the websocket
+    pub enable_rpc_requests: bool,
+    /// Maximum number of active subscriptions of the connections from each IP address,
+    /// unlimited if `None`
+    pub max_subscriptions_per_ip: Option<usize>,
 }
 
 impl Default for PubSubConfig {
     fn default() -> Self {
         Self {
             enable_block_subscription: false,
+            enable_transaction_subscription: false,
             enable_vote_subscription: false,
             max_active_subscriptions: MAX_ACTIVE_SUBSCRIPTIONS,
             queue_capacity_items: DEFAULT_QUEUE_CAPACITY_ITEMS,
             queue_capacity_bytes: DEFAULT_QUEUE_CAPACITY_BYTES,
             worker_threads: DEFAULT_WORKER_THREADS,
             notification_threads: None,
+            enable_rpc_requests: false,
+            max_subscriptions_per_ip: None,
         }
     }
 }
@@ -60,12 +72,15 @@ impl PubSubConfig {
     pub fn default_for_tests() -> Self {
         Self {
             enable_block_subscription: false,
+            enable_transaction_subscription: false,
             enable_vote_subscription: false,
             max_active_subscriptions: MAX_ACTIVE_SUBSCRIPTIONS,
             queue_capacity_items: DEFAULT_TEST_QUEUE_CAPACITY_ITEMS,
             queue_capacity_bytes: DEFAULT_QUEUE_CAPACITY_BYTES,
             worker_threads: DEFAULT_WORKER_THREADS,
             notification_threads: Some(2),
+            enable_rpc_requests: false,
+            max_subscriptions_per_ip: None,
         }
     }
 }
@@ -79,6 +94,18 @@ impl PubSubService {
         pubsub_config: PubSubConfig,
         subscriptions: &Arc<RpcSubscriptions>,
         pubsub_addr: SocketAddr,
+    ) -> (Trigger, Self) {
+        Self::new_with_request_processor(pubsub_config, subscriptions, pubsub_addr, None)
+    }
+
+    /// Like `new`, but JSON RPC requests which are not subscriptions are served by
+    /// `request_processor` if it is given, with the limits of its `JsonRpcConfig`, on the
+    /// `rpc_runtime` of the JSON RPC service
+    pub fn new_with_request_processor(
+        pubsub_config: PubSubConfig,
+        subscriptions: &Arc<RpcSubscriptions>,
+        pubsub_addr: SocketAddr,
+        request_processor: Option<(JsonRpcRequestProcessor, Handle)>,
     ) -> (Trigger, Self) {
         let subscription_control = subscriptions.control().clone();
         info!("rpc_pubsub bound to {:?}", pubsub_addr);
@@ -96,6 +123,7 @@ impl PubSubService {
                     pubsub_addr,
                     pubsub_config,
                     subscription_control,
+                    request_processor,
                     tripwire,
                 )) {
                     error!("pubsub service failed: {}", err);
@@ -115,6 +143,69 @@ impl PubSubService {
     }
 }
 
+type ConnectionSubscriptions = DashMap<SubscriptionId, SubscriptionToken>;
+
+/// The subscriptions of the open connections from each IP address
+type IpConnections = Mutex<HashMap<IpAddr, Vec<Weak<ConnectionSubscriptions>>>>;
+
+/// Limits the active subscriptions of all connections from the IP address of one connection
+pub struct SubscriptionQuota {
+    ip_connections: Arc<IpConnections>,
+    ip: IpAddr,
+    max_subscriptions: usize,
+    current_subscriptions: Weak<ConnectionSubscriptions>,
+}
+
+impl SubscriptionQuota {
+    fn new(
+        ip_connections: Arc<IpConnections>,
+        ip: IpAddr,
+        max_subscriptions: usize,
+        current_subscriptions: &Arc<ConnectionSubscriptions>,
+    ) -> Self {
+        let current_subscriptions = Arc::downgrade(current_subscriptions);
+        ip_connections
+            .lock()
+            .unwrap()
+            .entry(ip)
+            .or_default()
+            .push(current_subscriptions.clone());
+        Self {
+            ip_connections,
+            ip,
+            max_subscriptions,
+            current_subscriptions,
+        }
+    }
+
+    /// Runs `subscribe` unless the connections from this IP address have reached the
+    /// subscription limit. The limit stays locked meanwhile, so concurrent connections from the
+    /// address can't exceed it together.
+    pub fn try_subscribe<T>(&self, subscribe: impl FnOnce() -> T) -> Option<T> {
+        let ip_connections = self.ip_connections.lock().unwrap();
+        let subscriptions: usize = ip_connections
+            .get(&self.ip)
+            .into_iter()
+            .flatten()
+            .filter_map(Weak::upgrade)
+            .map(|current_subscriptions| current_subscriptions.len())
+            .sum();
+        (subscriptions < self.max_subscriptions).then(subscribe)
+    }
+}
+
+impl Drop for SubscriptionQuota {
+    fn drop(&mut self) {
+        let mut ip_connections = self.ip_connections.lock().unwrap();
+        if let Some(connections) = ip_connections.get_mut(&self.ip) {
+            connections.retain(|connection| !connection.ptr_eq(&self.current_subscriptions));
+            if connections.is_empty() {
+                ip_connections.remove(&self.ip);
+            }
+        }
+    }
+}
+
 struct BroadcastHandler {
     current_subscriptions: Arc<DashMap<SubscriptionId, SubscriptionToken>>,
 }
@@ -148,6 +239,9 @@ fn count_final(params: &SubscriptionParams) {
         SubscriptionParams::Block(_) => {
             inc_new_counter_info!("rpc-pubsub-final-slot-txs", 1);
         }
+        SubscriptionParams::Transaction(_) => {
+            inc_new_counter_info!("rpc-pubsub-final-transactions", 1);
+        }
     }
 }
 
@@ -236,12 +330,14 @@ pub fn test_connection(
     let rpc_impl = RpcSolPubSubImpl::new(
         PubSubConfig {
             enable_block_subscription: true,
+            enable_transaction_subscription: true,
             enable_vote_subscription: true,
             queue_capacity_items: 100,
             ..PubSubConfig::default()
         },
         subscriptions.control().clone(),
         Arc::clone(&current_subscriptions),
+        None,
     );
     let broadcast_handler = BroadcastHandler {
         current_subscriptions,
@@ -265,10 +361,46 @@ enum Error {
     NotificationIsGone,
 }
 
+enum RequestHandler {
+    /// Serves subscriptions only
+    PubSub(IoHandler),
+    /// Serves subscriptions and the methods of the JSON RPC service, on its runtime
+    Rpc(
+        Arc<MetaIoHandler<JsonRpcRequestProcessor, RpcRateLimitMiddleware>>,
+        JsonRpcRequestProcessor,
+        Handle,
+    ),
+}
+
+impl RequestHandler {
+    async fn handle_request(&self, request: &str) -> Option<String> {
+        match self {
+            Self::PubSub(io) => io.handle_request(request).await,
+            Self::Rpc(io, request_processor, rpc_runtime) => {
+                // RPC methods may block, so run them on the threads of the JSON RPC service
+                // rather than stall the notifications of the connections served by this runtime
+                let io = Arc::clone(io);
+                let request = request.to_string();
+                let request_processor = request_processor.clone();
+                rpc_runtime
+                    .spawn(async move { io.handle_request(&request, request_processor).await })
+                    .await
+                    .unwrap_or_else(|err| {
+                        warn!("JSON RPC request over pubsub failed: {}", err);
+                        None
+                    })
+            }
+        }
+    }
+}
+
 async fn handle_connection(
     socket: TcpStream,
+    addr: SocketAddr,
     subscription_control: SubscriptionControl,
     config: PubSubConfig,
+    request_processor: Option<(JsonRpcRequestProcessor, Handle)>,
+    ip_connections: Arc<IpConnections>,
     mut tripwire: Tripwire,
 ) -> Result<(), Error> {
     let mut server = Server::new(socket.compat());
@@ -278,19 +410,55 @@ async fn handle_connection(
         protocol: None,
     };
     server.send_response(&accept).await?;
-    let (mut sender, mut receiver) = server.into_builder().finish();
+    let mut builder = server.into_builder();
+    if let Some((request_processor, _)) = &request_processor {
+        builder.set_max_message_size(
+            request_processor
+                .config()
+                .max_request_body_size
+                .unwrap_or(MAX_REQUEST_BODY_SIZE),
+        );
+    }
+    let (mut sender, mut receiver) = builder.finish();
 
     let mut broadcast_receiver = subscription_control.broadcast_receiver();
     let mut data = Vec::new();
     let current_subscriptions = Arc::new(DashMap::new());
 
-    let mut json_rpc_handler = IoHandler::new();
+    let subscription_quota = config.max_subscriptions_per_ip.map(|max_subscriptions| {
+        SubscriptionQuota::new(
+            ip_connections,
+            addr.ip(),
+            max_subscriptions,
+            &current_subscriptions,
+        )
+    });
     let rpc_impl = RpcSolPubSubImpl::new(
         config,
         subscription_control,
         Arc::clone(&current_subscriptions),
+        subscription_quota,
     );
-    json_rpc_handler.extend_with(rpc_impl.to_delegate());
+    let json_rpc_handler = match request_processor {
+        Some((request_processor, rpc_runtime)) => {
+            let rpc_config = request_processor.config();
+            let mut io = rpc_io_handler(rpc_config.full_api, rpc_config.obsolete_v1_7_api);
+            io.extend_with(rpc_impl.to_delegate());
+            // Calls are charged to the same rate limits as HTTP requests. The handshake does not
+            // expose the API key header, so connections are limited by IP address.
+            let request_processor = match request_processor.rate_limiter() {
+                Some(rate_limiter) => request_processor
+                    .with_rate_limit_client(rate_limiter.client_id(None, Some(addr.ip()))),
+                None => request_processor,
+            };
+            RequestHandler::Rpc(Arc::new(io), request_processor, rpc_runtime)
+        }
+        None => {
+            let mut io = IoHandler::new();
+            io.extend_with(rpc_impl.to_delegate());
+            RequestHandler::PubSub(io)
+        }
+    };
     let broadcast_handler = BroadcastHandler {
         current_subscriptions,
     };
@@ -345,10 +513,12 @@ async fn listen(
     listen_address: SocketAddr,
     config: PubSubConfig,
     subscription_control: SubscriptionControl,
+    request_processor: Option<(JsonRpcRequestProcessor, Handle)>,
     mut tripwire: Tripwire,
 ) -> io::Result<()> {
     let listener = tokio::net::TcpListener::bind(&listen_address).await?;
     let counter = TokenCounter::new("rpc_pubsub_connections");
+    let ip_connections = Arc::new(IpConnections::default());
     loop {
         select! {
             result = listener.accept() => match result {
@@ -356,11 +526,19 @@ async fn listen(
                     debug!("new client ({:?})", addr);
                     let subscription_control = subscription_control.clone();
                     let config = config.clone();
+                    let request_processor = request_processor.clone();
+                    let ip_connections = Arc::clone(&ip_connections);
                     let tripwire = tripwire.clone();
                     let counter_token = counter.create_token();
                     tokio::spawn(async move {
                         let handle = handle_connection(
-                            socket, subscription_control, config, tripwire
+                            socket,
+                            addr,
+                            subscription_control,
+                            config,
+                            request_processor,
+                            ip_connections,
+                            tripwire,
                         );
                         match handle.await {
                             Ok(()) => debug!("connection closed ({:?})", addr),
@@ -388,7 +566,7 @@ mod tests {
             genesis_utils::{create_genesis_config, GenesisConfigInfo},
         },
         std::{
-            net::{IpAddr, Ipv4Addr},
+            net::Ipv4Addr,
             sync::{
                 atomic::{AtomicBool, AtomicU64},
                 RwLock,
@@ -418,4 +596,62 @@ mod tests {
         let thread = pubsub_service.thread_hdl.thread();
         assert_eq!(thread.name().unwrap(), "solRpcPubSub");
     }
+
+    #[test]
+    fn test_subscription_quota() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let subscriptions = Arc::new(RpcSubscriptions::new_for_tests(
+            &exit,
+            Arc::new(AtomicU64::default()),
+            bank_forks,
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+        ));
+        let subscribe =
+            |quota: &SubscriptionQuota, connection: &ConnectionSubscriptions, params| {
+                quota
+                    .try_subscribe(|| {
+                        let token = subscriptions.control().subscribe(params).unwrap();
+                        connection.insert(token.id(), token);
+                    })
+                    .is_some()
+            };
+
+        let ip_connections = Arc::new(IpConnections::default());
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let other_ip = IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8));
+        let connection_a = Arc::new(ConnectionSubscriptions::new());
+        let connection_b = Arc::new(ConnectionSubscriptions::new());
+        let connection_c = Arc::new(ConnectionSubscriptions::new());
+        let quota_a = SubscriptionQuota::new(ip_connections.clone(), ip, 2, &connection_a);
+        let quota_b = SubscriptionQuota::new(ip_connections.clone(), ip, 2, &connection_b);
+        let quota_c = SubscriptionQuota::new(ip_connections.clone(), other_ip, 2, &connection_c);
+
+        assert!(subscribe(&quota_a, &connection_a, SubscriptionParams::Slot));
+        assert!(subscribe(&quota_b, &connection_b, SubscriptionParams::Root));
+        assert!(!subscribe(
+            &quota_a,
+            &connection_a,
+            SubscriptionParams::Vote
+        ));
+        assert!(!subscribe(
+            &quota_b,
+            &connection_b,
+            SubscriptionParams::Vote
+        ));
+        assert_eq!(connection_b.len(), 1);
+        assert!(subscribe(&quota_c, &connection_c, SubscriptionParams::Vote));
+
+        // Closing a connection releases its subscriptions
+        drop(quota_a);
+        drop(connection_a);
+        assert!(subscribe(&quota_b, &connection_b, SubscriptionParams::Vote));
+        drop(quota_b);
+        assert!(!ip_connections.lock().unwrap().contains_key(&ip));
+    }
 }
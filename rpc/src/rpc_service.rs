@@ -11,13 +11,21 @@ use {
         },
         rpc_cache::LargestAccountsCache,
         rpc_health::*,
+        rpc_subscriptions::RpcSubscriptions,
     },
     crossbeam_channel::unbounded,
-    jsonrpc_core::{futures::prelude::*, MetaIoHandler},
+    jsonrpc_core::{
+        futures::{
+            future::{self, Either},
+            prelude::*,
+        },
+        Call, MetaIoHandler, Middleware, Output, Response,
+    },
     jsonrpc_http_server::{
         hyper, AccessControlAllowOrigin, CloseHandle, DomainsValidation, RequestMiddleware,
         RequestMiddlewareAction, ServerBuilder,
     },
+    lru::LruCache,
     regex::Regex,
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::{
@@ -28,6 +36,7 @@ use {
     solana_metrics::inc_new_counter_info,
     solana_perf::thread::renice_this_thread,
     solana_poh::poh_recorder::PohRecorder,
+    solana_rpc_client_api::custom_error::RpcCustomError,
     solana_runtime::{
         bank_forks::BankForks, commitment::BlockCommitmentCache,
         prioritization_fee_cache::PrioritizationFeeCache,
@@ -42,15 +51,17 @@ use {
     solana_storage_bigtable::CredentialType,
     solana_tpu_client::connection_cache::ConnectionCache,
     std::{
-        collections::HashSet,
-        net::SocketAddr,
+        collections::{HashMap, HashSet},
+        net::{IpAddr, SocketAddr},
         path::{Path, PathBuf},
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
-            Arc, RwLock,
+            Arc, Mutex, RwLock,
         },
         thread::{self, Builder, JoinHandle},
+        time::Instant,
     },
+    tokio::runtime::Handle,
     tokio_util::codec::{BytesCodec, FramedRead},
 };
 
@@ -58,11 +69,263 @@ const FULL_SNAPSHOT_REQUEST_PATH: &str = "/snapshot.tar.bz2";
 const INCREMENTAL_SNAPSHOT_REQUEST_PATH: &str = "/incremental-snapshot.tar.bz2";
 const LARGEST_ACCOUNTS_CACHE_DURATION: u64 = 60 * 60 * 2;
 
+/// Cost of the methods missing from `RpcRateLimitConfig::method_costs`
+pub const DEFAULT_RPC_METHOD_COST: u64 = 1;
+/// Header carrying the API key of an HTTP request
+pub const RPC_API_KEY_HEADER: &str = "x-api-key";
+/// The least recently charged client's token bucket is dropped once more clients than this are
+/// tracked
+const MAX_RATE_LIMITED_CLIENTS: usize = 100_000;
+
+/// A token bucket: clients are credited `units_per_second` cost units, up to `burst_units`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcRateLimit {
+    pub units_per_second: u64,
+    pub burst_units: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcRateLimitConfig {
+    /// Limit of each client IP address, unlimited if `None`
+    pub ip_limit: Option<RpcRateLimit>,
+    /// Limit of each API key, unlimited if `None`
+    pub api_key_limit: Option<RpcRateLimit>,
+    /// Requests presenting one of these keys are limited per key instead of per IP address
+    pub api_keys: HashSet<String>,
+    /// Cost of each method, in units of the rate limits
+    pub method_costs: HashMap<String, u64>,
+    /// Identify HTTP clients by the address a trusted proxy appends to the `X-Forwarded-For`
+    /// header. The HTTP server does not expose peer addresses, so otherwise `ip_limit` only
+    /// applies to WebSocket clients and HTTP clients without an API key are not limited.
+    pub trust_forwarded_for: bool,
+}
+
+impl Default for RpcRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            ip_limit: None,
+            api_key_limit: None,
+            api_keys: HashSet::new(),
+            method_costs: default_rpc_method_costs(),
+            trust_forwarded_for: false,
+        }
+    }
+}
+
+/// Costs of the methods which scan accounts or read history, relative to `getSlot`
+pub fn default_rpc_method_costs() -> HashMap<String, u64> {
+    [
+        ("getProgramAccounts", 100),
+        ("getLargestAccounts", 100),
+        ("getSupply", 50),
+        ("getTokenLargestAccounts", 50),
+        ("getTokenAccountsByOwner", 20),
+        ("getTokenAccountsByDelegate", 20),
+        ("getBlock", 20),
+        ("simulateBundle", 20),
+        ("getMultipleAccounts", 10),
+        ("getSignaturesForAddress", 10),
+        ("getAccountHistory", 10),
+        ("simulateTransaction", 10),
+        ("getTransaction", 5),
+    ]
+    .into_iter()
+    .map(|(method, cost)| (method.to_string(), cost))
+    .collect()
+}
+
+/// The identity requests are rate limited by
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum RpcClientId {
+    ApiKey(String),
+    Ip(IpAddr),
+    /// HTTP clients without an API key, unless `trust_forwarded_for` is set. They can't be told
+    /// apart, so they are not rate limited rather than share one bucket.
+    #[default]
+    Unidentified,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, limit: &RpcRateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * limit.units_per_second as f64).min(limit.burst_units as f64);
+        self.updated = now;
+    }
+
+    /// Seconds until the bucket is out of debt, `None` if it is not in debt
+    fn retry_after_secs(&self, limit: &RpcRateLimit) -> Option<u64> {
+        (self.tokens < 0.0).then(|| {
+            if limit.units_per_second == 0 {
+                u64::MAX
+            } else {
+                (-self.tokens / limit.units_per_second as f64)
+                    .ceil()
+                    .max(1.0) as u64
+            }
+        })
+    }
+}
+
+/// Token bucket rate limiting of each client, shared by the HTTP and WebSocket endpoints.
+///
+/// A call is refused while its client's bucket is in debt; otherwise the cost of its method is
+/// charged, possibly putting the bucket in debt, so expensive methods can't be starved by cheap
+/// ones.
+#[derive(Debug)]
+pub struct RpcRateLimiter {
+    config: RpcRateLimitConfig,
+    buckets: Mutex<LruCache<RpcClientId, TokenBucket>>,
+}
+
+impl RpcRateLimiter {
+    pub fn new(config: RpcRateLimitConfig) -> Self {
+        if config.ip_limit.is_some() && !config.trust_forwarded_for {
+            warn!("HTTP JSON RPC clients without an API key are not rate limited");
+        }
+        Self {
+            config,
+            buckets: Mutex::new(LruCache::new(MAX_RATE_LIMITED_CLIENTS)),
+        }
+    }
+
+    fn limit(&self, client: &RpcClientId) -> Option<&RpcRateLimit> {
+        match client {
+            RpcClientId::ApiKey(_) => self.config.api_key_limit.as_ref(),
+            RpcClientId::Ip(_) => self.config.ip_limit.as_ref(),
+            RpcClientId::Unidentified => None,
+        }
+    }
+
+    pub fn method_cost(&self, method: &str) -> u64 {
+        self.config
+            .method_costs
+            .get(method)
+            .copied()
+            .unwrap_or(DEFAULT_RPC_METHOD_COST)
+    }
+
+    /// Identifies a client by its API key if the key is known, and by `ip` otherwise
+    pub fn client_id(&self, api_key: Option<&str>, ip: Option<IpAddr>) -> RpcClientId {
+        match (api_key, ip) {
+            (Some(api_key), _) if self.config.api_keys.contains(api_key) => {
+                RpcClientId::ApiKey(api_key.to_string())
+            }
+            (_, Some(ip)) => RpcClientId::Ip(ip),
+            (_, None) => RpcClientId::Unidentified,
+        }
+    }
+
+    fn http_client_id(&self, request: &hyper::Request<hyper::Body>) -> RpcClientId {
+        let api_key = request
+            .headers()
+            .get(RPC_API_KEY_HEADER)
+            .and_then(|api_key| api_key.to_str().ok());
+        let ip = if self.config.trust_forwarded_for {
+            request
+                .headers()
+                .get("x-forwarded-for")
+                .and_then(|forwarded_for| forwarded_for.to_str().ok())
+                // Only the last address is appended by the proxy, the others come from the client
+                .and_then(|forwarded_for| forwarded_for.rsplit(',').next())
+                .and_then(|ip| ip.trim().parse().ok())
+        } else {
+            None
+        };
+        self.client_id(api_key, ip)
+    }
+
+    /// Seconds until `client` may make calls again, `None` if it may now
+    pub fn retry_after_secs(&self, client: &RpcClientId, now: Instant) -> Option<u64> {
+        let limit = self.limit(client)?;
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.get_mut(client)?;
+        bucket.refill(limit, now);
+        bucket.retry_after_secs(limit)
+    }
+
+    /// Charges `client` for a call of `method`, or returns the seconds until it may retry
+    pub fn charge(&self, client: &RpcClientId, method: &str, now: Instant) -> Result<(), u64> {
+        let limit = match self.limit(client) {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+        let mut buckets = self.buckets.lock().unwrap();
+        if !buckets.contains(client) {
+            // Evicts the least recently charged client once full. A full bucket is equivalent to
+            // a missing one, and the bucket of an active client is rarely the least recent.
+            buckets.put(
+                client.clone(),
+                TokenBucket {
+                    tokens: limit.burst_units as f64,
+                    updated: now,
+                },
+            );
+        }
+        let bucket = buckets.get_mut(client).unwrap();
+        bucket.refill(limit, now);
+        if let Some(retry_after_secs) = bucket.retry_after_secs(limit) {
+            return Err(retry_after_secs);
+        }
+        bucket.tokens -= self.method_cost(method) as f64;
+        Ok(())
+    }
+}
+
+/// Refuses the calls of clients whose rate limit bucket is in debt, and charges the others
+#[derive(Default)]
+pub(crate) struct RpcRateLimitMiddleware;
+
+impl Middleware<JsonRpcRequestProcessor> for RpcRateLimitMiddleware {
+    type Future = future::Ready<Option<Response>>;
+    type CallFuture = future::Ready<Option<Output>>;
+
+    fn on_call<F, X>(
+        &self,
+        call: Call,
+        meta: JsonRpcRequestProcessor,
+        next: F,
+    ) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, JsonRpcRequestProcessor) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        if let Some((rate_limiter, client)) = meta.rate_limit() {
+            let method = match &call {
+                Call::MethodCall(method_call) => Some(&method_call.method),
+                Call::Notification(notification) => Some(&notification.method),
+                Call::Invalid { .. } => None,
+            };
+            if let Some(method) = method {
+                if let Err(retry_after_secs) = rate_limiter.charge(client, method, Instant::now()) {
+                    inc_new_counter_info!("rpc-rate-limited-calls", 1);
+                    let output = match call {
+                        Call::MethodCall(method_call) => Some(Output::from(
+                            Err(RpcCustomError::RateLimited { retry_after_secs }.into()),
+                            method_call.id,
+                            method_call.jsonrpc,
+                        )),
+                        Call::Notification(_) | Call::Invalid { .. } => None,
+                    };
+                    return Either::Left(future::ready(output));
+                }
+            }
+        }
+        Either::Right(next(call, meta))
+    }
+}
+
 pub struct JsonRpcService {
     thread_hdl: JoinHandle<()>,
 
-    #[cfg(test)]
-    pub request_processor: JsonRpcRequestProcessor, // Used only by test_rpc_new()...
+    request_processor: JsonRpcRequestProcessor,
+    runtime: Handle,
 
     close_handle: Option<CloseHandle>,
 }
@@ -74,6 +337,7 @@ struct RpcRequestMiddleware {
     snapshot_config: Option<SnapshotConfig>,
     bank_forks: Arc<RwLock<BankForks>>,
     health: Arc<RpcHealth>,
+    rate_limiter: Option<Arc<RpcRateLimiter>>,
 }
 
 impl RpcRequestMiddleware {
@@ -82,6 +346,7 @@ impl RpcRequestMiddleware {
         snapshot_config: Option<SnapshotConfig>,
         bank_forks: Arc<RwLock<BankForks>>,
         health: Arc<RpcHealth>,
+        rate_limiter: Option<Arc<RpcRateLimiter>>,
     ) -> Self {
         Self {
             ledger_path,
@@ -96,6 +361,7 @@ impl RpcRequestMiddleware {
             snapshot_config,
             bank_forks,
             health,
+            rate_limiter,
         }
     }
 
@@ -122,6 +388,29 @@ impl RpcRequestMiddleware {
             .unwrap()
     }
 
+    /// A `429 Too Many Requests` response, if the client of `request` is over its rate limit
+    fn rate_limited_response(
+        &self,
+        request: &hyper::Request<hyper::Body>,
+    ) -> Option<hyper::Response<hyper::Body>> {
+        let rate_limiter = self.rate_limiter.as_ref()?;
+        let client = rate_limiter.http_client_id(request);
+        let retry_after_secs = rate_limiter.retry_after_secs(&client, Instant::now())?;
+        inc_new_counter_info!("rpc-rate-limited-requests", 1);
+        let response = Response::from(
+            RpcCustomError::RateLimited { retry_after_secs }.into(),
+            Some(jsonrpc_core::Version::V2),
+        );
+        Some(
+            hyper::Response::builder()
+                .status(hyper::StatusCode::TOO_MANY_REQUESTS)
+                .header(hyper::header::RETRY_AFTER, retry_after_secs)
+                .header(hyper::header::CONTENT_TYPE, "application/json")
+                .body(hyper::Body::from(serde_json::to_string(&response).unwrap()))
+                .unwrap(),
+        )
+    }
+
     fn is_file_get_path(&self, path: &str) -> bool {
         if path == DEFAULT_GENESIS_DOWNLOAD_PATH {
             return true;
@@ -241,6 +530,18 @@ impl RpcRequestMiddleware {
         info!("health check: {}", response);
         response
     }
+
+    fn health_report_response(report: RpcHealthReport) -> hyper::Response<hyper::Body> {
+        let status = match report.status {
+            RpcHealthCheckStatus::Pass => hyper::StatusCode::OK,
+            RpcHealthCheckStatus::Fail => hyper::StatusCode::SERVICE_UNAVAILABLE,
+        };
+        hyper::Response::builder()
+            .status(status)
+            .header(hyper::header::CONTENT_TYPE, "application/json")
+            .body(hyper::Body::from(serde_json::to_string(&report).unwrap()))
+            .unwrap()
+    }
 }
 
 impl RequestMiddleware for RpcRequestMiddleware {
@@ -305,6 +606,14 @@ impl RequestMiddleware for RpcRequestMiddleware {
                 .body(hyper::Body::from(self.health_check()))
                 .unwrap()
                 .into()
+        } else if request.uri().path() == "/health/live" {
+            Self::health_report_response(self.health.check_live()).into()
+        } else if request.uri().path() == "/health/ready" {
+            let report = self.health.check_ready();
+            info!("readiness check: {:?}", report.status);
+            Self::health_report_response(report).into()
+        } else if let Some(response) = self.rate_limited_response(&request) {
+            response.into()
         } else {
             request.into()
         }
@@ -334,6 +643,28 @@ fn process_rest(bank_forks: &Arc<RwLock<BankForks>>, path: &str) -> Option<Strin
     }
 }
 
+/// Builds the handler for the RPC methods enabled by `full_api` and `obsolete_v1_7_api`, rate
+/// limited by the `RpcRateLimiter` of the request processor
+pub(crate) fn rpc_io_handler(
+    full_api: bool,
+    obsolete_v1_7_api: bool,
+) -> MetaIoHandler<JsonRpcRequestProcessor, RpcRateLimitMiddleware> {
+    let mut io = MetaIoHandler::with_middleware(RpcRateLimitMiddleware::default());
+
+    io.extend_with(rpc_minimal::MinimalImpl.to_delegate());
+    if full_api {
+        io.extend_with(rpc_bank::BankDataImpl.to_delegate());
+        io.extend_with(rpc_accounts::AccountsDataImpl.to_delegate());
+        io.extend_with(rpc_full::FullImpl.to_delegate());
+        io.extend_with(rpc_deprecated_v1_7::DeprecatedV1_7Impl.to_delegate());
+        io.extend_with(rpc_deprecated_v1_9::DeprecatedV1_9Impl.to_delegate());
+    }
+    if obsolete_v1_7_api {
+        io.extend_with(rpc_obsolete_v1_7::ObsoleteV1_7Impl.to_delegate());
+    }
+    io
+}
+
 impl JsonRpcService {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -348,11 +679,13 @@ impl JsonRpcService {
         genesis_hash: Hash,
         ledger_path: &Path,
         validator_exit: Arc<RwLock<Exit>>,
-        known_validators: Option<HashSet<Pubkey>>,
+        known_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
         override_health_check: Arc<AtomicBool>,
         startup_verification_complete: Arc<AtomicBool>,
+        start_progress: Arc<RwLock<ValidatorStartProgress>>,
+        rpc_subscriptions: Option<Arc<RpcSubscriptions>>,
         optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
-        send_transaction_service_config: send_transaction_service::Config,
+        send_transaction_service_config: Arc<RwLock<send_transaction_service::Config>>,
         max_slots: Arc<MaxSlots>,
         leader_schedule_cache: Arc<LeaderScheduleCache>,
         connection_cache: Arc<ConnectionCache>,
@@ -370,6 +703,15 @@ impl JsonRpcService {
             config.health_check_slot_distance,
             override_health_check,
             startup_verification_complete,
+            start_progress,
+            bank_forks.clone(),
+            current_transaction_status_slot.clone(),
+            rpc_subscriptions,
+            config.health_check_max_pubsub_backlog,
+            // Transaction statuses are only written with the transaction history enabled
+            config
+                .health_check_max_blockstore_write_lag
+                .filter(|_| config.enable_rpc_transaction_history),
         ));
 
         let largest_accounts_cache = Arc::new(RwLock::new(LargestAccountsCache::new(
@@ -402,6 +744,7 @@ impl JsonRpcService {
                 ref bigtable_instance_name,
                 ref bigtable_app_profile_id,
                 timeout,
+                ref local_storage_path,
             }) = config.rpc_bigtable_config
             {
                 let bigtable_config = solana_storage_bigtable::LedgerStorageConfig {
@@ -410,6 +753,7 @@ impl JsonRpcService {
                     credential_type: CredentialType::Filepath(None),
                     instance_name: bigtable_instance_name.clone(),
                     app_profile_id: bigtable_app_profile_id.clone(),
+                    local_storage_path: local_storage_path.clone(),
                 };
                 runtime
                     .block_on(solana_storage_bigtable::LedgerStorage::new_with_config(
@@ -471,7 +815,7 @@ impl JsonRpcService {
 
         let leader_info =
             poh_recorder.map(|recorder| ClusterTpuInfo::new(cluster_info.clone(), recorder));
-        let _send_transaction_service = Arc::new(SendTransactionService::new_with_config(
+        let _send_transaction_service = Arc::new(SendTransactionService::new_with_shared_config(
             tpu_address,
             &bank_forks,
             leader_info,
@@ -480,8 +824,8 @@ impl JsonRpcService {
             send_transaction_service_config,
         ));
 
-        #[cfg(test)]
-        let test_request_processor = request_processor.clone();
+        let service_request_processor = request_processor.clone();
+        let service_runtime = runtime.handle().clone();
 
         let ledger_path = ledger_path.to_path_buf();
 
@@ -491,29 +835,23 @@ impl JsonRpcService {
             .spawn(move || {
                 renice_this_thread(rpc_niceness_adj).unwrap();
 
-                let mut io = MetaIoHandler::default();
-
-                io.extend_with(rpc_minimal::MinimalImpl.to_delegate());
-                if full_api {
-                    io.extend_with(rpc_bank::BankDataImpl.to_delegate());
-                    io.extend_with(rpc_accounts::AccountsDataImpl.to_delegate());
-                    io.extend_with(rpc_full::FullImpl.to_delegate());
-                    io.extend_with(rpc_deprecated_v1_7::DeprecatedV1_7Impl.to_delegate());
-                    io.extend_with(rpc_deprecated_v1_9::DeprecatedV1_9Impl.to_delegate());
-                }
-                if obsolete_v1_7_api {
-                    io.extend_with(rpc_obsolete_v1_7::ObsoleteV1_7Impl.to_delegate());
-                }
+                let io = rpc_io_handler(full_api, obsolete_v1_7_api);
 
                 let request_middleware = RpcRequestMiddleware::new(
                     ledger_path,
                     snapshot_config,
                     bank_forks.clone(),
                     health.clone(),
+                    request_processor.rate_limiter().cloned(),
                 );
                 let server = ServerBuilder::with_meta_extractor(
                     io,
-                    move |_req: &hyper::Request<hyper::Body>| request_processor.clone(),
+                    move |req: &hyper::Request<hyper::Body>| match request_processor.rate_limiter()
+                    {
+                        Some(rate_limiter) => request_processor
+                            .with_rate_limit_client(rate_limiter.http_client_id(req)),
+                        None => request_processor.clone(),
+                    },
                 )
                 .event_loop_executor(runtime.handle().clone())
                 .threads(1)
@@ -551,12 +889,22 @@ impl JsonRpcService {
             .register_exit(Box::new(move || close_handle_.close()));
         Ok(Self {
             thread_hdl,
-            #[cfg(test)]
-            request_processor: test_request_processor,
+            request_processor: service_request_processor,
+            runtime: service_runtime,
             close_handle: Some(close_handle),
         })
     }
 
+    /// The request processor serving this service, which can be shared with other transports
+    pub fn request_processor(&self) -> &JsonRpcRequestProcessor {
+        &self.request_processor
+    }
+
+    /// The runtime this service runs its requests on, which other transports can run theirs on
+    pub fn runtime(&self) -> &Handle {
+        &self.runtime
+    }
+
     pub fn exit(&mut self) {
         if let Some(c) = self.close_handle.take() {
             c.close()
@@ -593,6 +941,7 @@ mod tests {
         std::{
             io::Write,
             net::{IpAddr, Ipv4Addr},
+            time::Duration,
         },
         tokio::runtime::Runtime,
     };
@@ -636,15 +985,17 @@ mod tests {
             Hash::default(),
             &PathBuf::from("farf"),
             validator_exit,
-            None,
+            Arc::default(),
             Arc::new(AtomicBool::new(false)),
             Arc::new(AtomicBool::new(true)),
+            Arc::new(RwLock::new(ValidatorStartProgress::Running)),
+            None,
             optimistically_confirmed_bank,
-            send_transaction_service::Config {
+            Arc::new(RwLock::new(send_transaction_service::Config {
                 retry_rate_ms: 1000,
                 leader_forward_count: 1,
                 ..send_transaction_service::Config::default()
-            },
+            })),
             Arc::new(MaxSlots::default()),
             Arc::new(LeaderScheduleCache::default()),
             connection_cache,
@@ -695,12 +1046,14 @@ mod tests {
             None,
             bank_forks.clone(),
             RpcHealth::stub(),
+            None,
         );
         let rrm_with_snapshot_config = RpcRequestMiddleware::new(
             PathBuf::from("/"),
             Some(SnapshotConfig::default()),
             bank_forks,
             RpcHealth::stub(),
+            None,
         );
 
         assert!(rrm.is_file_get_path(DEFAULT_GENESIS_DOWNLOAD_PATH));
@@ -771,6 +1124,7 @@ mod tests {
             None,
             create_bank_forks(),
             RpcHealth::stub(),
+            None,
         );
 
         // File does not exist => request should fail.
@@ -826,6 +1180,7 @@ mod tests {
             None,
             create_bank_forks(),
             RpcHealth::stub(),
+            None,
         );
         assert_eq!(rm.health_check(), "ok");
     }
@@ -848,13 +1203,22 @@ mod tests {
 
         let health = Arc::new(RpcHealth::new(
             cluster_info.clone(),
-            Some(known_validators.clone().into_iter().collect()),
+            Arc::new(RwLock::new(Some(
+                known_validators.clone().into_iter().collect(),
+            ))),
             health_check_slot_distance,
             override_health_check.clone(),
             startup_verification_complete,
+            Arc::new(RwLock::new(ValidatorStartProgress::Running)),
+            create_bank_forks(),
+            Arc::default(),
+            None,
+            None,
+            None,
         ));
 
-        let rm = RpcRequestMiddleware::new(PathBuf::from("/"), None, create_bank_forks(), health);
+        let rm =
+            RpcRequestMiddleware::new(PathBuf::from("/"), None, create_bank_forks(), health, None);
 
         // No account hashes for this node or any known validators
         assert_eq!(rm.health_check(), "unknown");
@@ -924,4 +1288,209 @@ mod tests {
             .unwrap();
         assert_eq!(rm.health_check(), "behind");
     }
+
+    #[test]
+    fn test_health_ready() {
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::default(),
+            Arc::new(Keypair::new()),
+            SocketAddrSpace::Unspecified,
+        ));
+        let startup_verification_complete = Arc::new(AtomicBool::new(false));
+        let start_progress = Arc::new(RwLock::new(ValidatorStartProgress::StartingServices));
+        let bank_forks = create_bank_forks();
+        let root_bank = bank_forks.read().unwrap().root_bank();
+        bank_forks.write().unwrap().insert(Bank::new_from_parent(
+            &root_bank,
+            &Pubkey::default(),
+            10,
+        ));
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::new(5));
+        let health = RpcHealth::new(
+            cluster_info,
+            Arc::default(),
+            150,
+            Arc::new(AtomicBool::new(false)),
+            startup_verification_complete.clone(),
+            start_progress.clone(),
+            bank_forks,
+            max_complete_transaction_status_slot.clone(),
+            None,
+            Some(1_000),
+            Some(3),
+        );
+        let check_status = |report: &RpcHealthReport, name| {
+            report
+                .checks
+                .iter()
+                .find(|check| check.name == name)
+                .map(|check| check.status)
+        };
+
+        // Still starting up, but alive
+        let report = health.check_live();
+        assert_eq!(report.status, RpcHealthCheckStatus::Pass);
+        assert_eq!(
+            report.start_progress,
+            ValidatorStartProgress::StartingServices
+        );
+        let report = health.check_ready();
+        assert_eq!(report.status, RpcHealthCheckStatus::Fail);
+        assert_eq!(
+            check_status(&report, "startProgress"),
+            Some(RpcHealthCheckStatus::Fail)
+        );
+        assert_eq!(
+            check_status(&report, "snapshotVerification"),
+            Some(RpcHealthCheckStatus::Fail)
+        );
+        assert_eq!(
+            check_status(&report, "slotDistance"),
+            Some(RpcHealthCheckStatus::Pass)
+        );
+        // No subscriptions to check the backlog of
+        assert_eq!(check_status(&report, "pubsubBacklog"), None);
+        assert_eq!(
+            check_status(&report, "blockstoreWriteLag"),
+            Some(RpcHealthCheckStatus::Fail)
+        );
+
+        *start_progress.write().unwrap() = ValidatorStartProgress::Running;
+        startup_verification_complete.store(true, Ordering::Release);
+        let report = health.check_ready();
+        assert_eq!(report.status, RpcHealthCheckStatus::Fail);
+        let write_lag = report
+            .checks
+            .iter()
+            .find(|check| check.name == "blockstoreWriteLag")
+            .unwrap();
+        assert_eq!(write_lag.observed, Some(5));
+        assert_eq!(write_lag.threshold, Some(3));
+
+        // Transaction statuses caught up to within the threshold
+        max_complete_transaction_status_slot.store(7, Ordering::SeqCst);
+        let report = health.check_ready();
+        assert_eq!(report.status, RpcHealthCheckStatus::Pass);
+
+        let response = RpcRequestMiddleware::health_report_response(report);
+        assert_eq!(response.status(), hyper::StatusCode::OK);
+        let response = RpcRequestMiddleware::health_report_response(RpcHealthReport {
+            status: RpcHealthCheckStatus::Fail,
+            start_progress: ValidatorStartProgress::Running,
+            checks: vec![],
+        });
+        assert_eq!(response.status(), hyper::StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_rpc_rate_limiter() {
+        let api_key = "key".to_string();
+        let rate_limiter = RpcRateLimiter::new(RpcRateLimitConfig {
+            ip_limit: Some(RpcRateLimit {
+                units_per_second: 10,
+                burst_units: 20,
+            }),
+            api_key_limit: None,
+            api_keys: HashSet::from([api_key.clone()]),
+            ..RpcRateLimitConfig::default()
+        });
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let client = rate_limiter.client_id(Some("unknown key"), Some(ip));
+        assert_eq!(client, RpcClientId::Ip(ip));
+        assert_eq!(
+            rate_limiter.client_id(Some(&api_key), Some(ip)),
+            RpcClientId::ApiKey(api_key.clone())
+        );
+        assert_eq!(
+            rate_limiter.client_id(None, None),
+            RpcClientId::Unidentified
+        );
+
+        // Clients which can't be told apart don't share a bucket
+        let now = Instant::now();
+        for _ in 0..10 {
+            assert_eq!(
+                rate_limiter.charge(&RpcClientId::Unidentified, "getProgramAccounts", now),
+                Ok(())
+            );
+        }
+
+        // An expensive call may overdraw the bucket, after which calls are refused until it
+        // refills
+        assert_eq!(rate_limiter.retry_after_secs(&client, now), None);
+        assert_eq!(rate_limiter.charge(&client, "getSlot", now), Ok(()));
+        assert_eq!(
+            rate_limiter.charge(&client, "getProgramAccounts", now),
+            Ok(())
+        );
+        assert_eq!(rate_limiter.retry_after_secs(&client, now), Some(9));
+        assert_eq!(rate_limiter.charge(&client, "getSlot", now), Err(9));
+        let later = now + Duration::from_secs(9);
+        assert_eq!(rate_limiter.retry_after_secs(&client, later), None);
+        assert_eq!(rate_limiter.charge(&client, "getSlot", later), Ok(()));
+
+        // Other clients have their own buckets, and API keys are unlimited
+        let other_client = RpcClientId::Ip(IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8)));
+        assert_eq!(rate_limiter.charge(&other_client, "getSlot", now), Ok(()));
+        let api_key_client = RpcClientId::ApiKey(api_key);
+        for _ in 0..10 {
+            assert_eq!(
+                rate_limiter.charge(&api_key_client, "getProgramAccounts", now),
+                Ok(())
+            );
+        }
+
+        // The least recently charged clients are forgotten once too many are tracked
+        assert_eq!(
+            rate_limiter.charge(&client, "getProgramAccounts", later),
+            Ok(())
+        );
+        assert_eq!(rate_limiter.charge(&client, "getSlot", later), Err(10));
+        for ip in 0..MAX_RATE_LIMITED_CLIENTS as u32 {
+            let other_client = RpcClientId::Ip(IpAddr::V4(Ipv4Addr::from(ip)));
+            assert_eq!(rate_limiter.charge(&other_client, "getSlot", later), Ok(()));
+        }
+        assert_eq!(rate_limiter.charge(&client, "getSlot", later), Ok(()));
+    }
+
+    #[test]
+    fn test_rate_limited_response() {
+        let rate_limiter = Arc::new(RpcRateLimiter::new(RpcRateLimitConfig {
+            ip_limit: Some(RpcRateLimit {
+                units_per_second: 1,
+                burst_units: 1,
+            }),
+            trust_forwarded_for: true,
+            ..RpcRateLimitConfig::default()
+        }));
+        let rrm = RpcRequestMiddleware::new(
+            PathBuf::from("/"),
+            None,
+            create_bank_forks(),
+            RpcHealth::stub(),
+            Some(rate_limiter.clone()),
+        );
+        let request = |forwarded_for: &str| {
+            hyper::Request::post("http://localhost:8899/")
+                .header("x-forwarded-for", forwarded_for)
+                .body(hyper::Body::empty())
+                .unwrap()
+        };
+        assert!(rrm.rate_limited_response(&request("1.2.3.4")).is_none());
+
+        let client = RpcClientId::Ip(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)));
+        rate_limiter
+            .charge(&client, "getProgramAccounts", Instant::now())
+            .unwrap();
+        let response = rrm
+            .rate_limited_response(&request("10.0.0.1, 1.2.3.4"))
+            .unwrap();
+        assert_eq!(response.status(), hyper::StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(hyper::header::RETRY_AFTER));
+        // Addresses set by the client are ignored
+        assert!(rrm
+            .rate_limited_response(&request("1.2.3.4, 5.6.7.8"))
+            .is_none());
+        assert!(rrm.rate_limited_response(&request("5.6.7.8")).is_none());
+    }
 }
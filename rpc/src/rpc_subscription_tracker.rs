@@ -49,6 +49,7 @@ pub enum SubscriptionParams {
     Slot,
     SlotsUpdates,
     Root,
+    Transaction(TransactionSubscriptionParams),
     Vote,
 }
 
@@ -63,6 +64,7 @@ impl SubscriptionParams {
             SubscriptionParams::SlotsUpdates => "slotsUpdatesNotification",
             SubscriptionParams::Block(_) => "blockNotification",
             SubscriptionParams::Root => "rootNotification",
+            SubscriptionParams::Transaction(_) => "transactionNotification",
             SubscriptionParams::Vote => "voteNotification",
         }
     }
@@ -74,6 +76,7 @@ impl SubscriptionParams {
             SubscriptionParams::Program(params) => Some(params.commitment),
             SubscriptionParams::Signature(params) => Some(params.commitment),
             SubscriptionParams::Block(params) => Some(params.commitment),
+            SubscriptionParams::Transaction(params) => Some(params.commitment),
             SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
             | SubscriptionParams::Root
@@ -91,6 +94,7 @@ impl SubscriptionParams {
             SubscriptionParams::Root
             | SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
+            | SubscriptionParams::Transaction(_)
             | SubscriptionParams::Vote => return false,
         };
         !commitment.is_confirmed()
@@ -106,6 +110,7 @@ impl SubscriptionParams {
             SubscriptionParams::Root
             | SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
+            | SubscriptionParams::Transaction(_)
             | SubscriptionParams::Vote => return false,
         };
         commitment.is_confirmed()
@@ -169,6 +174,17 @@ pub struct ProgramSubscriptionParams {
     pub with_context: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TransactionSubscriptionParams {
+    pub commitment: CommitmentConfig,
+    pub encoding: UiTransactionEncoding,
+    pub max_supported_transaction_version: Option<u8>,
+    /// Sorted, so that `account_include` and `account_exclude` can be binary searched
+    pub account_include: Vec<Pubkey>,
+    pub account_exclude: Vec<Pubkey>,
+    pub failed: Option<bool>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SignatureSubscriptionParams {
     pub signature: Signature,
@@ -414,6 +430,76 @@ impl LogsSubscriptionsIndex {
     }
 }
 
+/// Transaction subscriptions, indexed by the accounts a transaction must mention to be notified
+/// to them, so that each transaction is only matched against the subscriptions it may concern
+#[derive(Default)]
+pub struct TransactionSubscriptionsIndex {
+    /// Subscriptions without an account include filter
+    all: HashMap<SubscriptionId, Arc<SubscriptionInfo>>,
+    by_account: HashMap<Pubkey, HashMap<SubscriptionId, Arc<SubscriptionInfo>>>,
+    count: usize,
+}
+
+impl TransactionSubscriptionsIndex {
+    fn add(&mut self, params: &TransactionSubscriptionParams, info: &Arc<SubscriptionInfo>) {
+        if params.account_include.is_empty() {
+            self.all.insert(info.id, Arc::clone(info));
+        }
+        for account in &params.account_include {
+            self.by_account
+                .entry(*account)
+                .or_default()
+                .insert(info.id, Arc::clone(info));
+        }
+        self.count += 1;
+    }
+
+    fn remove(&mut self, params: &TransactionSubscriptionParams, id: SubscriptionId) {
+        if params.account_include.is_empty() && self.all.remove(&id).is_none() {
+            warn!("Subscriptions inconsistency (missing entry in transaction watchers)");
+        }
+        for account in &params.account_include {
+            if let Entry::Occupied(mut entry) = self.by_account.entry(*account) {
+                entry.get_mut().remove(&id);
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+            } else {
+                warn!("Subscriptions inconsistency (missing entry in transaction watchers)");
+            }
+        }
+        self.count -= 1;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Every subscription, once
+    pub fn subscriptions(&self) -> HashMap<SubscriptionId, &Arc<SubscriptionInfo>> {
+        self.all
+            .iter()
+            .chain(self.by_account.values().flatten())
+            .map(|(id, info)| (*id, info))
+            .collect()
+    }
+
+    /// The subscriptions a transaction mentioning `account_keys` may be notified to, before
+    /// applying their exclude and status filters
+    pub fn candidates<'a>(
+        &self,
+        account_keys: impl Iterator<Item = &'a Pubkey>,
+    ) -> HashMap<SubscriptionId, &Arc<SubscriptionInfo>> {
+        let mut candidates: HashMap<_, _> = self.all.iter().map(|(id, info)| (*id, info)).collect();
+        for account_key in account_keys {
+            if let Some(subscriptions) = self.by_account.get(account_key) {
+                candidates.extend(subscriptions.iter().map(|(id, info)| (*id, info)));
+            }
+        }
+        candidates
+    }
+}
+
 pub struct SubscriptionsTracker {
     logs_subscriptions_index: LogsSubscriptionsIndex,
     by_signature: HashMap<Signature, HashMap<SubscriptionId, Arc<SubscriptionInfo>>>,
@@ -421,6 +507,10 @@ pub struct SubscriptionsTracker {
     commitment_watchers: HashMap<SubscriptionId, Arc<SubscriptionInfo>>,
     // Accounts, logs, programs, signatures (gossip)
     gossip_watchers: HashMap<SubscriptionId, Arc<SubscriptionInfo>>,
+    // Transactions (not gossip)
+    transaction_commitment_watchers: TransactionSubscriptionsIndex,
+    // Transactions (gossip)
+    transaction_gossip_watchers: TransactionSubscriptionsIndex,
     // Slots, slots updates, roots, votes.
     node_progress_watchers: HashMap<SubscriptionParams, Arc<SubscriptionInfo>>,
 }
@@ -437,6 +527,8 @@ impl SubscriptionsTracker {
             by_signature: HashMap::new(),
             commitment_watchers: HashMap::new(),
             gossip_watchers: HashMap::new(),
+            transaction_commitment_watchers: TransactionSubscriptionsIndex::default(),
+            transaction_gossip_watchers: TransactionSubscriptionsIndex::default(),
             node_progress_watchers: HashMap::new(),
         }
     }
@@ -464,6 +556,13 @@ impl SubscriptionsTracker {
                     .or_default()
                     .insert(id, Arc::clone(&info));
             }
+            SubscriptionParams::Transaction(params) => {
+                if params.commitment.is_confirmed() {
+                    self.transaction_gossip_watchers.add(params, &info);
+                } else {
+                    self.transaction_commitment_watchers.add(params, &info);
+                }
+            }
             _ => {}
         }
         if info.params.is_commitment_watcher() {
@@ -496,6 +595,13 @@ impl SubscriptionsTracker {
                     warn!("Subscriptions inconsistency (missing entry in by_signature)");
                 }
             }
+            SubscriptionParams::Transaction(params) => {
+                if params.commitment.is_confirmed() {
+                    self.transaction_gossip_watchers.remove(params, id);
+                } else {
+                    self.transaction_commitment_watchers.remove(params, id);
+                }
+            }
             _ => {}
         }
         if params.is_commitment_watcher() {
@@ -529,6 +635,14 @@ impl SubscriptionsTracker {
         &self.gossip_watchers
     }
 
+    pub fn transaction_commitment_watchers(&self) -> &TransactionSubscriptionsIndex {
+        &self.transaction_commitment_watchers
+    }
+
+    pub fn transaction_gossip_watchers(&self) -> &TransactionSubscriptionsIndex {
+        &self.transaction_gossip_watchers
+    }
+
     pub fn node_progress_watchers(&self) -> &HashMap<SubscriptionParams, Arc<SubscriptionInfo>> {
         &self.node_progress_watchers
     }
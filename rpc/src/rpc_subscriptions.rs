@@ -9,7 +9,8 @@ use {
             AccountSubscriptionParams, BlockSubscriptionKind, BlockSubscriptionParams,
             LogsSubscriptionKind, LogsSubscriptionParams, ProgramSubscriptionParams,
             SignatureSubscriptionParams, SubscriptionControl, SubscriptionId, SubscriptionInfo,
-            SubscriptionParams, SubscriptionsTracker,
+            SubscriptionParams, SubscriptionsTracker, TransactionSubscriptionParams,
+            TransactionSubscriptionsIndex,
         },
     },
     crossbeam_channel::{Receiver, RecvTimeoutError, SendError, Sender},
@@ -22,7 +23,8 @@ use {
     solana_rpc_client_api::response::{
         ProcessedSignatureResult, ReceivedSignatureResult, Response as RpcResponse, RpcBlockUpdate,
         RpcBlockUpdateError, RpcKeyedAccount, RpcLogsResponse, RpcResponseContext,
-        RpcSignatureResult, RpcVote, SlotInfo, SlotUpdate,
+        RpcSignatureResult, RpcTransactionUpdate, RpcTransactionUpdateError, RpcVote, SlotInfo,
+        SlotUpdate,
     },
     solana_runtime::{
         bank::{Bank, TransactionLogInfo},
@@ -33,17 +35,19 @@ use {
     solana_sdk::{
         account::{AccountSharedData, ReadableAccount},
         clock::Slot,
+        commitment_config::CommitmentConfig,
         pubkey::Pubkey,
         signature::Signature,
         timing::timestamp,
         transaction,
     },
     solana_transaction_status::{
-        BlockEncodingOptions, ConfirmedBlock, EncodeError, VersionedConfirmedBlock,
+        BlockEncodingOptions, ConfirmedBlock, EncodeError, UiTransactionEncoding,
+        VersionedConfirmedBlock, VersionedTransactionWithStatusMeta,
     },
     std::{
         cell::RefCell,
-        collections::{HashMap, VecDeque},
+        collections::{BTreeMap, HashMap, HashSet, VecDeque},
         io::Cursor,
         iter, str,
         sync::{
@@ -365,6 +369,72 @@ fn filter_block_result_txs(
     }))
 }
 
+/// Whether `transaction`, one of the candidates `TransactionSubscriptionsIndex` found for a
+/// subscription, passes the rest of the subscription's filters
+fn transaction_matches(
+    transaction: &VersionedTransactionWithStatusMeta,
+    params: &TransactionSubscriptionParams,
+) -> bool {
+    if let Some(failed) = params.failed {
+        if transaction.meta.status.is_err() != failed {
+            return false;
+        }
+    }
+    !transaction
+        .account_keys()
+        .iter()
+        .any(|key| params.account_exclude.binary_search(key).is_ok())
+}
+
+fn transaction_update(
+    transaction: &VersionedTransactionWithStatusMeta,
+    slot: Slot,
+    encoding: UiTransactionEncoding,
+    max_supported_transaction_version: Option<u8>,
+) -> RpcTransactionUpdate {
+    let signature = transaction.transaction.signatures[0].to_string();
+    match transaction
+        .clone()
+        .encode(encoding, max_supported_transaction_version, true)
+    {
+        Ok(transaction) => RpcTransactionUpdate {
+            signature,
+            slot,
+            transaction: Some(transaction),
+            err: None,
+        },
+        Err(EncodeError::UnsupportedTransactionVersion(version)) => RpcTransactionUpdate {
+            signature,
+            slot,
+            transaction: None,
+            err: Some(RpcTransactionUpdateError::UnsupportedTransactionVersion(
+                version,
+            )),
+        },
+    }
+}
+
+/// The slot a subscription with `commitment` is notified up to
+fn commitment_slot(commitment: &CommitmentConfig, commitment_slots: &CommitmentSlots) -> Slot {
+    if commitment.is_finalized() {
+        commitment_slots.highest_confirmed_root
+    } else if commitment.is_confirmed() {
+        commitment_slots.highest_confirmed_slot
+    } else {
+        commitment_slots.slot
+    }
+}
+
+/// The slots a block or transaction subscription, last notified up to `last_unnotified_slot`
+/// exclusive, should now be notified of: those on the fork of `bank`, through `bank` itself
+fn slots_to_notify(bank: &Bank, last_unnotified_slot: Slot) -> Vec<Slot> {
+    let mut slots_to_notify: Vec<_> = (last_unnotified_slot..bank.slot()).collect();
+    let ancestors = bank.proper_ancestors_set();
+    slots_to_notify.retain(|slot| ancestors.contains(slot));
+    slots_to_notify.push(bank.slot());
+    slots_to_notify
+}
+
 fn filter_account_result(
     result: Option<(AccountSharedData, Slot)>,
     params: &AccountSubscriptionParams,
@@ -718,6 +788,14 @@ impl RpcSubscriptions {
         &self.control
     }
 
+    /// The number of notifications queued but not yet processed by the notification threads
+    pub fn notification_backlog(&self) -> usize {
+        self.notification_sender
+            .as_ref()
+            .map(|notification_sender| notification_sender.len())
+            .unwrap_or_default()
+    }
+
     /// Notify subscribers of changes to any accounts or new signatures since
     /// the bank's last checkpoint.
     pub fn notify_subscribers(&self, commitment_slots: CommitmentSlots) {
@@ -865,6 +943,7 @@ impl RpcSubscriptions {
                             RpcSubscriptions::notify_watchers(
                                 max_complete_transaction_status_slot.clone(),
                                 subscriptions.commitment_watchers(),
+                                subscriptions.transaction_commitment_watchers(),
                                 &bank_forks,
                                 &blockstore,
                                 &commitment_slots,
@@ -881,6 +960,7 @@ impl RpcSubscriptions {
                             RpcSubscriptions::notify_watchers(
                                 max_complete_transaction_status_slot.clone(),
                                 subscriptions.gossip_watchers(),
+                                subscriptions.transaction_gossip_watchers(),
                                 &bank_forks,
                                 &blockstore,
                                 &commitment_slots,
@@ -932,9 +1012,125 @@ impl RpcSubscriptions {
         }
     }
 
+    /// Notify the transaction subscriptions of the transactions of the slots they were not
+    /// notified of yet. Each block is read once, and each transaction is encoded once per
+    /// encoding and max supported version, however many subscriptions it is notified to.
+    /// Returns the number of subscriptions and of notifications
+    fn notify_transaction_watchers(
+        max_complete_transaction_status_slot: &AtomicU64,
+        transaction_watchers: &TransactionSubscriptionsIndex,
+        bank_forks: &RwLock<BankForks>,
+        blockstore: &Blockstore,
+        commitment_slots: &CommitmentSlots,
+        notifier: &RpcNotifier,
+    ) -> (usize, usize) {
+        if transaction_watchers.is_empty() {
+            return (0, 0);
+        }
+        let subscriptions = transaction_watchers.subscriptions();
+        let max_complete_transaction_status_slot =
+            max_complete_transaction_status_slot.load(Ordering::SeqCst);
+
+        // Like block subscriptions, catch up on the slots that were not notified since the last
+        // notification
+        let mut subscriptions_by_slot = BTreeMap::<Slot, HashSet<SubscriptionId>>::new();
+        for subscription in subscriptions.values() {
+            let slot = match subscription.commitment() {
+                Some(commitment) => commitment_slot(&commitment, commitment_slots),
+                None => {
+                    error!("missing commitment in notify_transaction_watchers");
+                    continue;
+                }
+            };
+            let bank = match bank_forks.read().unwrap().get(slot) {
+                Some(bank) => bank,
+                None => continue,
+            };
+            let last_unnotified_slot = {
+                let mut w_last_unnotified_slot = subscription.last_notified_slot.write().unwrap();
+                if *w_last_unnotified_slot == 0 {
+                    *w_last_unnotified_slot = slot;
+                }
+                *w_last_unnotified_slot
+            };
+            for slot in slots_to_notify(&bank, last_unnotified_slot) {
+                // Already notified, when triggered again for the same slot
+                if slot < last_unnotified_slot {
+                    continue;
+                }
+                if slot > max_complete_transaction_status_slot {
+                    break;
+                }
+                subscriptions_by_slot
+                    .entry(slot)
+                    .or_default()
+                    .insert(subscription.id());
+            }
+        }
+
+        let mut num_notified = 0;
+        // Subscriptions are not notified past a slot whose block can't be read, and retry on the
+        // next notification trigger
+        let mut blocked = HashSet::new();
+        for (slot, slot_subscriptions) in subscriptions_by_slot {
+            let block = match blockstore.get_complete_block(slot, false) {
+                Ok(block) => block,
+                Err(err) => {
+                    error!("get_complete_block error: {}", err);
+                    blocked.extend(slot_subscriptions);
+                    continue;
+                }
+            };
+            for transaction in &block.transactions {
+                let mut updates = HashMap::new();
+                let candidates = transaction_watchers.candidates(transaction.account_keys().iter());
+                for (id, subscription) in candidates {
+                    if !slot_subscriptions.contains(&id) || blocked.contains(&id) {
+                        continue;
+                    }
+                    let params = match subscription.params() {
+                        SubscriptionParams::Transaction(params) => params,
+                        _ => {
+                            error!("wrong subscription type in transaction watchers");
+                            continue;
+                        }
+                    };
+                    if !transaction_matches(transaction, params) {
+                        continue;
+                    }
+                    let update = updates
+                        .entry((params.encoding, params.max_supported_transaction_version))
+                        .or_insert_with(|| {
+                            transaction_update(
+                                transaction,
+                                slot,
+                                params.encoding,
+                                params.max_supported_transaction_version,
+                            )
+                        });
+                    notifier.notify(
+                        RpcResponse::from(RpcNotificationResponse {
+                            context: RpcNotificationContext { slot },
+                            value: &*update,
+                        }),
+                        subscription,
+                        false,
+                    );
+                    num_notified += 1;
+                }
+            }
+            for id in slot_subscriptions.difference(&blocked) {
+                *subscriptions[id].last_notified_slot.write().unwrap() = slot + 1;
+            }
+        }
+        (subscriptions.len(), num_notified)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn notify_watchers(
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         subscriptions: &HashMap<SubscriptionId, Arc<SubscriptionInfo>>,
+        transaction_watchers: &TransactionSubscriptionsIndex,
         bank_forks: &Arc<RwLock<BankForks>>,
         blockstore: &Blockstore,
         commitment_slots: &CommitmentSlots,
@@ -958,16 +1154,19 @@ impl RpcSubscriptions {
         let num_signatures_found = AtomicUsize::new(0);
         let num_signatures_notified = AtomicUsize::new(0);
 
+        let (num_transactions_found, num_transactions_notified) = Self::notify_transaction_watchers(
+            &max_complete_transaction_status_slot,
+            transaction_watchers,
+            bank_forks,
+            blockstore,
+            commitment_slots,
+            notifier,
+        );
+
         let subscriptions = subscriptions.into_par_iter();
         subscriptions.for_each(|(_id, subscription)| {
             let slot = if let Some(commitment) = subscription.commitment() {
-                if commitment.is_finalized() {
-                    Some(commitment_slots.highest_confirmed_root)
-                } else if commitment.is_confirmed() {
-                    Some(commitment_slots.highest_confirmed_slot)
-                } else {
-                    Some(commitment_slots.slot)
-                }
+                Some(commitment_slot(&commitment, commitment_slots))
             } else {
                 error!("missing commitment in notify_watchers");
                 None
@@ -1017,12 +1216,7 @@ impl RpcSubscriptions {
                             if *w_last_unnotified_slot == 0 {
                                 *w_last_unnotified_slot = slot;
                             }
-                            let mut slots_to_notify: Vec<_> =
-                                (*w_last_unnotified_slot..slot).collect();
-                            let ancestors = bank.proper_ancestors_set();
-                            slots_to_notify.retain(|slot| ancestors.contains(slot));
-                            slots_to_notify.push(slot);
-                            for s in slots_to_notify {
+                            for s in slots_to_notify(&bank, *w_last_unnotified_slot) {
                                 // To avoid skipping a slot that fails this condition,
                                 // caused by non-deterministic concurrency accesses, we
                                 // break out of the loop. Besides if the current `s` is
@@ -1147,11 +1341,12 @@ impl RpcSubscriptions {
         let total_notified = num_accounts_notified.load(Ordering::Relaxed)
             + num_logs_notified.load(Ordering::Relaxed)
             + num_programs_notified.load(Ordering::Relaxed)
-            + num_signatures_notified.load(Ordering::Relaxed);
+            + num_signatures_notified.load(Ordering::Relaxed)
+            + num_transactions_notified;
         let total_ms = total_time.as_ms();
         if total_notified > 0 || total_ms > 10 {
             debug!(
-                "notified({}): accounts: {} / {} logs: {} / {} programs: {} / {} signatures: {} / {}",
+                "notified({}): accounts: {} / {} logs: {} / {} programs: {} / {} signatures: {} / {} transactions: {} / {}",
                 source,
                 num_accounts_found.load(Ordering::Relaxed),
                 num_accounts_notified.load(Ordering::Relaxed),
@@ -1161,6 +1356,8 @@ impl RpcSubscriptions {
                 num_programs_notified.load(Ordering::Relaxed),
                 num_signatures_found.load(Ordering::Relaxed),
                 num_signatures_notified.load(Ordering::Relaxed),
+                num_transactions_found,
+                num_transactions_notified,
             );
             inc_new_counter_info!("rpc-subscription-notify-bank-or-gossip", total_notified);
             datapoint_info!(
@@ -1206,6 +1403,8 @@ impl RpcSubscriptions {
                     num_signatures_notified.load(Ordering::Relaxed),
                     i64
                 ),
+                ("num_transaction_subscriptions", num_transactions_found, i64),
+                ("num_transactions_notified", num_transactions_notified, i64),
                 ("notifications_time", total_time.as_us() as i64, i64),
             );
             inc_new_counter_info!(
@@ -1262,7 +1461,7 @@ pub(crate) mod tests {
         solana_rpc_client_api::config::{
             RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
             RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcTransactionLogsFilter, RpcTransactionSubscribeConfig, RpcTransactionSubscribeFilter,
         },
         solana_runtime::{
             commitment::BlockCommitment,
@@ -1676,6 +1875,243 @@ pub(crate) mod tests {
             .assert_unsubscribed(&SubscriptionParams::Block(params));
     }
 
+    #[test]
+    #[serial]
+    fn test_check_confirmed_transaction_subscribe() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let ledger_path = get_tmp_ledger_path!();
+        let blockstore = Blockstore::open(&ledger_path).unwrap();
+        let blockstore = Arc::new(blockstore);
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::default());
+        let subscriptions = Arc::new(RpcSubscriptions::new_for_tests_with_blockstore(
+            &exit,
+            max_complete_transaction_status_slot,
+            blockstore.clone(),
+            bank_forks.clone(),
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+        ));
+        let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&subscriptions);
+        let keypair1 = Keypair::new();
+        let keypair2 = Keypair::new();
+        let filter = RpcTransactionSubscribeFilter {
+            account_include: Some(vec![keypair1.pubkey().to_string()]),
+            account_exclude: None,
+            failed: Some(false),
+        };
+        let config = RpcTransactionSubscribeConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+            encoding: Some(UiTransactionEncoding::Json),
+            max_supported_transaction_version: None,
+        };
+        let params = TransactionSubscriptionParams {
+            commitment: config.commitment.unwrap(),
+            encoding: config.encoding.unwrap(),
+            max_supported_transaction_version: None,
+            account_include: vec![keypair1.pubkey()],
+            account_exclude: vec![],
+            failed: Some(false),
+        };
+        let sub_id = rpc.transaction_subscribe(filter, Some(config)).unwrap();
+
+        subscriptions
+            .control
+            .assert_subscribed(&SubscriptionParams::Transaction(params.clone()));
+
+        let bank = bank_forks.read().unwrap().working_bank();
+        let keypair3 = Keypair::new();
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::new(blockstore.max_root()));
+        bank.transfer(rent_exempt_amount, &mint_keypair, &keypair2.pubkey())
+            .unwrap();
+        populate_blockstore_for_tests(
+            create_test_transaction_entries(
+                vec![&mint_keypair, &keypair1, &keypair2, &keypair3],
+                bank.clone(),
+            )
+            .0,
+            bank,
+            blockstore.clone(),
+            max_complete_transaction_status_slot,
+        );
+
+        let slot = 0;
+        subscriptions.notify_gossip_subscribers(slot);
+
+        // Each successful transaction mentioning keypair1 is notified on its own
+        let block = blockstore.get_complete_block(slot, false).unwrap();
+        let expected_transactions = block
+            .transactions
+            .into_iter()
+            .filter(|tx_with_meta| {
+                tx_with_meta.meta.status.is_ok()
+                    && tx_with_meta
+                        .account_keys()
+                        .iter()
+                        .any(|key| key == &keypair1.pubkey())
+            })
+            .collect::<Vec<_>>();
+        assert!(!expected_transactions.is_empty());
+        for tx_with_meta in expected_transactions {
+            let actual_resp = receiver.recv();
+            let actual_resp = serde_json::from_str::<serde_json::Value>(&actual_resp).unwrap();
+            let expected_resp = RpcTransactionUpdate {
+                signature: tx_with_meta.transaction.signatures[0].to_string(),
+                slot,
+                transaction: Some(tx_with_meta.encode(params.encoding, None, true).unwrap()),
+                err: None,
+            };
+            let expected_resp = json!({
+               "jsonrpc": "2.0",
+               "method": "transactionNotification",
+               "params": {
+                   "result": {
+                       "context": { "slot": slot },
+                       "value": expected_resp,
+                   },
+                   "subscription": 0,
+               }
+            });
+            assert_eq!(expected_resp, actual_resp);
+        }
+        let should_err = receiver.recv_timeout(Duration::from_millis(300));
+        assert!(should_err.is_err());
+
+        rpc.transaction_unsubscribe(sub_id).unwrap();
+        subscriptions
+            .control
+            .assert_unsubscribed(&SubscriptionParams::Transaction(params));
+    }
+
+    #[test]
+    #[serial]
+    fn test_check_processed_transaction_subscribe() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let ledger_path = get_tmp_ledger_path!();
+        let blockstore = Blockstore::open(&ledger_path).unwrap();
+        let blockstore = Arc::new(blockstore);
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::default());
+        let subscriptions = Arc::new(RpcSubscriptions::new_for_tests_with_blockstore(
+            &exit,
+            max_complete_transaction_status_slot,
+            blockstore.clone(),
+            bank_forks.clone(),
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+        ));
+        let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&subscriptions);
+        let keypair1 = Keypair::new();
+        let keypair2 = Keypair::new();
+        let keypair3 = Keypair::new();
+        // Without an include filter, every transaction not mentioning keypair1
+        let filter = RpcTransactionSubscribeFilter {
+            account_include: None,
+            account_exclude: Some(vec![keypair1.pubkey().to_string()]),
+            failed: None,
+        };
+        let config = RpcTransactionSubscribeConfig {
+            commitment: Some(CommitmentConfig::processed()),
+            encoding: Some(UiTransactionEncoding::Base64),
+            max_supported_transaction_version: Some(0),
+        };
+        let params = TransactionSubscriptionParams {
+            commitment: config.commitment.unwrap(),
+            encoding: config.encoding.unwrap(),
+            max_supported_transaction_version: Some(0),
+            account_include: vec![],
+            account_exclude: vec![keypair1.pubkey()],
+            failed: None,
+        };
+        let sub_id = rpc.transaction_subscribe(filter, Some(config)).unwrap();
+        subscriptions
+            .control
+            .assert_subscribed(&SubscriptionParams::Transaction(params.clone()));
+
+        let bank = bank_forks.read().unwrap().working_bank();
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::new(blockstore.max_root()));
+        bank.transfer(rent_exempt_amount, &mint_keypair, &keypair2.pubkey())
+            .unwrap();
+        populate_blockstore_for_tests(
+            create_test_transaction_entries(
+                vec![&mint_keypair, &keypair1, &keypair2, &keypair3],
+                bank.clone(),
+            )
+            .0,
+            bank,
+            blockstore.clone(),
+            max_complete_transaction_status_slot,
+        );
+
+        let slot = 0;
+        // Gossip only notifies confirmed subscriptions
+        subscriptions.notify_gossip_subscribers(slot);
+        assert!(receiver.recv_timeout(Duration::from_millis(300)).is_err());
+        subscriptions.notify_subscribers(CommitmentSlots {
+            slot,
+            ..CommitmentSlots::default()
+        });
+
+        // Only the failed transaction from keypair2 to keypair3 does not mention keypair1
+        let block = blockstore.get_complete_block(slot, false).unwrap();
+        let tx_with_meta = block
+            .transactions
+            .into_iter()
+            .find(|tx_with_meta| tx_with_meta.meta.status.is_err())
+            .unwrap();
+        let actual_resp = receiver.recv();
+        let actual_resp = serde_json::from_str::<serde_json::Value>(&actual_resp).unwrap();
+        let expected_resp = RpcTransactionUpdate {
+            signature: tx_with_meta.transaction.signatures[0].to_string(),
+            slot,
+            transaction: Some(tx_with_meta.encode(params.encoding, Some(0), true).unwrap()),
+            err: None,
+        };
+        let expected_resp = json!({
+           "jsonrpc": "2.0",
+           "method": "transactionNotification",
+           "params": {
+               "result": {
+                   "context": { "slot": slot },
+                   "value": expected_resp,
+               },
+               "subscription": 0,
+           }
+        });
+        assert_eq!(expected_resp, actual_resp);
+        assert!(receiver.recv_timeout(Duration::from_millis(300)).is_err());
+
+        // Slots are notified once
+        subscriptions.notify_subscribers(CommitmentSlots {
+            slot,
+            ..CommitmentSlots::default()
+        });
+        assert!(receiver.recv_timeout(Duration::from_millis(300)).is_err());
+
+        rpc.transaction_unsubscribe(sub_id).unwrap();
+        subscriptions
+            .control
+            .assert_unsubscribed(&SubscriptionParams::Transaction(params));
+    }
+
     #[test]
     #[serial]
     fn test_check_finalized_block_subscribe() {
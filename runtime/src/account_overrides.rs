@@ -1,6 +1,13 @@
 use {
-    solana_sdk::{account::AccountSharedData, pubkey::Pubkey, sysvar},
-    std::collections::HashMap,
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        account_utils::StateMut,
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        pubkey::Pubkey,
+        sysvar,
+        transaction_context::TransactionAccount,
+    },
+    std::collections::{HashMap, HashSet},
 };
 
 /// Encapsulates overridden accounts, typically used for transaction simulations
@@ -28,4 +35,23 @@ impl AccountOverrides {
     pub fn get(&self, pubkey: &Pubkey) -> Option<&AccountSharedData> {
         self.accounts.get(pubkey)
     }
+
+    /// Gets the programs among `accounts` whose program account or program data is overridden,
+    /// as their cached executors may be stale
+    pub fn overridden_programs(&self, accounts: &[TransactionAccount]) -> HashSet<Pubkey> {
+        accounts
+            .iter()
+            .filter(|(pubkey, account)| {
+                account.executable()
+                    && (self.accounts.contains_key(pubkey)
+                        || (bpf_loader_upgradeable::check_id(account.owner())
+                            && matches!(
+                                account.state(),
+                                Ok(UpgradeableLoaderState::Program { programdata_address })
+                                    if self.accounts.contains_key(&programdata_address)
+                            )))
+            })
+            .map(|(pubkey, _)| *pubkey)
+            .collect()
+    }
 }
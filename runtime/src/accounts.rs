@@ -356,10 +356,11 @@ impl Accounts {
                                     programdata_address,
                                 }) = account.state()
                                 {
-                                    if let Some((programdata_account, _)) = self
-                                        .accounts_db
-                                        .load_with_fixed_root(ancestors, &programdata_address)
-                                    {
+                                    if let Some(programdata_account) = self.load_with_overrides(
+                                        ancestors,
+                                        &programdata_address,
+                                        account_overrides,
+                                    ) {
                                         loaded_programdata_account_size =
                                             programdata_account.data().len();
                                         account_deps
@@ -416,6 +417,7 @@ impl Accounts {
                             error_counters,
                             &mut accumulated_accounts_data_size,
                             requested_loaded_accounts_data_size_limit,
+                            account_overrides,
                         )
                     })
                     .collect::<Result<Vec<Vec<IndexOfAccount>>>>()?;
@@ -497,6 +499,22 @@ impl Accounts {
         )
     }
 
+    /// Load an account, unless it is overridden
+    fn load_with_overrides(
+        &self,
+        ancestors: &Ancestors,
+        key: &Pubkey,
+        account_overrides: Option<&AccountOverrides>,
+    ) -> Option<AccountSharedData> {
+        match account_overrides.and_then(|overrides| overrides.get(key)) {
+            Some(account_override) => Some(account_override.clone()),
+            None => self
+                .accounts_db
+                .load_with_fixed_root(ancestors, key)
+                .map(|(account, _)| account),
+        }
+    }
+
     fn load_executable_accounts(
         &self,
         ancestors: &Ancestors,
@@ -505,6 +523,7 @@ impl Accounts {
         error_counters: &mut TransactionErrorMetrics,
         accumulated_accounts_data_size: &mut usize,
         requested_loaded_accounts_data_size_limit: Option<NonZeroUsize>,
+        account_overrides: Option<&AccountOverrides>,
     ) -> Result<Vec<IndexOfAccount>> {
         let mut account_indices = Vec::new();
         let (mut program_id, already_loaded_as_non_loader) =
@@ -528,27 +547,25 @@ impl Accounts {
             depth += 1;
             let mut loaded_account_total_size: usize = 0;
 
-            program_account_index = match self
-                .accounts_db
-                .load_with_fixed_root(ancestors, &program_id)
-            {
-                Some((program_account, _)) => {
-                    let account_index = accounts.len() as IndexOfAccount;
-                    // do not double count account size for program account on top of call chain
-                    // that has already been loaded during load_transaction as non-loader account.
-                    // Other accounts data size in the call chain are counted.
-                    if !(depth == 1 && already_loaded_as_non_loader) {
-                        loaded_account_total_size =
-                            loaded_account_total_size.saturating_add(program_account.data().len());
+            program_account_index =
+                match self.load_with_overrides(ancestors, &program_id, account_overrides) {
+                    Some(program_account) => {
+                        let account_index = accounts.len() as IndexOfAccount;
+                        // do not double count account size for program account on top of call chain
+                        // that has already been loaded during load_transaction as non-loader account.
+                        // Other accounts data size in the call chain are counted.
+                        if !(depth == 1 && already_loaded_as_non_loader) {
+                            loaded_account_total_size = loaded_account_total_size
+                                .saturating_add(program_account.data().len());
+                        }
+                        accounts.push((program_id, program_account));
+                        account_index
                     }
-                    accounts.push((program_id, program_account));
-                    account_index
-                }
-                None => {
-                    error_counters.account_not_found += 1;
-                    return Err(TransactionError::ProgramAccountNotFound);
-                }
-            };
+                    None => {
+                        error_counters.account_not_found += 1;
+                        return Err(TransactionError::ProgramAccountNotFound);
+                    }
+                };
             let program = &accounts[program_account_index as usize].1;
             if !program.executable() {
                 error_counters.invalid_program_for_execution += 1;
@@ -565,11 +582,12 @@ impl Accounts {
                     programdata_address,
                 }) = program.state()
                 {
-                    let programdata_account_index = match self
-                        .accounts_db
-                        .load_with_fixed_root(ancestors, &programdata_address)
-                    {
-                        Some((programdata_account, _)) => {
+                    let programdata_account_index = match self.load_with_overrides(
+                        ancestors,
+                        &programdata_address,
+                        account_overrides,
+                    ) {
+                        Some(programdata_account) => {
                             let account_index = accounts.len() as IndexOfAccount;
                             if !(depth == 1 && already_loaded_as_non_loader) {
                                 loaded_account_total_size = loaded_account_total_size
@@ -956,6 +974,20 @@ impl Accounts {
         }
     }
 
+    /// Like `load_while_filtering`, but aborts the scan once `limit` accounts were collected
+    fn load_page_while_filtering<F: Fn(&AccountSharedData) -> bool>(
+        collector: &mut Vec<TransactionAccount>,
+        some_account_tuple: Option<(&Pubkey, AccountSharedData, Slot)>,
+        filter: F,
+        limit: usize,
+        config: &ScanConfig,
+    ) {
+        Self::load_while_filtering(collector, some_account_tuple, filter);
+        if collector.len() >= limit {
+            config.abort();
+        }
+    }
+
     fn load_with_slot(
         collector: &mut Vec<PubkeyAccountSlot>,
         some_account_tuple: Option<(&Pubkey, AccountSharedData, Slot)>,
@@ -1066,7 +1098,7 @@ impl Accounts {
             .index_scan_accounts(
                 ancestors,
                 bank_id,
-                *index_key,
+                index_key.clone(),
                 |some_account_tuple| {
                     Self::load_while_filtering(&mut collector, some_account_tuple, |account| {
                         let use_account = filter(account);
@@ -1089,6 +1121,69 @@ impl Accounts {
         Self::maybe_abort_scan(result, &config)
     }
 
+    /// Load, in key order, up to `limit` of the accounts owned by `program_id` that pass
+    /// `filter`, starting after `config.start_after`
+    pub fn load_page_by_program_with_filter<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        program_id: &Pubkey,
+        filter: F,
+        config: &ScanConfig,
+        limit: usize,
+    ) -> ScanResult<Vec<TransactionAccount>> {
+        let config = config.recreate_with_abort();
+        let mut collector = Vec::new();
+        self.accounts_db
+            .scan_accounts(
+                ancestors,
+                bank_id,
+                |some_account_tuple| {
+                    Self::load_page_while_filtering(
+                        &mut collector,
+                        some_account_tuple,
+                        |account| account.owner() == program_id && filter(account),
+                        limit,
+                        &config,
+                    )
+                },
+                &config,
+            )
+            .map(|_| collector)
+    }
+
+    /// Load, in key order, up to `limit` of the accounts under `index_key` that pass `filter`,
+    /// starting after `config.start_after`
+    pub fn load_page_by_index_key_with_filter<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        index_key: &IndexKey,
+        filter: F,
+        config: &ScanConfig,
+        limit: usize,
+    ) -> ScanResult<Vec<TransactionAccount>> {
+        let config = config.recreate_with_abort();
+        let mut collector = Vec::new();
+        self.accounts_db
+            .index_scan_accounts(
+                ancestors,
+                bank_id,
+                index_key.clone(),
+                |some_account_tuple| {
+                    Self::load_page_while_filtering(
+                        &mut collector,
+                        some_account_tuple,
+                        &filter,
+                        limit,
+                        &config,
+                    )
+                },
+                &config,
+            )
+            .map(|_| collector)
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.accounts_db.account_indexes.include_key(key)
     }
@@ -1496,6 +1591,7 @@ mod tests {
     use {
         super::*,
         crate::{
+            accounts_index::AccountIndex,
             bank::{DurableNonceFee, TransactionExecutionDetails},
             rent_collector::RentCollector,
         },
@@ -1550,6 +1646,7 @@ mod tests {
                 return_data: None,
                 executed_units: 0,
                 accounts_data_len_delta: 0,
+                execution_trace: None,
             },
             tx_executor_cache: Rc::new(RefCell::new(TransactionExecutorCache::default())),
         }
@@ -2297,6 +2394,71 @@ mod tests {
         assert_eq!(loaded, vec![]);
     }
 
+    #[test]
+    fn test_load_page_by_program_with_filter() {
+        let program_id = Pubkey::new(&[2; 32]);
+        let pubkeys: Vec<_> = (10..16).map(|i| Pubkey::new(&[i; 32])).collect();
+        for indexes in [
+            AccountSecondaryIndexes::default(),
+            AccountSecondaryIndexes {
+                keys: None,
+                indexes: [AccountIndex::ProgramId].into_iter().collect(),
+            },
+        ] {
+            let accounts = Accounts::new_with_config_for_tests(
+                Vec::new(),
+                &ClusterType::Development,
+                indexes,
+                false,
+                AccountShrinkThreshold::default(),
+            );
+            // Store in reverse order, and skip an account owned by another program
+            for (i, pubkey) in pubkeys.iter().enumerate().rev() {
+                let owner = if i == 2 {
+                    Pubkey::new(&[3; 32])
+                } else {
+                    program_id
+                };
+                accounts.store_slow_uncached(0, pubkey, &AccountSharedData::new(1, 0, &owner));
+            }
+            let ancestors = Ancestors::from(vec![0]);
+            let load_page = |start_after, limit| {
+                let config = ScanConfig {
+                    start_after,
+                    ..ScanConfig::default()
+                };
+                let page = if accounts.accounts_db.account_indexes.is_empty() {
+                    accounts.load_page_by_program_with_filter(
+                        &ancestors,
+                        0,
+                        &program_id,
+                        |_| true,
+                        &config,
+                        limit,
+                    )
+                } else {
+                    accounts.load_page_by_index_key_with_filter(
+                        &ancestors,
+                        0,
+                        &IndexKey::ProgramId(program_id),
+                        |account| account.owner() == &program_id,
+                        &config,
+                        limit,
+                    )
+                };
+                page.unwrap()
+                    .into_iter()
+                    .map(|(pubkey, _)| pubkey)
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(load_page(None, 2), vec![pubkeys[0], pubkeys[1]]);
+            assert_eq!(load_page(Some(pubkeys[1]), 2), vec![pubkeys[3], pubkeys[4]]);
+            assert_eq!(load_page(Some(pubkeys[4]), 2), vec![pubkeys[5]]);
+            assert_eq!(load_page(Some(pubkeys[5]), 2), vec![]);
+        }
+    }
+
     #[test]
     fn test_load_accounts_executable_with_write_lock() {
         let mut accounts: Vec<TransactionAccount> = Vec::new();
@@ -2576,6 +2738,7 @@ mod tests {
                 &mut error_counters,
                 &mut 0,
                 None,
+                None,
             ),
             Err(TransactionError::ProgramAccountNotFound)
         );
@@ -4090,6 +4253,7 @@ mod tests {
                     &mut error_counters,
                     &mut accumulated_accounts_data_size,
                     NonZeroUsize::new(expect_accumulated_accounts_data_size),
+                    None,
                 )
                 .is_ok());
             assert_eq!(
@@ -4111,6 +4275,7 @@ mod tests {
                     &mut error_counters,
                     &mut accumulated_accounts_data_size,
                     NonZeroUsize::new(expect_accumulated_accounts_data_size),
+                    None,
                 )
                 .is_ok());
             assert_eq!(
@@ -4131,6 +4296,7 @@ mod tests {
                     &mut error_counters,
                     &mut accumulated_accounts_data_size,
                     NonZeroUsize::new(expect_accumulated_accounts_data_size),
+                    None,
                 ),
                 Err(TransactionError::MaxLoadedAccountsDataSizeExceeded)
             );
@@ -4199,6 +4365,7 @@ mod tests {
                     &mut error_counters,
                     &mut accumulated_accounts_data_size,
                     NonZeroUsize::new(expect_accumulated_accounts_data_size),
+                    None,
                 )
                 .is_ok());
             assert_eq!(
@@ -4222,6 +4389,7 @@ mod tests {
                     &mut error_counters,
                     &mut accumulated_accounts_data_size,
                     NonZeroUsize::new(expect_accumulated_accounts_data_size),
+                    None,
                 )
                 .is_ok());
             assert_eq!(
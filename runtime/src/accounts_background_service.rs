@@ -16,6 +16,7 @@ use {
     crossbeam_channel::{Receiver, SendError, Sender},
     log::*,
     rand::{thread_rng, Rng},
+    serde_derive::{Deserialize, Serialize},
     solana_measure::measure::Measure,
     solana_sdk::{
         clock::{BankId, Slot},
@@ -32,6 +33,7 @@ use {
         thread::{self, sleep, Builder, JoinHandle},
         time::{Duration, Instant},
     },
+    thiserror::Error,
 };
 
 const INTERVAL_MS: u64 = 100;
@@ -138,6 +140,35 @@ impl Debug for SnapshotRequest {
 pub enum SnapshotRequestType {
     Snapshot,
     EpochAccountsHash,
+    /// A snapshot of the root bank requested by the operator, outside of the snapshot intervals
+    OnDemandSnapshot(OnDemandSnapshotKind),
+}
+
+/// The kind of snapshot archive an on-demand snapshot request produces
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnDemandSnapshotKind {
+    Full,
+    /// Falls back to a full snapshot when there is no full snapshot to be incremental to
+    Incremental,
+}
+
+#[derive(Debug, Error)]
+pub enum OnDemandSnapshotError {
+    #[error("snapshots are disabled")]
+    SnapshotsDisabled,
+
+    #[error("startup verification of the root bank is not complete")]
+    StartupVerificationIncomplete,
+
+    #[error("no full snapshot archive older than slot {0} to base an incremental snapshot on")]
+    NoFullSnapshotArchive(Slot),
+
+    #[error("a snapshot of root slot {0} was already requested, try again once the root advances")]
+    AlreadyRequested(Slot),
+
+    #[error("the accounts background service has stopped")]
+    ServiceStopped,
 }
 
 pub struct SnapshotRequestHandler {
@@ -376,7 +407,7 @@ impl SnapshotRequestHandler {
         let mut snapshot_time = Measure::start("snapshot_time");
         let snapshot_storages = snapshot_utils::get_snapshot_storages(&snapshot_root_bank);
         let accounts_package = match request_type {
-            SnapshotRequestType::Snapshot => {
+            SnapshotRequestType::Snapshot | SnapshotRequestType::OnDemandSnapshot(_) => {
                 let bank_snapshot_info = snapshot_utils::add_bank_snapshot(
                     &self.snapshot_config.bank_snapshots_dir,
                     &snapshot_root_bank,
@@ -731,7 +762,17 @@ fn new_accounts_package_type(
     let block_height = snapshot_request.snapshot_root_bank.block_height();
     match snapshot_request.request_type {
         SnapshotRequestType::EpochAccountsHash => AccountsPackageType::EpochAccountsHash,
-        _ => {
+        SnapshotRequestType::OnDemandSnapshot(kind) => match (kind, last_full_snapshot_slot) {
+            (OnDemandSnapshotKind::Incremental, Some(last_full_snapshot_slot))
+                if last_full_snapshot_slot < snapshot_request.snapshot_root_bank.slot() =>
+            {
+                AccountsPackageType::Snapshot(SnapshotType::IncrementalSnapshot(
+                    last_full_snapshot_slot,
+                ))
+            }
+            _ => AccountsPackageType::Snapshot(SnapshotType::FullSnapshot),
+        },
+        SnapshotRequestType::Snapshot => {
             if snapshot_utils::should_take_full_snapshot(
                 block_height,
                 snapshot_config.full_snapshot_archive_interval_slots,
@@ -947,4 +988,45 @@ mod test {
             .get_next_snapshot_request(Some(240))
             .is_none());
     }
+
+    #[test]
+    fn test_on_demand_snapshot_package_type() {
+        let snapshot_config = SnapshotConfig {
+            full_snapshot_archive_interval_slots: 100,
+            incremental_snapshot_archive_interval_slots: 10,
+            ..SnapshotConfig::default()
+        };
+        let genesis_config_info = create_genesis_config(10);
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config_info.genesis_config));
+        let bank = Arc::new(Bank::new_from_parent(&bank0, &Pubkey::new_unique(), 7));
+        let package_type = |kind, last_full_snapshot_slot| {
+            let snapshot_request = SnapshotRequest {
+                snapshot_root_bank: Arc::clone(&bank),
+                status_cache_slot_deltas: Vec::default(),
+                request_type: SnapshotRequestType::OnDemandSnapshot(kind),
+                enqueued: Instant::now(),
+            };
+            new_accounts_package_type(&snapshot_request, &snapshot_config, last_full_snapshot_slot)
+        };
+
+        // On-demand snapshots are taken regardless of the snapshot intervals
+        assert_eq!(
+            package_type(OnDemandSnapshotKind::Full, Some(0)),
+            AccountsPackageType::Snapshot(SnapshotType::FullSnapshot)
+        );
+        assert_eq!(
+            package_type(OnDemandSnapshotKind::Incremental, Some(0)),
+            AccountsPackageType::Snapshot(SnapshotType::IncrementalSnapshot(0))
+        );
+
+        // An incremental snapshot needs an older full snapshot
+        assert_eq!(
+            package_type(OnDemandSnapshotKind::Incremental, None),
+            AccountsPackageType::Snapshot(SnapshotType::FullSnapshot)
+        );
+        assert_eq!(
+            package_type(OnDemandSnapshotKind::Incremental, Some(7)),
+            AccountsPackageType::Snapshot(SnapshotType::FullSnapshot)
+        );
+    }
 }
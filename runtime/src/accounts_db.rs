@@ -43,8 +43,17 @@ use {
         append_vec::{AppendVec, StoredAccountMeta, StoredMeta, StoredMetaWriteVersion},
         bank::Rewrites,
         cache_hash_data::{CacheHashData, CacheHashDataFile},
+        cold_storage::{
+            ColdBlock, ColdBlockCache, ColdStorageConfig, DEFAULT_COLD_STORAGE_CACHE_LIMIT_BYTES,
+        },
         contains::Contains,
         epoch_accounts_hash::EpochAccountsHashManager,
+        persisted_accounts_index::{
+            PersistedAccountInfo, PersistedAccountsIndexFooter, PersistedAccountsIndexHeader,
+            PersistedAccountsIndexReader, PersistedAccountsIndexWriter, PersistedEntry,
+            PersistedStorage, StorageFingerprint,
+        },
+        program_stats::ProgramStatsCollector,
         pubkey_bins::PubkeyBinCalculator24,
         read_only_accounts_cache::ReadOnlyAccountsCache,
         rent_collector::RentCollector,
@@ -339,6 +348,8 @@ pub const ACCOUNTS_DB_CONFIG_FOR_TESTING: AccountsDbConfig = AccountsDbConfig {
     ancient_append_vec_offset: None,
     skip_initial_hash_calc: false,
     exhaustively_verify_refcounts: false,
+    cold_storage: None,
+    program_access_stats: false,
 };
 pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig {
     index: Some(ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS),
@@ -350,6 +361,8 @@ pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig
     ancient_append_vec_offset: None,
     skip_initial_hash_calc: false,
     exhaustively_verify_refcounts: false,
+    cold_storage: None,
+    program_access_stats: false,
 };
 
 pub type BinnedHashData = Vec<Vec<CalculateHashIntermediate>>;
@@ -410,6 +423,10 @@ pub struct AccountsDbConfig {
     pub ancient_append_vec_offset: Option<Slot>,
     pub skip_initial_hash_calc: bool,
     pub exhaustively_verify_refcounts: bool,
+    /// if Some, ancient append vecs are compressed into the cold tier
+    pub cold_storage: Option<ColdStorageConfig>,
+    /// true to count writes and read cache lookups per program
+    pub program_access_stats: bool,
 }
 
 pub struct FoundStoredAccount<'a> {
@@ -664,8 +681,9 @@ pub enum LoadHint {
 #[derive(Debug)]
 pub enum LoadedAccountAccessor<'a> {
     // StoredAccountMeta can't be held directly here due to its lifetime dependency to
-    // AccountStorageEntry
-    Stored(Option<(Arc<AccountStorageEntry>, usize)>),
+    // AccountStorageEntry. For a cold storage, the last element holds the decompressed block
+    // the account is read from.
+    Stored(Option<(Arc<AccountStorageEntry>, usize, Option<Arc<ColdBlock>>)>),
     // None value in Cached variant means the cache was flushed
     Cached(Option<Cow<'a, CachedAccount>>),
 }
@@ -711,11 +729,12 @@ impl<'a> LoadedAccountAccessor<'a> {
                 // between reading the accounts index and calling this function to
                 // get account meta from the storage entry here
                 maybe_storage_entry
-                    .as_ref()
-                    .and_then(|(storage_entry, offset)| {
+                    .as_mut()
+                    .and_then(|(storage_entry, offset, block)| {
                         storage_entry
-                            .get_stored_account_meta(*offset)
-                            .map(LoadedAccount::Stored)
+                            .accounts
+                            .get_account_with_block(*offset, block)
+                            .map(|(account, _next)| LoadedAccount::Stored(account))
                     })
             }
         }
@@ -1045,10 +1064,6 @@ impl AccountStorageEntry {
         self.accounts.flush()
     }
 
-    fn get_stored_account_meta(&self, offset: usize) -> Option<StoredAccountMeta> {
-        Some(self.accounts.get_account(offset)?.0)
-    }
-
     fn add_account(&self, num_bytes: usize) {
         let mut count_and_status = self.count_and_status.write().unwrap();
         *count_and_status = (count_and_status.0 + 1, count_and_status.1);
@@ -1190,6 +1205,10 @@ pub const EXPIRATION_TTL_SECONDS: u64 = 1800;
 
 impl RecycleStores {
     fn add_entry(&mut self, new_entry: Arc<AccountStorageEntry>) {
+        if new_entry.accounts.is_cold() {
+            // cold storages are read only, so they can never be reused. Just drop them.
+            return;
+        }
         self.total_bytes += new_entry.total_bytes();
         self.entries.push((Instant::now(), new_entry))
     }
@@ -1199,6 +1218,10 @@ impl RecycleStores {
     }
 
     fn add_entries(&mut self, new_entries: Vec<Arc<AccountStorageEntry>>) {
+        let new_entries = new_entries
+            .into_iter()
+            .filter(|e| !e.accounts.is_cold())
+            .collect::<Vec<_>>();
         self.total_bytes += new_entries.iter().map(|e| e.total_bytes()).sum::<u64>();
         let now = Instant::now();
         for new_entry in new_entries {
@@ -1275,6 +1298,15 @@ pub struct AccountsDb {
     /// Some(offset) means for slots up to (max_slot - (slots_per_epoch - 'offset')), put them in ancient append vecs
     pub ancient_append_vec_offset: Option<Slot>,
 
+    /// Some(config) iff ancient append vecs are compressed into cold storages
+    pub(crate) cold_storage_config: Option<ColdStorageConfig>,
+
+    /// decompressed blocks of every cold storage, bounded by `ColdStorageConfig::cache_limit_bytes`
+    cold_block_cache: Arc<ColdBlockCache>,
+
+    /// per-program write and read cache counters, and the latest per-program stats report
+    pub program_stats: ProgramStatsCollector,
+
     /// true iff we want to skip the initial hash calculation on startup
     pub skip_initial_hash_calc: bool,
 
@@ -1406,6 +1438,9 @@ pub struct AccountsDb {
     /// debug feature to scan every append vec and verify refcounts are equal
     exhaustively_verify_refcounts: bool,
 
+    /// true if the index was loaded by load_persisted_accounts_index() rather than generated
+    pub(crate) loaded_persisted_accounts_index: AtomicBool,
+
     /// the full accounts hash calculation as of a predetermined block height 'N'
     /// to be included in the bank hash at a predetermined block height 'M'
     /// The cadence is once per epoch, all nodes calculate a full accounts hash as of a known slot calculated using 'N'
@@ -2337,6 +2372,9 @@ impl AccountsDb {
             skip_rewrites: false,
             skip_initial_hash_calc: false,
             ancient_append_vec_offset: None,
+            cold_storage_config: None,
+            cold_block_cache: Arc::new(ColdBlockCache::new(DEFAULT_COLD_STORAGE_CACHE_LIMIT_BYTES)),
+            program_stats: ProgramStatsCollector::default(),
             accounts_index,
             storage: AccountStorage::default(),
             accounts_cache: AccountsCache::default(),
@@ -2387,6 +2425,7 @@ impl AccountsDb {
             num_hash_scan_passes,
             log_dead_slots: AtomicBool::new(true),
             exhaustively_verify_refcounts: false,
+            loaded_persisted_accounts_index: AtomicBool::default(),
             epoch_accounts_hash_manager: EpochAccountsHashManager::new_invalid(),
         }
     }
@@ -2458,6 +2497,15 @@ impl AccountsDb {
             .map(|config| config.exhaustively_verify_refcounts)
             .unwrap_or_default();
 
+        let cold_storage_config = accounts_db_config
+            .as_ref()
+            .and_then(|config| config.cold_storage);
+
+        let program_access_stats = accounts_db_config
+            .as_ref()
+            .map(|config| config.program_access_stats)
+            .unwrap_or_default();
+
         let filler_account_suffix = if filler_accounts_config.count > 0 {
             Some(solana_sdk::pubkey::new_rand())
         } else {
@@ -2469,6 +2517,13 @@ impl AccountsDb {
             skip_rewrites,
             skip_initial_hash_calc,
             ancient_append_vec_offset,
+            cold_storage_config,
+            cold_block_cache: Arc::new(ColdBlockCache::new(
+                cold_storage_config
+                    .map(|config| config.cache_limit_bytes)
+                    .unwrap_or(DEFAULT_COLD_STORAGE_CACHE_LIMIT_BYTES),
+            )),
+            program_stats: ProgramStatsCollector::new(program_access_stats),
             cluster_type: Some(*cluster_type),
             account_indexes,
             caching_enabled,
@@ -2934,7 +2989,7 @@ impl AccountsDb {
                             dirty_ancient_stores.fetch_add(1, Ordering::Relaxed);
                         }
                         oldest_dirty_slot = oldest_dirty_slot.min(*slot);
-                        store.accounts.account_iter().for_each(|account| {
+                        store.accounts.scan_accounts(|account| {
                             pubkeys.insert(*account.pubkey());
                         });
                     });
@@ -3033,7 +3088,7 @@ impl AccountsDb {
                 .get_slot_storage_entries(slot)
                 .unwrap_or_default()
             {
-                storage.all_accounts().iter().for_each(|account| {
+                storage.accounts.scan_accounts(|account| {
                     let pk = account.pubkey();
                     match pubkey_refcount.entry(*pk) {
                         dashmap::mapref::entry::Entry::Occupied(mut occupied_entry) => {
@@ -4307,10 +4362,118 @@ impl AccountsDb {
         }
 
         let can_randomly_shrink = true;
-        self.combine_ancient_slots(
-            self.get_sorted_potential_ancient_slots(),
-            can_randomly_shrink,
-        );
+        let sorted_slots = self.get_sorted_potential_ancient_slots();
+        self.combine_ancient_slots(sorted_slots.clone(), can_randomly_shrink);
+        self.move_ancient_slots_to_cold_storage(&sorted_slots);
+    }
+
+    /// compress the ancient append vecs in 'sorted_slots' into cold storages, oldest first.
+    /// The newest ancient append vec is skipped since later slots may still be appended to it.
+    /// Stops once 'max_bytes_per_pass' bytes were compressed; the rest are left to later passes.
+    fn move_ancient_slots_to_cold_storage(&self, sorted_slots: &[Slot]) {
+        let config = match self.cold_storage_config {
+            Some(config) => config,
+            None => return,
+        };
+        let mut total = Measure::start("move_ancient_slots_to_cold_storage");
+        let mut ancient_storages = sorted_slots
+            .iter()
+            .filter_map(|slot| {
+                let mut storages = self.get_storages_for_slot(*slot)?;
+                (storages.len() == 1 && is_ancient(&storages[0].accounts))
+                    .then(|| storages.pop().unwrap())
+            })
+            .collect::<Vec<_>>();
+        // the newest ancient append vec may become the 'current' ancient append vec in the next pass
+        ancient_storages.pop();
+
+        let mut moved = 0;
+        let mut deferred = 0;
+        let mut bytes_compressed = 0;
+        let mut bytes_before = 0;
+        let mut bytes_after = 0;
+        for storage in ancient_storages {
+            if storage.accounts.is_cold() || !storage.has_accounts() {
+                continue;
+            }
+            if bytes_compressed >= config.max_bytes_per_pass {
+                deferred += 1;
+                continue;
+            }
+            // failed attempts count against the limit too
+            bytes_compressed += storage.written_bytes();
+            if let Some(cold) = self.move_storage_to_cold_storage(&storage, config.block_size) {
+                moved += 1;
+                bytes_before += storage.written_bytes();
+                bytes_after += std::fs::metadata(cold.get_path())
+                    .map(|metadata| metadata.len())
+                    .unwrap_or_default();
+            }
+        }
+        total.stop();
+
+        if moved > 0 {
+            datapoint_info!(
+                "cold_storage_move",
+                ("storages", moved, i64),
+                ("deferred_storages", deferred, i64),
+                ("bytes_before", bytes_before, i64),
+                ("bytes_after", bytes_after, i64),
+                ("total_us", total.as_us(), i64),
+            );
+        }
+    }
+
+    /// Replace 'storage' with a cold storage holding the same accounts at the same offsets,
+    /// so the accounts index does not need to change.
+    /// Returns the new storage, or None if 'storage' could not be replaced.
+    fn move_storage_to_cold_storage(
+        &self,
+        storage: &Arc<AccountStorageEntry>,
+        block_size: usize,
+    ) -> Option<Arc<AccountStorageEntry>> {
+        let slot = storage.slot();
+        let id = storage.append_vec_id();
+        let mut cold_path = storage.get_path().into_os_string();
+        cold_path.push(".cold");
+        let accounts = AppendVec::new_cold(
+            &storage.accounts,
+            Path::new(&cold_path),
+            block_size,
+            Arc::clone(&self.cold_block_cache),
+        )
+        .map_err(|err| {
+            error!(
+                "cold_storage: failed to compress slot: {}, id: {}: {}",
+                slot, id, err
+            )
+        })
+        .ok()?;
+        let cold = Arc::new(AccountStorageEntry::new_existing(
+            slot,
+            id,
+            accounts,
+            storage.approx_stored_count(),
+        ));
+
+        let slot_stores = self.storage.get_slot_stores(slot)?;
+        let mut slot_stores = slot_stores.write().unwrap();
+        if !slot_stores
+            .get(&id)
+            .map(|existing| Arc::ptr_eq(existing, storage))
+            .unwrap_or_default()
+        {
+            // 'storage' was shrunk or removed while we were compressing it
+            return None;
+        }
+        // Only clean removes accounts from ancient storages, and it runs on the same thread as
+        // this, so the alive counts cannot change underneath us.
+        let (count, _status) = *storage.count_and_status.read().unwrap();
+        *cold.count_and_status.write().unwrap() = (count, AccountStorageStatus::Full);
+        cold.alive_bytes
+            .store(storage.alive_bytes(), Ordering::Release);
+        slot_stores.insert(id, Arc::clone(&cold));
+        Some(cold)
     }
 
     /// create and return new ancient append vec
@@ -4420,6 +4583,7 @@ impl AccountsDb {
         }
         let storage = all_storages.first().unwrap();
         let accounts = &storage.accounts;
+        let is_cold = accounts.is_cold();
 
         self.shrink_ancient_stats
             .slots_considered
@@ -4439,7 +4603,10 @@ impl AccountsDb {
             } else {
                 false
             };
-            if is_candidate || (can_randomly_shrink && thread_rng().gen_range(0, 100) == 0) {
+            // cold storages are expensive to rewrite, so they are only shrunk when they need it
+            let randomly_shrink =
+                !is_cold && can_randomly_shrink && thread_rng().gen_range(0, 100) == 0;
+            if is_candidate || randomly_shrink {
                 // we are a candidate for shrink, so either append us to the previous append vec
                 // or recreate us as a new append vec and eliminate the dead accounts
                 info!("ancient_append_vec: shrinking full ancient: {}", slot);
@@ -4453,6 +4620,11 @@ impl AccountsDb {
                     .fetch_add(1, Ordering::Relaxed);
                 return true;
             }
+            if is_cold {
+                // cold storages are read only, so nothing can be appended to them
+                *current_ancient = CurrentAncientAppendVec::default();
+                return false;
+            }
             // this slot is ancient and can become the 'current' ancient for other slots to be squashed into
             *current_ancient = CurrentAncientAppendVec::new(slot, Arc::clone(storage));
             return false; // we're done with this slot - this slot IS the ancient append vec
@@ -4842,11 +5014,17 @@ impl AccountsDb {
         F: FnMut(Option<(&Pubkey, AccountSharedData, Slot)>),
     {
         let key = match &index_key {
-            IndexKey::ProgramId(key) => key,
-            IndexKey::SplTokenMint(key) => key,
-            IndexKey::SplTokenOwner(key) => key,
+            IndexKey::ProgramId(key) => Some(key),
+            IndexKey::SplTokenMint(key) => Some(key),
+            IndexKey::SplTokenOwner(key) => Some(key),
+            IndexKey::SplTokenDelegate(key) => Some(key),
+            // data offset indexes are configured explicitly, so the included keys don't apply
+            IndexKey::DataOffset(..) => None,
         };
-        if !self.account_indexes.include_key(key) {
+        if !key
+            .map(|key| self.account_indexes.include_key(key))
+            .unwrap_or(true)
+        {
             // the requested key was not indexed in the secondary index, so do a normal scan
             let used_index = false;
             self.scan_accounts(ancestors, bank_id, scan_func, config)?;
@@ -4918,7 +5096,7 @@ impl AccountsDb {
                 .unwrap_or_default();
             self.thread_pool.install(|| {
                 storage_maps.par_iter().for_each(|storage| {
-                    storage.accounts.account_iter().for_each(|account| {
+                    storage.accounts.scan_accounts(|account| {
                         storage_scan_func(&retval, LoadedAccount::Stored(account))
                     })
                 });
@@ -5344,6 +5522,8 @@ impl AccountsDb {
                 if !in_write_cache {
                     let result = self.read_only_accounts_cache.load(*pubkey, slot);
                     if let Some(account) = result {
+                        self.program_stats
+                            .record_read_cache_lookup(account.owner(), true);
                         if matches!(load_zero_lamports, LoadZeroLamports::None)
                             && account.is_zero_lamport()
                         {
@@ -5381,6 +5561,10 @@ impl AccountsDb {
         }
 
         if self.caching_enabled && !is_cached {
+            if !load_into_read_cache_only {
+                self.program_stats
+                    .record_read_cache_lookup(account.owner(), false);
+            }
             /*
             We show this store into the read-only cache for account 'A' and future loads of 'A' from the read-only cache are
             safe/reflect 'A''s latest state on this fork.
@@ -5437,7 +5621,7 @@ impl AccountsDb {
                 let maybe_storage_entry = self
                     .storage
                     .get_account_storage_entry(slot, *store_id)
-                    .map(|account_storage_entry| (account_storage_entry, *offset));
+                    .map(|account_storage_entry| (account_storage_entry, *offset, None));
                 LoadedAccountAccessor::Stored(maybe_storage_entry)
             }
         }
@@ -7054,7 +7238,7 @@ impl AccountsDb {
         let mut len = storages.len();
         if len == 1 {
             // only 1 storage, so no need to interleave between multiple storages based on write_version
-            storages[0].accounts.account_iter().for_each(|account| {
+            storages[0].accounts.scan_accounts(|account| {
                 if scanner.filter(account.pubkey()) {
                     scanner.found_account(&LoadedAccount::Stored(account))
                 }
@@ -8260,11 +8444,11 @@ impl AccountsDb {
                     .into_par_iter()
                     .map(|store| {
                         let slot = store.slot();
+                        let mut pubkeys = Vec::with_capacity(store.approx_stored_count());
                         store
                             .accounts
-                            .account_iter()
-                            .map(|account| (slot, *account.pubkey()))
-                            .collect::<Vec<(Slot, Pubkey)>>()
+                            .scan_accounts(|account| pubkeys.push((slot, *account.pubkey())));
+                        pubkeys
                     })
                     .flatten()
                     .collect::<HashSet<_>>()
@@ -8326,6 +8510,7 @@ impl AccountsDb {
             let account = accounts.account(index);
             total_data += account.data().len();
             stats.update(account);
+            self.program_stats.record_write(account.owner());
         });
 
         self.stats
@@ -9065,6 +9250,13 @@ impl AccountsDb {
         if let Some(limit) = limit_load_slot_count_from_snapshot {
             slots.truncate(limit); // get rid of the newer slots and keep just the older
         }
+        if limit_load_slot_count_from_snapshot.is_none() && !verify {
+            if let Some(index_generation_info) =
+                self.load_persisted_accounts_index(&slots, &genesis_config.epoch_schedule)
+            {
+                return index_generation_info;
+            }
+        }
         let max_slot = slots.last().cloned().unwrap_or_default();
         let schedule = genesis_config.epoch_schedule;
         let rent_collector = RentCollector::new(
@@ -9314,6 +9506,354 @@ impl AccountsDb {
         }
     }
 
+    /// true if the accounts index may be saved and loaded, see save_accounts_index()
+    pub fn is_accounts_index_persisted(&self) -> bool {
+        self.accounts_index.persisted_index_path.is_some()
+    }
+
+    /// Saves the accounts index, so that a later start over the same storages can load it instead
+    /// of running generate_index(). Only rooted accounts which have been flushed to storages are
+    /// saved. Nothing may be stored or cleaned while this runs, as is the case on shutdown.
+    ///
+    /// A start loads the storages of the latest snapshot, so the index is saved right after a full
+    /// snapshot of the root, by bank_to_full_snapshot_archive_and_accounts_index(). The index is
+    /// written one bin at a time.
+    pub fn save_accounts_index(&self) -> IoResult<()> {
+        let path = match &self.accounts_index.persisted_index_path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if !self.account_indexes.is_empty() || self.filler_accounts_enabled() {
+            info!("not saving the accounts index: secondary indexes and filler accounts are only built by generate_index()");
+            return Ok(());
+        }
+        let mut measure = Measure::start("save_accounts_index");
+        // the storages a snapshot of the root holds, see get_snapshot_storages()
+        let storages = self
+            .storage
+            .map
+            .iter()
+            .filter(|slot_stores| self.accounts_index.is_alive_root(*slot_stores.key()))
+            .flat_map(|slot_stores| {
+                slot_stores
+                    .value()
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .filter(|(_id, store)| store.has_accounts())
+                    .map(|(id, store)| (*id, Arc::clone(store)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<HashMap<_, _>>();
+
+        let header = PersistedAccountsIndexHeader {
+            storages: storages
+                .iter()
+                .map(|(id, store)| PersistedStorage {
+                    id: *id,
+                    fingerprint: StorageFingerprint::new(store),
+                    count: store.count(),
+                    alive_bytes: store.alive_bytes(),
+                })
+                .collect(),
+            bins: self.accounts_index.account_maps.len(),
+        };
+        let mut writer = PersistedAccountsIndexWriter::new(path, &header)?;
+        let mut accounts_data_len = 0;
+        let mut num_entries = 0;
+        for map in self.accounts_index.account_maps.iter() {
+            let entries = map
+                .items(&..)
+                .into_iter()
+                .filter_map(|(pubkey, entry)| {
+                    let mut ref_count = entry.ref_count();
+                    let slot_list = entry
+                        .slot_list
+                        .read()
+                        .unwrap()
+                        .iter()
+                        .filter_map(|(slot, account_info)| {
+                            if account_info.is_cached() {
+                                return None;
+                            }
+                            if !storages.contains_key(&account_info.store_id()) {
+                                // stored in a slot which is not rooted
+                                ref_count = ref_count.saturating_sub(1);
+                                return None;
+                            }
+                            Some(PersistedAccountInfo {
+                                slot: *slot,
+                                store_id: account_info.store_id(),
+                                offset: account_info.offset(),
+                                stored_size: account_info.stored_size(),
+                                is_zero_lamport: account_info.is_zero_lamport(),
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    let newest = slot_list.iter().max_by_key(|info| info.slot)?;
+                    if !newest.is_zero_lamport {
+                        if let Some((account, _next)) = storages[&newest.store_id]
+                            .accounts
+                            .get_account(newest.offset)
+                        {
+                            accounts_data_len += account.meta.data_len;
+                        }
+                    }
+                    Some(PersistedEntry {
+                        pubkey,
+                        ref_count,
+                        slot_list,
+                    })
+                })
+                .collect::<Vec<_>>();
+            num_entries += entries.len();
+            writer.write_bin(&entries)?;
+        }
+
+        writer.finish(&PersistedAccountsIndexFooter {
+            uncleaned_roots: self
+                .accounts_index
+                .clone_uncleaned_roots()
+                .into_iter()
+                .collect(),
+            uncleaned_pubkeys: self
+                .uncleaned_pubkeys
+                .iter()
+                .filter(|entry| self.accounts_index.is_alive_root(*entry.key()))
+                .map(|entry| (*entry.key(), entry.value().clone()))
+                .collect(),
+            accounts_data_len,
+            rent_paying_accounts: self
+                .accounts_index
+                .rent_paying_accounts_by_partition
+                .get()
+                .map(|rent_paying_accounts_by_partition| {
+                    rent_paying_accounts_by_partition
+                        .accounts
+                        .iter()
+                        .flatten()
+                        .copied()
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })?;
+        measure.stop();
+        info!(
+            "saved the accounts index to {}: {} entries, {} storages, {}",
+            path.display(),
+            num_entries,
+            header.storages.len(),
+            measure,
+        );
+        Ok(())
+    }
+
+    /// Loads the index saved by save_accounts_index(), if it was saved against the storages of
+    /// 'slots'. Returns None if the index has to be generated.
+    ///
+    /// Every slot list item is checked against the storage it points to before anything is
+    /// inserted: the account at its offset must be stored under the entry's pubkey, with the
+    /// saved size and lamports. The file is read twice, once to check and once to insert, so
+    /// the entries are never all in memory.
+    fn load_persisted_accounts_index(
+        &self,
+        slots: &[Slot],
+        epoch_schedule: &EpochSchedule,
+    ) -> Option<IndexGenerationInfo> {
+        let path = self.accounts_index.persisted_index_path.as_ref()?;
+        let mut measure = Measure::start("load_persisted_accounts_index");
+        let read_error = |err: IoError| {
+            warn!(
+                "failed to load the persisted accounts index from {}: {}",
+                path.display(),
+                err
+            );
+        };
+        let mut reader = match PersistedAccountsIndexReader::take(path) {
+            Ok(reader) => reader?,
+            Err(err) => {
+                read_error(err);
+                return None;
+            }
+        };
+        if !self.account_indexes.is_empty() || self.filler_accounts_enabled() {
+            info!("ignoring the persisted accounts index: secondary indexes and filler accounts are only built by generate_index()");
+            return None;
+        }
+        let mismatch = || {
+            info!("the persisted accounts index does not match the account storages, generating the index");
+        };
+
+        // Storage ids are reassigned when storages are loaded, so match storages by their contents
+        let mut loaded_storages =
+            HashMap::<StorageFingerprint, Arc<AccountStorageEntry>>::default();
+        for slot in slots {
+            for store in self
+                .storage
+                .get_slot_storage_entries(*slot)
+                .unwrap_or_default()
+            {
+                if loaded_storages
+                    .insert(StorageFingerprint::new(&store), store)
+                    .is_some()
+                {
+                    mismatch();
+                    return None;
+                }
+            }
+        }
+        let header = reader.header();
+        if loaded_storages.len() != header.storages.len() {
+            mismatch();
+            return None;
+        }
+        let mut storages =
+            HashMap::<AppendVecId, (Arc<AccountStorageEntry>, PersistedStorage)>::default();
+        for persisted_storage in &header.storages {
+            match loaded_storages.remove(&persisted_storage.fingerprint) {
+                Some(store) => {
+                    storages.insert(persisted_storage.id, (store, persisted_storage.clone()));
+                }
+                None => {
+                    mismatch();
+                    return None;
+                }
+            }
+        }
+        let bins = header.bins;
+
+        let entry_matches = |entry: &PersistedEntry| {
+            !entry.slot_list.is_empty()
+                && entry.slot_list.iter().all(|info| {
+                    let (store, persisted_storage) = match storages.get(&info.store_id) {
+                        Some(storage) => storage,
+                        None => return false,
+                    };
+                    persisted_storage.fingerprint.slot == info.slot
+                        && store
+                            .accounts
+                            .get_account(info.offset)
+                            .map(|(account, _next)| {
+                                account.meta.pubkey == entry.pubkey
+                                    && account.stored_size == info.stored_size as usize
+                                    && (account.account_meta.lamports == 0) == info.is_zero_lamport
+                            })
+                            .unwrap_or_default()
+                })
+        };
+        let mut num_entries = 0;
+        for _ in 0..bins {
+            let entries = match reader.read_bin() {
+                Ok(entries) => entries,
+                Err(err) => {
+                    read_error(err);
+                    return None;
+                }
+            };
+            if !entries.par_iter().all(entry_matches) {
+                mismatch();
+                return None;
+            }
+            num_entries += entries.len();
+        }
+        let footer = match reader.read_footer().and_then(|footer| {
+            reader.rewind()?;
+            Ok(footer)
+        }) {
+            Ok(footer) => footer,
+            Err(err) => {
+                read_error(err);
+                return None;
+            }
+        };
+
+        // Every entry matched, so nothing below falls back to generate_index()
+        for _ in 0..bins {
+            let entries = reader
+                .read_bin()
+                .expect("persisted accounts index was read before");
+            entries.par_chunks(4096).for_each(|entries| {
+                for entry in entries {
+                    let slot_list = entry
+                        .slot_list
+                        .iter()
+                        .map(|info| {
+                            let (store, _) = &storages[&info.store_id];
+                            (
+                                info.slot,
+                                AccountInfo::new(
+                                    StorageLocation::AppendVec(store.append_vec_id(), info.offset),
+                                    info.stored_size,
+                                    u64::from(!info.is_zero_lamport),
+                                ),
+                            )
+                        })
+                        .collect();
+                    self.accounts_index
+                        .get_bin(&entry.pubkey)
+                        .insert_persisted_entry(entry.pubkey, slot_list, entry.ref_count);
+                }
+            });
+        }
+
+        let uncleaned_roots = footer.uncleaned_roots.into_iter().collect::<HashSet<_>>();
+        for root in slots {
+            // see generate_index() for how 'add_root' treats uncleaned roots
+            self.accounts_index
+                .add_root(*root, !uncleaned_roots.contains(root));
+        }
+        for (slot, pubkeys) in footer.uncleaned_pubkeys {
+            self.uncleaned_pubkeys.insert(slot, pubkeys);
+        }
+
+        for (store, persisted_storage) in storages.values() {
+            // Should be default at this point
+            assert_eq!(store.alive_bytes(), 0);
+            store.count_and_status.write().unwrap().0 = persisted_storage.count;
+            store
+                .alive_bytes
+                .store(persisted_storage.alive_bytes, Ordering::SeqCst);
+            if self.caching_enabled
+                && store.count() != 0
+                && self.is_candidate_for_shrink(store, false)
+            {
+                self.shrink_candidate_slots
+                    .lock()
+                    .unwrap()
+                    .entry(store.slot())
+                    .or_default()
+                    .insert(store.append_vec_id(), Arc::clone(store));
+            }
+        }
+
+        let mut rent_paying_accounts_by_partition =
+            RentPayingAccountsByPartition::new(epoch_schedule);
+        footer
+            .rent_paying_accounts
+            .iter()
+            .for_each(|pubkey| rent_paying_accounts_by_partition.add_account(pubkey));
+        self.loaded_persisted_accounts_index
+            .store(true, Ordering::Relaxed);
+
+        measure.stop();
+        info!(
+            "loaded the persisted accounts index from {}: {} entries, {}",
+            path.display(),
+            num_entries,
+            measure,
+        );
+        datapoint_info!(
+            "load_persisted_accounts_index",
+            ("entries", num_entries, i64),
+            ("storages", storages.len(), i64),
+            ("load_us", measure.as_us(), i64),
+        );
+        Some(IndexGenerationInfo {
+            accounts_data_len: footer.accounts_data_len,
+            rent_paying_accounts_by_partition,
+        })
+    }
+
     /// Startup processes can consume large amounts of memory while inserting accounts into the index as fast as possible.
     /// Calling this can slow down the insertion process to allow flushing to disk to keep pace.
     fn maybe_throttle_index_generation(&self) {
@@ -9374,7 +9914,7 @@ impl AccountsDb {
                         .storage
                         .get_account_storage_entry(*slot, account_info.store_id());
                     let mut accessor = LoadedAccountAccessor::Stored(
-                        maybe_storage_entry.map(|entry| (entry, account_info.offset())),
+                        maybe_storage_entry.map(|entry| (entry, account_info.offset(), None)),
                     );
                     let loaded_account = accessor.check_and_get_loaded_account();
                     accounts_data_len_from_duplicates += loaded_account.data().len();
@@ -9750,6 +10290,7 @@ pub mod tests {
             },
             append_vec::{test_utils::TempFile, AccountMeta},
             cache_hash_data_stats::CacheHashDataStats,
+            cold_storage::DEFAULT_COLD_STORAGE_BLOCK_SIZE,
             inline_spl_token,
         },
         assert_matches::assert_matches,
@@ -11852,7 +12393,7 @@ pub mod tests {
             .index_scan_accounts(
                 &Ancestors::default(),
                 bank_id,
-                index_key,
+                index_key.clone(),
                 |key, _| {
                     found_accounts.insert(*key);
                 },
@@ -11874,7 +12415,7 @@ pub mod tests {
                 .index_scan_accounts(
                     &Ancestors::default(),
                     bank_id,
-                    index_key,
+                    index_key.clone(),
                     |account| {
                         found_accounts.insert(*account.unwrap().0);
                     },
@@ -17682,6 +18223,84 @@ pub mod tests {
         assert_eq!(after_stored_accounts.len(), 2);
     }
 
+    #[test]
+    fn test_move_ancient_slots_to_cold_storage() {
+        let (mut db, _tf, ancient_slot) = get_one_ancient_append_vec_and_others(true, 0);
+        db.cold_storage_config = Some(ColdStorageConfig::default());
+        // the cache releases every block as soon as it is read
+        db.cold_block_cache = Arc::new(ColdBlockCache::new(0));
+
+        // the newest ancient append vec may still be appended to, so it stays as is
+        db.move_ancient_slots_to_cold_storage(&[ancient_slot]);
+        let ancient = db
+            .get_storages_for_slot(ancient_slot)
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert!(!ancient.accounts.is_cold());
+
+        let cold = db
+            .move_storage_to_cold_storage(&ancient, DEFAULT_COLD_STORAGE_BLOCK_SIZE)
+            .unwrap();
+        let stored = db.get_storages_for_slot(ancient_slot).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert!(Arc::ptr_eq(&stored[0], &cold));
+        drop(stored);
+        assert!(cold.accounts.is_cold());
+        assert!(is_ancient(&cold.accounts));
+        assert_eq!(cold.append_vec_id(), ancient.append_vec_id());
+        assert_eq!(cold.count(), ancient.count());
+        assert_eq!(cold.alive_bytes(), ancient.alive_bytes());
+        assert_eq!(cold.written_bytes(), ancient.written_bytes());
+        assert_eq!(cold.status(), AccountStorageStatus::Full);
+        // a cold storage cannot be written to
+        assert_eq!(cold.accounts.remaining_bytes(), 0);
+
+        let accounts = ancient.all_accounts();
+        assert_eq!(accounts.len(), 1);
+        let mut scanned = vec![];
+        cold.accounts
+            .scan_accounts(|account| scanned.push((*account.pubkey(), account.clone_account())));
+        assert_eq!(
+            scanned,
+            accounts
+                .iter()
+                .map(|account| (*account.pubkey(), account.clone_account()))
+                .collect::<Vec<_>>()
+        );
+
+        // accounts are read back through the unchanged index entries, with each load holding
+        // the block it reads from
+        let ancestors = Ancestors::default();
+        for account in &accounts {
+            assert_eq!(
+                db.load_without_fixed_root(&ancestors, account.pubkey()),
+                Some((account.clone_account(), ancient_slot))
+            );
+        }
+        assert_eq!(db.cold_block_cache.bytes(), 0);
+        assert_eq!(
+            cold.all_accounts()
+                .iter()
+                .map(|account| (*account.pubkey(), *account.hash, account.offset))
+                .collect::<Vec<_>>(),
+            accounts
+                .iter()
+                .map(|account| (*account.pubkey(), *account.hash, account.offset))
+                .collect::<Vec<_>>()
+        );
+
+        // cold storages never become the current ancient append vec
+        let mut current_ancient = CurrentAncientAppendVec::new(ancient_slot, Arc::clone(&ancient));
+        assert!(!db.should_move_to_ancient_append_vec(
+            &vec![Arc::clone(&cold)],
+            &mut current_ancient,
+            ancient_slot,
+            CAN_RANDOMLY_SHRINK_FALSE,
+        ));
+        assert!(current_ancient.slot_and_append_vec.is_none());
+    }
+
     fn get_one_ancient_append_vec_and_others(
         alive: bool,
         num_normal_slots: usize,
@@ -17965,4 +18584,168 @@ pub mod tests {
         make_ancient_append_vec_full(&full);
         full
     }
+
+    #[test]
+    fn test_persisted_accounts_index() {
+        solana_logger::setup();
+        let genesis_config = solana_sdk::genesis_config::create_genesis_config(100).0;
+        let temp_dir = TempDir::new().unwrap();
+        let persisted_index_path = temp_dir.path().join("accounts_index");
+        let new_db = |persisted_index_path: Option<PathBuf>| {
+            AccountsDb::new_with_config(
+                Vec::new(),
+                &ClusterType::Development,
+                AccountSecondaryIndexes::default(),
+                true,
+                AccountShrinkThreshold::default(),
+                Some(AccountsDbConfig {
+                    index: Some(AccountsIndexConfig {
+                        persisted_index_path,
+                        ..ACCOUNTS_INDEX_CONFIG_FOR_TESTING
+                    }),
+                    ..ACCOUNTS_DB_CONFIG_FOR_TESTING
+                }),
+                None,
+                &Arc::default(),
+            )
+        };
+        // load the storages of 'from' into 'to' under new ids, as loading a snapshot does
+        let load_storages = |from: &AccountsDb, to: &AccountsDb, slots: &[Slot]| {
+            for slot in slots {
+                for store in from.get_storages_for_slot(*slot).unwrap() {
+                    let (accounts, num_accounts) =
+                        AppendVec::new_from_file(store.get_path(), store.accounts.len()).unwrap();
+                    let id = to.next_id() + 1000;
+                    to.insert_store(
+                        *slot,
+                        Arc::new(AccountStorageEntry::new_existing(
+                            *slot,
+                            id,
+                            accounts,
+                            num_accounts,
+                        )),
+                    );
+                }
+            }
+        };
+        let index_entry = |db: &AccountsDb, pubkey: &Pubkey| {
+            db.accounts_index
+                .get_account_read_entry(pubkey)
+                .map(|entry| {
+                    (
+                        entry
+                            .slot_list()
+                            .iter()
+                            .map(|(slot, _)| *slot)
+                            .collect::<Vec<_>>(),
+                        entry.ref_count(),
+                    )
+                })
+        };
+
+        let owner = Pubkey::new_unique();
+        let key1 = Pubkey::new_unique();
+        let key2 = Pubkey::new_unique();
+        let account1 = AccountSharedData::new(1, 10, &owner);
+        let account2 = AccountSharedData::new(2, 20, &owner);
+        let db = new_db(Some(persisted_index_path.clone()));
+        db.store_cached((1, &[(&key1, &account1), (&key2, &account1)][..]), None);
+        db.add_root(1);
+        db.store_cached((2, &[(&key1, &account2)][..]), None);
+        db.add_root(2);
+        // not rooted, so not saved
+        db.store_cached((3, &[(&key2, &account2)][..]), None);
+        db.flush_accounts_cache(true, None);
+        db.save_accounts_index().unwrap();
+        assert!(persisted_index_path.exists());
+
+        let regenerated_db = new_db(None);
+        load_storages(&db, &regenerated_db, &[1, 2]);
+        let regenerated = regenerated_db.generate_index(None, false, &genesis_config);
+
+        let loaded_db = new_db(Some(persisted_index_path.clone()));
+        load_storages(&db, &loaded_db, &[1, 2]);
+        let loaded = loaded_db.generate_index(None, false, &genesis_config);
+        assert!(!persisted_index_path.exists());
+        assert!(loaded_db
+            .loaded_persisted_accounts_index
+            .load(Ordering::Relaxed));
+        assert!(!regenerated_db
+            .loaded_persisted_accounts_index
+            .load(Ordering::Relaxed));
+        assert_eq!(loaded.accounts_data_len, 20 + 10);
+        assert_eq!(loaded.accounts_data_len, regenerated.accounts_data_len);
+        assert_eq!(index_entry(&loaded_db, &key1), Some((vec![1, 2], 2)));
+        assert_eq!(index_entry(&loaded_db, &key2), Some((vec![1], 1)));
+        for key in [key1, key2] {
+            assert_eq!(
+                index_entry(&loaded_db, &key),
+                index_entry(&regenerated_db, &key)
+            );
+        }
+        assert_eq!(
+            loaded_db.load_without_fixed_root(&Ancestors::default(), &key1),
+            Some((account2, 2))
+        );
+        assert_eq!(
+            loaded_db.load_without_fixed_root(&Ancestors::default(), &key2),
+            Some((account1, 1))
+        );
+        for slot in [1, 2] {
+            let loaded_store = &loaded_db.get_storages_for_slot(slot).unwrap()[0];
+            let regenerated_store = &regenerated_db.get_storages_for_slot(slot).unwrap()[0];
+            assert_eq!(loaded_store.count(), regenerated_store.count());
+            assert_eq!(loaded_store.alive_bytes(), regenerated_store.alive_bytes());
+        }
+        assert!(loaded_db.accounts_index.is_alive_root(2));
+
+        // the storage of slot 2 is missing, so the index is generated
+        db.save_accounts_index().unwrap();
+        let mismatched_db = new_db(Some(persisted_index_path.clone()));
+        load_storages(&db, &mismatched_db, &[1]);
+        mismatched_db.generate_index(None, false, &genesis_config);
+        assert!(!persisted_index_path.exists());
+        assert!(!mismatched_db
+            .loaded_persisted_accounts_index
+            .load(Ordering::Relaxed));
+        assert_eq!(index_entry(&mismatched_db, &key1), Some((vec![1], 1)));
+
+        // the storages match, but key1 and key2 point at each other's accounts in slot 1
+        db.save_accounts_index().unwrap();
+        let mut reader = PersistedAccountsIndexReader::take(&persisted_index_path)
+            .unwrap()
+            .unwrap();
+        let header = reader.header().clone();
+        let mut bins = (0..header.bins)
+            .map(|_| reader.read_bin().unwrap())
+            .collect::<Vec<_>>();
+        let footer = reader.read_footer().unwrap();
+        let mut slot1_infos = bins
+            .iter_mut()
+            .flatten()
+            .flat_map(|entry| entry.slot_list.iter_mut())
+            .filter(|info| info.slot == 1)
+            .collect::<Vec<_>>();
+        assert_eq!(slot1_infos.len(), 2);
+        let offset = slot1_infos[0].offset;
+        slot1_infos[0].offset = slot1_infos[1].offset;
+        slot1_infos[1].offset = offset;
+        let mut writer = PersistedAccountsIndexWriter::new(&persisted_index_path, &header).unwrap();
+        for bin in &bins {
+            writer.write_bin(bin).unwrap();
+        }
+        writer.finish(&footer).unwrap();
+        let mismatched_db = new_db(Some(persisted_index_path.clone()));
+        load_storages(&db, &mismatched_db, &[1, 2]);
+        mismatched_db.generate_index(None, false, &genesis_config);
+        assert!(!mismatched_db
+            .loaded_persisted_accounts_index
+            .load(Ordering::Relaxed));
+        for key in [key1, key2] {
+            assert_eq!(
+                index_entry(&mismatched_db, &key),
+                index_entry(&regenerated_db, &key)
+            );
+        }
+    }
 }
@@ -12,6 +12,7 @@ use {
         rolling_bit_field::RollingBitField,
         secondary_index::*,
     },
+    dashmap::DashMap,
     log::*,
     once_cell::sync::OnceCell,
     ouroboros::self_referencing,
@@ -48,6 +49,8 @@ pub const BINS_DEFAULT: usize = 8192;
 pub const BINS_FOR_TESTING: usize = 2; // we want > 1, but each bin is a few disk files with a disk based index, so fewer is better
 pub const BINS_FOR_BENCHMARKS: usize = 8192;
 pub const FLUSH_THREADS_TESTING: usize = 1;
+/// Maximum number of data bytes a `DataOffsetIndex` can be keyed by
+pub const MAX_DATA_OFFSET_INDEX_LENGTH: usize = 128;
 pub const ACCOUNTS_INDEX_CONFIG_FOR_TESTING: AccountsIndexConfig = AccountsIndexConfig {
     bins: Some(BINS_FOR_TESTING),
     flush_threads: Some(FLUSH_THREADS_TESTING),
@@ -56,6 +59,7 @@ pub const ACCOUNTS_INDEX_CONFIG_FOR_TESTING: AccountsIndexConfig = AccountsIndex
     ages_to_stay_in_cache: None,
     scan_results_limit_bytes: None,
     started_from_validator: false,
+    persisted_index_path: None,
 };
 pub const ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS: AccountsIndexConfig = AccountsIndexConfig {
     bins: Some(BINS_FOR_BENCHMARKS),
@@ -65,6 +69,7 @@ pub const ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS: AccountsIndexConfig = AccountsIn
     ages_to_stay_in_cache: None,
     scan_results_limit_bytes: None,
     started_from_validator: false,
+    persisted_index_path: None,
 };
 pub type ScanResult<T> = Result<T, ScanError>;
 pub type SlotList<T> = Vec<(Slot, T)>;
@@ -92,6 +97,10 @@ pub struct ScanConfig {
     /// true to allow return of all matching items and allow them to be unsorted.
     /// This is more efficient.
     pub collect_all_unsorted: bool,
+
+    /// when set, only visit the keys greater than this one. Unless `collect_all_unsorted`,
+    /// keys are visited in order, so a scan can be resumed after the last key it visited.
+    pub start_after: Option<Pubkey>,
 }
 
 impl ScanConfig {
@@ -114,6 +123,7 @@ impl ScanConfig {
         ScanConfig {
             abort: Some(self.abort.as_ref().map(Arc::clone).unwrap_or_default()),
             collect_all_unsorted: self.collect_all_unsorted,
+            start_after: self.start_after,
         }
     }
 
@@ -151,11 +161,14 @@ enum ScanTypes<R: RangeBounds<Pubkey>> {
     Indexed(IndexKey),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum IndexKey {
     ProgramId(Pubkey),
     SplTokenMint(Pubkey),
     SplTokenOwner(Pubkey),
+    SplTokenDelegate(Pubkey),
+    /// The data bytes of accounts in a `DataOffsetIndex`
+    DataOffset(DataOffsetIndex, Vec<u8>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -163,6 +176,23 @@ pub enum AccountIndex {
     ProgramId,
     SplTokenMint,
     SplTokenOwner,
+    SplTokenDelegate,
+    DataOffset(DataOffsetIndex),
+}
+
+/// Indexes the accounts owned by `program_id` by the `length` bytes of their data at `offset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DataOffsetIndex {
+    pub program_id: Pubkey,
+    pub offset: usize,
+    pub length: usize,
+}
+
+impl DataOffsetIndex {
+    /// The bytes 'account_data' is indexed by, if it is long enough
+    pub fn key<'a>(&self, account_data: &'a [u8]) -> Option<&'a [u8]> {
+        account_data.get(self.offset..self.offset.checked_add(self.length)?)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -198,6 +228,8 @@ pub struct AccountsIndexConfig {
     pub scan_results_limit_bytes: Option<usize>,
     /// true if the accounts index is being created as a result of being started as a validator (as opposed to test, etc.)
     pub started_from_validator: bool,
+    /// where the index is saved on a clean shutdown, and loaded from at startup
+    pub persisted_index_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -213,6 +245,12 @@ impl AccountSecondaryIndexes {
     pub fn contains(&self, index: &AccountIndex) -> bool {
         self.indexes.contains(index)
     }
+    pub fn data_offset_indexes(&self) -> impl Iterator<Item = &DataOffsetIndex> {
+        self.indexes.iter().filter_map(|index| match index {
+            AccountIndex::DataOffset(data_offset_index) => Some(data_offset_index),
+            _ => None,
+        })
+    }
     pub fn include_key(&self, key: &Pubkey) -> bool {
         match &self.keys {
             Some(options) => options.exclude ^ options.keys.contains(key),
@@ -671,6 +709,10 @@ pub struct AccountsIndex<T: IndexValue> {
     program_id_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
     spl_token_mint_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
     spl_token_owner_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
+    spl_token_delegate_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
+    /// one index per `DataOffsetIndex`, created when the first account is indexed
+    data_offset_indexes:
+        DashMap<DataOffsetIndex, SecondaryIndex<DashMapSecondaryIndexEntry, Vec<u8>>>,
     pub(crate) roots_tracker: RwLock<RootsTracker>,
     ongoing_scan_roots: RwLock<BTreeMap<Slot, u64>>,
     // Each scan has some latest slot `S` that is the tip of the fork the scan
@@ -701,6 +743,9 @@ pub struct AccountsIndex<T: IndexValue> {
 
     /// populated at generate_index time - accounts that could possibly be rent paying
     pub rent_paying_accounts_by_partition: OnceCell<RentPayingAccountsByPartition>,
+
+    /// where the index is saved on a clean shutdown, and loaded from at startup
+    pub(crate) persisted_index_path: Option<PathBuf>,
 }
 
 impl<T: IndexValue> AccountsIndex<T> {
@@ -712,6 +757,9 @@ impl<T: IndexValue> AccountsIndex<T> {
         let scan_results_limit_bytes = config
             .as_ref()
             .and_then(|config| config.scan_results_limit_bytes);
+        let persisted_index_path = config
+            .as_ref()
+            .and_then(|config| config.persisted_index_path.clone());
         let (account_maps, bin_calculator, storage) = Self::allocate_accounts_index(config, exit);
         Self {
             account_maps,
@@ -725,6 +773,10 @@ impl<T: IndexValue> AccountsIndex<T> {
             spl_token_owner_index: SecondaryIndex::<RwLockSecondaryIndexEntry>::new(
                 "spl_token_owner_index_stats",
             ),
+            spl_token_delegate_index: SecondaryIndex::<DashMapSecondaryIndexEntry>::new(
+                "spl_token_delegate_index_stats",
+            ),
+            data_offset_indexes: DashMap::default(),
             roots_tracker: RwLock::<RootsTracker>::default(),
             ongoing_scan_roots: RwLock::<BTreeMap<Slot, u64>>::default(),
             removed_bank_ids: Mutex::<HashSet<BankId>>::default(),
@@ -735,6 +787,7 @@ impl<T: IndexValue> AccountsIndex<T> {
             active_scans: AtomicUsize::default(),
             max_distance_to_min_scan_slot: AtomicU64::default(),
             rent_paying_accounts_by_partition: OnceCell::default(),
+            persisted_index_path,
         }
     }
 
@@ -946,7 +999,24 @@ impl<T: IndexValue> AccountsIndex<T> {
         match scan_type {
             ScanTypes::Unindexed(range) => {
                 // Pass "" not to log metrics, so RPC doesn't get spammy
-                self.do_scan_accounts(metric_name, ancestors, func, range, Some(max_root), config);
+                match (range, config.start_after) {
+                    (None, Some(start_after)) => self.do_scan_accounts(
+                        metric_name,
+                        ancestors,
+                        func,
+                        Some((Excluded(start_after), Unbounded)),
+                        Some(max_root),
+                        config,
+                    ),
+                    (range, _) => self.do_scan_accounts(
+                        metric_name,
+                        ancestors,
+                        func,
+                        range,
+                        Some(max_root),
+                        config,
+                    ),
+                }
             }
             ScanTypes::Indexed(IndexKey::ProgramId(program_id)) => {
                 self.do_scan_secondary_index(
@@ -978,6 +1048,28 @@ impl<T: IndexValue> AccountsIndex<T> {
                     config,
                 );
             }
+            ScanTypes::Indexed(IndexKey::SplTokenDelegate(delegate_key)) => {
+                self.do_scan_secondary_index(
+                    ancestors,
+                    func,
+                    &self.spl_token_delegate_index,
+                    &delegate_key,
+                    Some(max_root),
+                    config,
+                );
+            }
+            ScanTypes::Indexed(IndexKey::DataOffset(data_offset_index, data)) => {
+                if let Some(index) = self.data_offset_indexes.get(&data_offset_index) {
+                    self.do_scan_secondary_index(
+                        ancestors,
+                        func,
+                        &index,
+                        &data,
+                        Some(max_root),
+                        config,
+                    );
+                }
+            }
         }
 
         {
@@ -1088,18 +1180,26 @@ impl<T: IndexValue> AccountsIndex<T> {
     fn do_scan_secondary_index<
         F,
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
+        K: SecondaryIndexKey,
     >(
         &self,
         ancestors: &Ancestors,
         mut func: F,
-        index: &SecondaryIndex<SecondaryIndexEntryType>,
-        index_key: &Pubkey,
+        index: &SecondaryIndex<SecondaryIndexEntryType, K>,
+        index_key: &K,
         max_root: Option<Slot>,
         config: &ScanConfig,
     ) where
         F: FnMut(&Pubkey, (&T, Slot)),
     {
-        for pubkey in index.get(index_key) {
+        let mut pubkeys = index.get(index_key);
+        if let Some(start_after) = config.start_after {
+            pubkeys.retain(|pubkey| *pubkey > start_after);
+        }
+        if !config.collect_all_unsorted {
+            pubkeys.sort_unstable();
+        }
+        for pubkey in pubkeys {
             // Maybe these reads from the AccountsIndex can be batched every time it
             // grabs the read lock as well...
             if let AccountIndexGetResult::Found(list_r, index) =
@@ -1469,6 +1569,14 @@ impl<T: IndexValue> AccountsIndex<T> {
                     }
                 }
             }
+
+            if account_indexes.contains(&AccountIndex::SplTokenDelegate) {
+                if let Some(delegate_key) = G::unpack_account_delegate(account_data) {
+                    if account_indexes.include_key(delegate_key) {
+                        self.spl_token_delegate_index.insert(delegate_key, pubkey);
+                    }
+                }
+            }
         }
     }
 
@@ -1486,6 +1594,17 @@ impl<T: IndexValue> AccountsIndex<T> {
             info!("secondary index: {:?}", AccountIndex::SplTokenOwner);
             self.spl_token_owner_index.log_contents();
         }
+        if !self.spl_token_delegate_index.index.is_empty() {
+            info!("secondary index: {:?}", AccountIndex::SplTokenDelegate);
+            self.spl_token_delegate_index.log_contents();
+        }
+        for entry in self.data_offset_indexes.iter() {
+            info!(
+                "secondary index: {:?}",
+                AccountIndex::DataOffset(*entry.key())
+            );
+            entry.value().log_contents();
+        }
     }
 
     pub(crate) fn update_secondary_indexes(
@@ -1534,6 +1653,23 @@ impl<T: IndexValue> AccountsIndex<T> {
             account_data,
             account_indexes,
         );
+
+        for data_offset_index in account_indexes.data_offset_indexes() {
+            if data_offset_index.program_id != *account_owner {
+                continue;
+            }
+            if let Some(key) = data_offset_index.key(account_data) {
+                if !self.data_offset_indexes.contains_key(data_offset_index) {
+                    self.data_offset_indexes
+                        .entry(*data_offset_index)
+                        .or_insert_with(|| SecondaryIndex::new("data_offset_index_stats"));
+                }
+                self.data_offset_indexes
+                    .get(data_offset_index)
+                    .unwrap()
+                    .insert(&key.to_vec(), pubkey);
+            }
+        }
     }
 
     pub(crate) fn get_bin(&self, pubkey: &Pubkey) -> AccountMaps<T> {
@@ -1694,6 +1830,16 @@ impl<T: IndexValue> AccountsIndex<T> {
         if account_indexes.contains(&AccountIndex::SplTokenMint) {
             self.spl_token_mint_index.remove_by_inner_key(inner_key);
         }
+
+        if account_indexes.contains(&AccountIndex::SplTokenDelegate) {
+            self.spl_token_delegate_index.remove_by_inner_key(inner_key);
+        }
+
+        for data_offset_index in account_indexes.data_offset_indexes() {
+            if let Some(index) = self.data_offset_indexes.get(data_offset_index) {
+                index.remove_by_inner_key(inner_key);
+            }
+        }
     }
 
     fn purge_older_root_entries(
@@ -3763,6 +3909,132 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_spl_token_delegate_secondary_index() {
+        let secondary_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([AccountIndex::SplTokenDelegate]),
+        };
+        let index = AccountsIndex::<bool>::default_for_tests();
+        let delegate = Pubkey::new_unique();
+        let mut account_data = vec![0; inline_spl_token::Account::get_packed_len()];
+        account_data[SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET..SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET + 32]
+            .copy_from_slice(&delegate.to_bytes());
+
+        // The delegate is not set
+        let account_key = Pubkey::new_unique();
+        index.update_secondary_indexes(
+            &account_key,
+            &AccountSharedData::create(0, account_data.clone(), inline_spl_token::id(), false, 0),
+            &secondary_indexes,
+        );
+        assert!(index.spl_token_delegate_index.index.is_empty());
+
+        account_data[SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET..SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET]
+            .copy_from_slice(&1u32.to_le_bytes());
+        let mut account_data_with_extensions = account_data.clone();
+        account_data_with_extensions.push(inline_spl_token_2022::ACCOUNTTYPE_ACCOUNT);
+        account_data_with_extensions.extend([0; 4]);
+        let mut expected_keys = vec![];
+        for (token_id, account_data) in [
+            (inline_spl_token::id(), &account_data),
+            (inline_spl_token_2022::id(), &account_data),
+            (inline_spl_token_2022::id(), &account_data_with_extensions),
+        ] {
+            let account_key = Pubkey::new_unique();
+            index.update_secondary_indexes(
+                &account_key,
+                &AccountSharedData::create(0, account_data.clone(), token_id, false, 0),
+                &secondary_indexes,
+            );
+            expected_keys.push(account_key);
+        }
+        let mut found_keys = index.spl_token_delegate_index.get(&delegate);
+        found_keys.sort();
+        expected_keys.sort();
+        assert_eq!(found_keys, expected_keys);
+
+        // A token-2022 multisig is not mistaken for an account with extensions
+        let mut multisig_data = account_data_with_extensions;
+        multisig_data.resize(SPL_TOKEN_MULTISIG_LENGTH, 0);
+        index.update_secondary_indexes(
+            &Pubkey::new_unique(),
+            &AccountSharedData::create(0, multisig_data, inline_spl_token_2022::id(), false, 0),
+            &secondary_indexes,
+        );
+        assert_eq!(
+            index.spl_token_delegate_index.get(&delegate).len(),
+            expected_keys.len()
+        );
+    }
+
+    #[test]
+    fn test_data_offset_secondary_index() {
+        let program_id = Pubkey::new_unique();
+        let data_offset_index = DataOffsetIndex {
+            program_id,
+            offset: 4,
+            length: 2,
+        };
+        let secondary_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([AccountIndex::DataOffset(data_offset_index)]),
+        };
+        let index = AccountsIndex::<bool>::default_for_tests();
+        let account_key = Pubkey::new_unique();
+        let account_data = vec![0, 0, 0, 0, 7, 8, 9];
+
+        // Wrong program id
+        index.update_secondary_indexes(
+            &account_key,
+            &AccountSharedData::create(0, account_data.clone(), Pubkey::default(), false, 0),
+            &secondary_indexes,
+        );
+        assert!(index.data_offset_indexes.is_empty());
+
+        // Account data too short
+        index.update_secondary_indexes(
+            &account_key,
+            &AccountSharedData::create(0, account_data[..5].to_vec(), program_id, false, 0),
+            &secondary_indexes,
+        );
+        assert!(index.data_offset_indexes.is_empty());
+
+        let slot = 0;
+        index.upsert(
+            slot,
+            slot,
+            &account_key,
+            &AccountSharedData::create(0, account_data, program_id, false, 0),
+            &secondary_indexes,
+            true,
+            &mut vec![],
+            UPSERT_POPULATE_RECLAIMS,
+        );
+        let secondary_index = index.data_offset_indexes.get(&data_offset_index).unwrap();
+        assert_eq!(secondary_index.get(&vec![7, 8]), vec![account_key]);
+        drop(secondary_index);
+
+        let mut found_accounts = vec![];
+        index
+            .index_scan_accounts(
+                &Ancestors::from(vec![slot]),
+                0,
+                IndexKey::DataOffset(data_offset_index, vec![7, 8]),
+                |pubkey, _| found_accounts.push(*pubkey),
+                &ScanConfig::default(),
+            )
+            .unwrap();
+        assert_eq!(found_accounts, vec![account_key]);
+
+        // Everything should be deleted
+        index.slot_list_mut(&account_key, |slot_list| slot_list.clear());
+        let _ = index.handle_dead_keys(&[&account_key], &secondary_indexes);
+        let secondary_index = index.data_offset_indexes.get(&data_offset_index).unwrap();
+        assert!(secondary_index.index.is_empty());
+        assert!(secondary_index.reverse_index.is_empty());
+    }
+
     fn run_test_secondary_indexes_same_slot_and_forks<
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
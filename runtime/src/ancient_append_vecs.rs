@@ -94,8 +94,9 @@ pub fn get_ancient_append_vec_capacity() -> u64 {
 }
 
 /// is this a max-size append vec designed to be used as an ancient append vec?
+/// Cold append vecs are only ever created from ancient append vecs.
 pub fn is_ancient(storage: &AppendVec) -> bool {
-    storage.capacity() >= get_ancient_append_vec_capacity()
+    storage.is_cold() || storage.capacity() >= get_ancient_append_vec_capacity()
 }
 
 #[cfg(test)]
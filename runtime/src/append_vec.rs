@@ -5,6 +5,7 @@
 //! <https://docs.solana.com/implemented-proposals/persistent-account-storage>
 
 use {
+    crate::cold_storage::{ColdBlock, ColdBlockCache, ColdStorage},
     log::*,
     memmap2::MmapMut,
     serde::{Deserialize, Serialize},
@@ -23,7 +24,7 @@ use {
         path::{Path, PathBuf},
         sync::{
             atomic::{AtomicUsize, Ordering},
-            Mutex,
+            Arc, Mutex,
         },
     },
 };
@@ -169,6 +170,84 @@ impl<'a> Iterator for AppendVecAccountsIter<'a> {
     }
 }
 
+/// Bytes of an append vec beginning at offset `start`, read with the same offsets as the append
+/// vec itself: either the whole mmap, or one block of a cold storage.
+#[derive(Clone, Copy)]
+struct StoredAccountsBytes<'a> {
+    bytes: &'a [u8],
+    start: usize,
+}
+
+impl<'a> StoredAccountsBytes<'a> {
+    /// Get a reference to the data at `offset` of `size` bytes if that slice
+    /// doesn't overrun `bytes`. Otherwise return None.
+    /// Also return the offset of the first byte after the requested data that
+    /// falls on a 64-byte boundary.
+    fn get_slice(&self, offset: usize, size: usize) -> Option<(&'a [u8], usize)> {
+        let begin = offset.checked_sub(self.start)?;
+        let (end, overflow) = begin.overflowing_add(size);
+        if overflow || end > self.bytes.len() {
+            return None;
+        }
+        Some((&self.bytes[begin..end], u64_align!(offset + size)))
+    }
+
+    /// Return a reference to the type at `offset` if its data doesn't overrun `bytes`.
+    /// Otherwise return None. Also return the offset of the first byte after the requested data
+    /// that falls on a 64-byte boundary.
+    fn get_type<T>(&self, offset: usize) -> Option<(&'a T, usize)> {
+        let (data, next) = self.get_slice(offset, mem::size_of::<T>())?;
+        let ptr: *const T = data.as_ptr() as *const T;
+        //UNSAFE: The cast is safe because the slice is aligned and fits into the memory
+        //and the lifetime of the &T is tied to the memory `bytes` borrows
+        Some((unsafe { &*ptr }, next))
+    }
+
+    fn get_account(&self, offset: usize) -> Option<(StoredAccountMeta<'a>, usize)> {
+        let (meta, next): (&'a StoredMeta, _) = self.get_type(offset)?;
+        let (account_meta, next): (&'a AccountMeta, _) = self.get_type(next)?;
+        let (hash, next): (&'a Hash, _) = self.get_type(next)?;
+        let (data, next) = self.get_slice(next, meta.data_len as usize)?;
+        let stored_size = next - offset;
+        Some((
+            StoredAccountMeta {
+                meta,
+                account_meta,
+                data,
+                offset,
+                stored_size,
+                hash,
+            },
+            next,
+        ))
+    }
+}
+
+impl<'a> From<&'a ColdBlock> for StoredAccountsBytes<'a> {
+    fn from(block: &'a ColdBlock) -> Self {
+        Self {
+            bytes: block.bytes(),
+            start: block.offset(),
+        }
+    }
+}
+
+/// The memory backing an `AppendVec`
+#[derive(Debug)]
+enum AppendVecData {
+    /// A file-backed block of memory that is used to store the data for each appended item.
+    Mmap(MmapMut),
+    /// Read-only, compressed blocks that are decompressed when they are read.
+    Cold(ColdStorage),
+}
+
+#[cfg(RUSTC_WITH_SPECIALIZATION)]
+impl solana_frozen_abi::abi_example::AbiExample for AppendVecData {
+    fn example() -> Self {
+        Self::Mmap(<MmapMut as solana_frozen_abi::abi_example::AbiExample>::example())
+    }
+}
+
 /// A thread-safe, file-backed block of memory used to store `Account` instances. Append operations
 /// are serialized such that only one thread updates the internal `append_lock` at a time. No
 /// restrictions are placed on reading. That is, one may read items from one thread while another
@@ -178,8 +257,9 @@ pub struct AppendVec {
     /// The file path where the data is stored.
     path: PathBuf,
 
-    /// A file-backed block of memory that is used to store the data for each appended item.
-    map: MmapMut,
+    /// The block of memory that is used to store the data for each appended item.
+    /// Cold append vecs hold the same bytes compressed and cannot be appended to.
+    data: AppendVecData,
 
     /// A lock used to serialize append operations.
     append_lock: Mutex<()>,
@@ -254,7 +334,7 @@ impl AppendVec {
 
         AppendVec {
             path: file.to_path_buf(),
-            map,
+            data: AppendVecData::Mmap(map),
             // This mutex forces append to be single threaded, but concurrent with reads
             // See UNSAFE usage in `append_ptr`
             append_lock: Mutex::new(()),
@@ -293,10 +373,18 @@ impl AppendVec {
     }
 
     pub fn flush(&self) -> io::Result<()> {
-        self.map.flush()
+        match &self.data {
+            AppendVecData::Mmap(map) => map.flush(),
+            // cold append vecs are fully written when they are created
+            AppendVecData::Cold(_) => Ok(()),
+        }
     }
 
     pub fn reset(&self) {
+        if self.is_cold() {
+            // cold append vecs are read only
+            return;
+        }
         // This mutex forces append to be single threaded, but concurrent with reads
         // See UNSAFE usage in `append_ptr`
         let _lock = self.append_lock.lock().unwrap();
@@ -324,6 +412,56 @@ impl AppendVec {
         format!("{}.{}", slot, id)
     }
 
+    /// Compress the accounts in `source` into a new, read-only cold append vec at `path`.
+    /// Every account remains at the same offset, so existing index entries stay valid.
+    /// Blocks read one account at a time are kept in `cache`.
+    pub fn new_cold(
+        source: &AppendVec,
+        path: &Path,
+        block_size: usize,
+        cache: Arc<ColdBlockCache>,
+    ) -> io::Result<Self> {
+        let map = match &source.data {
+            AppendVecData::Mmap(map) => map,
+            AppendVecData::Cold(_) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("{} is already cold", source.path.display()),
+                ))
+            }
+        };
+        let len = source.len();
+        let cold = ColdStorage::create(
+            path,
+            &map[..len],
+            source.account_iter().map(|account| account.offset),
+            block_size,
+            cache,
+        )?;
+
+        Ok(AppendVec {
+            path: path.to_path_buf(),
+            data: AppendVecData::Cold(cold),
+            append_lock: Mutex::new(()),
+            current_len: AtomicUsize::new(len),
+            // no room to append
+            file_size: len as u64,
+            remove_on_drop: true,
+        })
+    }
+
+    /// true if this append vec is stored compressed in the cold tier
+    pub fn is_cold(&self) -> bool {
+        matches!(self.data, AppendVecData::Cold(_))
+    }
+
+    pub(crate) fn cold_storage(&self) -> Option<&ColdStorage> {
+        match &self.data {
+            AppendVecData::Mmap(_) => None,
+            AppendVecData::Cold(cold) => Some(cold),
+        }
+    }
+
     pub fn new_from_file<P: AsRef<Path>>(path: P, current_len: usize) -> io::Result<(Self, usize)> {
         let new = Self::new_from_file_unchecked(path, current_len)?;
 
@@ -363,7 +501,7 @@ impl AppendVec {
 
         Ok(AppendVec {
             path: path.as_ref().to_path_buf(),
-            map,
+            data: AppendVecData::Mmap(map),
             append_lock: Mutex::new(()),
             current_len: AtomicUsize::new(current_len),
             file_size,
@@ -392,31 +530,27 @@ impl AppendVec {
         (offset == aligned_current_len, num_accounts)
     }
 
-    /// Get a reference to the data at `offset` of `size` bytes if that slice
-    /// doesn't overrun the internal buffer. Otherwise return None.
-    /// Also return the offset of the first byte after the requested data that
-    /// falls on a 64-byte boundary.
-    fn get_slice(&self, offset: usize, size: usize) -> Option<(&[u8], usize)> {
-        let (next, overflow) = offset.overflowing_add(size);
-        if overflow || next > self.len() {
-            return None;
+    /// The bytes holding the account at `offset`. For a cold append vec, this is the block
+    /// holding `offset`, pinned for as long as this append vec lives.
+    fn stored_accounts_bytes(&self, offset: usize) -> Option<StoredAccountsBytes> {
+        match &self.data {
+            AppendVecData::Mmap(map) => Some(StoredAccountsBytes {
+                bytes: &map[..self.len()],
+                start: 0,
+            }),
+            AppendVecData::Cold(cold) => Some(cold.pinned_block(cold.block_index(offset)?).into()),
         }
-        let data = &self.map[offset..next];
-        let next = u64_align!(next);
-
-        Some((
-            //UNSAFE: This unsafe creates a slice that represents a chunk of self.map memory
-            //The lifetime of this slice is tied to &self, since it points to self.map memory
-            unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, size) },
-            next,
-        ))
     }
 
     /// Copy `len` bytes from `src` to the first 64-byte boundary after position `offset` of
     /// the internal buffer. Then update `offset` to the first byte after the copied data.
     fn append_ptr(&self, offset: &mut usize, src: *const u8, len: usize) {
         let pos = u64_align!(*offset);
-        let data = &self.map[pos..(pos + len)];
+        let map = match &self.data {
+            AppendVecData::Mmap(map) => map,
+            AppendVecData::Cold(_) => panic!("cannot append to cold append vec {:?}", self.path),
+        };
+        let data = &map[pos..(pos + len)];
         //UNSAFE: This mut append is safe because only 1 thread can append at a time
         //Mutex<()> guarantees exclusive write access to the memory occupied in
         //the range.
@@ -450,37 +584,45 @@ impl AppendVec {
         Some(pos)
     }
 
-    /// Return a reference to the type at `offset` if its data doesn't overrun the internal buffer.
-    /// Otherwise return None. Also return the offset of the first byte after the requested data
-    /// that falls on a 64-byte boundary.
-    fn get_type<'a, T>(&self, offset: usize) -> Option<(&'a T, usize)> {
-        let (data, next) = self.get_slice(offset, mem::size_of::<T>())?;
-        let ptr: *const T = data.as_ptr() as *const T;
-        //UNSAFE: The cast is safe because the slice is aligned and fits into the memory
-        //and the lifetime of the &T is tied to self, which holds the underlying memory map
-        Some((unsafe { &*ptr }, next))
-    }
-
     /// Return account metadata for the account at `offset` if its data doesn't overrun
     /// the internal buffer. Otherwise return None. Also return the offset of the first byte
     /// after the requested data that falls on a 64-byte boundary.
+    /// A cold append vec keeps the block it read for as long as it lives. Prefer
+    /// `get_account_with_block` for point loads and `scan_accounts` for scans.
     pub fn get_account<'a>(&'a self, offset: usize) -> Option<(StoredAccountMeta<'a>, usize)> {
-        let (meta, next): (&'a StoredMeta, _) = self.get_type(offset)?;
-        let (account_meta, next): (&'a AccountMeta, _) = self.get_type(next)?;
-        let (hash, next): (&'a Hash, _) = self.get_type(next)?;
-        let (data, next) = self.get_slice(next, meta.data_len as usize)?;
-        let stored_size = next - offset;
-        Some((
-            StoredAccountMeta {
-                meta,
-                account_meta,
-                data,
-                offset,
-                stored_size,
-                hash,
-            },
-            next,
-        ))
+        self.stored_accounts_bytes(offset)?.get_account(offset)
+    }
+
+    /// Like `get_account`, but a cold append vec reads the block holding the account through the
+    /// shared block cache and `block` keeps it alive, rather than this append vec.
+    pub fn get_account_with_block<'a>(
+        &'a self,
+        offset: usize,
+        block: &'a mut Option<Arc<ColdBlock>>,
+    ) -> Option<(StoredAccountMeta<'a>, usize)> {
+        match &self.data {
+            AppendVecData::Mmap(_) => self.get_account(offset),
+            AppendVecData::Cold(cold) => {
+                let block = block.insert(cold.block(cold.block_index(offset)?));
+                StoredAccountsBytes::from(&**block).get_account(offset)
+            }
+        }
+    }
+
+    /// Call `f` with each account in turn. A cold append vec is read one block at a time,
+    /// without caching the blocks, so each account can only be borrowed for the call.
+    pub fn scan_accounts(&self, mut f: impl FnMut(StoredAccountMeta)) {
+        match &self.data {
+            AppendVecData::Mmap(_) => self.account_iter().for_each(f),
+            AppendVecData::Cold(cold) => cold.for_each_block(|block| {
+                let bytes = StoredAccountsBytes::from(block);
+                let mut offset = block.offset();
+                while let Some((account, next)) = bytes.get_account(offset) {
+                    f(account);
+                    offset = next;
+                }
+            }),
+        }
     }
 
     #[cfg(test)]
@@ -747,6 +889,66 @@ pub mod tests {
         assert_eq!(av.get_account_test(index1).unwrap(), account1);
     }
 
+    #[test]
+    fn test_append_vec_new_cold() {
+        let path = get_append_vec_path("test_append_vec_new_cold");
+        let av = AppendVec::new(&path.path, true, 1024 * 1024);
+        let size = 1000;
+        let indexes = (0..size)
+            .map(|sample| {
+                av.append_account_test(&create_test_account(sample))
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let cold_path = get_append_vec_path("test_append_vec_new_cold.cold");
+        // small blocks so the accounts are spread across many of them
+        let cache = Arc::new(ColdBlockCache::new(u64::MAX));
+        let cold = AppendVec::new_cold(&av, &cold_path.path, 4096, Arc::clone(&cache)).unwrap();
+        assert!(cold.is_cold());
+        assert!(!av.is_cold());
+        assert_eq!(cold.len(), av.len());
+        assert_eq!(cold.capacity(), av.len() as u64);
+        assert_eq!(cold.remaining_bytes(), 0);
+        assert!(AppendVec::new_cold(&cold, &cold_path.path, 4096, Arc::clone(&cache)).is_err());
+
+        let mut offsets = vec![];
+        cold.scan_accounts(|account| {
+            assert_eq!(
+                (account.meta.clone(), account.clone_account()),
+                create_test_account(offsets.len())
+            );
+            offsets.push(account.offset);
+        });
+        assert_eq!(offsets, indexes);
+        // scans do not cache blocks
+        assert_eq!(cache.bytes(), 0);
+
+        for (sample, index) in indexes.iter().enumerate() {
+            let mut block = None;
+            let (account, _next) = cold.get_account_with_block(*index, &mut block).unwrap();
+            assert_eq!(
+                (account.meta.clone(), account.clone_account()),
+                create_test_account(sample)
+            );
+        }
+        assert_eq!(cache.bytes(), cold.len());
+
+        for (sample, index) in indexes.iter().enumerate() {
+            assert_eq!(
+                cold.get_account_test(*index).unwrap(),
+                create_test_account(sample)
+            );
+        }
+        assert_eq!(cold.accounts(0).len(), size);
+
+        // cold append vecs are read only
+        assert!(cold.append_account_test(&create_test_account(0)).is_none());
+        cold.reset();
+        assert_eq!(cold.len(), av.len());
+        cold.flush().unwrap();
+    }
+
     #[test]
     fn test_append_vec_append_many() {
         let path = get_append_vec_path("test_append_many");
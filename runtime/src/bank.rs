@@ -58,6 +58,7 @@ use {
         epoch_stakes::{EpochStakes, NodeVoteAccounts},
         inline_spl_associated_token_account, inline_spl_token,
         message_processor::MessageProcessor,
+        program_stats::{ProgramStats, ProgramStatsReport},
         rent_collector::{CollectedInfo, RentCollector},
         runtime_config::RuntimeConfig,
         snapshot_hash::SnapshotHash,
@@ -98,6 +99,7 @@ use {
         log_collector::LogCollector,
         sysvar_cache::SysvarCache,
         timings::{ExecuteTimingType, ExecuteTimings},
+        trace_collector::{ExecutionTrace, TraceCollector, TraceConfig},
     },
     solana_sdk::{
         account::{
@@ -380,6 +382,7 @@ pub struct TransactionExecutionDetails {
     /// The change in accounts data len for this transaction.
     /// NOTE: This value is valid IFF `status` is `Ok`.
     pub accounts_data_len_delta: i64,
+    pub execution_trace: Option<ExecutionTrace>,
 }
 
 /// Type safe representation of a transaction execution attempt which
@@ -476,6 +479,7 @@ pub struct TransactionSimulationResult {
     pub post_simulation_accounts: Vec<TransactionAccount>,
     pub units_consumed: u64,
     pub return_data: Option<TransactionReturnData>,
+    pub execution_trace: Option<ExecutionTrace>,
 }
 pub struct TransactionBalancesSet {
     pub pre_balances: TransactionBalances,
@@ -3788,9 +3792,102 @@ impl Bank {
         &self,
         transaction: SanitizedTransaction,
     ) -> TransactionSimulationResult {
-        let account_keys = transaction.message().account_keys();
-        let number_of_accounts = account_keys.len();
-        let account_overrides = self.get_account_overrides_for_simulation(&account_keys);
+        let mut account_overrides = AccountOverrides::default();
+        self.add_account_overrides_for_simulation(
+            &transaction.message().account_keys(),
+            &mut account_overrides,
+        );
+        self.simulate_transaction_with_overrides(transaction, &account_overrides, None)
+    }
+
+    /// Run a transaction against a frozen bank without committing the results, loading the
+    /// overridden accounts in place of their state in the bank
+    pub fn simulate_transaction_with_account_overrides(
+        &self,
+        transaction: SanitizedTransaction,
+        mut account_overrides: AccountOverrides,
+    ) -> TransactionSimulationResult {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        self.add_account_overrides_for_simulation(
+            &transaction.message().account_keys(),
+            &mut account_overrides,
+        );
+        self.simulate_transaction_with_overrides(transaction, &account_overrides, None)
+    }
+
+    /// Run a transaction against a frozen bank without committing the results, recording the
+    /// invoked instructions in an `ExecutionTrace`
+    pub fn simulate_transaction_with_trace(
+        &self,
+        transaction: SanitizedTransaction,
+        mut account_overrides: AccountOverrides,
+        trace_config: &TraceConfig,
+    ) -> TransactionSimulationResult {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        self.add_account_overrides_for_simulation(
+            &transaction.message().account_keys(),
+            &mut account_overrides,
+        );
+        self.simulate_transaction_with_overrides(
+            transaction,
+            &account_overrides,
+            Some(trace_config),
+        )
+    }
+
+    /// Run an ordered list of transactions against a frozen bank without committing the results,
+    /// each one seeing the account changes of the ones before it. Stops at the first transaction
+    /// that fails, which is the last one with a result
+    pub fn simulate_bundle(
+        &self,
+        transactions: Vec<SanitizedTransaction>,
+    ) -> Vec<TransactionSimulationResult> {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        let mut account_overrides = AccountOverrides::default();
+        let mut results = Vec::with_capacity(transactions.len());
+        for transaction in transactions {
+            let message = transaction.message();
+            let account_keys = message.account_keys();
+            self.add_account_overrides_for_simulation(&account_keys, &mut account_overrides);
+            let writable_keys: Vec<_> = account_keys
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| message.is_writable(*i))
+                .map(|(_, key)| *key)
+                .collect();
+
+            let result =
+                self.simulate_transaction_with_overrides(transaction, &account_overrides, None);
+            if result.result.is_err() {
+                results.push(result);
+                break;
+            }
+            for (pubkey, account) in &result.post_simulation_accounts {
+                if writable_keys.contains(pubkey) {
+                    // Like the accounts db, load closed accounts as default accounts
+                    let account = if account.lamports() == 0 {
+                        AccountSharedData::default()
+                    } else {
+                        account.clone()
+                    };
+                    account_overrides.set_account(pubkey, Some(account));
+                }
+            }
+            results.push(result);
+        }
+        results
+    }
+
+    fn simulate_transaction_with_overrides(
+        &self,
+        transaction: SanitizedTransaction,
+        account_overrides: &AccountOverrides,
+        trace_config: Option<&TraceConfig>,
+    ) -> TransactionSimulationResult {
+        let number_of_accounts = transaction.message().account_keys().len();
         let batch = self.prepare_simulation_batch(transaction);
         let mut timings = ExecuteTimings::default();
 
@@ -3808,8 +3905,9 @@ impl Bank {
             true,
             true,
             &mut timings,
-            Some(&account_overrides),
+            Some(account_overrides),
             None,
+            trace_config,
         );
 
         let post_simulation_accounts = loaded_transactions
@@ -3839,11 +3937,13 @@ impl Bank {
 
         let execution_result = execution_results.pop().unwrap();
         let flattened_result = execution_result.flattened_result();
-        let (logs, return_data) = match execution_result {
-            TransactionExecutionResult::Executed { details, .. } => {
-                (details.log_messages, details.return_data)
-            }
-            TransactionExecutionResult::NotExecuted(_) => (None, None),
+        let (logs, return_data, execution_trace) = match execution_result {
+            TransactionExecutionResult::Executed { details, .. } => (
+                details.log_messages,
+                details.return_data,
+                details.execution_trace,
+            ),
+            TransactionExecutionResult::NotExecuted(_) => (None, None, None),
         };
         let logs = logs.unwrap_or_default();
 
@@ -3853,13 +3953,19 @@ impl Bank {
             post_simulation_accounts,
             units_consumed,
             return_data,
+            execution_trace,
         }
     }
 
-    fn get_account_overrides_for_simulation(&self, account_keys: &AccountKeys) -> AccountOverrides {
-        let mut account_overrides = AccountOverrides::default();
+    fn add_account_overrides_for_simulation(
+        &self,
+        account_keys: &AccountKeys,
+        account_overrides: &mut AccountOverrides,
+    ) {
         let slot_history_id = sysvar::slot_history::id();
-        if account_keys.iter().any(|pubkey| *pubkey == slot_history_id) {
+        if account_keys.iter().any(|pubkey| *pubkey == slot_history_id)
+            && account_overrides.get(&slot_history_id).is_none()
+        {
             let current_account = self.get_account_with_fixed_root(&slot_history_id);
             let slot_history = current_account
                 .as_ref()
@@ -3874,7 +3980,6 @@ impl Bank {
                 }
             }
         }
-        account_overrides
     }
 
     pub fn unlock_accounts(&self, batch: &mut TransactionBatch) {
@@ -4136,6 +4241,7 @@ impl Bank {
             &program,
             programdata.as_ref().unwrap_or(&program),
             self.runtime_config.bpf_jit,
+            false, // enable_instruction_tracing
         )
         .map(|(executor, _create_executor_metrics)| executor)
         .map_err(|err| TransactionError::InstructionError(0, err))
@@ -4165,9 +4271,27 @@ impl Bank {
         timings: &mut ExecuteTimings,
         error_counters: &mut TransactionErrorMetrics,
         log_messages_bytes_limit: Option<usize>,
+        account_overrides: Option<&AccountOverrides>,
+        trace_config: Option<&TraceConfig>,
     ) -> TransactionExecutionResult {
         let mut get_tx_executor_cache_time = Measure::start("get_tx_executor_cache_time");
         let tx_executor_cache = self.get_tx_executor_cache(&loaded_transaction.accounts);
+        // Overridden programs are compiled from their overridden accounts, and kept out of the
+        // bank's cache
+        let overridden_programs = account_overrides
+            .map(|account_overrides| {
+                account_overrides.overridden_programs(&loaded_transaction.accounts)
+            })
+            .unwrap_or_default();
+        let remove_overridden_executors = || {
+            if !overridden_programs.is_empty() {
+                tx_executor_cache
+                    .borrow_mut()
+                    .executors
+                    .retain(|key, _| !overridden_programs.contains(key));
+            }
+        };
+        remove_overridden_executors();
         get_tx_executor_cache_time.stop();
         saturating_add_assign!(
             timings.execute_accessories.get_executors_us,
@@ -4217,6 +4341,8 @@ impl Bank {
             None
         };
 
+        let trace_collector = trace_config.cloned().map(TraceCollector::new_ref);
+
         let (blockhash, lamports_per_signature) = self.last_blockhash_and_lamports_per_signature();
 
         let mut executed_units = 0u64;
@@ -4229,6 +4355,7 @@ impl Bank {
             &mut transaction_context,
             self.rent_collector.rent,
             log_collector.clone(),
+            trace_collector.clone(),
             tx_executor_cache.clone(),
             self.feature_set.clone(),
             compute_budget,
@@ -4247,6 +4374,7 @@ impl Bank {
         );
 
         let mut store_missing_executors_time = Measure::start("store_missing_executors_time");
+        remove_overridden_executors();
         self.store_missing_executors(&tx_executor_cache);
         store_missing_executors_time.stop();
         saturating_add_assign!(
@@ -4292,6 +4420,12 @@ impl Bank {
                     .ok()
             });
 
+        let execution_trace = trace_collector.and_then(|trace_collector| {
+            Rc::try_unwrap(trace_collector)
+                .map(|trace_collector| trace_collector.into_inner().into())
+                .ok()
+        });
+
         let inner_instructions = if enable_cpi_recording {
             Some(inner_instructions_list_from_instruction_trace(
                 &transaction_context,
@@ -4340,6 +4474,7 @@ impl Bank {
                 return_data,
                 executed_units,
                 accounts_data_len_delta,
+                execution_trace,
             },
             tx_executor_cache,
         }
@@ -4356,6 +4491,7 @@ impl Bank {
         timings: &mut ExecuteTimings,
         account_overrides: Option<&AccountOverrides>,
         log_messages_bytes_limit: Option<usize>,
+        trace_config: Option<&TraceConfig>,
     ) -> LoadAndExecuteTransactionsOutput {
         let sanitized_txs = batch.sanitized_transactions();
         debug!("processing transactions: {}", sanitized_txs.len());
@@ -4473,6 +4609,8 @@ impl Bank {
                         timings,
                         &mut error_counters,
                         log_messages_bytes_limit,
+                        account_overrides,
+                        trace_config,
                     )
                 }
             })
@@ -6100,6 +6238,7 @@ impl Bank {
             timings,
             None,
             log_messages_bytes_limit,
+            None,
         );
 
         let (last_blockhash, lamports_per_signature) =
@@ -6550,6 +6689,44 @@ impl Bank {
         )
     }
 
+    /// Get, in key order, up to `limit` of the program accounts that pass `filter`, starting
+    /// after `config.start_after`
+    pub fn get_filtered_program_accounts_page<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        program_id: &Pubkey,
+        filter: F,
+        config: &ScanConfig,
+        limit: usize,
+    ) -> ScanResult<Vec<TransactionAccount>> {
+        self.rc.accounts.load_page_by_program_with_filter(
+            &self.ancestors,
+            self.bank_id,
+            program_id,
+            filter,
+            config,
+            limit,
+        )
+    }
+
+    /// Get, in key order, up to `limit` of the indexed accounts that pass `filter`, starting
+    /// after `config.start_after`
+    pub fn get_filtered_indexed_accounts_page<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        index_key: &IndexKey,
+        filter: F,
+        config: &ScanConfig,
+        limit: usize,
+    ) -> ScanResult<Vec<TransactionAccount>> {
+        self.rc.accounts.load_page_by_index_key_with_filter(
+            &self.ancestors,
+            self.bank_id,
+            index_key,
+            filter,
+            config,
+            limit,
+        )
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.rc.accounts.account_indexes_include_key(key)
     }
@@ -7716,6 +7893,39 @@ impl Bank {
         total_accounts_stats
     }
 
+    /// Scan all the accounts for this bank and calculate stats for each owning program,
+    /// including the write and read cache counts collected by the accounts db since the
+    /// previous call.
+    /// Only the lamports, data length and owner of each account are read, without copying the
+    /// account. The scan is unchecked, so this bank should be rooted, or otherwise not at risk of
+    /// being purged while it runs.
+    pub fn get_program_stats(&self) -> ProgramStatsReport {
+        let rent = &self.rent_collector().rent;
+        let mut programs = HashMap::<Pubkey, ProgramStats>::default();
+        self.rc.accounts.accounts_db.unchecked_scan_accounts(
+            "get_program_stats",
+            &self.ancestors,
+            |_pubkey, loaded_account, _slot| {
+                let lamports = loaded_account.lamports();
+                if lamports != 0 {
+                    programs
+                        .entry(*loaded_account.owner())
+                        .or_default()
+                        .accumulate_account(lamports, loaded_account.data().len(), rent);
+                }
+            },
+            &ScanConfig::default(),
+        );
+        let access_window = self
+            .rc
+            .accounts
+            .accounts_db
+            .program_stats
+            .take_access_counts(&mut programs);
+
+        ProgramStatsReport::new(self.slot(), access_window, programs)
+    }
+
     /// Get the EAH that will be used by snapshots
     ///
     /// Since snapshots are taken on roots, if the bank is in the EAH calculation window then an
@@ -8068,6 +8278,7 @@ pub(crate) mod tests {
                 return_data: None,
                 executed_units: 0,
                 accounts_data_len_delta: 0,
+                execution_trace: None,
             },
             tx_executor_cache: Rc::new(RefCell::new(TransactionExecutorCache::default())),
         }
@@ -10621,6 +10832,148 @@ pub(crate) mod tests {
         assert_eq!(bank.get_balance(&pubkey), amount);
     }
 
+    #[test]
+    fn test_simulate_bundle() {
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1.));
+        let bank = Bank::new_for_tests(&genesis_config);
+        bank.freeze();
+        let amount = genesis_config.rent.minimum_balance(0);
+        let keypair1 = Keypair::new();
+        let pubkey2 = solana_sdk::pubkey::new_rand();
+        let blockhash = genesis_config.hash();
+
+        // The second transfer spends lamports only the first one provides
+        let fund_tx = SanitizedTransaction::from_transaction_for_tests(
+            system_transaction::transfer(&mint_keypair, &keypair1.pubkey(), amount * 3, blockhash),
+        );
+        let spend_tx = SanitizedTransaction::from_transaction_for_tests(
+            system_transaction::transfer(&keypair1, &pubkey2, amount, blockhash),
+        );
+        let results = bank.simulate_bundle(vec![fund_tx.clone(), spend_tx.clone()]);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].result, Ok(()));
+        assert_eq!(results[1].result, Ok(()));
+        let post_balance = |result: &TransactionSimulationResult, pubkey: &Pubkey| {
+            result
+                .post_simulation_accounts
+                .iter()
+                .find(|(key, _)| key == pubkey)
+                .map(|(_, account)| account.lamports())
+        };
+        assert_eq!(
+            post_balance(&results[0], &keypair1.pubkey()),
+            Some(amount * 3)
+        );
+        assert_eq!(post_balance(&results[1], &pubkey2), Some(amount));
+
+        // Nothing is committed
+        assert_eq!(bank.get_balance(&keypair1.pubkey()), 0);
+        assert_eq!(bank.get_balance(&pubkey2), 0);
+        assert_eq!(bank.transaction_count(), 0);
+
+        // The bundle stops at the first failure
+        let results = bank.simulate_bundle(vec![spend_tx, fund_tx]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].result, Err(TransactionError::AccountNotFound));
+    }
+
+    #[test]
+    fn test_simulate_transaction_with_account_overrides() {
+        let (genesis_config, _mint_keypair) = create_genesis_config(sol_to_lamports(1.));
+        let bank = Bank::new_for_tests(&genesis_config);
+        bank.freeze();
+        let amount = genesis_config.rent.minimum_balance(0);
+        let keypair = Keypair::new();
+        let pubkey = solana_sdk::pubkey::new_rand();
+        let transaction = SanitizedTransaction::from_transaction_for_tests(
+            system_transaction::transfer(&keypair, &pubkey, amount, genesis_config.hash()),
+        );
+
+        let result = bank.simulate_transaction(transaction.clone());
+        assert_eq!(result.result, Err(TransactionError::AccountNotFound));
+
+        // The payer only exists in the overrides
+        let mut account_overrides = AccountOverrides::default();
+        account_overrides.set_account(
+            &keypair.pubkey(),
+            Some(AccountSharedData::new(amount * 3, 0, &system_program::id())),
+        );
+        let result =
+            bank.simulate_transaction_with_account_overrides(transaction, account_overrides);
+        assert_eq!(result.result, Ok(()));
+        assert!(result
+            .post_simulation_accounts
+            .iter()
+            .any(|(key, account)| key == &pubkey && account.lamports() == amount));
+        assert_eq!(bank.get_balance(&keypair.pubkey()), 0);
+        assert_eq!(bank.get_balance(&pubkey), 0);
+    }
+
+    #[test]
+    fn test_simulate_transaction_with_trace() {
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1.));
+        let bank = Bank::new_for_tests(&genesis_config);
+        bank.freeze();
+        let amount = genesis_config.rent.minimum_balance(0);
+        let pubkey = solana_sdk::pubkey::new_rand();
+        let transaction = SanitizedTransaction::from_transaction_for_tests(
+            system_transaction::transfer(&mint_keypair, &pubkey, amount, genesis_config.hash()),
+        );
+
+        assert_eq!(
+            bank.simulate_transaction(transaction.clone())
+                .execution_trace,
+            None
+        );
+
+        let result = bank.simulate_transaction_with_trace(
+            transaction.clone(),
+            AccountOverrides::default(),
+            &TraceConfig::default(),
+        );
+        assert_eq!(result.result, Ok(()));
+        let execution_trace = result.execution_trace.unwrap();
+        assert!(!execution_trace.truncated);
+        assert_eq!(execution_trace.frames.len(), 1);
+        let frame = &execution_trace.frames[0];
+        assert_eq!(frame.program_id, system_program::id());
+        assert_eq!(frame.stack_height, TRANSACTION_LEVEL_STACK_HEIGHT);
+        assert_eq!(frame.error, None);
+        assert!(frame.inner_frames.is_empty());
+        // The fee is collected before the instruction runs
+        let mint_balance = bank.get_balance(&mint_keypair.pubkey())
+            - bank.get_fee_for_message(transaction.message()).unwrap();
+        assert_eq!(
+            frame
+                .account_diffs
+                .iter()
+                .map(|account_diff| (
+                    account_diff.pubkey,
+                    account_diff.pre_lamports,
+                    account_diff.post_lamports
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (mint_keypair.pubkey(), mint_balance, mint_balance - amount),
+                (pubkey, 0, amount),
+            ]
+        );
+
+        // Frames beyond the limit are dropped
+        let result = bank.simulate_transaction_with_trace(
+            transaction,
+            AccountOverrides::default(),
+            &TraceConfig {
+                max_frames: Some(0),
+                ..TraceConfig::default()
+            },
+        );
+        assert_eq!(result.result, Ok(()));
+        let execution_trace = result.execution_trace.unwrap();
+        assert!(execution_trace.truncated);
+        assert!(execution_trace.frames.is_empty());
+    }
+
     #[test]
     fn test_transfer_to_sysvar() {
         solana_logger::setup();
@@ -19985,6 +20338,60 @@ pub(crate) mod tests {
         );
     }
 
+    #[test]
+    fn test_get_program_stats() {
+        let (genesis_config, _mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let program_id = Pubkey::new_unique();
+        let rent_exempt_lamports = bank.get_minimum_balance_for_rent_exemption(10);
+        for (lamports, data_len) in [(rent_exempt_lamports, 10), (rent_exempt_lamports + 1, 10)] {
+            bank.store_account(
+                &Pubkey::new_unique(),
+                &AccountSharedData::new(lamports, data_len, &program_id),
+            );
+        }
+        // zero lamport accounts are not counted
+        bank.store_account(
+            &Pubkey::new_unique(),
+            &AccountSharedData::new(0, 10, &program_id),
+        );
+
+        let report = bank.get_program_stats();
+        assert_eq!(report.slot, bank.slot());
+        let (_, stats) = report
+            .programs
+            .iter()
+            .find(|(owner, _stats)| *owner == program_id)
+            .unwrap();
+        assert_eq!(
+            *stats,
+            ProgramStats {
+                accounts: 2,
+                data_bytes: 20,
+                rent_exempt_lamports: 2 * rent_exempt_lamports + 1,
+                ..ProgramStats::default()
+            }
+        );
+
+        let total_accounts_stats = bank.get_total_accounts_stats().unwrap();
+        assert_eq!(
+            report
+                .programs
+                .iter()
+                .map(|(_owner, stats)| stats.accounts)
+                .sum::<u64>(),
+            total_accounts_stats.num_accounts as u64
+        );
+        assert_eq!(
+            report
+                .programs
+                .iter()
+                .map(|(_owner, stats)| stats.data_bytes)
+                .sum::<u64>(),
+            total_accounts_stats.data_len as u64
+        );
+    }
+
     #[test]
     fn test_accounts_data_size_from_genesis() {
         let GenesisConfigInfo {
@@ -2,10 +2,14 @@
 
 use {
     crate::{
-        accounts_background_service::{AbsRequestSender, SnapshotRequest, SnapshotRequestType},
+        accounts_background_service::{
+            AbsRequestSender, OnDemandSnapshotError, OnDemandSnapshotKind, SnapshotRequest,
+            SnapshotRequestType,
+        },
         bank::Bank,
         epoch_accounts_hash,
         snapshot_config::SnapshotConfig,
+        snapshot_utils,
     },
     log::*,
     solana_measure::measure::Measure,
@@ -600,6 +604,54 @@ impl BankForks {
         )
     }
 
+    /// Ask AccountsBackgroundService to snapshot the current root bank, outside of the
+    /// configured snapshot intervals.  Returns the slot of the snapshot that will be taken.
+    pub fn request_on_demand_snapshot(
+        &self,
+        kind: OnDemandSnapshotKind,
+        accounts_background_request_sender: &AbsRequestSender,
+    ) -> Result<Slot, OnDemandSnapshotError> {
+        let snapshot_config = self
+            .snapshot_config
+            .as_ref()
+            .filter(|snapshot_config| snapshot_config.should_generate_snapshots())
+            .ok_or(OnDemandSnapshotError::SnapshotsDisabled)?;
+        if !accounts_background_request_sender.is_snapshot_creation_enabled() {
+            return Err(OnDemandSnapshotError::SnapshotsDisabled);
+        }
+
+        let root_bank = self.root_bank();
+        let root = root_bank.slot();
+        if !root_bank.is_startup_verification_complete() {
+            return Err(OnDemandSnapshotError::StartupVerificationIncomplete);
+        }
+        // The root bank may already be on its way to AccountsBackgroundService from `set_root()`
+        if root <= self.last_accounts_hash_slot {
+            return Err(OnDemandSnapshotError::AlreadyRequested(root));
+        }
+        if kind == OnDemandSnapshotKind::Incremental {
+            let full_snapshot_slot = snapshot_utils::get_highest_full_snapshot_archive_slot(
+                &snapshot_config.full_snapshot_archives_dir,
+            );
+            if !matches!(full_snapshot_slot, Some(full_snapshot_slot) if full_snapshot_slot < root)
+            {
+                return Err(OnDemandSnapshotError::NoFullSnapshotArchive(root));
+            }
+        }
+
+        info!("sending on-demand {kind:?} snapshot request, slot: {root}");
+        let status_cache_slot_deltas = root_bank.status_cache.read().unwrap().root_slot_deltas();
+        accounts_background_request_sender
+            .send_snapshot_request(SnapshotRequest {
+                snapshot_root_bank: root_bank,
+                status_cache_slot_deltas,
+                request_type: SnapshotRequestType::OnDemandSnapshot(kind),
+                enqueued: Instant::now(),
+            })
+            .map_err(|_| OnDemandSnapshotError::ServiceStopped)?;
+        Ok(root)
+    }
+
     pub fn set_snapshot_config(&mut self, snapshot_config: Option<SnapshotConfig>) {
         self.snapshot_config = snapshot_config;
     }
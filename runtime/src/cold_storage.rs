@@ -0,0 +1,601 @@
+//! Compressed, block-indexed storage for cold (ancient) account data.
+//!
+//! A cold storage holds exactly the bytes an append vec would hold, at the same offsets, but
+//! split into zstd compressed blocks. Blocks always begin and end on account boundaries, so a
+//! stored account never spans two blocks.
+//!
+//! Decompressed blocks are handed out as ref-counted `ColdBlock` handles. Blocks read one account
+//! at a time are kept in a `ColdBlockCache` shared by every cold storage, which releases the least
+//! recently read blocks above its size limit. Readers still holding a handle keep that block alive.
+//! Scans read each block without caching it.
+//!
+//! File layout:
+//! [compressed block 0]..[compressed block n][bincode ColdStorageHeader][u64 header offset, le]
+use {
+    crate::append_vec::MAXIMUM_APPEND_VEC_FILE_SIZE,
+    lru::LruCache,
+    once_cell::sync::OnceCell,
+    serde::{Deserialize, Serialize},
+    std::{
+        fs::{remove_file, File, OpenOptions},
+        io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+        mem,
+        path::Path,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+    },
+};
+
+const COLD_STORAGE_VERSION: u32 = 1;
+
+/// compression level passed to zstd
+const COLD_STORAGE_COMPRESSION_LEVEL: i32 = 3;
+
+/// uncompressed bytes to accumulate before a block is cut at the next account boundary
+pub const DEFAULT_COLD_STORAGE_BLOCK_SIZE: usize = 64 * 1024;
+
+/// default limit on decompressed cold storage blocks held in memory across all storages
+pub const DEFAULT_COLD_STORAGE_CACHE_LIMIT_BYTES: u64 = 1024 * 1024 * 1024; // 1 GiB
+
+/// default limit on the uncompressed bytes of ancient append vecs compressed in one pass
+pub const DEFAULT_COLD_STORAGE_MAX_BYTES_PER_PASS: u64 = 1024 * 1024 * 1024; // 1 GiB
+
+/// upper bound on the size of the serialized header
+const MAX_COLD_STORAGE_HEADER_SIZE: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+struct ColdBlockInfo {
+    /// offset of the first uncompressed byte of this block
+    offset: u64,
+    /// number of uncompressed bytes in this block
+    len: u64,
+    /// where the compressed bytes start in the file
+    file_offset: u64,
+    /// number of compressed bytes in the file
+    compressed_len: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ColdStorageHeader {
+    version: u32,
+    /// total number of uncompressed bytes
+    len: u64,
+    blocks: Vec<ColdBlockInfo>,
+}
+
+/// how ancient append vecs are moved to and read from the cold tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColdStorageConfig {
+    /// uncompressed bytes per compressed block
+    pub block_size: usize,
+    /// decompressed blocks are released, least recently read first, above this many bytes
+    pub cache_limit_bytes: u64,
+    /// each pass stops compressing ancient append vecs once this many bytes were compressed.
+    /// The remaining ones are compressed in later passes.
+    pub max_bytes_per_pass: u64,
+}
+
+impl Default for ColdStorageConfig {
+    fn default() -> Self {
+        Self {
+            block_size: DEFAULT_COLD_STORAGE_BLOCK_SIZE,
+            cache_limit_bytes: DEFAULT_COLD_STORAGE_CACHE_LIMIT_BYTES,
+            max_bytes_per_pass: DEFAULT_COLD_STORAGE_MAX_BYTES_PER_PASS,
+        }
+    }
+}
+
+/// The decompressed contents of one block.
+/// Stored as u64s so that accounts read out of a block are 8 byte aligned, like an mmap.
+#[derive(Debug)]
+pub struct ColdBlock {
+    /// offset of the first byte of this block in the cold storage
+    offset: usize,
+    len: usize,
+    words: Box<[u64]>,
+}
+
+impl ColdBlock {
+    fn new(offset: usize, data: &[u8]) -> Self {
+        let num_words = (data.len() + mem::size_of::<u64>() - 1) / mem::size_of::<u64>();
+        let mut words = vec![0u64; num_words].into_boxed_slice();
+        //UNSAFE: a [u64] is valid to view as bytes and is at least data.len() bytes long
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), words.as_mut_ptr() as *mut u8, data.len());
+        }
+        Self {
+            offset,
+            len: data.len(),
+            words,
+        }
+    }
+
+    /// offset of the first byte of this block in the cold storage
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        //UNSAFE: the words were initialized from len bytes, and the lifetime of the slice is tied
+        //to &self, which owns them
+        unsafe { std::slice::from_raw_parts(self.words.as_ptr() as *const u8, self.len) }
+    }
+}
+
+/// identifies the cold storage a cached block belongs to
+type ColdStorageId = u64;
+
+static NEXT_COLD_STORAGE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Decompressed blocks of every cold storage, keyed by (storage, block index).
+/// The least recently read blocks are released above `limit_bytes`.
+#[derive(Debug)]
+pub struct ColdBlockCache {
+    limit_bytes: usize,
+    inner: Mutex<ColdBlockCacheInner>,
+}
+
+#[derive(Debug)]
+struct ColdBlockCacheInner {
+    blocks: LruCache<(ColdStorageId, usize), Arc<ColdBlock>>,
+    /// sum of the lengths of the blocks in `blocks`
+    bytes: usize,
+}
+
+impl ColdBlockCache {
+    pub fn new(limit_bytes: u64) -> Self {
+        Self {
+            limit_bytes: limit_bytes as usize,
+            inner: Mutex::new(ColdBlockCacheInner {
+                blocks: LruCache::unbounded(),
+                bytes: 0,
+            }),
+        }
+    }
+
+    /// number of decompressed bytes held by the cache
+    pub fn bytes(&self) -> usize {
+        self.inner.lock().unwrap().bytes
+    }
+
+    fn get(&self, key: (ColdStorageId, usize)) -> Option<Arc<ColdBlock>> {
+        self.inner.lock().unwrap().blocks.get(&key).cloned()
+    }
+
+    /// like `get`, but does not make the block more recently read
+    fn peek(&self, key: (ColdStorageId, usize)) -> Option<Arc<ColdBlock>> {
+        self.inner.lock().unwrap().blocks.peek(&key).cloned()
+    }
+
+    fn insert(&self, key: (ColdStorageId, usize), block: Arc<ColdBlock>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.bytes += block.len;
+        if let Some(previous) = inner.blocks.put(key, block) {
+            inner.bytes -= previous.len;
+        }
+        while inner.bytes > self.limit_bytes {
+            match inner.blocks.pop_lru() {
+                Some((_key, block)) => inner.bytes -= block.len,
+                None => break,
+            }
+        }
+    }
+
+    /// release the blocks of a cold storage which is being dropped
+    fn remove_storage(&self, storage: ColdStorageId, num_blocks: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        for index in 0..num_blocks {
+            if let Some(block) = inner.blocks.pop(&(storage, index)) {
+                inner.bytes -= block.len;
+            }
+        }
+    }
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[derive(Debug)]
+pub struct ColdStorage {
+    id: ColdStorageId,
+    file: Mutex<File>,
+    /// total number of uncompressed bytes
+    len: usize,
+    blocks: Vec<ColdBlockInfo>,
+    cache: Arc<ColdBlockCache>,
+    /// Blocks read through `pinned_block`, for readers which borrow accounts from the storage
+    /// itself rather than from a block handle. Those borrows may point into the blocks for as
+    /// long as the storage lives, so the blocks are only released when the storage is dropped.
+    /// Only rare whole-storage readers, like shrink, read cold storages this way.
+    pinned: Vec<OnceCell<Arc<ColdBlock>>>,
+}
+
+impl Drop for ColdStorage {
+    fn drop(&mut self) {
+        self.cache.remove_storage(self.id, self.blocks.len());
+    }
+}
+
+impl ColdStorage {
+    /// Compress `data` into a new cold storage file at `path`.
+    /// `account_offsets` are the ascending offsets where each stored account begins.
+    /// Blocks are only ever cut at these offsets.
+    pub fn create(
+        path: &Path,
+        data: &[u8],
+        account_offsets: impl IntoIterator<Item = usize>,
+        block_size: usize,
+        cache: Arc<ColdBlockCache>,
+    ) -> io::Result<Self> {
+        let _ignored = remove_file(path);
+        let result = Self::write(path, data, account_offsets, block_size).and_then(|_| {
+            let storage = Self::open(path, cache)?;
+            if storage.len != data.len() {
+                return Err(invalid_data(format!(
+                    "cold storage length mismatch: {} != {}",
+                    storage.len,
+                    data.len()
+                )));
+            }
+            Ok(storage)
+        });
+        if result.is_err() {
+            let _ignored = remove_file(path);
+        }
+        result
+    }
+
+    fn write(
+        path: &Path,
+        data: &[u8],
+        account_offsets: impl IntoIterator<Item = usize>,
+        block_size: usize,
+    ) -> io::Result<()> {
+        let mut block_ranges = vec![];
+        let mut start = 0;
+        for offset in account_offsets {
+            if offset > data.len() {
+                break;
+            }
+            if offset.saturating_sub(start) >= block_size {
+                block_ranges.push(start..offset);
+                start = offset;
+            }
+        }
+        if start < data.len() {
+            block_ranges.push(start..data.len());
+        }
+
+        let file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        let mut writer = BufWriter::new(file);
+        let mut blocks = Vec::with_capacity(block_ranges.len());
+        let mut file_offset = 0;
+        for range in block_ranges {
+            let compressed =
+                zstd::bulk::compress(&data[range.clone()], COLD_STORAGE_COMPRESSION_LEVEL)?;
+            writer.write_all(&compressed)?;
+            blocks.push(ColdBlockInfo {
+                offset: range.start as u64,
+                len: range.len() as u64,
+                file_offset,
+                compressed_len: compressed.len() as u64,
+            });
+            file_offset += compressed.len() as u64;
+        }
+
+        let header = ColdStorageHeader {
+            version: COLD_STORAGE_VERSION,
+            len: data.len() as u64,
+            blocks,
+        };
+        bincode::serialize_into(&mut writer, &header)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        writer.write_all(&file_offset.to_le_bytes())?;
+        writer.into_inner()?.sync_all()
+    }
+
+    /// Open an existing cold storage file, reading and validating its block index.
+    pub fn open(path: &Path, cache: Arc<ColdBlockCache>) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let footer_len = mem::size_of::<u64>() as u64;
+        if file_len < footer_len {
+            return Err(invalid_data(format!(
+                "cold storage too small: {}",
+                path.display()
+            )));
+        }
+        file.seek(SeekFrom::Start(file_len - footer_len))?;
+        let mut footer = [0u8; mem::size_of::<u64>()];
+        file.read_exact(&mut footer)?;
+        let header_offset = u64::from_le_bytes(footer);
+        let header_len = (file_len - footer_len)
+            .checked_sub(header_offset)
+            .filter(|len| *len <= MAX_COLD_STORAGE_HEADER_SIZE)
+            .ok_or_else(|| {
+                invalid_data(format!(
+                    "bad cold storage header offset {}: {}",
+                    header_offset,
+                    path.display()
+                ))
+            })?;
+        file.seek(SeekFrom::Start(header_offset))?;
+        let mut header = vec![0u8; header_len as usize];
+        file.read_exact(&mut header)?;
+        let header: ColdStorageHeader = bincode::deserialize(&header)
+            .map_err(|err| invalid_data(format!("{}: {}", err, path.display())))?;
+
+        if header.version != COLD_STORAGE_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported cold storage version {}: {}",
+                header.version,
+                path.display()
+            )));
+        }
+        let mut expected_offset = 0;
+        for block in &header.blocks {
+            let in_file = block
+                .file_offset
+                .checked_add(block.compressed_len)
+                .map(|end| end <= header_offset)
+                .unwrap_or_default();
+            if block.offset != expected_offset || block.len == 0 || !in_file {
+                return Err(invalid_data(format!(
+                    "corrupt cold storage block index: {}",
+                    path.display()
+                )));
+            }
+            expected_offset = expected_offset.saturating_add(block.len);
+        }
+        if expected_offset != header.len || header.len > MAXIMUM_APPEND_VEC_FILE_SIZE {
+            return Err(invalid_data(format!(
+                "cold storage blocks do not cover {} bytes: {}",
+                header.len,
+                path.display()
+            )));
+        }
+
+        Ok(Self {
+            id: NEXT_COLD_STORAGE_ID.fetch_add(1, Ordering::Relaxed),
+            file: Mutex::new(file),
+            len: header.len as usize,
+            pinned: (0..header.blocks.len())
+                .map(|_| OnceCell::default())
+                .collect(),
+            blocks: header.blocks,
+            cache,
+        })
+    }
+
+    /// number of uncompressed bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Index of the block holding the byte at `offset`
+    pub fn block_index(&self, offset: usize) -> Option<usize> {
+        if offset >= self.len {
+            return None;
+        }
+        self.blocks
+            .partition_point(|block| block.offset as usize <= offset)
+            .checked_sub(1)
+    }
+
+    /// Get block `index` through the shared block cache, decompressing it if it is not cached.
+    /// Panics if the block cannot be read from disk, in the same way a failed mmap read would fault.
+    pub fn block(&self, index: usize) -> Arc<ColdBlock> {
+        let key = (self.id, index);
+        if let Some(block) = self.cache.get(key) {
+            return block;
+        }
+        let block = Arc::new(self.read_block_or_panic(index));
+        self.cache.insert(key, Arc::clone(&block));
+        block
+    }
+
+    /// Get block `index`, keeping it for as long as this storage lives. See `pinned`.
+    pub fn pinned_block(&self, index: usize) -> &ColdBlock {
+        self.pinned[index].get_or_init(|| self.block(index))
+    }
+
+    /// Call `f` with each block in turn. Blocks which are not cached are decompressed without
+    /// caching them, so scanning a storage does not evict the blocks other readers use.
+    /// Panics if a block cannot be read from disk, in the same way a failed mmap read would fault.
+    pub fn for_each_block(&self, mut f: impl FnMut(&ColdBlock)) {
+        for index in 0..self.blocks.len() {
+            match self.cache.peek((self.id, index)) {
+                Some(block) => f(&block),
+                None => f(&self.read_block_or_panic(index)),
+            }
+        }
+    }
+
+    fn read_block_or_panic(&self, index: usize) -> ColdBlock {
+        self.read_block(index)
+            .unwrap_or_else(|err| panic!("failed to read cold storage block {}: {}", index, err))
+    }
+
+    /// read and decompress block `index` from disk, bypassing the cache
+    fn read_block(&self, index: usize) -> io::Result<ColdBlock> {
+        let block = &self.blocks[index];
+        let mut compressed = vec![0u8; block.compressed_len as usize];
+        {
+            let mut file = self.file.lock().unwrap();
+            file.seek(SeekFrom::Start(block.file_offset))?;
+            file.read_exact(&mut compressed)?;
+        }
+        let data = zstd::bulk::decompress(&compressed, block.len as usize)?;
+        if data.len() != block.len as usize {
+            return Err(invalid_data(format!(
+                "cold storage block {} decompressed to {} bytes, expected {}",
+                index,
+                data.len(),
+                block.len
+            )));
+        }
+        Ok(ColdBlock::new(block.offset as usize, &data))
+    }
+
+    /// Write the uncompressed contents to `writer`, one block at a time, without caching them.
+    pub fn write_uncompressed(&self, writer: &mut impl Write) -> io::Result<()> {
+        for index in 0..self.blocks.len() {
+            match self.cache.peek((self.id, index)) {
+                Some(block) => writer.write_all(block.bytes())?,
+                None => writer.write_all(self.read_block(index)?.bytes())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use {super::*, tempfile::TempDir};
+
+    fn sample_data(accounts: usize, account_len: usize) -> (Vec<u8>, Vec<usize>) {
+        let mut data = vec![];
+        let mut offsets = vec![];
+        for i in 0..accounts {
+            offsets.push(data.len());
+            data.extend((0..account_len).map(|j| ((i * 7 + j) % 251) as u8));
+        }
+        (data, offsets)
+    }
+
+    fn new_cache(limit_bytes: u64) -> Arc<ColdBlockCache> {
+        Arc::new(ColdBlockCache::new(limit_bytes))
+    }
+
+    #[test]
+    fn test_cold_storage_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("0.0");
+        let account_len = 1000;
+        let (data, offsets) = sample_data(100, account_len);
+        let cache = new_cache(u64::MAX);
+        let storage = ColdStorage::create(
+            &path,
+            &data,
+            offsets.iter().cloned(),
+            4096,
+            Arc::clone(&cache),
+        )
+        .unwrap();
+        assert_eq!(storage.len(), data.len());
+        // blocks are cut at the first account boundary at or past 4096 bytes
+        assert_eq!(storage.blocks.len(), 20);
+        assert_eq!(cache.bytes(), 0);
+
+        for offset in &offsets {
+            let block = storage.block(storage.block_index(*offset).unwrap());
+            let start = offset - block.offset();
+            assert_eq!(
+                &block.bytes()[start..start + account_len],
+                &data[*offset..*offset + account_len]
+            );
+            assert_eq!(block.bytes().as_ptr() as usize % mem::size_of::<u64>(), 0);
+        }
+        assert_eq!(cache.bytes(), data.len());
+
+        assert_eq!(storage.block_index(0), Some(0));
+        assert_eq!(storage.block_index(5000 - 1), Some(0));
+        assert_eq!(storage.block_index(5000), Some(1));
+        assert_eq!(storage.block_index(data.len() - 1), Some(19));
+        assert_eq!(storage.block_index(data.len()), None);
+
+        let reopened = ColdStorage::open(&path, Arc::clone(&cache)).unwrap();
+        assert_eq!(reopened.blocks, storage.blocks);
+        drop(storage);
+        assert_eq!(cache.bytes(), 0);
+
+        let mut uncompressed = vec![];
+        reopened.write_uncompressed(&mut uncompressed).unwrap();
+        assert_eq!(uncompressed, data);
+        let mut scanned = vec![];
+        reopened.for_each_block(|block| {
+            assert_eq!(block.offset(), scanned.len());
+            scanned.extend_from_slice(block.bytes());
+        });
+        assert_eq!(scanned, data);
+        // neither path caches blocks
+        assert_eq!(cache.bytes(), 0);
+    }
+
+    #[test]
+    fn test_cold_block_cache_limit() {
+        let dir = TempDir::new().unwrap();
+        let (data, offsets) = sample_data(10, 100);
+        let cache = new_cache(500);
+        let storage = ColdStorage::create(
+            &dir.path().join("0.0"),
+            &data,
+            offsets.iter().cloned(),
+            200,
+            Arc::clone(&cache),
+        )
+        .unwrap();
+        let other = ColdStorage::create(
+            &dir.path().join("1.1"),
+            &data,
+            offsets.iter().cloned(),
+            200,
+            Arc::clone(&cache),
+        )
+        .unwrap();
+        assert_eq!(storage.blocks.len(), 5);
+
+        let held = storage.block(0);
+        storage.block(1);
+        other.block(0);
+        assert_eq!(cache.bytes(), 600 - 200);
+        // block 0 of `storage` was the least recently read, so it was released from the cache,
+        // but the handle still holds it
+        assert_eq!(held.bytes(), &data[..200]);
+        assert!(cache.peek((storage.id, 0)).is_none());
+        assert!(cache.peek((storage.id, 1)).is_some());
+        assert!(cache.peek((other.id, 0)).is_some());
+
+        // reading block 1 makes it the most recently read, so block 0 of `other` goes next
+        storage.block(1);
+        storage.block(2);
+        assert!(cache.peek((other.id, 0)).is_none());
+        assert_eq!(cache.bytes(), 400);
+
+        // pinned blocks outlive the cache entry
+        let pinned = storage.pinned_block(4);
+        other.block(1);
+        other.block(2);
+        assert!(cache.peek((storage.id, 4)).is_none());
+        assert_eq!(pinned.bytes(), &data[800..]);
+
+        drop(other);
+        assert_eq!(cache.bytes(), 0);
+    }
+
+    #[test]
+    fn test_cold_storage_open_corrupt() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("0.0");
+        std::fs::write(&path, [1u8; 4]).unwrap();
+        assert!(ColdStorage::open(&path, new_cache(0)).is_err());
+        std::fs::write(&path, u64::MAX.to_le_bytes()).unwrap();
+        assert!(ColdStorage::open(&path, new_cache(0)).is_err());
+
+        let (data, offsets) = sample_data(10, 100);
+        drop(ColdStorage::create(&path, &data, offsets, 200, new_cache(0)).unwrap());
+        let mut bytes = std::fs::read(&path).unwrap();
+        let len = bytes.len();
+        // point the footer past the header
+        bytes[len - 8..].copy_from_slice(&(len as u64).to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+        assert!(ColdStorage::open(&path, new_cache(0)).is_err());
+    }
+}
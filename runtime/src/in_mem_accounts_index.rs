@@ -743,6 +743,27 @@ impl<T: IndexValue> InMemAccountsIndex<T> {
         }
     }
 
+    /// Insert an entry loaded from a persisted accounts index, keeping its ref count.
+    /// 'pubkey' must not already be in the index.
+    pub fn insert_persisted_entry(
+        &self,
+        pubkey: Pubkey,
+        slot_list: SlotList<T>,
+        ref_count: RefCount,
+    ) {
+        assert!(!slot_list.is_empty());
+        let new_entry = Arc::new(AccountMapEntryInner::new(
+            slot_list,
+            ref_count,
+            AccountMapEntryMeta::new_dirty(&self.storage, false),
+        ));
+        let previous = self.map_internal.write().unwrap().insert(pubkey, new_entry);
+        assert!(previous.is_none(), "{} is already in the index", pubkey);
+        let stats = self.stats();
+        stats.inc_mem_count(self.bin);
+        stats.inc_insert();
+    }
+
     /// Look at the currently held ranges. If 'range' is already included in what is
     ///  being held, then add 'range' to the currently held list AND return true
     /// If 'range' is NOT already included in what is being held, then return false
@@ -21,7 +21,11 @@ pub(crate) mod program_v3_4_0 {
 */
 pub const SPL_TOKEN_ACCOUNT_MINT_OFFSET: usize = 0;
 pub const SPL_TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+/// Offset of the `COption` tag of the delegate, which is 1 when a delegate is set
+pub const SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET: usize = 72;
+pub const SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET: usize = 76;
 const SPL_TOKEN_ACCOUNT_LENGTH: usize = 165;
+pub(crate) const SPL_TOKEN_MULTISIG_LENGTH: usize = 355;
 
 pub(crate) trait GenericTokenAccount {
     fn valid_account_data(account_data: &[u8]) -> bool;
@@ -56,6 +60,21 @@ pub(crate) trait GenericTokenAccount {
             None
         }
     }
+
+    fn unpack_account_delegate(account_data: &[u8]) -> Option<&Pubkey> {
+        if Self::valid_account_data(account_data)
+            && account_data
+                [SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET..SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET]
+                == 1u32.to_le_bytes()
+        {
+            Some(Self::unpack_pubkey_unchecked(
+                account_data,
+                SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET,
+            ))
+        } else {
+            None
+        }
+    }
 }
 
 pub struct Account;
@@ -9,10 +9,12 @@ pub const ACCOUNTTYPE_ACCOUNT: u8 = 2;
 pub struct Account;
 impl GenericTokenAccount for Account {
     fn valid_account_data(account_data: &[u8]) -> bool {
+        // A multisig is longer than an account, so its data could pass for the account type
         inline_spl_token::Account::valid_account_data(account_data)
-            || ACCOUNTTYPE_ACCOUNT
-                == *account_data
-                    .get(inline_spl_token::Account::get_packed_len())
-                    .unwrap_or(&0)
+            || (account_data.len() != inline_spl_token::SPL_TOKEN_MULTISIG_LENGTH
+                && ACCOUNTTYPE_ACCOUNT
+                    == *account_data
+                        .get(inline_spl_token::Account::get_packed_len())
+                        .unwrap_or(&0))
     }
 }
@@ -30,6 +30,7 @@ pub mod bucket_map_holder_stats;
 pub mod builtins;
 pub mod cache_hash_data;
 pub mod cache_hash_data_stats;
+pub mod cold_storage;
 pub mod commitment;
 pub mod contains;
 pub mod cost_model;
@@ -47,8 +48,10 @@ pub mod loader_utils;
 pub mod message_processor;
 pub mod non_circulating_supply;
 mod nonce_keyed_account;
+mod persisted_accounts_index;
 pub mod prioritization_fee;
 pub mod prioritization_fee_cache;
+pub mod program_stats;
 mod pubkey_bins;
 mod read_only_accounts_cache;
 pub mod rent_collector;
@@ -8,11 +8,13 @@ use {
         log_collector::LogCollector,
         sysvar_cache::SysvarCache,
         timings::{ExecuteDetailsTimings, ExecuteTimings},
+        trace_collector::TraceCollector,
     },
     solana_sdk::{
         account::WritableAccount,
         feature_set::FeatureSet,
         hash::Hash,
+        instruction::TRANSACTION_LEVEL_STACK_HEIGHT,
         message::SanitizedMessage,
         precompiles::is_precompile,
         rent::Rent,
@@ -57,6 +59,7 @@ impl MessageProcessor {
         transaction_context: &mut TransactionContext,
         rent: Rent,
         log_collector: Option<Rc<RefCell<LogCollector>>>,
+        trace_collector: Option<Rc<RefCell<TraceCollector>>>,
         tx_executor_cache: Rc<RefCell<TransactionExecutorCache>>,
         feature_set: Arc<FeatureSet>,
         compute_budget: ComputeBudget,
@@ -73,6 +76,7 @@ impl MessageProcessor {
             builtin_programs,
             Cow::Borrowed(sysvar_cache),
             log_collector,
+            trace_collector,
             compute_budget,
             tx_executor_cache,
             feature_set,
@@ -130,7 +134,7 @@ impl MessageProcessor {
             }
 
             let result = if is_precompile {
-                invoke_context
+                let result = invoke_context
                     .transaction_context
                     .get_next_instruction_context()
                     .map(|instruction_context| {
@@ -143,7 +147,18 @@ impl MessageProcessor {
                     .and_then(|_| {
                         invoke_context.transaction_context.push()?;
                         invoke_context.transaction_context.pop()
-                    })
+                    });
+                // Precompiles are verified before execution, so their frames are empty
+                if let Some(trace_collector) = invoke_context.get_trace_collector() {
+                    let mut trace_collector = trace_collector.borrow_mut();
+                    trace_collector.begin_frame(
+                        *program_id,
+                        TRANSACTION_LEVEL_STACK_HEIGHT,
+                        Vec::new,
+                    );
+                    trace_collector.end_frame(0, &result, Vec::new);
+                }
+                result
             } else {
                 let mut time = Measure::start("execute_instruction");
                 let mut compute_units_consumed = 0;
@@ -311,6 +326,7 @@ mod tests {
             &mut transaction_context,
             rent_collector.rent,
             None,
+            None,
             tx_executor_cache.clone(),
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -361,6 +377,7 @@ mod tests {
             &mut transaction_context,
             rent_collector.rent,
             None,
+            None,
             tx_executor_cache.clone(),
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -401,6 +418,7 @@ mod tests {
             &mut transaction_context,
             rent_collector.rent,
             None,
+            None,
             tx_executor_cache,
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -538,6 +556,7 @@ mod tests {
             &mut transaction_context,
             rent_collector.rent,
             None,
+            None,
             tx_executor_cache.clone(),
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -572,6 +591,7 @@ mod tests {
             &mut transaction_context,
             rent_collector.rent,
             None,
+            None,
             tx_executor_cache.clone(),
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -603,6 +623,7 @@ mod tests {
             &mut transaction_context,
             rent_collector.rent,
             None,
+            None,
             tx_executor_cache,
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -683,6 +704,7 @@ mod tests {
             &mut transaction_context,
             RentCollector::default().rent,
             None,
+            None,
             Rc::new(RefCell::new(TransactionExecutorCache::default())),
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -0,0 +1,263 @@
+//! The accounts index, as written out when the validator shuts down cleanly.
+//!
+//! Generating the index at startup means scanning every append vec. When the storages present at
+//! startup are the same ones the index was saved against, the saved index is loaded instead.
+//! Storages are matched by slot and contents rather than by id, since append vec ids are
+//! reassigned when storages are loaded.
+//!
+//! The file holds a `PersistedAccountsIndexHeader`, the entries of each index bin in turn, and a
+//! `PersistedAccountsIndexFooter`. Entries are written and read one bin at a time, so the whole
+//! index is never held in memory.
+use {
+    crate::{
+        accounts_db::{AccountStorageEntry, AppendVecId},
+        append_vec::StoredMetaWriteVersion,
+    },
+    bincode::{deserialize_from, serialize_into},
+    serde_derive::{Deserialize, Serialize},
+    solana_sdk::{clock::Slot, pubkey::Pubkey},
+    std::{
+        fs::{self, File},
+        io::{self, BufReader, BufWriter, Seek, SeekFrom, Write},
+        path::{Path, PathBuf},
+    },
+};
+
+/// Bumped whenever the layout of the persisted accounts index changes
+const PERSISTED_ACCOUNTS_INDEX_VERSION: u32 = 2;
+
+/// Identifies the contents of a storage without scanning it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct StorageFingerprint {
+    pub(crate) slot: Slot,
+    /// Number of bytes written to the append vec
+    pub(crate) len: usize,
+    /// Pubkey and write version of the account stored at offset 0
+    pub(crate) first_account: Option<(Pubkey, StoredMetaWriteVersion)>,
+}
+
+impl StorageFingerprint {
+    pub(crate) fn new(store: &AccountStorageEntry) -> Self {
+        Self {
+            slot: store.slot(),
+            len: store.accounts.len(),
+            first_account: store
+                .accounts
+                .get_account(0)
+                .map(|(account, _next)| (account.meta.pubkey, account.meta.write_version)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PersistedStorage {
+    /// The id the storage had when the index was saved
+    pub(crate) id: AppendVecId,
+    pub(crate) fingerprint: StorageFingerprint,
+    pub(crate) count: usize,
+    pub(crate) alive_bytes: usize,
+}
+
+/// One item of an index entry's slot list
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PersistedAccountInfo {
+    pub(crate) slot: Slot,
+    /// The id of the storage, as found in `PersistedStorage::id`
+    pub(crate) store_id: AppendVecId,
+    pub(crate) offset: usize,
+    pub(crate) stored_size: u32,
+    pub(crate) is_zero_lamport: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PersistedEntry {
+    pub(crate) pubkey: Pubkey,
+    pub(crate) ref_count: u64,
+    pub(crate) slot_list: Vec<PersistedAccountInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct PersistedAccountsIndexHeader {
+    pub(crate) storages: Vec<PersistedStorage>,
+    /// Number of bins of entries which follow the header
+    pub(crate) bins: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub(crate) struct PersistedAccountsIndexFooter {
+    pub(crate) uncleaned_roots: Vec<Slot>,
+    pub(crate) uncleaned_pubkeys: Vec<(Slot, Vec<Pubkey>)>,
+    pub(crate) accounts_data_len: u64,
+    pub(crate) rent_paying_accounts: Vec<Pubkey>,
+}
+
+/// Writes an index to a temporary file, which is moved to its path once it is complete
+pub(crate) struct PersistedAccountsIndexWriter {
+    path: PathBuf,
+    temp_path: PathBuf,
+    writer: BufWriter<File>,
+    bins_left: usize,
+}
+
+impl PersistedAccountsIndexWriter {
+    pub(crate) fn new(path: &Path, header: &PersistedAccountsIndexHeader) -> io::Result<Self> {
+        let temp_path = path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&temp_path)?);
+        serialize_into(&mut writer, &PERSISTED_ACCOUNTS_INDEX_VERSION).map_err(to_io_error)?;
+        serialize_into(&mut writer, header).map_err(to_io_error)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            temp_path,
+            writer,
+            bins_left: header.bins,
+        })
+    }
+
+    /// Writes the entries of the next bin
+    pub(crate) fn write_bin(&mut self, entries: &[PersistedEntry]) -> io::Result<()> {
+        assert!(self.bins_left > 0, "more bins than in the header");
+        self.bins_left -= 1;
+        serialize_into(&mut self.writer, entries).map_err(to_io_error)
+    }
+
+    /// Writes the footer and moves the file to its path. The file only appears once it is complete.
+    pub(crate) fn finish(mut self, footer: &PersistedAccountsIndexFooter) -> io::Result<()> {
+        assert_eq!(self.bins_left, 0, "fewer bins than in the header");
+        serialize_into(&mut self.writer, footer).map_err(to_io_error)?;
+        self.writer.flush()?;
+        self.writer.get_ref().sync_all()?;
+        fs::rename(&self.temp_path, &self.path)
+    }
+}
+
+/// Reads an index written by `PersistedAccountsIndexWriter`
+pub(crate) struct PersistedAccountsIndexReader {
+    reader: BufReader<File>,
+    header: PersistedAccountsIndexHeader,
+    /// Position of the first bin in the file
+    bins_start: u64,
+}
+
+impl PersistedAccountsIndexReader {
+    /// Opens and removes the index at `path`, so a saved index is never used for more than one
+    /// start. Returns `Ok(None)` if there is no saved index.
+    pub(crate) fn take(path: &Path) -> io::Result<Option<Self>> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        // the open file can still be read
+        fs::remove_file(path)?;
+        let mut reader = BufReader::new(file);
+        let version: u32 = deserialize_from(&mut reader).map_err(to_io_error)?;
+        if version != PERSISTED_ACCOUNTS_INDEX_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported persisted accounts index version {}", version),
+            ));
+        }
+        let header = deserialize_from(&mut reader).map_err(to_io_error)?;
+        let bins_start = reader.stream_position()?;
+        Ok(Some(Self {
+            reader,
+            header,
+            bins_start,
+        }))
+    }
+
+    pub(crate) fn header(&self) -> &PersistedAccountsIndexHeader {
+        &self.header
+    }
+
+    /// Reads the entries of the next bin. Only `header().bins` bins may be read before the footer.
+    pub(crate) fn read_bin(&mut self) -> io::Result<Vec<PersistedEntry>> {
+        deserialize_from(&mut self.reader).map_err(to_io_error)
+    }
+
+    /// Reads the footer, once every bin has been read
+    pub(crate) fn read_footer(&mut self) -> io::Result<PersistedAccountsIndexFooter> {
+        deserialize_from(&mut self.reader).map_err(to_io_error)
+    }
+
+    /// Goes back to the first bin, to read the entries again
+    pub(crate) fn rewind(&mut self) -> io::Result<()> {
+        self.reader.seek(SeekFrom::Start(self.bins_start))?;
+        Ok(())
+    }
+}
+
+fn to_io_error(err: bincode::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    #[test]
+    fn test_write_and_take() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("accounts_index");
+        assert!(PersistedAccountsIndexReader::take(&path).unwrap().is_none());
+
+        let header = PersistedAccountsIndexHeader {
+            storages: vec![PersistedStorage {
+                id: 3,
+                fingerprint: StorageFingerprint {
+                    slot: 1,
+                    len: 136,
+                    first_account: Some((Pubkey::new_unique(), 7)),
+                },
+                count: 1,
+                alive_bytes: 136,
+            }],
+            bins: 2,
+        };
+        let bins = vec![
+            vec![PersistedEntry {
+                pubkey: Pubkey::new_unique(),
+                ref_count: 1,
+                slot_list: vec![PersistedAccountInfo {
+                    slot: 1,
+                    store_id: 3,
+                    offset: 0,
+                    stored_size: 136,
+                    is_zero_lamport: false,
+                }],
+            }],
+            vec![],
+        ];
+        let footer = PersistedAccountsIndexFooter {
+            uncleaned_roots: vec![1],
+            uncleaned_pubkeys: vec![(1, vec![Pubkey::new_unique()])],
+            accounts_data_len: 10,
+            rent_paying_accounts: vec![Pubkey::new_unique()],
+        };
+        let mut writer = PersistedAccountsIndexWriter::new(&path, &header).unwrap();
+        for bin in &bins {
+            writer.write_bin(bin).unwrap();
+            // nothing appears until the index is complete
+            assert!(!path.exists());
+        }
+        writer.finish(&footer).unwrap();
+        assert!(!path.with_extension("tmp").exists());
+
+        let mut reader = PersistedAccountsIndexReader::take(&path).unwrap().unwrap();
+        // the saved index is only used once
+        assert!(!path.exists());
+        assert_eq!(reader.header(), &header);
+        for _ in 0..2 {
+            for bin in &bins {
+                assert_eq!(&reader.read_bin().unwrap(), bin);
+            }
+            assert_eq!(reader.read_footer().unwrap(), footer);
+            reader.rewind().unwrap();
+        }
+        assert!(PersistedAccountsIndexReader::take(&path).unwrap().is_none());
+
+        fs::write(&path, [0xff; 8]).unwrap();
+        assert!(PersistedAccountsIndexReader::take(&path).is_err());
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,284 @@
+//! Per-program storage and access statistics, for capacity planning.
+//!
+//! Storage totals (accounts, data bytes and rent-exempt lamports) come from scanning every
+//! account in a bank. Writes and read-only accounts cache lookups are counted per owner by
+//! `AccountsDb` as they happen, when enabled with `AccountsDbConfig::program_access_stats`.
+//! Each report takes the access counts collected since the previous report.
+use {
+    dashmap::DashMap,
+    serde::{Deserialize, Serialize},
+    solana_sdk::{clock::Slot, pubkey::Pubkey, rent::Rent, timing::timestamp},
+    std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, RwLock,
+        },
+        time::Duration,
+    },
+};
+
+/// Stats for all of the accounts owned by a single program
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramStats {
+    /// Number of accounts owned by the program
+    pub accounts: u64,
+    /// Total data size of those accounts
+    pub data_bytes: u64,
+    /// Total lamports held by those accounts that are rent exempt
+    pub rent_exempt_lamports: u64,
+    /// Number of times an account owned by the program was stored during the access window
+    pub writes: u64,
+    /// Number of loads of an owned account that were served by the read-only accounts cache
+    /// during the access window
+    pub read_cache_hits: u64,
+    /// Number of loads of an owned account that missed the read-only accounts cache
+    /// during the access window
+    pub read_cache_misses: u64,
+}
+
+impl ProgramStats {
+    /// Add an account holding `lamports` and `data_len` bytes of data
+    pub fn accumulate_account(&mut self, lamports: u64, data_len: usize, rent: &Rent) {
+        self.accounts += 1;
+        self.data_bytes += data_len as u64;
+        if rent.is_exempt(lamports, data_len) {
+            self.rent_exempt_lamports += lamports;
+        }
+    }
+
+    /// Number of writes per second over `access_window`
+    pub fn writes_per_second(&self, access_window: Duration) -> f64 {
+        let secs = access_window.as_secs_f64();
+        if secs > 0.0 {
+            self.writes as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// The fraction of read-only accounts cache lookups that hit, or None if there were none
+    pub fn read_cache_hit_rate(&self) -> Option<f64> {
+        let lookups = self.read_cache_hits + self.read_cache_misses;
+        (lookups > 0).then(|| self.read_cache_hits as f64 / lookups as f64)
+    }
+}
+
+/// Stats for every program that owns an account in a bank
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProgramStatsReport {
+    /// The slot of the bank that was scanned
+    pub slot: Slot,
+    /// How long the writes and read cache lookups were counted for, ending with this report
+    pub access_window: Duration,
+    /// Sorted by data bytes, then number of accounts, largest first
+    pub programs: Vec<(Pubkey, ProgramStats)>,
+}
+
+impl ProgramStatsReport {
+    pub fn new(
+        slot: Slot,
+        access_window: Duration,
+        programs: HashMap<Pubkey, ProgramStats>,
+    ) -> Self {
+        let mut programs = programs.into_iter().collect::<Vec<_>>();
+        programs.sort_unstable_by(|(a_owner, a), (b_owner, b)| {
+            (b.data_bytes, b.accounts, a_owner).cmp(&(a.data_bytes, a.accounts, b_owner))
+        });
+        Self {
+            slot,
+            access_window,
+            programs,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ProgramAccessCounters {
+    writes: AtomicU64,
+    read_cache_hits: AtomicU64,
+    read_cache_misses: AtomicU64,
+}
+
+/// Counts writes and read-only accounts cache lookups per owner,
+/// and holds the most recently collected report
+#[derive(Debug, Default)]
+pub struct ProgramStatsCollector {
+    enabled: bool,
+    counters: DashMap<Pubkey, ProgramAccessCounters>,
+    /// timestamp (ms) at which the current counts started
+    window_start: AtomicU64,
+    latest_report: RwLock<Option<Arc<ProgramStatsReport>>>,
+}
+
+impl ProgramStatsCollector {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            window_start: AtomicU64::new(timestamp()),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn update_counters(&self, owner: &Pubkey, update: impl Fn(&ProgramAccessCounters)) {
+        if !self.enabled {
+            return;
+        }
+        match self.counters.get(owner) {
+            Some(counters) => update(&counters),
+            None => update(&self.counters.entry(*owner).or_default()),
+        }
+    }
+
+    pub fn record_write(&self, owner: &Pubkey) {
+        self.update_counters(owner, |counters| {
+            counters.writes.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    pub fn record_read_cache_lookup(&self, owner: &Pubkey, hit: bool) {
+        self.update_counters(owner, |counters| {
+            let counter = if hit {
+                &counters.read_cache_hits
+            } else {
+                &counters.read_cache_misses
+            };
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    /// Move the access counts collected since the previous call into `programs`, and start
+    /// counting again from zero. The counters of owners which were not accessed since the
+    /// previous call are dropped. Returns how long the counts were collected for.
+    pub fn take_access_counts(&self, programs: &mut HashMap<Pubkey, ProgramStats>) -> Duration {
+        let now = timestamp();
+        let window_start = self.window_start.swap(now, Ordering::Relaxed);
+        self.counters.retain(|owner, counters| {
+            let writes = counters.writes.swap(0, Ordering::Relaxed);
+            let read_cache_hits = counters.read_cache_hits.swap(0, Ordering::Relaxed);
+            let read_cache_misses = counters.read_cache_misses.swap(0, Ordering::Relaxed);
+            if writes == 0 && read_cache_hits == 0 && read_cache_misses == 0 {
+                return false;
+            }
+            let stats = programs.entry(*owner).or_default();
+            stats.writes += writes;
+            stats.read_cache_hits += read_cache_hits;
+            stats.read_cache_misses += read_cache_misses;
+            true
+        });
+        Duration::from_millis(now.saturating_sub(window_start))
+    }
+
+    pub fn set_latest_report(&self, report: ProgramStatsReport) {
+        *self.latest_report.write().unwrap() = Some(Arc::new(report));
+    }
+
+    pub fn latest_report(&self) -> Option<Arc<ProgramStatsReport>> {
+        self.latest_report.read().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_stats_accumulate_account() {
+        let rent = Rent::default();
+        let exempt_lamports = rent.minimum_balance(10);
+
+        let mut stats = ProgramStats::default();
+        stats.accumulate_account(exempt_lamports, 10, &rent);
+        stats.accumulate_account(1, 20, &rent);
+        assert_eq!(
+            stats,
+            ProgramStats {
+                accounts: 2,
+                data_bytes: 30,
+                rent_exempt_lamports: exempt_lamports,
+                ..ProgramStats::default()
+            }
+        );
+        assert_eq!(stats.read_cache_hit_rate(), None);
+        assert_eq!(stats.writes_per_second(Duration::from_secs(10)), 0.0);
+        stats.writes = 5;
+        assert_eq!(stats.writes_per_second(Duration::from_secs(10)), 0.5);
+        assert_eq!(stats.writes_per_second(Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn test_program_stats_collector() {
+        let owner1 = Pubkey::new_unique();
+        let owner2 = Pubkey::new_unique();
+
+        let disabled = ProgramStatsCollector::default();
+        disabled.record_write(&owner1);
+        disabled.record_read_cache_lookup(&owner1, true);
+        let mut programs = HashMap::default();
+        disabled.take_access_counts(&mut programs);
+        assert!(programs.is_empty());
+
+        let collector = ProgramStatsCollector::new(true);
+        collector.record_write(&owner1);
+        collector.record_write(&owner1);
+        collector.record_read_cache_lookup(&owner1, true);
+        collector.record_read_cache_lookup(&owner1, true);
+        collector.record_read_cache_lookup(&owner1, true);
+        collector.record_read_cache_lookup(&owner1, false);
+        collector.record_read_cache_lookup(&owner2, false);
+
+        let mut programs = HashMap::default();
+        programs.insert(
+            owner1,
+            ProgramStats {
+                accounts: 1,
+                data_bytes: 5,
+                ..ProgramStats::default()
+            },
+        );
+        let access_window = collector.take_access_counts(&mut programs);
+        let stats1 = programs[&owner1];
+        assert_eq!(
+            stats1,
+            ProgramStats {
+                accounts: 1,
+                data_bytes: 5,
+                rent_exempt_lamports: 0,
+                writes: 2,
+                read_cache_hits: 3,
+                read_cache_misses: 1,
+            }
+        );
+        assert_eq!(stats1.read_cache_hit_rate(), Some(0.75));
+        assert_eq!(programs[&owner2].read_cache_hit_rate(), Some(0.0));
+
+        // the counts were taken, so the next window starts from zero
+        collector.record_write(&owner2);
+        let mut next_programs = HashMap::default();
+        collector.take_access_counts(&mut next_programs);
+        assert_eq!(next_programs.len(), 1);
+        assert_eq!(
+            next_programs[&owner2],
+            ProgramStats {
+                writes: 1,
+                ..ProgramStats::default()
+            }
+        );
+        // owners which weren't accessed in a window are no longer tracked
+        assert_eq!(collector.counters.len(), 1);
+        collector.take_access_counts(&mut HashMap::default());
+        assert!(collector.counters.is_empty());
+
+        assert!(collector.latest_report().is_none());
+        let report = ProgramStatsReport::new(1, access_window, programs);
+        assert_eq!(report.programs[0].0, owner1);
+        assert_eq!(report.programs[1].0, owner2);
+        collector.set_latest_report(report.clone());
+        assert_eq!(*collector.latest_report().unwrap(), report);
+    }
+}
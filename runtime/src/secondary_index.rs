@@ -5,6 +5,7 @@ use {
     std::{
         collections::HashSet,
         fmt::Debug,
+        hash::Hash,
         sync::{
             atomic::{AtomicU64, Ordering},
             RwLock,
@@ -16,7 +17,11 @@ use {
 // if the key had different account data for the indexed key across different
 // slots. As this is rare, it should be ok to use a Vec here over a HashSet, even
 // though we are running some key existence checks.
-pub type SecondaryReverseIndexEntry = RwLock<Vec<Pubkey>>;
+pub type SecondaryReverseIndexEntry<K = Pubkey> = RwLock<Vec<K>>;
+
+/// The key an account is indexed by, such as its owner, or bytes of its data
+pub trait SecondaryIndexKey: Clone + Debug + Default + Eq + Hash + Send + Sync {}
+impl<K: Clone + Debug + Default + Eq + Hash + Send + Sync> SecondaryIndexKey for K {}
 
 pub trait SecondaryIndexEntry: Debug {
     fn insert_if_not_exists(&self, key: &Pubkey, inner_keys_count: &AtomicU64);
@@ -100,16 +105,21 @@ impl SecondaryIndexEntry for RwLockSecondaryIndexEntry {
 }
 
 #[derive(Debug, Default)]
-pub struct SecondaryIndex<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send> {
+pub struct SecondaryIndex<
+    SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
+    K: SecondaryIndexKey = Pubkey,
+> {
     metrics_name: &'static str,
     // Map from index keys to index values
-    pub index: DashMap<Pubkey, SecondaryIndexEntryType>,
-    pub reverse_index: DashMap<Pubkey, SecondaryReverseIndexEntry>,
+    pub index: DashMap<K, SecondaryIndexEntryType>,
+    pub reverse_index: DashMap<Pubkey, SecondaryReverseIndexEntry<K>>,
     stats: SecondaryIndexStats,
 }
 
-impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
-    SecondaryIndex<SecondaryIndexEntryType>
+impl<
+        SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
+        K: SecondaryIndexKey,
+    > SecondaryIndex<SecondaryIndexEntryType, K>
 {
     pub fn new(metrics_name: &'static str) -> Self {
         Self {
@@ -118,12 +128,12 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
         }
     }
 
-    pub fn insert(&self, key: &Pubkey, inner_key: &Pubkey) {
+    pub fn insert(&self, key: &K, inner_key: &Pubkey) {
         {
             let pubkeys_map = self
                 .index
                 .get(key)
-                .unwrap_or_else(|| self.index.entry(*key).or_default().downgrade());
+                .unwrap_or_else(|| self.index.entry(key.clone()).or_default().downgrade());
 
             pubkeys_map.insert_if_not_exists(inner_key, &self.stats.num_inner_keys);
         }
@@ -140,7 +150,7 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
             if should_insert {
                 let mut w_outer_keys = outer_keys.write().unwrap();
                 if !w_outer_keys.contains(key) {
-                    w_outer_keys.push(*key);
+                    w_outer_keys.push(key.clone());
                 }
             }
         }
@@ -164,7 +174,7 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
     }
 
     // Only safe to call from `remove_by_inner_key()` due to asserts
-    fn remove_index_entries(&self, outer_key: &Pubkey, removed_inner_key: &Pubkey) {
+    fn remove_index_entries(&self, outer_key: &K, removed_inner_key: &Pubkey) {
         let is_outer_key_empty = {
             let inner_key_map = self
                 .index
@@ -180,7 +190,7 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
         if is_outer_key_empty {
             // Other threads may have interleaved writes to this `key`,
             // so double-check again for its emptiness
-            if let Occupied(key_entry) = self.index.entry(*outer_key) {
+            if let Occupied(key_entry) = self.index.entry(outer_key.clone()) {
                 if key_entry.get().is_empty() {
                     key_entry.remove();
                 }
@@ -191,7 +201,7 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
     pub fn remove_by_inner_key(&self, inner_key: &Pubkey) {
         // Save off which keys in `self.index` had slots removed so we can remove them
         // after we purge the reverse index
-        let mut removed_outer_keys: HashSet<Pubkey> = HashSet::new();
+        let mut removed_outer_keys: HashSet<K> = HashSet::new();
 
         // Check if the entry for `inner_key` in the reverse index is empty
         // and can be removed
@@ -215,7 +225,7 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
             .fetch_sub(removed_outer_keys.len() as u64, Ordering::Relaxed);
     }
 
-    pub fn get(&self, key: &Pubkey) -> Vec<Pubkey> {
+    pub fn get(&self, key: &K) -> Vec<Pubkey> {
         if let Some(inner_keys_map) = self.index.get(key) {
             inner_keys_map.keys()
         } else {
@@ -223,18 +233,18 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
         }
     }
 
-    /// log top 20 (owner, # accounts) in descending order of # accounts
+    /// log top 20 (key, # accounts) in descending order of # accounts
     pub fn log_contents(&self) {
         let mut entries = self
             .index
             .iter()
-            .map(|entry| (entry.value().len(), *entry.key()))
+            .map(|entry| (entry.value().len(), entry.key().clone()))
             .collect::<Vec<_>>();
-        entries.sort_unstable();
+        entries.sort_unstable_by_key(|(len, _key)| *len);
         entries
             .iter()
             .rev()
             .take(20)
-            .for_each(|(v, k)| info!("owner: {}, accounts: {}", k, v));
+            .for_each(|(v, k)| info!("key: {:?}, accounts: {}", k, v));
     }
 }
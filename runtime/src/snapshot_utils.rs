@@ -363,6 +363,18 @@ pub fn archive_snapshot_package(
             storage.append_vec_id(),
         ));
 
+        if let Some(cold) = storage.accounts.cold_storage() {
+            // cold storages are compressed on disk, so archive them as a regular append vec
+            let mut output_file = BufWriter::new(
+                fs::File::create(&output_path)
+                    .map_err(|e| SnapshotError::IoWithSource(e, "create cold storage file"))?,
+            );
+            cold.write_uncompressed(&mut output_file)
+                .and_then(|_| output_file.flush())
+                .map_err(|e| SnapshotError::IoWithSource(e, "write cold storage file"))?;
+            continue;
+        }
+
         // `storage_path` - The file path where the AppendVec itself is located
         // `output_path` - The file path where the AppendVec will be placed in the staging directory.
         let storage_path =
@@ -2102,7 +2114,8 @@ pub fn get_snapshot_storages(bank: &Bank) -> SnapshotStorages {
 
 /// Convenience function to create a full snapshot archive out of any Bank, regardless of state.
 /// The Bank will be frozen during the process.
-/// This is only called from ledger-tool or tests. Warping is a special case as well.
+/// This is only called from ledger-tool or tests. Warping and saving the accounts index on exit
+/// are special cases as well.
 ///
 /// Requires:
 ///     - `bank` is complete
@@ -2144,6 +2157,36 @@ pub fn bank_to_full_snapshot_archive(
     )
 }
 
+/// Create a full snapshot archive of the root bank when the validator exits cleanly, then save
+/// the accounts index against the storages in it.  The next start loads the latest snapshot,
+/// which is this one, so it can load the saved index instead of generating it.
+///
+/// Nothing else may store or clean accounts while this runs.
+#[allow(clippy::too_many_arguments)]
+pub fn bank_to_full_snapshot_archive_and_accounts_index(
+    bank_snapshots_dir: impl AsRef<Path>,
+    bank: &Bank,
+    snapshot_version: Option<SnapshotVersion>,
+    full_snapshot_archives_dir: impl AsRef<Path>,
+    incremental_snapshot_archives_dir: impl AsRef<Path>,
+    archive_format: ArchiveFormat,
+    maximum_full_snapshot_archives_to_retain: usize,
+    maximum_incremental_snapshot_archives_to_retain: usize,
+) -> Result<FullSnapshotArchiveInfo> {
+    let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+        bank_snapshots_dir,
+        bank,
+        snapshot_version,
+        full_snapshot_archives_dir,
+        incremental_snapshot_archives_dir,
+        archive_format,
+        maximum_full_snapshot_archives_to_retain,
+        maximum_incremental_snapshot_archives_to_retain,
+    )?;
+    bank.rc.accounts.accounts_db.save_accounts_index()?;
+    Ok(full_snapshot_archive_info)
+}
+
 /// Convenience function to create an incremental snapshot archive out of any Bank, regardless of
 /// state.  The Bank will be frozen during the process.
 /// This is only called from ledger-tool or tests. Warping is a special case as well.
@@ -2316,7 +2359,11 @@ pub fn should_take_incremental_snapshot(
 mod tests {
     use {
         super::*,
-        crate::{accounts_db::ACCOUNTS_DB_CONFIG_FOR_TESTING, status_cache::Status},
+        crate::{
+            accounts_db::ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            accounts_index::{AccountsIndexConfig, ACCOUNTS_INDEX_CONFIG_FOR_TESTING},
+            status_cache::Status,
+        },
         assert_matches::assert_matches,
         bincode::{deserialize_from, serialize_into},
         solana_sdk::{
@@ -3387,6 +3434,136 @@ mod tests {
         assert_eq!(*bank4, roundtrip_bank);
     }
 
+    /// Test restarting with a persisted accounts index.  The index is saved along with a full
+    /// snapshot of the root, and loading that snapshot loads the saved index.  An index saved
+    /// without a snapshot does not match the storages of the latest snapshot, so it is not used.
+    #[test]
+    fn test_bank_to_full_snapshot_archive_and_accounts_index() {
+        solana_logger::setup();
+        let collector = Pubkey::new_unique();
+        let key1 = Keypair::new();
+        let key2 = Keypair::new();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let persisted_index_path = temp_dir.path().join("persisted_accounts_index");
+        let accounts_db_config = AccountsDbConfig {
+            index: Some(AccountsIndexConfig {
+                persisted_index_path: Some(persisted_index_path.clone()),
+                ..ACCOUNTS_INDEX_CONFIG_FOR_TESTING
+            }),
+            ..ACCOUNTS_DB_CONFIG_FOR_TESTING
+        };
+
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1_000_000.));
+        let bank0 = Arc::new(Bank::new_with_paths(
+            &genesis_config,
+            Arc::<RuntimeConfig>::default(),
+            Vec::new(),
+            None,
+            None,
+            AccountSecondaryIndexes::default(),
+            true,
+            AccountShrinkThreshold::default(),
+            false,
+            Some(accounts_db_config.clone()),
+            None,
+            &Arc::default(),
+        ));
+        bank0
+            .transfer(sol_to_lamports(1.), &mint_keypair, &key1.pubkey())
+            .unwrap();
+        while !bank0.is_complete() {
+            bank0.register_tick(&Hash::new_unique());
+        }
+        let bank1 = Arc::new(Bank::new_from_parent(&bank0, &collector, 1));
+        bank1
+            .transfer(sol_to_lamports(1.), &mint_keypair, &key1.pubkey())
+            .unwrap();
+        bank1
+            .transfer(sol_to_lamports(2.), &mint_keypair, &key2.pubkey())
+            .unwrap();
+        while !bank1.is_complete() {
+            bank1.register_tick(&Hash::new_unique());
+        }
+
+        let accounts_dir = tempfile::TempDir::new().unwrap();
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive_and_accounts_index(
+            bank_snapshots_dir.path(),
+            &bank1,
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            ArchiveFormat::Tar,
+            DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        )
+        .unwrap();
+        assert!(persisted_index_path.exists());
+
+        let restart = || {
+            bank_from_snapshot_archives(
+                &[PathBuf::from(accounts_dir.path())],
+                bank_snapshots_dir.path(),
+                &full_snapshot_archive_info,
+                None,
+                &genesis_config,
+                &RuntimeConfig::default(),
+                None,
+                None,
+                AccountSecondaryIndexes::default(),
+                true,
+                None,
+                AccountShrinkThreshold::default(),
+                false,
+                false,
+                false,
+                Some(accounts_db_config.clone()),
+                None,
+                &Arc::default(),
+            )
+            .unwrap()
+            .0
+        };
+        let loaded_persisted_index = |bank: &Bank| {
+            bank.rc
+                .accounts
+                .accounts_db
+                .loaded_persisted_accounts_index
+                .load(std::sync::atomic::Ordering::Relaxed)
+        };
+        let roundtrip_bank = restart();
+        assert!(!persisted_index_path.exists());
+        assert!(loaded_persisted_index(&roundtrip_bank));
+        assert_eq!(*bank1, roundtrip_bank);
+        assert_eq!(
+            roundtrip_bank.get_balance(&key1.pubkey()),
+            sol_to_lamports(2.)
+        );
+        assert_eq!(
+            roundtrip_bank.get_balance(&key2.pubkey()),
+            sol_to_lamports(2.)
+        );
+
+        // the root moves past the snapshot before the index is saved
+        let bank2 = Arc::new(Bank::new_from_parent(&bank1, &collector, 2));
+        bank2
+            .transfer(sol_to_lamports(1.), &mint_keypair, &key2.pubkey())
+            .unwrap();
+        while !bank2.is_complete() {
+            bank2.register_tick(&Hash::new_unique());
+        }
+        bank2.squash();
+        bank2.force_flush_accounts_cache();
+        bank2.rc.accounts.accounts_db.save_accounts_index().unwrap();
+        assert!(persisted_index_path.exists());
+        let roundtrip_bank = restart();
+        assert!(!persisted_index_path.exists());
+        assert!(!loaded_persisted_index(&roundtrip_bank));
+        assert_eq!(*bank1, roundtrip_bank);
+    }
+
     /// Test roundtrip of bank to snapshots, then back again, with incremental snapshots.  In this
     /// version, build up a few slots and take a full snapshot.  Continue on a few more slots and
     /// take an incremental snapshot.  Rebuild the bank from both the incremental snapshot and full
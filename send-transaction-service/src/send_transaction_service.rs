@@ -355,6 +355,25 @@ impl SendTransactionService {
         receiver: Receiver<TransactionInfo>,
         connection_cache: &Arc<ConnectionCache>,
         config: Config,
+    ) -> Self {
+        Self::new_with_shared_config(
+            tpu_address,
+            bank_forks,
+            leader_info,
+            receiver,
+            connection_cache,
+            Arc::new(RwLock::new(config)),
+        )
+    }
+
+    /// Like `new_with_config()`, but the service picks up changes made to `config` while it runs
+    pub fn new_with_shared_config<T: TpuInfo + std::marker::Send + 'static>(
+        tpu_address: SocketAddr,
+        bank_forks: &Arc<RwLock<BankForks>>,
+        leader_info: Option<T>,
+        receiver: Receiver<TransactionInfo>,
+        connection_cache: &Arc<ConnectionCache>,
+        config: Arc<RwLock<Config>>,
     ) -> Self {
         let stats_report = Arc::new(SendTransactionServiceStatsReport::default());
 
@@ -397,7 +416,7 @@ impl SendTransactionService {
         receiver: Receiver<TransactionInfo>,
         leader_info_provider: Arc<Mutex<CurrentLeaderInfo<T>>>,
         connection_cache: Arc<ConnectionCache>,
-        config: Config,
+        shared_config: Arc<RwLock<Config>>,
         retry_transactions: Arc<Mutex<HashMap<Signature, TransactionInfo>>>,
         stats_report: Arc<SendTransactionServiceStatsReport>,
         exit: Arc<AtomicBool>,
@@ -407,11 +426,12 @@ impl SendTransactionService {
 
         info!(
             "Starting send-transaction-service::receive_txn_thread with config {:?}",
-            config
+            shared_config.read().unwrap()
         );
         Builder::new()
             .name("solStxReceive".to_string())
             .spawn(move || loop {
+                let config = shared_config.read().unwrap().clone();
                 let recv_timeout_ms = config.batch_send_rate_ms;
                 let stats = &stats_report.stats;
                 let recv_result = receiver.recv_timeout(Duration::from_millis(recv_timeout_ms));
@@ -504,18 +524,19 @@ impl SendTransactionService {
         bank_forks: Arc<RwLock<BankForks>>,
         leader_info_provider: Arc<Mutex<CurrentLeaderInfo<T>>>,
         connection_cache: Arc<ConnectionCache>,
-        config: Config,
+        shared_config: Arc<RwLock<Config>>,
         retry_transactions: Arc<Mutex<HashMap<Signature, TransactionInfo>>>,
         stats_report: Arc<SendTransactionServiceStatsReport>,
         exit: Arc<AtomicBool>,
     ) -> JoinHandle<()> {
         info!(
             "Starting send-transaction-service::retry_thread with config {:?}",
-            config
+            shared_config.read().unwrap()
         );
         Builder::new()
             .name("solStxRetry".to_string())
             .spawn(move || loop {
+                let config = shared_config.read().unwrap().clone();
                 let retry_interval_ms = config.retry_rate_ms;
                 let stats = &stats_report.stats;
                 sleep(Duration::from_millis(
@@ -0,0 +1,70 @@
+// The storage primitives `LedgerStorage` is built on
+
+pub use crate::bigtable::{CellName, CellValue, Error, Result, RowData, RowKey};
+use async_trait::async_trait;
+
+/// A store of tables holding rows of named cells, sorted by row key; the data model of
+/// Bigtable. `LedgerStorage` keeps its `blocks`, `tx`, `tx-by-addr` and `dictionaries` tables,
+/// with the same row keys and cell encodings, in any store implementing this trait.
+#[async_trait]
+pub trait LedgerStorageBackend: Send + Sync {
+    /// Get `table` row keys in lexical order.
+    ///
+    /// If `start_at` is provided, the row key listing will start with key.
+    /// Otherwise the listing will start from the start of the table.
+    ///
+    /// If `end_at` is provided, the row key listing will end at the key. Otherwise it will
+    /// continue until the `rows_limit` is reached or the end of the table, whichever comes first.
+    /// If `rows_limit` is zero, this method will return an empty array.
+    async fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>>;
+
+    /// Check whether a row key exists in a `table`
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool>;
+
+    /// Get latest data from `table`, listed like the row keys of `get_row_keys()`
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>>;
+
+    /// Get latest data from multiple rows of `table`, if those rows exist.
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>>;
+
+    /// Like `get_multi_row_data()`, but retrying on failure with an exponential backoff,
+    /// for callers that must not give up on a transient error
+    async fn get_multi_row_data_with_retry(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        self.get_multi_row_data(table_name, row_keys).await
+    }
+
+    /// Get latest data from a single row of `table`, if that row exists. Returns
+    /// `Error::RowNotFound` if that row does not exist.
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData>;
+
+    /// Store data for one or more `table` rows in the `family_name` column family
+    async fn put_row_data(
+        &self,
+        table_name: &str,
+        family_name: &str,
+        row_data: &[(&RowKey, RowData)],
+    ) -> Result<()>;
+
+    /// Delete one or more `table` rows
+    async fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()>;
+}
@@ -3,9 +3,11 @@
 use {
     crate::{
         access_token::{AccessToken, Scope},
-        compression::{compress_best, decompress},
+        backend::LedgerStorageBackend,
+        compression::{decompress, CompressionDictionaries},
         root_ca_certificate, CredentialType,
     },
+    async_trait::async_trait,
     backoff::{future::retry, ExponentialBackoff},
     log::*,
     std::{
@@ -82,6 +84,9 @@ pub enum Error {
 
     #[error("Timeout")]
     Timeout,
+
+    #[error("Local storage: {0}")]
+    LocalStorage(String),
 }
 
 impl std::convert::From<std::io::Error> for Error {
@@ -246,56 +251,81 @@ impl BigTableConnection {
             timeout: self.timeout,
         }
     }
+}
 
-    pub async fn put_bincode_cells_with_retry<T>(
+#[async_trait]
+impl LedgerStorageBackend for BigTableConnection {
+    async fn get_row_keys(
         &self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: serde::ser::Serialize,
-    {
-        retry(ExponentialBackoff::default(), || async {
-            let mut client = self.client();
-            Ok(client.put_bincode_cells(table, cells).await?)
-        })
-        .await
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>> {
+        self.client()
+            .get_row_keys(table_name, start_at, end_at, rows_limit)
+            .await
+    }
+
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool> {
+        self.client().row_key_exists(table_name, row_key).await
     }
 
-    pub async fn delete_rows_with_retry(&self, table: &str, row_keys: &[RowKey]) -> Result<()> {
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        self.client()
+            .get_row_data(table_name, start_at, end_at, rows_limit)
+            .await
+    }
+
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        self.client().get_multi_row_data(table_name, row_keys).await
+    }
+
+    async fn get_multi_row_data_with_retry(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
         retry(ExponentialBackoff::default(), || async {
             let mut client = self.client();
-            Ok(client.delete_rows(table, row_keys).await?)
+            Ok(client.get_multi_row_data(table_name, row_keys).await?)
         })
         .await
     }
 
-    pub async fn get_bincode_cells_with_retry<T>(
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData> {
+        self.client().get_single_row_data(table_name, row_key).await
+    }
+
+    async fn put_row_data(
         &self,
-        table: &str,
-        row_keys: &[RowKey],
-    ) -> Result<Vec<(RowKey, Result<T>)>>
-    where
-        T: serde::de::DeserializeOwned,
-    {
+        table_name: &str,
+        family_name: &str,
+        row_data: &[(&RowKey, RowData)],
+    ) -> Result<()> {
         retry(ExponentialBackoff::default(), || async {
             let mut client = self.client();
-            Ok(client.get_bincode_cells(table, row_keys).await?)
+            Ok(client
+                .put_row_data(table_name, family_name, row_data)
+                .await?)
         })
         .await
     }
 
-    pub async fn put_protobuf_cells_with_retry<T>(
-        &self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: prost::Message,
-    {
+    async fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()> {
         retry(ExponentialBackoff::default(), || async {
             let mut client = self.client();
-            Ok(client.put_protobuf_cells(table, cells).await?)
+            Ok(client.delete_rows(table_name, row_keys).await?)
         })
         .await
     }
@@ -696,141 +726,33 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
 
         Ok(())
     }
-
-    pub async fn get_bincode_cell<T>(&mut self, table: &str, key: RowKey) -> Result<T>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        let row_data = self.get_single_row_data(table, key.clone()).await?;
-        deserialize_bincode_cell_data(&row_data, table, key.to_string())
-    }
-
-    pub async fn get_bincode_cells<T>(
-        &mut self,
-        table: &str,
-        keys: &[RowKey],
-    ) -> Result<Vec<(RowKey, Result<T>)>>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        Ok(self
-            .get_multi_row_data(table, keys)
-            .await?
-            .into_iter()
-            .map(|(key, row_data)| {
-                let key_str = key.to_string();
-                (
-                    key,
-                    deserialize_bincode_cell_data(&row_data, table, key_str),
-                )
-            })
-            .collect())
-    }
-
-    pub async fn get_protobuf_or_bincode_cell<B, P>(
-        &mut self,
-        table: &str,
-        key: RowKey,
-    ) -> Result<CellData<B, P>>
-    where
-        B: serde::de::DeserializeOwned,
-        P: prost::Message + Default,
-    {
-        let row_data = self.get_single_row_data(table, key.clone()).await?;
-        deserialize_protobuf_or_bincode_cell_data(&row_data, table, key)
-    }
-
-    pub async fn get_protobuf_or_bincode_cells<'a, B, P>(
-        &mut self,
-        table: &'a str,
-        row_keys: impl IntoIterator<Item = RowKey>,
-    ) -> Result<impl Iterator<Item = (RowKey, CellData<B, P>)> + 'a>
-    where
-        B: serde::de::DeserializeOwned,
-        P: prost::Message + Default,
-    {
-        Ok(self
-            .get_multi_row_data(
-                table,
-                row_keys.into_iter().collect::<Vec<RowKey>>().as_slice(),
-            )
-            .await?
-            .into_iter()
-            .map(|(key, row_data)| {
-                let key_str = key.to_string();
-                (
-                    key,
-                    deserialize_protobuf_or_bincode_cell_data(&row_data, table, key_str).unwrap(),
-                )
-            }))
-    }
-
-    pub async fn put_bincode_cells<T>(
-        &mut self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: serde::ser::Serialize,
-    {
-        let mut bytes_written = 0;
-        let mut new_row_data = vec![];
-        for (row_key, data) in cells {
-            let data = compress_best(&bincode::serialize(&data).unwrap())?;
-            bytes_written += data.len();
-            new_row_data.push((row_key, vec![("bin".to_string(), data)]));
-        }
-
-        self.put_row_data(table, "x", &new_row_data).await?;
-        Ok(bytes_written)
-    }
-
-    pub async fn put_protobuf_cells<T>(
-        &mut self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: prost::Message,
-    {
-        let mut bytes_written = 0;
-        let mut new_row_data = vec![];
-        for (row_key, data) in cells {
-            let mut buf = Vec::with_capacity(data.encoded_len());
-            data.encode(&mut buf).unwrap();
-            let data = compress_best(&buf)?;
-            bytes_written += data.len();
-            new_row_data.push((row_key, vec![("proto".to_string(), data)]));
-        }
-
-        self.put_row_data(table, "x", &new_row_data).await?;
-        Ok(bytes_written)
-    }
 }
 
 pub(crate) fn deserialize_protobuf_or_bincode_cell_data<B, P>(
     row_data: RowDataSlice,
     table: &str,
     key: RowKey,
+    dictionaries: &CompressionDictionaries,
 ) -> Result<CellData<B, P>>
 where
     B: serde::de::DeserializeOwned,
     P: prost::Message + Default,
 {
-    match deserialize_protobuf_cell_data(row_data, table, key.to_string()) {
+    match deserialize_protobuf_cell_data(row_data, table, key.to_string(), dictionaries) {
         Ok(result) => return Ok(CellData::Protobuf(result)),
         Err(err) => match err {
             Error::ObjectNotFound(_) => {}
             _ => return Err(err),
         },
     }
-    deserialize_bincode_cell_data(row_data, table, key).map(CellData::Bincode)
+    deserialize_bincode_cell_data(row_data, table, key, dictionaries).map(CellData::Bincode)
 }
 
 pub(crate) fn deserialize_protobuf_cell_data<T>(
     row_data: RowDataSlice,
     table: &str,
     key: RowKey,
+    dictionaries: &CompressionDictionaries,
 ) -> Result<T>
 where
     T: prost::Message + Default,
@@ -841,7 +763,7 @@ where
         .ok_or_else(|| Error::ObjectNotFound(format!("{}/{}", table, key)))?
         .1;
 
-    let data = decompress(value)?;
+    let data = decompress(value, dictionaries)?;
     T::decode(&data[..]).map_err(|err| {
         warn!("Failed to deserialize {}/{}: {}", table, key, err);
         Error::ObjectCorrupt(format!("{}/{}", table, key))
@@ -852,6 +774,7 @@ pub(crate) fn deserialize_bincode_cell_data<T>(
     row_data: RowDataSlice,
     table: &str,
     key: RowKey,
+    dictionaries: &CompressionDictionaries,
 ) -> Result<T>
 where
     T: serde::de::DeserializeOwned,
@@ -862,7 +785,7 @@ where
         .ok_or_else(|| Error::ObjectNotFound(format!("{}/{}", table, key)))?
         .1;
 
-    let data = decompress(value)?;
+    let data = decompress(value, dictionaries)?;
     bincode::deserialize(&data).map_err(|err| {
         warn!("Failed to deserialize {}/{}: {}", table, key, err);
         Error::ObjectCorrupt(format!("{}/{}", table, key))
@@ -873,7 +796,7 @@ where
 mod tests {
     use {
         super::*,
-        crate::StoredConfirmedBlock,
+        crate::{compression::compress_best, StoredConfirmedBlock},
         prost::Message,
         solana_sdk::{
             hash::Hash, message::v0::LoadedAddresses, signature::Keypair, system_transaction,
@@ -942,13 +865,14 @@ mod tests {
         };
         let bincode_block = compress_best(
             &bincode::serialize::<StoredConfirmedBlock>(&expected_block.clone().into()).unwrap(),
+            None,
         )
         .unwrap();
 
         let protobuf_block = confirmed_block_into_protobuf(expected_block.clone());
         let mut buf = Vec::with_capacity(protobuf_block.encoded_len());
         protobuf_block.encode(&mut buf).unwrap();
-        let protobuf_block = compress_best(&buf).unwrap();
+        let protobuf_block = compress_best(&buf, None).unwrap();
 
         let deserialized = deserialize_protobuf_or_bincode_cell_data::<
             StoredConfirmedBlock,
@@ -957,6 +881,7 @@ mod tests {
             &[("proto".to_string(), protobuf_block.clone())],
             "",
             "".to_string(),
+            &CompressionDictionaries::default(),
         )
         .unwrap();
         if let CellData::Protobuf(protobuf_block) = deserialized {
@@ -972,6 +897,7 @@ mod tests {
             &[("bin".to_string(), bincode_block.clone())],
             "",
             "".to_string(),
+            &CompressionDictionaries::default(),
         )
         .unwrap();
         if let CellData::Bincode(bincode_block) = deserialized {
@@ -997,7 +923,12 @@ mod tests {
         let result = deserialize_protobuf_or_bincode_cell_data::<
             StoredConfirmedBlock,
             generated::ConfirmedBlock,
-        >(&[("proto".to_string(), bincode_block)], "", "".to_string());
+        >(
+            &[("proto".to_string(), bincode_block)],
+            "",
+            "".to_string(),
+            &CompressionDictionaries::default(),
+        );
         assert!(result.is_err());
 
         let result = deserialize_protobuf_or_bincode_cell_data::<
@@ -1007,19 +938,30 @@ mod tests {
             &[("proto".to_string(), vec![1, 2, 3, 4])],
             "",
             "".to_string(),
+            &CompressionDictionaries::default(),
         );
         assert!(result.is_err());
 
         let result = deserialize_protobuf_or_bincode_cell_data::<
             StoredConfirmedBlock,
             generated::ConfirmedBlock,
-        >(&[("bin".to_string(), protobuf_block)], "", "".to_string());
+        >(
+            &[("bin".to_string(), protobuf_block)],
+            "",
+            "".to_string(),
+            &CompressionDictionaries::default(),
+        );
         assert!(result.is_err());
 
         let result = deserialize_protobuf_or_bincode_cell_data::<
             StoredConfirmedBlock,
             generated::ConfirmedBlock,
-        >(&[("bin".to_string(), vec![1, 2, 3, 4])], "", "".to_string());
+        >(
+            &[("bin".to_string(), vec![1, 2, 3, 4])],
+            "",
+            "".to_string(),
+            &CompressionDictionaries::default(),
+        );
         assert!(result.is_err());
     }
 }
@@ -1,6 +1,10 @@
 use {
     enum_iterator::{all, Sequence},
-    std::io::{self, BufReader, Read, Write},
+    std::{
+        collections::HashMap,
+        io::{self, BufReader, Read, Write},
+    },
+    zstd::dict::{DecoderDictionary, EncoderDictionary},
 };
 
 #[derive(Debug, Serialize, Deserialize, Sequence)]
@@ -9,43 +13,176 @@ pub enum CompressionMethod {
     Bzip2,
     Gzip,
     Zstd,
+    // Zstd with a trained dictionary, whose id follows the method in the header
+    ZstdDictionary,
+}
+
+/// The compression level of dictionary compression
+const DICTIONARY_COMPRESSION_LEVEL: i32 = 0;
+
+/// A zstd dictionary trained on ledger data
+pub struct CompressionDictionary {
+    id: u32,
+    encoder: EncoderDictionary<'static>,
+    decoder: DecoderDictionary<'static>,
+}
+
+impl CompressionDictionary {
+    pub fn new(id: u32, dictionary: &[u8]) -> Self {
+        Self {
+            id,
+            encoder: EncoderDictionary::copy(dictionary, DICTIONARY_COMPRESSION_LEVEL),
+            decoder: DecoderDictionary::copy(dictionary),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+/// The dictionaries cells may be compressed with, by id
+#[derive(Default)]
+pub struct CompressionDictionaries {
+    dictionaries: HashMap<u32, CompressionDictionary>,
+}
+
+impl CompressionDictionaries {
+    pub fn insert(&mut self, dictionary: CompressionDictionary) {
+        self.dictionaries.insert(dictionary.id, dictionary);
+    }
+
+    pub fn get(&self, id: u32) -> Option<&CompressionDictionary> {
+        self.dictionaries.get(&id)
+    }
+
+    pub fn contains(&self, id: u32) -> bool {
+        self.dictionaries.contains_key(&id)
+    }
+
+    /// The most recently trained dictionary, which new cells are compressed with
+    pub fn latest(&self) -> Option<&CompressionDictionary> {
+        self.dictionaries
+            .iter()
+            .max_by_key(|(id, _)| **id)
+            .map(|(_, dictionary)| dictionary)
+    }
+}
+
+/// Train a dictionary of at most `max_size` bytes on uncompressed cell data
+pub fn train_dictionary(samples: &[Vec<u8>], max_size: usize) -> Result<Vec<u8>, io::Error> {
+    zstd::dict::from_samples(samples, max_size)
 }
 
 fn decompress_reader<'a, R: Read + 'a>(
     method: CompressionMethod,
     stream: R,
+    dictionary: Option<&'a CompressionDictionary>,
 ) -> Result<Box<dyn Read + 'a>, io::Error> {
     let buf_reader = BufReader::new(stream);
     let decompress_reader: Box<dyn Read> = match method {
         CompressionMethod::Bzip2 => Box::new(bzip2::bufread::BzDecoder::new(buf_reader)),
         CompressionMethod::Gzip => Box::new(flate2::read::GzDecoder::new(buf_reader)),
         CompressionMethod::Zstd => Box::new(zstd::stream::read::Decoder::new(buf_reader)?),
+        CompressionMethod::ZstdDictionary => {
+            let dictionary = dictionary.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::Other, "compression dictionary missing")
+            })?;
+            Box::new(zstd::stream::read::Decoder::with_prepared_dictionary(
+                buf_reader,
+                &dictionary.decoder,
+            )?)
+        }
         CompressionMethod::NoCompression => Box::new(buf_reader),
     };
     Ok(decompress_reader)
 }
 
-pub fn decompress(data: &[u8]) -> Result<Vec<u8>, io::Error> {
-    let method_size = bincode::serialized_size(&CompressionMethod::NoCompression).unwrap();
-    if (data.len() as u64) < method_size {
+fn method_size() -> usize {
+    bincode::serialized_size(&CompressionMethod::NoCompression).unwrap() as usize
+}
+
+fn dictionary_id_size() -> usize {
+    bincode::serialized_size(&0u32).unwrap() as usize
+}
+
+fn deserialize_header_field<T: serde::de::DeserializeOwned>(
+    data: &[u8],
+    name: &str,
+) -> Result<T, io::Error> {
+    bincode::deserialize(data).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::Other,
+            format!("{} deserialize failed: {}", name, err),
+        )
+    })
+}
+
+/// The id of the dictionary `data` was compressed with, if any
+pub fn dictionary_id(data: &[u8]) -> Option<u32> {
+    let method_size = method_size();
+    match bincode::deserialize(data.get(..method_size)?).ok()? {
+        CompressionMethod::ZstdDictionary => {
+            bincode::deserialize(data.get(method_size..method_size + dictionary_id_size())?).ok()
+        }
+        _ => None,
+    }
+}
+
+pub fn decompress(
+    data: &[u8],
+    dictionaries: &CompressionDictionaries,
+) -> Result<Vec<u8>, io::Error> {
+    let method_size = method_size();
+    if data.len() < method_size {
         return Err(io::Error::new(
             io::ErrorKind::Other,
             format!("data len too small: {}", data.len()),
         ));
     }
-    let method = bincode::deserialize(&data[..method_size as usize]).map_err(|err| {
-        io::Error::new(
-            io::ErrorKind::Other,
-            format!("method deserialize failed: {}", err),
-        )
-    })?;
+    let method = deserialize_header_field(&data[..method_size], "method")?;
 
-    let mut reader = decompress_reader(method, &data[method_size as usize..])?;
+    let (dictionary, header_size) = match method {
+        CompressionMethod::ZstdDictionary => {
+            let header_size = method_size + dictionary_id_size();
+            if data.len() < header_size {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("data len too small: {}", data.len()),
+                ));
+            }
+            let id = deserialize_header_field(&data[method_size..header_size], "dictionary id")?;
+            let dictionary = dictionaries.get(id).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("unknown compression dictionary: {}", id),
+                )
+            })?;
+            (Some(dictionary), header_size)
+        }
+        _ => (None, method_size),
+    };
+
+    let mut reader = decompress_reader(method, &data[header_size..], dictionary)?;
     let mut uncompressed_data = vec![];
     reader.read_to_end(&mut uncompressed_data)?;
     Ok(uncompressed_data)
 }
 
+pub fn compress_with_dictionary(
+    dictionary: &CompressionDictionary,
+    data: &[u8],
+) -> Result<Vec<u8>, io::Error> {
+    let mut compressed_data = bincode::serialize(&CompressionMethod::ZstdDictionary).unwrap();
+    compressed_data.extend(bincode::serialize(&dictionary.id).unwrap());
+    let mut e = zstd::stream::write::Encoder::with_prepared_dictionary(
+        compressed_data,
+        &dictionary.encoder,
+    )?;
+    e.write_all(data)?;
+    e.finish()
+}
+
 pub fn compress(method: CompressionMethod, data: &[u8]) -> Result<Vec<u8>, io::Error> {
     let mut compressed_data = bincode::serialize(&method).unwrap();
     compressed_data.extend(
@@ -66,6 +203,12 @@ pub fn compress(method: CompressionMethod, data: &[u8]) -> Result<Vec<u8>, io::E
                 e.write_all(data)?;
                 e.finish()?
             }
+            CompressionMethod::ZstdDictionary => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "compressing with a dictionary requires the dictionary",
+                ))
+            }
             CompressionMethod::NoCompression => data.to_vec(),
         }
         .into_iter(),
@@ -74,10 +217,20 @@ pub fn compress(method: CompressionMethod, data: &[u8]) -> Result<Vec<u8>, io::E
     Ok(compressed_data)
 }
 
-pub fn compress_best(data: &[u8]) -> Result<Vec<u8>, io::Error> {
+pub fn compress_best(
+    data: &[u8],
+    dictionary: Option<&CompressionDictionary>,
+) -> Result<Vec<u8>, io::Error> {
     let mut candidates = vec![];
     for method in all::<CompressionMethod>() {
-        candidates.push(compress(method, data)?);
+        match method {
+            CompressionMethod::ZstdDictionary => {
+                if let Some(dictionary) = dictionary {
+                    candidates.push(compress_with_dictionary(dictionary, data)?);
+                }
+            }
+            method => candidates.push(compress(method, data)?),
+        }
     }
 
     Ok(candidates
@@ -94,7 +247,11 @@ mod test {
     fn test_compress_uncompress() {
         let data = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
         assert_eq!(
-            decompress(&compress_best(&data).expect("compress_best")).expect("decompress"),
+            decompress(
+                &compress_best(&data, None).expect("compress_best"),
+                &CompressionDictionaries::default()
+            )
+            .expect("decompress"),
             data
         );
     }
@@ -102,6 +259,45 @@ mod test {
     #[test]
     fn test_compress() {
         let data = vec![0; 256];
-        assert!(compress_best(&data).expect("compress_best").len() < data.len());
+        assert!(compress_best(&data, None).expect("compress_best").len() < data.len());
+    }
+
+    #[test]
+    fn test_compress_with_dictionary() {
+        let samples: Vec<Vec<u8>> = (0..1000u32)
+            .map(|i| {
+                format!(
+                    "{{\"slot\":{},\"blockhash\":\"{:x}\",\"rewards\":[]}}",
+                    i,
+                    i * 7919
+                )
+                .into_bytes()
+            })
+            .collect();
+        let dictionary = train_dictionary(&samples, 4096).expect("train_dictionary");
+        let mut dictionaries = CompressionDictionaries::default();
+        dictionaries.insert(CompressionDictionary::new(3, &dictionary));
+        assert_eq!(
+            dictionaries.latest().map(|dictionary| dictionary.id()),
+            Some(3)
+        );
+
+        let data = &samples[42];
+        let compressed =
+            compress_best(data, dictionaries.latest()).expect("compress_best with dictionary");
+        assert_eq!(dictionary_id(&compressed), Some(3));
+        assert!(compressed.len() < compress_best(data, None).unwrap().len());
+        assert_eq!(
+            decompress(&compressed, &dictionaries).expect("decompress"),
+            *data
+        );
+
+        // Cells compressed with an unknown dictionary can not be read
+        assert!(decompress(&compressed, &CompressionDictionaries::default()).is_err());
+
+        // Cells compressed without a dictionary are still readable
+        let compressed = compress(CompressionMethod::Gzip, data).unwrap();
+        assert_eq!(dictionary_id(&compressed), None);
+        assert_eq!(decompress(&compressed, &dictionaries).unwrap(), *data);
     }
 }
@@ -1,7 +1,10 @@
 #![allow(clippy::integer_arithmetic)]
 
 use {
-    crate::bigtable::RowKey,
+    crate::{
+        bigtable::{RowData, RowKey},
+        compression::{CompressionDictionaries, CompressionDictionary},
+    },
     log::*,
     serde::{Deserialize, Serialize},
     solana_metrics::{datapoint_info, inc_new_counter_debug},
@@ -24,6 +27,9 @@ use {
     std::{
         collections::{HashMap, HashSet},
         convert::TryInto,
+        path::PathBuf,
+        sync::{Arc, Mutex, RwLock},
+        time::{Duration, Instant},
     },
     thiserror::Error,
     tokio::task::JoinError,
@@ -36,10 +42,14 @@ extern crate solana_metrics;
 extern crate serde_derive;
 
 mod access_token;
+pub mod backend;
 mod bigtable;
 mod compression;
+mod local_storage;
 mod root_ca_certificate;
 
+pub use backend::LedgerStorageBackend;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("BigTable: {0}")]
@@ -384,6 +394,8 @@ pub struct LedgerStorageConfig {
     pub credential_type: CredentialType,
     pub instance_name: String,
     pub app_profile_id: String,
+    /// Keep the ledger in a local RocksDB database at this path instead of Bigtable
+    pub local_storage_path: Option<PathBuf>,
 }
 
 impl Default for LedgerStorageConfig {
@@ -394,13 +406,20 @@ impl Default for LedgerStorageConfig {
             credential_type: CredentialType::Filepath(None),
             instance_name: DEFAULT_INSTANCE_NAME.to_string(),
             app_profile_id: DEFAULT_APP_PROFILE_ID.to_string(),
+            local_storage_path: None,
         }
     }
 }
 
+/// How often uploaders reload the compression dictionaries, to start compressing with one
+/// trained since they were started
+const DICTIONARIES_RELOAD_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 #[derive(Clone)]
 pub struct LedgerStorage {
-    connection: bigtable::BigTableConnection,
+    backend: Arc<dyn LedgerStorageBackend>,
+    dictionaries: Arc<RwLock<CompressionDictionaries>>,
+    dictionaries_loaded: Arc<Mutex<Instant>>,
 }
 
 impl LedgerStorage {
@@ -425,7 +444,12 @@ impl LedgerStorage {
             instance_name,
             app_profile_id,
             credential_type,
+            local_storage_path,
         } = config;
+        if let Some(local_storage_path) = local_storage_path {
+            let local_storage = local_storage::LocalStorage::open(&local_storage_path, read_only)?;
+            return Ok(Self::new_with_backend(Arc::new(local_storage)).await);
+        }
         let connection = bigtable::BigTableConnection::new(
             instance_name.as_str(),
             app_profile_id.as_str(),
@@ -434,7 +458,21 @@ impl LedgerStorage {
             credential_type,
         )
         .await?;
-        Ok(Self { connection })
+        Ok(Self::new_with_backend(Arc::new(connection)).await)
+    }
+
+    /// Keep the ledger in any store implementing `LedgerStorageBackend`
+    pub async fn new_with_backend(backend: Arc<dyn LedgerStorageBackend>) -> Self {
+        let ledger_storage = Self {
+            backend,
+            dictionaries: Arc::default(),
+            dictionaries_loaded: Arc::new(Mutex::new(Instant::now())),
+        };
+        // Instances without a dictionaries table only hold cells compressed without one
+        if let Err(err) = ledger_storage.load_compression_dictionaries().await {
+            warn!("Failed to load the compression dictionaries: {}", err);
+        }
+        ledger_storage
     }
 
     pub async fn new_with_stringified_credential(credential: String) -> Result<Self> {
@@ -449,8 +487,7 @@ impl LedgerStorage {
     pub async fn get_first_available_block(&self) -> Result<Option<Slot>> {
         debug!("LedgerStorage::get_first_available_block request received");
         inc_new_counter_debug!("storage-bigtable-query", 1);
-        let mut bigtable = self.connection.client();
-        let blocks = bigtable.get_row_keys("blocks", None, None, 1).await?;
+        let blocks = self.backend.get_row_keys("blocks", None, None, 1).await?;
         if blocks.is_empty() {
             return Ok(None);
         }
@@ -467,8 +504,8 @@ impl LedgerStorage {
             start_slot, limit
         );
         inc_new_counter_debug!("storage-bigtable-query", 1);
-        let mut bigtable = self.connection.client();
-        let blocks = bigtable
+        let blocks = self
+            .backend
             .get_row_keys(
                 "blocks",
                 Some(slot_to_blocks_key(start_slot)),
@@ -489,9 +526,8 @@ impl LedgerStorage {
             slots
         );
         inc_new_counter_debug!("storage-bigtable-query", 1);
-        let mut bigtable = self.connection.client();
         let row_keys = slots.iter().copied().map(slot_to_blocks_key);
-        let data = bigtable
+        let data = self
             .get_protobuf_or_bincode_cells("blocks", row_keys)
             .await?
             .filter_map(
@@ -516,8 +552,7 @@ impl LedgerStorage {
             slot
         );
         inc_new_counter_debug!("storage-bigtable-query", 1);
-        let mut bigtable = self.connection.client();
-        let block_cell_data = bigtable
+        let block_cell_data = self
             .get_protobuf_or_bincode_cell::<StoredConfirmedBlock, generated::ConfirmedBlock>(
                 "blocks",
                 slot_to_blocks_key(slot),
@@ -542,9 +577,8 @@ impl LedgerStorage {
             slot
         );
         inc_new_counter_debug!("storage-bigtable-query", 1);
-        let mut bigtable = self.connection.client();
-
-        let block_exists = bigtable
+        let block_exists = self
+            .backend
             .row_key_exists("blocks", slot_to_blocks_key(slot))
             .await?;
 
@@ -557,8 +591,7 @@ impl LedgerStorage {
             signature
         );
         inc_new_counter_debug!("storage-bigtable-query", 1);
-        let mut bigtable = self.connection.client();
-        let transaction_info = bigtable
+        let transaction_info = self
             .get_bincode_cell::<TransactionInfo>("tx", signature.to_string())
             .await
             .map_err(|err| match err {
@@ -578,11 +611,10 @@ impl LedgerStorage {
             signatures
         );
         inc_new_counter_debug!("storage-bigtable-query", 1);
-        let mut bigtable = self.connection.client();
 
         // Fetch transactions info
         let keys = signatures.iter().map(|s| s.to_string()).collect::<Vec<_>>();
-        let cells = bigtable
+        let cells = self
             .get_bincode_cells::<TransactionInfo>("tx", &keys)
             .await?;
 
@@ -640,10 +672,9 @@ impl LedgerStorage {
             signature
         );
         inc_new_counter_debug!("storage-bigtable-query", 1);
-        let mut bigtable = self.connection.client();
 
         // Figure out which block the transaction is located in
-        let TransactionInfo { slot, index, .. } = bigtable
+        let TransactionInfo { slot, index, .. } = self
             .get_bincode_cell("tx", signature.to_string())
             .await
             .map_err(|err| match err {
@@ -700,14 +731,13 @@ impl LedgerStorage {
             address
         );
         inc_new_counter_debug!("storage-bigtable-query", 1);
-        let mut bigtable = self.connection.client();
         let address_prefix = format!("{}/", address);
 
         // Figure out where to start listing from based on `before_signature`
         let (first_slot, before_transaction_index) = match before_signature {
             None => (Slot::MAX, 0),
             Some(before_signature) => {
-                let TransactionInfo { slot, index, .. } = bigtable
+                let TransactionInfo { slot, index, .. } = self
                     .get_bincode_cell("tx", before_signature.to_string())
                     .await?;
 
@@ -719,7 +749,7 @@ impl LedgerStorage {
         let (last_slot, until_transaction_index) = match until_signature {
             None => (0, u32::MAX),
             Some(until_signature) => {
-                let TransactionInfo { slot, index, .. } = bigtable
+                let TransactionInfo { slot, index, .. } = self
                     .get_bincode_cell("tx", until_signature.to_string())
                     .await?;
 
@@ -729,7 +759,7 @@ impl LedgerStorage {
 
         let mut infos = vec![];
 
-        let starting_slot_tx_len = bigtable
+        let starting_slot_tx_len = self
             .get_protobuf_or_bincode_cell::<Vec<LegacyTransactionByAddrInfo>, tx_by_addr::TransactionByAddr>(
                 "tx-by-addr",
                 format!("{}{}", address_prefix, slot_to_tx_by_addr_key(first_slot)),
@@ -745,7 +775,8 @@ impl LedgerStorage {
 
         // Return the next tx-by-addr data of amount `limit` plus extra to account for the largest
         // number that might be flitered out
-        let tx_by_addr_data = bigtable
+        let tx_by_addr_data = self
+            .backend
             .get_row_data(
                 "tx-by-addr",
                 Some(format!(
@@ -761,7 +792,10 @@ impl LedgerStorage {
                 limit as i64 + starting_slot_tx_len as i64,
             )
             .await?;
+        self.load_missing_compression_dictionaries(tx_by_addr_data.iter().map(|(_, data)| data))
+            .await?;
 
+        let dictionaries = self.dictionaries.read().unwrap();
         'outer: for (row_key, data) in tx_by_addr_data {
             let slot = !key_to_slot(&row_key[address_prefix.len()..]).ok_or_else(|| {
                 bigtable::Error::ObjectCorrupt(format!(
@@ -770,10 +804,11 @@ impl LedgerStorage {
                 ))
             })?;
 
-            let deserialized_cell_data = bigtable::deserialize_protobuf_or_bincode_cell_data::<
-                Vec<LegacyTransactionByAddrInfo>,
-                tx_by_addr::TransactionByAddr,
-            >(&data, "tx-by-addr", row_key.clone())?;
+            let deserialized_cell_data =
+                bigtable::deserialize_protobuf_or_bincode_cell_data::<
+                    Vec<LegacyTransactionByAddrInfo>,
+                    tx_by_addr::TransactionByAddr,
+                >(&data, "tx-by-addr", row_key.clone(), &dictionaries)?;
 
             let mut cell_data: Vec<TransactionByAddrInfo> = match deserialized_cell_data {
                 bigtable::CellData::Bincode(tx_by_addr) => {
@@ -825,6 +860,7 @@ impl LedgerStorage {
         slot: Slot,
         confirmed_block: VersionedConfirmedBlock,
     ) -> Result<()> {
+        self.maybe_reload_compression_dictionaries().await;
         let mut by_addr: HashMap<&Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
 
         let mut tx_cells = vec![];
@@ -879,21 +915,23 @@ impl LedgerStorage {
         let mut tasks = vec![];
 
         if !tx_cells.is_empty() {
-            let conn = self.connection.clone();
+            let storage = self.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_bincode_cells_with_retry::<TransactionInfo>("tx", &tx_cells)
+                storage
+                    .put_bincode_cells::<TransactionInfo>("tx", &tx_cells)
                     .await
             }));
         }
 
         if !tx_by_addr_cells.is_empty() {
-            let conn = self.connection.clone();
+            let storage = self.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_protobuf_cells_with_retry::<tx_by_addr::TransactionByAddr>(
-                    "tx-by-addr",
-                    &tx_by_addr_cells,
-                )
-                .await
+                storage
+                    .put_protobuf_cells::<tx_by_addr::TransactionByAddr>(
+                        "tx-by-addr",
+                        &tx_by_addr_cells,
+                    )
+                    .await
             }));
         }
 
@@ -930,8 +968,7 @@ impl LedgerStorage {
         // `get_confirmed_block()` and `get_confirmed_blocks()`
         let blocks_cells = [(slot_to_blocks_key(slot), confirmed_block.into())];
         bytes_written += self
-            .connection
-            .put_protobuf_cells_with_retry::<generated::ConfirmedBlock>("blocks", &blocks_cells)
+            .put_protobuf_cells::<generated::ConfirmedBlock>("blocks", &blocks_cells)
             .await?;
         datapoint_info!(
             "storage-bigtable-upload-block",
@@ -993,8 +1030,7 @@ impl LedgerStorage {
         let tx_deletion_rows = if !expected_tx_infos.is_empty() {
             let signatures = expected_tx_infos.keys().cloned().collect::<Vec<_>>();
             let fetched_tx_infos: HashMap<String, std::result::Result<UploadedTransaction, _>> =
-                self.connection
-                    .get_bincode_cells_with_retry::<TransactionInfo>("tx", &signatures)
+                self.get_bincode_cells_with_retry::<TransactionInfo>("tx", &signatures)
                     .await?
                     .into_iter()
                     .map(|(signature, tx_info_res)| (signature, tx_info_res.map(Into::into)))
@@ -1032,19 +1068,17 @@ impl LedgerStorage {
 
         if !dry_run {
             if !address_slot_rows.is_empty() {
-                self.connection
-                    .delete_rows_with_retry("tx-by-addr", &address_slot_rows)
+                self.backend
+                    .delete_rows("tx-by-addr", &address_slot_rows)
                     .await?;
             }
 
             if !tx_deletion_rows.is_empty() {
-                self.connection
-                    .delete_rows_with_retry("tx", &tx_deletion_rows)
-                    .await?;
+                self.backend.delete_rows("tx", &tx_deletion_rows).await?;
             }
 
-            self.connection
-                .delete_rows_with_retry("blocks", &[slot_to_blocks_key(slot)])
+            self.backend
+                .delete_rows("blocks", &[slot_to_blocks_key(slot)])
                 .await?;
         }
 
@@ -1058,15 +1092,571 @@ impl LedgerStorage {
 
         Ok(())
     }
+
+    /// Train a compression dictionary on the blocks of up to `limit` slots starting at
+    /// `start_slot`, and store it as the dictionary new cells are compressed with. Returns the
+    /// id of the new dictionary.
+    pub async fn train_compression_dictionary(
+        &self,
+        start_slot: Slot,
+        limit: usize,
+        max_dictionary_size: usize,
+    ) -> Result<u32> {
+        self.load_compression_dictionaries().await?;
+        let rows = self
+            .backend
+            .get_row_data(
+                "blocks",
+                Some(slot_to_blocks_key(start_slot)),
+                None,
+                limit as i64,
+            )
+            .await?;
+        self.load_missing_compression_dictionaries(rows.iter().map(|(_, data)| data))
+            .await?;
+
+        let samples = {
+            let dictionaries = self.dictionaries.read().unwrap();
+            rows.iter()
+                .flat_map(|(_, row_data)| row_data.iter())
+                .map(|(_, value)| compression::decompress(value, &dictionaries))
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        };
+        info!(
+            "Training a compression dictionary on {} blocks ({} bytes)",
+            samples.len(),
+            samples.iter().map(Vec::len).sum::<usize>()
+        );
+        let dictionary = compression::train_dictionary(&samples, max_dictionary_size)?;
+
+        let id = self
+            .dictionaries
+            .read()
+            .unwrap()
+            .latest()
+            .map(|dictionary| dictionary.id() + 1)
+            .unwrap_or_default();
+        self.backend
+            .put_row_data(
+                "dictionaries",
+                "x",
+                &[(
+                    &dictionary_id_to_key(id),
+                    vec![("zstd".to_string(), dictionary.clone())],
+                )],
+            )
+            .await?;
+        self.dictionaries
+            .write()
+            .unwrap()
+            .insert(CompressionDictionary::new(id, &dictionary));
+        Ok(id)
+    }
+
+    /// Read the compression dictionaries stored so far
+    async fn load_compression_dictionaries(&self) -> bigtable::Result<()> {
+        let rows = self
+            .backend
+            .get_row_data("dictionaries", None, None, i64::MAX)
+            .await?;
+        let mut dictionaries = CompressionDictionaries::default();
+        for (row_key, row_data) in rows {
+            let id = u32::from_str_radix(&row_key, 16)
+                .map_err(|_| bigtable::Error::ObjectCorrupt(format!("dictionaries/{}", row_key)))?;
+            let (_, dictionary) = row_data
+                .iter()
+                .find(|(name, _)| name == "zstd")
+                .ok_or_else(|| {
+                    bigtable::Error::ObjectNotFound(format!("dictionaries/{}", row_key))
+                })?;
+            dictionaries.insert(CompressionDictionary::new(id, dictionary));
+        }
+        *self.dictionaries.write().unwrap() = dictionaries;
+        Ok(())
+    }
+
+    /// Reload the compression dictionaries every `DICTIONARIES_RELOAD_INTERVAL`, so that cells
+    /// get compressed with the latest dictionary even if it was trained by another process
+    async fn maybe_reload_compression_dictionaries(&self) {
+        {
+            let mut dictionaries_loaded = self.dictionaries_loaded.lock().unwrap();
+            if dictionaries_loaded.elapsed() < DICTIONARIES_RELOAD_INTERVAL {
+                return;
+            }
+            *dictionaries_loaded = Instant::now();
+        }
+        if let Err(err) = self.load_compression_dictionaries().await {
+            warn!("Failed to reload the compression dictionaries: {}", err);
+        }
+    }
+
+    /// Reload the compression dictionaries if any of the cells of `rows` were compressed with a
+    /// dictionary stored since they were last read
+    async fn load_missing_compression_dictionaries<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a RowData>,
+    ) -> bigtable::Result<()> {
+        let missing_dictionary = {
+            let dictionaries = self.dictionaries.read().unwrap();
+            rows.flat_map(|row_data| row_data.iter())
+                .filter_map(|(_, value)| compression::dictionary_id(value))
+                .any(|id| !dictionaries.contains(id))
+        };
+        if missing_dictionary {
+            self.load_compression_dictionaries().await?;
+        }
+        Ok(())
+    }
+
+    async fn get_bincode_cell<T>(&self, table: &str, key: RowKey) -> bigtable::Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let row_data = self.backend.get_single_row_data(table, key.clone()).await?;
+        self.load_missing_compression_dictionaries(std::iter::once(&row_data))
+            .await?;
+        bigtable::deserialize_bincode_cell_data(
+            &row_data,
+            table,
+            key,
+            &self.dictionaries.read().unwrap(),
+        )
+    }
+
+    async fn get_bincode_cells<T>(
+        &self,
+        table: &str,
+        keys: &[RowKey],
+    ) -> bigtable::Result<Vec<(RowKey, bigtable::Result<T>)>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let rows = self.backend.get_multi_row_data(table, keys).await?;
+        self.deserialize_bincode_cells(table, rows).await
+    }
+
+    async fn get_bincode_cells_with_retry<T>(
+        &self,
+        table: &str,
+        keys: &[RowKey],
+    ) -> bigtable::Result<Vec<(RowKey, bigtable::Result<T>)>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let rows = self
+            .backend
+            .get_multi_row_data_with_retry(table, keys)
+            .await?;
+        self.deserialize_bincode_cells(table, rows).await
+    }
+
+    async fn deserialize_bincode_cells<T>(
+        &self,
+        table: &str,
+        rows: Vec<(RowKey, RowData)>,
+    ) -> bigtable::Result<Vec<(RowKey, bigtable::Result<T>)>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.load_missing_compression_dictionaries(rows.iter().map(|(_, data)| data))
+            .await?;
+        let dictionaries = self.dictionaries.read().unwrap();
+        Ok(rows
+            .into_iter()
+            .map(|(key, row_data)| {
+                let key_str = key.to_string();
+                (
+                    key,
+                    bigtable::deserialize_bincode_cell_data(
+                        &row_data,
+                        table,
+                        key_str,
+                        &dictionaries,
+                    ),
+                )
+            })
+            .collect())
+    }
+
+    async fn get_protobuf_or_bincode_cell<B, P>(
+        &self,
+        table: &str,
+        key: RowKey,
+    ) -> bigtable::Result<bigtable::CellData<B, P>>
+    where
+        B: serde::de::DeserializeOwned,
+        P: prost::Message + Default,
+    {
+        let row_data = self.backend.get_single_row_data(table, key.clone()).await?;
+        self.load_missing_compression_dictionaries(std::iter::once(&row_data))
+            .await?;
+        bigtable::deserialize_protobuf_or_bincode_cell_data(
+            &row_data,
+            table,
+            key,
+            &self.dictionaries.read().unwrap(),
+        )
+    }
+
+    async fn get_protobuf_or_bincode_cells<B, P>(
+        &self,
+        table: &str,
+        row_keys: impl IntoIterator<Item = RowKey>,
+    ) -> bigtable::Result<impl Iterator<Item = (RowKey, bigtable::CellData<B, P>)>>
+    where
+        B: serde::de::DeserializeOwned,
+        P: prost::Message + Default,
+    {
+        let rows = self
+            .backend
+            .get_multi_row_data(
+                table,
+                row_keys.into_iter().collect::<Vec<RowKey>>().as_slice(),
+            )
+            .await?;
+        self.load_missing_compression_dictionaries(rows.iter().map(|(_, data)| data))
+            .await?;
+        let dictionaries = self.dictionaries.read().unwrap();
+        Ok(rows
+            .into_iter()
+            .map(|(key, row_data)| {
+                let key_str = key.to_string();
+                (
+                    key,
+                    bigtable::deserialize_protobuf_or_bincode_cell_data(
+                        &row_data,
+                        table,
+                        key_str,
+                        &dictionaries,
+                    )
+                    .unwrap(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .into_iter())
+    }
+
+    /// Compress `data` the best way, including with the latest compression dictionary
+    fn compress_cell(&self, data: &[u8]) -> bigtable::Result<Vec<u8>> {
+        let dictionaries = self.dictionaries.read().unwrap();
+        Ok(compression::compress_best(data, dictionaries.latest())?)
+    }
+
+    async fn put_bincode_cells<T>(
+        &self,
+        table: &str,
+        cells: &[(RowKey, T)],
+    ) -> bigtable::Result<usize>
+    where
+        T: serde::ser::Serialize,
+    {
+        let mut bytes_written = 0;
+        let mut new_row_data = vec![];
+        for (row_key, data) in cells {
+            let data = self.compress_cell(&bincode::serialize(&data).unwrap())?;
+            bytes_written += data.len();
+            new_row_data.push((row_key, vec![("bin".to_string(), data)]));
+        }
+
+        self.backend.put_row_data(table, "x", &new_row_data).await?;
+        Ok(bytes_written)
+    }
+
+    async fn put_protobuf_cells<T>(
+        &self,
+        table: &str,
+        cells: &[(RowKey, T)],
+    ) -> bigtable::Result<usize>
+    where
+        T: prost::Message,
+    {
+        let mut bytes_written = 0;
+        let mut new_row_data = vec![];
+        for (row_key, data) in cells {
+            let mut buf = Vec::with_capacity(data.encoded_len());
+            data.encode(&mut buf).unwrap();
+            let data = self.compress_cell(&buf)?;
+            bytes_written += data.len();
+            new_row_data.push((row_key, vec![("proto".to_string(), data)]));
+        }
+
+        self.backend.put_row_data(table, "x", &new_row_data).await?;
+        Ok(bytes_written)
+    }
+}
+
+fn dictionary_id_to_key(id: u32) -> RowKey {
+    format!("{:08x}", id)
 }
 
 #[cfg(test)]
 mod test {
-    use super::*;
+    use {
+        super::*,
+        solana_sdk::{hash::Hash, signature::Keypair, system_transaction},
+        tempfile::TempDir,
+    };
 
     #[test]
     fn test_slot_to_key() {
         assert_eq!(slot_to_key(0), "0000000000000000");
         assert_eq!(slot_to_key(!0), "ffffffffffffffff");
     }
+
+    fn transfer_block(slot: Slot, to: &Pubkey) -> VersionedConfirmedBlock {
+        let transaction = system_transaction::transfer(&Keypair::new(), to, 42, Hash::default());
+        VersionedConfirmedBlock {
+            previous_blockhash: Hash::new_unique().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot: slot.saturating_sub(1),
+            transactions: vec![VersionedTransactionWithStatusMeta {
+                transaction: VersionedTransaction::from(transaction),
+                meta: TransactionStatusMeta {
+                    pre_balances: vec![100, 0, 1],
+                    post_balances: vec![53, 42, 1],
+                    fee: 5,
+                    ..TransactionStatusMeta::default()
+                },
+            }],
+            rewards: vec![],
+            block_time: Some(slot as UnixTimestamp),
+            block_height: Some(slot),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compression_dictionary() {
+        let ledger_path = TempDir::new().unwrap();
+        let storage = LedgerStorage::new_with_config(LedgerStorageConfig {
+            read_only: false,
+            local_storage_path: Some(ledger_path.path().to_path_buf()),
+            ..LedgerStorageConfig::default()
+        })
+        .await
+        .unwrap();
+        let to = Pubkey::new_unique();
+        for slot in 0..200 {
+            storage
+                .upload_confirmed_block(slot, transfer_block(slot, &to))
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            storage
+                .train_compression_dictionary(0, 200, 4096)
+                .await
+                .unwrap(),
+            0
+        );
+
+        // New cells are compressed with the dictionary
+        let block = transfer_block(200, &to);
+        let signature = block.transactions[0].transaction.signatures[0];
+        storage.upload_confirmed_block(200, block).await.unwrap();
+        let row_data = storage
+            .backend
+            .get_single_row_data("blocks", slot_to_blocks_key(200))
+            .await
+            .unwrap();
+        assert_eq!(compression::dictionary_id(&row_data[0].1), Some(0));
+
+        // Readers started before the dictionary was trained load it when they meet it
+        let reader = LedgerStorage {
+            backend: storage.backend.clone(),
+            dictionaries: Arc::default(),
+            dictionaries_loaded: Arc::new(Mutex::new(Instant::now())),
+        };
+        let confirmed_block = reader.get_confirmed_block(200).await.unwrap();
+        assert_eq!(
+            confirmed_block.transactions[0].transaction_signature(),
+            &signature
+        );
+        assert_eq!(
+            reader.get_signature_status(&signature).await.unwrap().slot,
+            200
+        );
+        // Cells compressed without the dictionary are still readable
+        assert_eq!(
+            reader.get_confirmed_block(0).await.unwrap().block_height,
+            Some(0)
+        );
+
+        // Uploaders started before the dictionary was trained switch to it once they reload
+        let uploader = LedgerStorage {
+            backend: storage.backend.clone(),
+            dictionaries: Arc::default(),
+            dictionaries_loaded: Arc::new(Mutex::new(Instant::now())),
+        };
+        uploader
+            .upload_confirmed_block(201, transfer_block(201, &to))
+            .await
+            .unwrap();
+        let row_data = storage
+            .backend
+            .get_single_row_data("blocks", slot_to_blocks_key(201))
+            .await
+            .unwrap();
+        assert_eq!(compression::dictionary_id(&row_data[0].1), None);
+        *uploader.dictionaries_loaded.lock().unwrap() -= DICTIONARIES_RELOAD_INTERVAL;
+        uploader
+            .upload_confirmed_block(202, transfer_block(202, &to))
+            .await
+            .unwrap();
+        let row_data = storage
+            .backend
+            .get_single_row_data("blocks", slot_to_blocks_key(202))
+            .await
+            .unwrap();
+        assert_eq!(compression::dictionary_id(&row_data[0].1), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_local_storage() {
+        let ledger_path = TempDir::new().unwrap();
+        let storage = LedgerStorage::new_with_config(LedgerStorageConfig {
+            read_only: false,
+            local_storage_path: Some(ledger_path.path().to_path_buf()),
+            ..LedgerStorageConfig::default()
+        })
+        .await
+        .unwrap();
+        assert_eq!(storage.get_first_available_block().await.unwrap(), None);
+
+        let from = Keypair::new();
+        let to = Pubkey::new_unique();
+        let transaction = system_transaction::transfer(&from, &to, 42, Hash::default());
+        let signature = transaction.signatures[0];
+        let block = |slot: Slot| VersionedConfirmedBlock {
+            previous_blockhash: Hash::new_unique().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot: slot.saturating_sub(1),
+            transactions: if slot == 3 {
+                vec![VersionedTransactionWithStatusMeta {
+                    transaction: VersionedTransaction::from(transaction.clone()),
+                    meta: TransactionStatusMeta {
+                        pre_balances: vec![100, 0, 1],
+                        post_balances: vec![53, 42, 1],
+                        fee: 5,
+                        ..TransactionStatusMeta::default()
+                    },
+                }]
+            } else {
+                vec![]
+            },
+            rewards: vec![],
+            block_time: Some(slot as UnixTimestamp),
+            block_height: Some(slot),
+        };
+        for slot in [1, 3, 4] {
+            storage
+                .upload_confirmed_block(slot, block(slot))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(storage.get_first_available_block().await.unwrap(), Some(1));
+        assert_eq!(
+            storage.get_confirmed_blocks(2, 10).await.unwrap(),
+            vec![3, 4]
+        );
+        assert!(storage.confirmed_block_exists(4).await.unwrap());
+        assert!(!storage.confirmed_block_exists(2).await.unwrap());
+        let confirmed_block = storage.get_confirmed_block(3).await.unwrap();
+        assert_eq!(confirmed_block.transactions.len(), 1);
+        assert_eq!(
+            confirmed_block.transactions[0].transaction_signature(),
+            &signature
+        );
+
+        let status = storage.get_signature_status(&signature).await.unwrap();
+        assert_eq!(status.slot, 3);
+        assert_eq!(status.status, Ok(()));
+        let signatures = storage
+            .get_confirmed_signatures_for_address(&to, None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(signatures[0].0.signature, signature);
+
+        storage.delete_confirmed_block(3, false).await.unwrap();
+        assert!(!storage.confirmed_block_exists(3).await.unwrap());
+        assert!(matches!(
+            storage.get_signature_status(&signature).await,
+            Err(Error::SignatureNotFound)
+        ));
+        assert!(storage
+            .get_confirmed_signatures_for_address(&to, None, None, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_local_storage_merges_rows_written_together() {
+        let ledger_path = TempDir::new().unwrap();
+        let local_storage = local_storage::LocalStorage::open(ledger_path.path(), false).unwrap();
+        let row_key = "row".to_string();
+        let cell = |name: &str, value: u8| (name.to_string(), vec![value]);
+        local_storage
+            .put_row_data("tx", "x", &[(&row_key, vec![cell("a", 0), cell("b", 0)])])
+            .await
+            .unwrap();
+        local_storage
+            .put_row_data(
+                "tx",
+                "x",
+                &[
+                    (&row_key, vec![cell("b", 1)]),
+                    (&row_key, vec![cell("c", 1), cell("b", 2)]),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            local_storage
+                .get_single_row_data("tx", row_key)
+                .await
+                .unwrap(),
+            vec![cell("a", 0), cell("b", 2), cell("c", 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_storage_read_only_follows_uploader() {
+        let ledger_path = TempDir::new().unwrap();
+        let uploader = LedgerStorage::new_with_config(LedgerStorageConfig {
+            read_only: false,
+            local_storage_path: Some(ledger_path.path().to_path_buf()),
+            ..LedgerStorageConfig::default()
+        })
+        .await
+        .unwrap();
+        let to = Pubkey::new_unique();
+        uploader
+            .upload_confirmed_block(1, transfer_block(1, &to))
+            .await
+            .unwrap();
+
+        let reader = LedgerStorage::new_with_config(LedgerStorageConfig {
+            read_only: true,
+            local_storage_path: Some(ledger_path.path().to_path_buf()),
+            ..LedgerStorageConfig::default()
+        })
+        .await
+        .unwrap();
+        assert!(reader.confirmed_block_exists(1).await.unwrap());
+        assert!(!reader.confirmed_block_exists(2).await.unwrap());
+
+        // Blocks uploaded after the reader opened the database show up once it catches up
+        uploader
+            .upload_confirmed_block(2, transfer_block(2, &to))
+            .await
+            .unwrap();
+        std::thread::sleep(local_storage::CATCH_UP_INTERVAL);
+        assert!(reader.confirmed_block_exists(2).await.unwrap());
+        assert_eq!(
+            reader.get_confirmed_blocks(0, 10).await.unwrap(),
+            vec![1, 2]
+        );
+    }
 }
@@ -0,0 +1,308 @@
+// A `LedgerStorageBackend` keeping the ledger tables in a local RocksDB database
+
+use {
+    crate::{
+        backend::LedgerStorageBackend,
+        bigtable::{Error, Result, RowData, RowKey},
+    },
+    async_trait::async_trait,
+    log::*,
+    rocksdb::{ColumnFamily, Direction, IteratorMode, Options, WriteBatch, DB},
+    std::{
+        collections::{hash_map::Entry, HashMap},
+        path::Path,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+};
+
+/// The tables `LedgerStorage` reads and writes, one column family each
+const TABLES: &[&str] = &["blocks", "tx", "tx-by-addr", "dictionaries"];
+
+/// How often a read-only instance picks up the rows written by the instance
+/// uploading to the database
+pub(crate) const CATCH_UP_INTERVAL: Duration = Duration::from_secs(1);
+
+impl std::convert::From<rocksdb::Error> for Error {
+    fn from(err: rocksdb::Error) -> Self {
+        Self::LocalStorage(err.into_string())
+    }
+}
+
+impl std::convert::From<bincode::Error> for Error {
+    fn from(err: bincode::Error) -> Self {
+        Self::LocalStorage(err.to_string())
+    }
+}
+
+/// Stores each table row under its row key, as the bincode encoding of its cells
+///
+/// A read-only instance opens the database as a RocksDB secondary instance, so
+/// it can follow another process uploading to the same database. It catches up
+/// with the uploader at most every `CATCH_UP_INTERVAL`, before a read.
+///
+/// RocksDB calls block, so they run on tokio's blocking thread pool rather than
+/// on the runtime of the caller.
+pub struct LocalStorage {
+    db: Arc<LocalDb>,
+}
+
+struct LocalDb {
+    db: DB,
+    /// When a read-only instance last caught up with the uploading instance
+    last_catch_up: Option<Mutex<Instant>>,
+    /// Held while a write reads the rows it updates and writes them back, so
+    /// concurrent writes to the same row do not lose each other's cells
+    write_lock: Mutex<()>,
+}
+
+impl LocalStorage {
+    pub fn open(path: &Path, read_only: bool) -> Result<Self> {
+        Ok(Self {
+            db: Arc::new(LocalDb::open(path, read_only)?),
+        })
+    }
+
+    /// Run `f` with the database on the blocking thread pool
+    async fn with_db<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&LocalDb) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let db = Arc::clone(&self.db);
+        tokio::task::spawn_blocking(move || f(&db))
+            .await
+            .map_err(|err| Error::LocalStorage(err.to_string()))?
+    }
+}
+
+impl LocalDb {
+    fn open(path: &Path, read_only: bool) -> Result<Self> {
+        info!(
+            "Opening local ledger storage at {:?} ({})",
+            path,
+            if read_only { "read-only" } else { "read-write" }
+        );
+        if read_only {
+            let secondary_path = path.join("solana-secondary");
+            let mut options = Options::default();
+            // Required for a secondary instance,
+            // see https://github.com/facebook/rocksdb/wiki/Secondary-instance
+            options.set_max_open_files(-1);
+            let db = DB::open_cf_as_secondary(&options, path, &secondary_path, TABLES)?;
+            Ok(Self {
+                db,
+                last_catch_up: Some(Mutex::new(Instant::now())),
+                write_lock: Mutex::default(),
+            })
+        } else {
+            let mut options = Options::default();
+            options.create_if_missing(true);
+            options.create_missing_column_families(true);
+            let db = DB::open_cf(&options, path, TABLES)?;
+            Ok(Self {
+                db,
+                last_catch_up: None,
+                write_lock: Mutex::default(),
+            })
+        }
+    }
+
+    /// Pick up the rows written by the uploading instance since the last catch up,
+    /// if it was long enough ago
+    fn maybe_catch_up_with_primary(&self) -> Result<()> {
+        // Skip it if another reader is catching up already
+        if let Some(Ok(mut last_catch_up)) = self.last_catch_up.as_ref().map(Mutex::try_lock) {
+            if last_catch_up.elapsed() >= CATCH_UP_INTERVAL {
+                self.db.try_catch_up_with_primary()?;
+                *last_catch_up = Instant::now();
+            }
+        }
+        Ok(())
+    }
+
+    fn table(&self, table_name: &str) -> Result<&ColumnFamily> {
+        self.maybe_catch_up_with_primary()?;
+        self.db
+            .cf_handle(table_name)
+            .ok_or_else(|| Error::LocalStorage(format!("Unknown table: {}", table_name)))
+    }
+
+    fn get_row(&self, table: &ColumnFamily, row_key: &str) -> Result<Option<RowData>> {
+        self.db
+            .get_cf(table, row_key)?
+            .map(|value| bincode::deserialize(&value).map_err(Error::from))
+            .transpose()
+    }
+
+    /// Visit the rows of `table_name` in row key order, from `start_at` through `end_at`
+    /// inclusive, until `visit` returns false
+    fn scan_rows(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        mut visit: impl FnMut(RowKey, &[u8]) -> Result<bool>,
+    ) -> Result<()> {
+        let table = self.table(table_name)?;
+        let mode = match &start_at {
+            Some(start_at) => IteratorMode::From(start_at.as_bytes(), Direction::Forward),
+            None => IteratorMode::Start,
+        };
+        for item in self.db.iterator_cf(table, mode) {
+            let (key, value) = item?;
+            let row_key = String::from_utf8(key.into_vec())
+                .map_err(|err| Error::LocalStorage(err.to_string()))?;
+            if matches!(&end_at, Some(end_at) if &row_key > end_at) {
+                break;
+            }
+            if !visit(row_key, &value)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Like Bigtable, only replace the cells being written. Cells written to the
+    /// same row twice in `row_data` are merged, the later value winning.
+    fn put_row_data(&self, table_name: &str, row_data: Vec<(RowKey, RowData)>) -> Result<()> {
+        let table = self.table(table_name)?;
+        let _write_lock = self.write_lock.lock().unwrap();
+        let mut rows = HashMap::<RowKey, RowData>::with_capacity(row_data.len());
+        for (row_key, cells) in row_data {
+            let row = match rows.entry(row_key) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let row = self.get_row(table, entry.key())?.unwrap_or_default();
+                    entry.insert(row)
+                }
+            };
+            for (cell_name, cell_value) in cells {
+                match row.iter_mut().find(|(name, _)| *name == cell_name) {
+                    Some((_, value)) => *value = cell_value,
+                    None => row.push((cell_name, cell_value)),
+                }
+            }
+        }
+        let mut batch = WriteBatch::default();
+        for (row_key, row) in rows {
+            batch.put_cf(table, row_key, bincode::serialize(&row)?);
+        }
+        self.db.write(batch)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LedgerStorageBackend for LocalStorage {
+    async fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>> {
+        if rows_limit <= 0 {
+            return Ok(vec![]);
+        }
+        let table_name = table_name.to_string();
+        self.with_db(move |db| {
+            let mut row_keys = vec![];
+            db.scan_rows(&table_name, start_at, end_at, |row_key, _| {
+                row_keys.push(row_key);
+                Ok((row_keys.len() as i64) < rows_limit)
+            })?;
+            Ok(row_keys)
+        })
+        .await
+    }
+
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool> {
+        let table_name = table_name.to_string();
+        self.with_db(move |db| {
+            let table = db.table(&table_name)?;
+            Ok(db.db.get_pinned_cf(table, row_key)?.is_some())
+        })
+        .await
+    }
+
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        if rows_limit <= 0 {
+            return Ok(vec![]);
+        }
+        let table_name = table_name.to_string();
+        self.with_db(move |db| {
+            let mut rows = vec![];
+            db.scan_rows(&table_name, start_at, end_at, |row_key, value| {
+                rows.push((row_key, bincode::deserialize(value)?));
+                Ok((rows.len() as i64) < rows_limit)
+            })?;
+            Ok(rows)
+        })
+        .await
+    }
+
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let table_name = table_name.to_string();
+        let row_keys = row_keys.to_vec();
+        self.with_db(move |db| {
+            let table = db.table(&table_name)?;
+            let mut rows = vec![];
+            for row_key in row_keys {
+                if let Some(row_data) = db.get_row(table, &row_key)? {
+                    rows.push((row_key, row_data));
+                }
+            }
+            Ok(rows)
+        })
+        .await
+    }
+
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData> {
+        let table_name = table_name.to_string();
+        self.with_db(move |db| {
+            let table = db.table(&table_name)?;
+            db.get_row(table, &row_key)?.ok_or(Error::RowNotFound)
+        })
+        .await
+    }
+
+    async fn put_row_data(
+        &self,
+        table_name: &str,
+        _family_name: &str,
+        row_data: &[(&RowKey, RowData)],
+    ) -> Result<()> {
+        let table_name = table_name.to_string();
+        let row_data = row_data
+            .iter()
+            .map(|(row_key, cells)| ((*row_key).clone(), cells.clone()))
+            .collect::<Vec<_>>();
+        self.with_db(move |db| db.put_row_data(&table_name, row_data))
+            .await
+    }
+
+    async fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()> {
+        let table_name = table_name.to_string();
+        let row_keys = row_keys.to_vec();
+        self.with_db(move |db| {
+            let table = db.table(&table_name)?;
+            let mut batch = WriteBatch::default();
+            for row_key in row_keys {
+                batch.delete_cf(table, row_key);
+            }
+            db.db.write(batch)?;
+            Ok(())
+        })
+        .await
+    }
+}
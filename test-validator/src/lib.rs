@@ -6,8 +6,11 @@ use {
     solana_client::rpc_request::MAX_MULTIPLE_ACCOUNTS,
     solana_core::{
         tower_storage::TowerStorage,
-        validator::{Validator, ValidatorConfig, ValidatorStartProgress},
+        validator::{
+            AdjustableValidatorConfig, Validator, ValidatorConfig, ValidatorStartProgress,
+        },
     },
+    solana_geyser_plugin_manager::geyser_plugin_manager::GeyserPluginManager,
     solana_gossip::{
         cluster_info::{ClusterInfo, Node},
         gossip_service::discover_cluster,
@@ -22,7 +25,8 @@ use {
     solana_rpc::{rpc::JsonRpcConfig, rpc_pubsub_service::PubSubConfig},
     solana_rpc_client::{nonblocking, rpc_client::RpcClient},
     solana_runtime::{
-        accounts_db::AccountsDbConfig, accounts_index::AccountsIndexConfig, bank_forks::BankForks,
+        accounts_background_service::AbsRequestSender, accounts_db::AccountsDbConfig,
+        accounts_index::AccountsIndexConfig, bank_forks::BankForks,
         genesis_utils::create_genesis_config_with_leader_ex,
         hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE, runtime_config::RuntimeConfig,
         snapshot_config::SnapshotConfig,
@@ -976,6 +980,22 @@ impl TestValidator {
     pub fn bank_forks(&self) -> Arc<RwLock<BankForks>> {
         self.validator.as_ref().unwrap().bank_forks.clone()
     }
+
+    pub fn adjustable_config(&self) -> AdjustableValidatorConfig {
+        self.validator.as_ref().unwrap().adjustable_config.clone()
+    }
+
+    pub fn accounts_background_request_sender(&self) -> AbsRequestSender {
+        self.validator
+            .as_ref()
+            .unwrap()
+            .accounts_background_request_sender
+            .clone()
+    }
+
+    pub fn geyser_plugin_manager(&self) -> Option<Arc<RwLock<GeyserPluginManager>>> {
+        self.validator.as_ref().unwrap().geyser_plugin_manager()
+    }
 }
 
 impl Drop for TestValidator {
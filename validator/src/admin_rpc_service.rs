@@ -7,22 +7,37 @@ use {
     log::*,
     serde::{de::Deserializer, Deserialize, Serialize},
     solana_core::{
-        consensus::Tower, tower_storage::TowerStorage, validator::ValidatorStartProgress,
+        consensus::Tower,
+        ledger_cleanup_service::DEFAULT_MIN_MAX_LEDGER_SHREDS,
+        tower_storage::TowerStorage,
+        validator::{AdjustableValidatorConfig, ValidatorStartProgress},
     },
+    solana_geyser_plugin_manager::geyser_plugin_manager::GeyserPluginManager,
     solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
-    solana_runtime::bank_forks::BankForks,
+    solana_runtime::{
+        accounts_background_service::{AbsRequestSender, OnDemandSnapshotKind},
+        bank_forks::BankForks,
+        program_stats::{ProgramStats, ProgramStatsReport},
+        snapshot_archive_info::SnapshotArchiveInfoGetter,
+        snapshot_utils,
+    },
     solana_sdk::{
+        clock::Slot,
         exit::Exit,
         pubkey::Pubkey,
         signature::{read_keypair_file, Keypair, Signer},
     },
+    solana_send_transaction_service::send_transaction_service::{
+        self, MAX_BATCH_SEND_RATE_MS, MAX_TRANSACTION_BATCH_SIZE, MAX_TRANSACTION_SENDS_PER_SECOND,
+    },
     std::{
-        collections::HashMap,
+        collections::{HashMap, HashSet},
         error,
         fmt::{self, Display},
         net::SocketAddr,
         path::{Path, PathBuf},
-        sync::{Arc, RwLock},
+        str::FromStr,
+        sync::{atomic::Ordering, Arc, RwLock},
         thread::{self, Builder},
         time::{Duration, SystemTime},
     },
@@ -33,6 +48,9 @@ pub struct AdminRpcRequestMetadataPostInit {
     pub cluster_info: Arc<ClusterInfo>,
     pub bank_forks: Arc<RwLock<BankForks>>,
     pub vote_account: Pubkey,
+    pub geyser_plugin_manager: Option<Arc<RwLock<GeyserPluginManager>>>,
+    pub accounts_background_request_sender: AbsRequestSender,
+    pub adjustable_config: AdjustableValidatorConfig,
 }
 
 #[derive(Clone)]
@@ -44,6 +62,8 @@ pub struct AdminRpcRequestMetadata {
     pub authorized_voter_keypairs: Arc<RwLock<Vec<Arc<Keypair>>>>,
     pub tower_storage: Arc<dyn TowerStorage>,
     pub staked_nodes_overrides: Arc<RwLock<HashMap<Pubkey, u64>>>,
+    /// `None` if the validator does not download snapshots while starting up
+    pub minimal_snapshot_download_speed: Option<Arc<RwLock<f32>>>,
     pub post_init: Arc<RwLock<Option<AdminRpcRequestMetadataPostInit>>>,
 }
 impl Metadata for AdminRpcRequestMetadata {}
@@ -61,6 +81,22 @@ impl AdminRpcRequestMetadata {
             ))
         }
     }
+
+    fn with_geyser_plugin_manager<F, R>(&self, func: F) -> Result<R>
+    where
+        F: FnOnce(&RwLock<GeyserPluginManager>) -> Result<R>,
+    {
+        self.with_post_init(|post_init| {
+            if let Some(geyser_plugin_manager) = post_init.geyser_plugin_manager.as_ref() {
+                func(geyser_plugin_manager)
+            } else {
+                Err(jsonrpc_core::error::Error::invalid_params(
+                    "Geyser plugins are not enabled, restart the validator with \
+                     --geyser-plugin-config or --geyser-plugin-always-enabled",
+                ))
+            }
+        })
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -133,6 +169,172 @@ impl Display for AdminRpcContactInfo {
     }
 }
 
+/// The settings `setConfig` can change, as reported by `getConfig`
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcConfig {
+    /// `None` if the validator does not download snapshots while starting up
+    pub minimal_snapshot_download_speed: Option<f32>,
+    /// `None` until validator start up is complete
+    pub validator: Option<AdminRpcValidatorConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcValidatorConfig {
+    pub send_transaction_service: AdminRpcSendTransactionServiceConfig,
+    /// `None` to repair from all validators
+    pub repair_validators: Option<Vec<String>>,
+    /// `None` to trust all validators
+    pub known_validators: Option<Vec<String>>,
+    /// `None` if ledger cleanup is disabled
+    pub max_ledger_shreds: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcSendTransactionServiceConfig {
+    pub retry_rate_ms: u64,
+    pub leader_forward_count: u64,
+    pub default_max_retries: Option<usize>,
+    pub service_max_retries: usize,
+    pub batch_size: usize,
+    pub batch_send_rate_ms: u64,
+}
+
+impl From<&send_transaction_service::Config> for AdminRpcSendTransactionServiceConfig {
+    fn from(config: &send_transaction_service::Config) -> Self {
+        Self {
+            retry_rate_ms: config.retry_rate_ms,
+            leader_forward_count: config.leader_forward_count,
+            default_max_retries: config.default_max_retries,
+            service_max_retries: config.service_max_retries,
+            batch_size: config.batch_size,
+            batch_send_rate_ms: config.batch_send_rate_ms,
+        }
+    }
+}
+
+/// A single `setConfig` change, such as `{"name": "maxLedgerShreds", "value": 100000000}`
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "name", content = "value")]
+pub enum AdminRpcSetting {
+    MinimalSnapshotDownloadSpeed(f32),
+    SendTransactionRetryRateMs(u64),
+    SendTransactionLeaderForwardCount(u64),
+    SendTransactionDefaultMaxRetries(Option<usize>),
+    SendTransactionServiceMaxRetries(usize),
+    SendTransactionBatchSize(usize),
+    SendTransactionBatchSendRateMs(u64),
+    RepairValidators(Option<Vec<String>>),
+    KnownValidators(Option<Vec<String>>),
+    MaxLedgerShreds(u64),
+}
+
+/// Progress of a snapshot requested with `createSnapshot`
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum AdminRpcSnapshotStatus {
+    /// Waiting for AccountsBackgroundService to take the bank snapshot
+    Pending,
+    /// The bank snapshot is taken and waiting to be archived
+    Packaging,
+    Archived {
+        path: PathBuf,
+    },
+    /// A snapshot of a later slot was archived instead, or the archive was already purged
+    Superseded {
+        path: PathBuf,
+    },
+}
+
+impl Display for AdminRpcSnapshotStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Pending => write!(f, "Pending"),
+            Self::Packaging => write!(f, "Packaging"),
+            Self::Archived { path } => write!(f, "Archived to {}", path.display()),
+            Self::Superseded { path } => {
+                write!(f, "Superseded by {}", path.display())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcProgramStats {
+    pub program_id: String,
+    #[serde(flatten)]
+    pub stats: ProgramStats,
+    pub read_cache_hit_rate: Option<f64>,
+}
+
+/// The most recent per-program stats collected by the validator
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcProgramStatsReport {
+    pub slot: Slot,
+    /// How long the writes and read cache lookups were counted for
+    pub access_window_secs: f64,
+    pub programs: Vec<AdminRpcProgramStats>,
+}
+
+impl AdminRpcProgramStatsReport {
+    fn new(report: &ProgramStatsReport, limit: Option<usize>) -> Self {
+        Self {
+            slot: report.slot,
+            access_window_secs: report.access_window.as_secs_f64(),
+            programs: report
+                .programs
+                .iter()
+                .take(limit.unwrap_or(usize::MAX))
+                .map(|(program_id, stats)| AdminRpcProgramStats {
+                    program_id: program_id.to_string(),
+                    stats: *stats,
+                    read_cache_hit_rate: stats.read_cache_hit_rate(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Display for AdminRpcProgramStatsReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Program stats at slot {}, writes and read cache lookups over the last {:.1}s",
+            self.slot, self.access_window_secs
+        )?;
+        writeln!(
+            f,
+            "{:<44} {:>12} {:>16} {:>22} {:>12} {:>14}",
+            "Program Id",
+            "Accounts",
+            "Data Bytes",
+            "Rent Exempt Lamports",
+            "Writes",
+            "Read Cache Hit"
+        )?;
+        for program in &self.programs {
+            writeln!(
+                f,
+                "{:<44} {:>12} {:>16} {:>22} {:>12} {:>14}",
+                program.program_id,
+                program.stats.accounts,
+                program.stats.data_bytes,
+                program.stats.rent_exempt_lamports,
+                program.stats.writes,
+                program
+                    .read_cache_hit_rate
+                    .map(|rate| format!("{:.1}%", rate * 100.))
+                    .unwrap_or_else(|| "-".to_string()),
+            )?;
+        }
+        Ok(())
+    }
+}
+
 #[rpc]
 pub trait AdminRpc {
     type Metadata;
@@ -183,6 +385,53 @@ pub trait AdminRpc {
 
     #[rpc(meta, name = "contactInfo")]
     fn contact_info(&self, meta: Self::Metadata) -> Result<AdminRpcContactInfo>;
+
+    #[rpc(meta, name = "listPlugins")]
+    fn list_plugins(&self, meta: Self::Metadata) -> Result<Vec<String>>;
+
+    #[rpc(meta, name = "loadPlugin")]
+    fn load_plugin(&self, meta: Self::Metadata, config_file: String) -> Result<String>;
+
+    #[rpc(meta, name = "unloadPlugin")]
+    fn unload_plugin(&self, meta: Self::Metadata, name: String) -> Result<()>;
+
+    #[rpc(meta, name = "reloadPlugin")]
+    fn reload_plugin(&self, meta: Self::Metadata, name: String) -> Result<()>;
+
+    #[rpc(meta, name = "createSnapshot")]
+    fn create_snapshot(&self, meta: Self::Metadata, kind: OnDemandSnapshotKind) -> Result<Slot>;
+
+    #[rpc(meta, name = "addRepairValidators")]
+    fn add_repair_validators(&self, meta: Self::Metadata, validators: Vec<String>) -> Result<()>;
+
+    #[rpc(meta, name = "removeRepairValidators")]
+    fn remove_repair_validators(&self, meta: Self::Metadata, validators: Vec<String>)
+        -> Result<()>;
+
+    #[rpc(meta, name = "listRepairDenylist")]
+    fn list_repair_denylist(&self, meta: Self::Metadata) -> Result<Vec<String>>;
+
+    #[rpc(meta, name = "addRepairDenylist")]
+    fn add_repair_denylist(&self, meta: Self::Metadata, peers: Vec<String>) -> Result<()>;
+
+    #[rpc(meta, name = "removeRepairDenylist")]
+    fn remove_repair_denylist(&self, meta: Self::Metadata, peers: Vec<String>) -> Result<()>;
+
+    #[rpc(meta, name = "getConfig")]
+    fn get_config(&self, meta: Self::Metadata) -> Result<AdminRpcConfig>;
+
+    #[rpc(meta, name = "setConfig")]
+    fn set_config(&self, meta: Self::Metadata, settings: Vec<AdminRpcSetting>) -> Result<()>;
+
+    #[rpc(meta, name = "snapshotStatus")]
+    fn snapshot_status(&self, meta: Self::Metadata, slot: Slot) -> Result<AdminRpcSnapshotStatus>;
+
+    #[rpc(meta, name = "programStats")]
+    fn program_stats(
+        &self,
+        meta: Self::Metadata,
+        limit: Option<usize>,
+    ) -> Result<Option<AdminRpcProgramStatsReport>>;
 }
 
 pub struct AdminRpcImpl;
@@ -324,6 +573,409 @@ impl AdminRpc for AdminRpcImpl {
     fn contact_info(&self, meta: Self::Metadata) -> Result<AdminRpcContactInfo> {
         meta.with_post_init(|post_init| Ok(post_init.cluster_info.my_contact_info().into()))
     }
+
+    fn list_plugins(&self, meta: Self::Metadata) -> Result<Vec<String>> {
+        debug!("list_plugins request received");
+        meta.with_geyser_plugin_manager(|geyser_plugin_manager| {
+            Ok(geyser_plugin_manager.read().unwrap().list_plugins())
+        })
+    }
+
+    fn load_plugin(&self, meta: Self::Metadata, config_file: String) -> Result<String> {
+        debug!("load_plugin request received");
+        meta.with_geyser_plugin_manager(|geyser_plugin_manager| {
+            let name = geyser_plugin_manager
+                .write()
+                .unwrap()
+                .load_plugin_from_config(Path::new(&config_file))
+                .map_err(|err| {
+                    jsonrpc_core::error::Error::invalid_params(format!(
+                        "Failed to load the Geyser plugin from {}: {:?}",
+                        config_file, err
+                    ))
+                })?;
+            info!("Geyser plugin {} loaded from {}", name, config_file);
+            Ok(name)
+        })
+    }
+
+    fn unload_plugin(&self, meta: Self::Metadata, name: String) -> Result<()> {
+        debug!("unload_plugin request received");
+        meta.with_geyser_plugin_manager(|geyser_plugin_manager| {
+            geyser_plugin_manager
+                .write()
+                .unwrap()
+                .unload_plugin(&name)
+                .map_err(|err| {
+                    jsonrpc_core::error::Error::invalid_params(format!(
+                        "Failed to unload the Geyser plugin {}: {:?}",
+                        name, err
+                    ))
+                })?;
+            info!("Geyser plugin {} unloaded", name);
+            Ok(())
+        })
+    }
+
+    fn reload_plugin(&self, meta: Self::Metadata, name: String) -> Result<()> {
+        debug!("reload_plugin request received");
+        meta.with_geyser_plugin_manager(|geyser_plugin_manager| {
+            geyser_plugin_manager
+                .write()
+                .unwrap()
+                .reload_plugin(&name)
+                .map_err(|err| {
+                    jsonrpc_core::error::Error::invalid_params(format!(
+                        "Failed to reload the Geyser plugin {}: {:?}",
+                        name, err
+                    ))
+                })?;
+            info!("Geyser plugin {} reloaded", name);
+            Ok(())
+        })
+    }
+
+    fn create_snapshot(&self, meta: Self::Metadata, kind: OnDemandSnapshotKind) -> Result<Slot> {
+        debug!("create_snapshot request received");
+        meta.with_post_init(|post_init| {
+            post_init
+                .bank_forks
+                .read()
+                .unwrap()
+                .request_on_demand_snapshot(kind, &post_init.accounts_background_request_sender)
+                .map_err(|err| {
+                    jsonrpc_core::error::Error::invalid_params(format!(
+                        "Failed to request a snapshot: {}",
+                        err
+                    ))
+                })
+        })
+    }
+
+    fn add_repair_validators(&self, meta: Self::Metadata, validators: Vec<String>) -> Result<()> {
+        debug!("add_repair_validators request received");
+        let validators = parse_pubkeys(&validators)?;
+        meta.with_post_init(|post_init| {
+            update_repair_validators(&post_init.adjustable_config, |repair_validators| {
+                // Repairing from all validators becomes repairing from the ones given only
+                repair_validators
+                    .get_or_insert_with(HashSet::default)
+                    .extend(&validators);
+                Ok(())
+            })
+        })
+    }
+
+    fn remove_repair_validators(
+        &self,
+        meta: Self::Metadata,
+        validators: Vec<String>,
+    ) -> Result<()> {
+        debug!("remove_repair_validators request received");
+        let validators = parse_pubkeys(&validators)?;
+        meta.with_post_init(|post_init| {
+            update_repair_validators(&post_init.adjustable_config, |repair_validators| {
+                repair_validators
+                    .as_mut()
+                    .ok_or_else(|| {
+                        jsonrpc_core::error::Error::invalid_params(
+                            "Repair is not restricted to a set of validators",
+                        )
+                    })?
+                    .retain(|validator| !validators.contains(validator));
+                Ok(())
+            })
+        })
+    }
+
+    fn list_repair_denylist(&self, meta: Self::Metadata) -> Result<Vec<String>> {
+        debug!("list_repair_denylist request received");
+        meta.with_post_init(|post_init| {
+            Ok(pubkeys_to_strings(
+                &post_init.adjustable_config.repair_denylist.read().unwrap(),
+            ))
+        })
+    }
+
+    fn add_repair_denylist(&self, meta: Self::Metadata, peers: Vec<String>) -> Result<()> {
+        debug!("add_repair_denylist request received");
+        let peers = parse_pubkeys(&peers)?;
+        meta.with_post_init(|post_init| {
+            post_init
+                .adjustable_config
+                .repair_denylist
+                .write()
+                .unwrap()
+                .extend(&peers);
+            info!("Repair denylist peers added: {:?}", peers);
+            Ok(())
+        })
+    }
+
+    fn remove_repair_denylist(&self, meta: Self::Metadata, peers: Vec<String>) -> Result<()> {
+        debug!("remove_repair_denylist request received");
+        let peers = parse_pubkeys(&peers)?;
+        meta.with_post_init(|post_init| {
+            post_init
+                .adjustable_config
+                .repair_denylist
+                .write()
+                .unwrap()
+                .retain(|peer| !peers.contains(peer));
+            info!("Repair denylist peers removed: {:?}", peers);
+            Ok(())
+        })
+    }
+
+    fn get_config(&self, meta: Self::Metadata) -> Result<AdminRpcConfig> {
+        debug!("get_config request received");
+        let minimal_snapshot_download_speed = meta
+            .minimal_snapshot_download_speed
+            .as_ref()
+            .map(|speed| *speed.read().unwrap());
+        let validator = meta.post_init.read().unwrap().as_ref().map(|post_init| {
+            let adjustable_config = &post_init.adjustable_config;
+            let validators_to_strings =
+                |validators: &Option<HashSet<Pubkey>>| validators.as_ref().map(pubkeys_to_strings);
+            AdminRpcValidatorConfig {
+                send_transaction_service: (&*adjustable_config
+                    .send_transaction_service_config
+                    .read()
+                    .unwrap())
+                    .into(),
+                repair_validators: validators_to_strings(
+                    &adjustable_config.repair_validators.read().unwrap(),
+                ),
+                known_validators: validators_to_strings(
+                    &adjustable_config.known_validators.read().unwrap(),
+                ),
+                max_ledger_shreds: adjustable_config
+                    .max_ledger_shreds
+                    .as_ref()
+                    .map(|max_ledger_shreds| max_ledger_shreds.load(Ordering::Relaxed)),
+            }
+        });
+        Ok(AdminRpcConfig {
+            minimal_snapshot_download_speed,
+            validator,
+        })
+    }
+
+    fn set_config(&self, meta: Self::Metadata, settings: Vec<AdminRpcSetting>) -> Result<()> {
+        debug!("set_config request received");
+        let post_init = meta.post_init.read().unwrap();
+        let adjustable_config = || {
+            post_init
+                .as_ref()
+                .map(|post_init| &post_init.adjustable_config)
+                .ok_or_else(|| {
+                    jsonrpc_core::error::Error::invalid_params(
+                        "Retry once validator start up is complete",
+                    )
+                })
+        };
+
+        // Validate every change before applying any of them
+        let mut minimal_snapshot_download_speed = None;
+        let mut send_transaction_service_config = None;
+        let mut repair_validators = None;
+        let mut known_validators = None;
+        let mut max_ledger_shreds = None;
+        for setting in settings {
+            match setting {
+                AdminRpcSetting::MinimalSnapshotDownloadSpeed(speed) => {
+                    if meta.minimal_snapshot_download_speed.is_none() {
+                        return Err(jsonrpc_core::error::Error::invalid_params(
+                            "The validator does not download snapshots",
+                        ));
+                    }
+                    if !speed.is_finite() || speed < 0. {
+                        return Err(jsonrpc_core::error::Error::invalid_params(format!(
+                            "Invalid minimal snapshot download speed: {}",
+                            speed
+                        )));
+                    }
+                    minimal_snapshot_download_speed = Some(speed);
+                }
+                AdminRpcSetting::RepairValidators(validators) => {
+                    adjustable_config()?;
+                    repair_validators = Some(parse_validators(validators)?);
+                }
+                AdminRpcSetting::KnownValidators(validators) => {
+                    adjustable_config()?;
+                    known_validators = Some(parse_validators(validators)?);
+                }
+                AdminRpcSetting::MaxLedgerShreds(shreds) => {
+                    if adjustable_config()?.max_ledger_shreds.is_none() {
+                        return Err(jsonrpc_core::error::Error::invalid_params(
+                            "Ledger cleanup is disabled, restart the validator with \
+                             --limit-ledger-size",
+                        ));
+                    }
+                    if shreds < DEFAULT_MIN_MAX_LEDGER_SHREDS {
+                        return Err(jsonrpc_core::error::Error::invalid_params(format!(
+                            "Max ledger shreds is too small, the minimum value is {}",
+                            DEFAULT_MIN_MAX_LEDGER_SHREDS
+                        )));
+                    }
+                    max_ledger_shreds = Some(shreds);
+                }
+                AdminRpcSetting::SendTransactionRetryRateMs(value) => {
+                    pending_send_transaction_service_config(
+                        &mut send_transaction_service_config,
+                        adjustable_config()?,
+                    )
+                    .retry_rate_ms = value;
+                }
+                AdminRpcSetting::SendTransactionLeaderForwardCount(value) => {
+                    pending_send_transaction_service_config(
+                        &mut send_transaction_service_config,
+                        adjustable_config()?,
+                    )
+                    .leader_forward_count = value;
+                }
+                AdminRpcSetting::SendTransactionDefaultMaxRetries(value) => {
+                    pending_send_transaction_service_config(
+                        &mut send_transaction_service_config,
+                        adjustable_config()?,
+                    )
+                    .default_max_retries = value;
+                }
+                AdminRpcSetting::SendTransactionServiceMaxRetries(value) => {
+                    pending_send_transaction_service_config(
+                        &mut send_transaction_service_config,
+                        adjustable_config()?,
+                    )
+                    .service_max_retries = value;
+                }
+                AdminRpcSetting::SendTransactionBatchSize(value) => {
+                    pending_send_transaction_service_config(
+                        &mut send_transaction_service_config,
+                        adjustable_config()?,
+                    )
+                    .batch_size = value;
+                }
+                AdminRpcSetting::SendTransactionBatchSendRateMs(value) => {
+                    pending_send_transaction_service_config(
+                        &mut send_transaction_service_config,
+                        adjustable_config()?,
+                    )
+                    .batch_send_rate_ms = value;
+                }
+            }
+        }
+        if let Some(config) = &send_transaction_service_config {
+            verify_send_transaction_service_config(config)
+                .map_err(jsonrpc_core::error::Error::invalid_params)?;
+        }
+
+        if let Some(speed) = minimal_snapshot_download_speed {
+            *meta
+                .minimal_snapshot_download_speed
+                .as_ref()
+                .unwrap()
+                .write()
+                .unwrap() = speed;
+            info!("Minimal snapshot download speed set to {}", speed);
+        }
+        if let Some(config) = send_transaction_service_config {
+            info!("Send transaction service config set to {:?}", config);
+            *adjustable_config()?
+                .send_transaction_service_config
+                .write()
+                .unwrap() = config;
+        }
+        if let Some(validators) = repair_validators {
+            update_repair_validators(adjustable_config()?, |repair_validators| {
+                *repair_validators = validators;
+                Ok(())
+            })?;
+        }
+        if let Some(validators) = known_validators {
+            info!("Known validators set to {:?}", validators);
+            *adjustable_config()?.known_validators.write().unwrap() = validators;
+        }
+        if let Some(shreds) = max_ledger_shreds {
+            adjustable_config()?
+                .max_ledger_shreds
+                .as_ref()
+                .unwrap()
+                .store(shreds, Ordering::Relaxed);
+            info!("Max ledger shreds set to {}", shreds);
+        }
+        Ok(())
+    }
+
+    fn snapshot_status(&self, meta: Self::Metadata, slot: Slot) -> Result<AdminRpcSnapshotStatus> {
+        debug!("snapshot_status request received");
+        meta.with_post_init(|post_init| {
+            let snapshot_config = post_init
+                .bank_forks
+                .read()
+                .unwrap()
+                .snapshot_config
+                .clone()
+                .ok_or_else(|| {
+                    jsonrpc_core::error::Error::invalid_params("Snapshots are disabled")
+                })?;
+
+            let full_snapshot_archives = snapshot_utils::get_full_snapshot_archives(
+                &snapshot_config.full_snapshot_archives_dir,
+            );
+            let incremental_snapshot_archives = snapshot_utils::get_incremental_snapshot_archives(
+                &snapshot_config.incremental_snapshot_archives_dir,
+            );
+            let archives = full_snapshot_archives
+                .iter()
+                .map(|archive| (archive.slot(), archive.path()))
+                .chain(
+                    incremental_snapshot_archives
+                        .iter()
+                        .map(|archive| (archive.slot(), archive.path())),
+                );
+
+            let mut latest_archive: Option<(Slot, &PathBuf)> = None;
+            for (archive_slot, path) in archives {
+                if archive_slot == slot {
+                    return Ok(AdminRpcSnapshotStatus::Archived { path: path.clone() });
+                }
+                if latest_archive.map_or(true, |(latest_slot, _)| archive_slot > latest_slot) {
+                    latest_archive = Some((archive_slot, path));
+                }
+            }
+
+            if snapshot_utils::get_bank_snapshots(&snapshot_config.bank_snapshots_dir)
+                .iter()
+                .any(|bank_snapshot| bank_snapshot.slot == slot)
+            {
+                return Ok(AdminRpcSnapshotStatus::Packaging);
+            }
+            Ok(match latest_archive {
+                Some((latest_slot, path)) if latest_slot > slot => {
+                    AdminRpcSnapshotStatus::Superseded { path: path.clone() }
+                }
+                _ => AdminRpcSnapshotStatus::Pending,
+            })
+        })
+    }
+
+    fn program_stats(
+        &self,
+        meta: Self::Metadata,
+        limit: Option<usize>,
+    ) -> Result<Option<AdminRpcProgramStatsReport>> {
+        debug!("program_stats request received");
+        meta.with_post_init(|post_init| {
+            let root_bank = post_init.bank_forks.read().unwrap().root_bank();
+            Ok(root_bank
+                .rc
+                .accounts
+                .accounts_db
+                .program_stats
+                .latest_report()
+                .map(|report| AdminRpcProgramStatsReport::new(&report, limit)))
+        })
+    }
 }
 
 impl AdminRpcImpl {
@@ -373,6 +1025,101 @@ impl AdminRpcImpl {
     }
 }
 
+/// The single place the repair validators are changed, by `setConfig` as well as by
+/// `addRepairValidators` and `removeRepairValidators`
+fn update_repair_validators<F>(
+    adjustable_config: &AdjustableValidatorConfig,
+    update: F,
+) -> Result<()>
+where
+    F: FnOnce(&mut Option<HashSet<Pubkey>>) -> Result<()>,
+{
+    let mut repair_validators = adjustable_config.repair_validators.write().unwrap();
+    update(&mut repair_validators)?;
+    info!("Repair validators set to {:?}", repair_validators);
+    if repair_validators
+        .as_ref()
+        .map(HashSet::is_empty)
+        .unwrap_or_default()
+    {
+        warn!("No repair validators left, repair is disabled");
+    }
+    Ok(())
+}
+
+fn parse_pubkeys(pubkeys: &[String]) -> Result<HashSet<Pubkey>> {
+    pubkeys
+        .iter()
+        .map(|pubkey| {
+            Pubkey::from_str(pubkey).map_err(|err| {
+                jsonrpc_core::error::Error::invalid_params(format!(
+                    "Invalid validator identity {}: {}",
+                    pubkey, err
+                ))
+            })
+        })
+        .collect()
+}
+
+fn parse_validators(validators: Option<Vec<String>>) -> Result<Option<HashSet<Pubkey>>> {
+    validators
+        .map(|validators| parse_pubkeys(&validators))
+        .transpose()
+}
+
+fn pubkeys_to_strings(pubkeys: &HashSet<Pubkey>) -> Vec<String> {
+    let mut pubkeys: Vec<_> = pubkeys.iter().map(|pubkey| pubkey.to_string()).collect();
+    pubkeys.sort();
+    pubkeys
+}
+
+/// The send transaction service config being built up by `set_config`, starting from the
+/// validator's current config
+fn pending_send_transaction_service_config<'a>(
+    config: &'a mut Option<send_transaction_service::Config>,
+    adjustable_config: &AdjustableValidatorConfig,
+) -> &'a mut send_transaction_service::Config {
+    config.get_or_insert_with(|| {
+        adjustable_config
+            .send_transaction_service_config
+            .read()
+            .unwrap()
+            .clone()
+    })
+}
+
+/// Apply the same limits as the `--rpc-send-*` arguments
+fn verify_send_transaction_service_config(
+    config: &send_transaction_service::Config,
+) -> std::result::Result<(), String> {
+    if !(1..=MAX_BATCH_SEND_RATE_MS as u64).contains(&config.batch_send_rate_ms) {
+        return Err(format!(
+            "The batch send rate must be between 1 and {} ms",
+            MAX_BATCH_SEND_RATE_MS
+        ));
+    }
+    if !(1..=MAX_TRANSACTION_BATCH_SIZE).contains(&config.batch_size) {
+        return Err(format!(
+            "The batch size must be between 1 and {}",
+            MAX_TRANSACTION_BATCH_SIZE
+        ));
+    }
+    if config.batch_send_rate_ms > config.retry_rate_ms {
+        return Err(format!(
+            "The batch send rate ({} ms) must be <= the retry rate ({} ms)",
+            config.batch_send_rate_ms, config.retry_rate_ms
+        ));
+    }
+    let tps = config.batch_size as u64 * 1000 / config.batch_send_rate_ms;
+    if tps > MAX_TRANSACTION_SENDS_PER_SECOND {
+        return Err(format!(
+            "'batch size * 1000 / batch send rate' must be smaller than {}",
+            MAX_TRANSACTION_SENDS_PER_SECOND
+        ));
+    }
+    Ok(())
+}
+
 // Start the Admin RPC interface
 pub fn run(ledger_path: &Path, metadata: AdminRpcRequestMetadata) {
     let admin_rpc_path = admin_rpc_path(ledger_path);
@@ -489,3 +1236,260 @@ pub fn load_staked_nodes_overrides(
         .into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_core::{tower_storage::NullTowerStorage, validator::ValidatorConfig},
+        solana_runtime::{bank::Bank, genesis_utils::create_genesis_config},
+        solana_streamer::socket::SocketAddrSpace,
+    };
+
+    fn new_test_metadata(
+        minimal_snapshot_download_speed: Option<f32>,
+        max_ledger_shreds: Option<u64>,
+    ) -> AdminRpcRequestMetadata {
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::default(),
+            Arc::new(Keypair::new()),
+            SocketAddrSpace::Unspecified,
+        ));
+        let genesis_config = create_genesis_config(10_000).genesis_config;
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(Bank::new_for_tests(
+            &genesis_config,
+        ))));
+        let adjustable_config = AdjustableValidatorConfig::new(&ValidatorConfig {
+            max_ledger_shreds,
+            ..ValidatorConfig::default_for_test()
+        });
+        AdminRpcRequestMetadata {
+            rpc_addr: None,
+            start_time: SystemTime::now(),
+            start_progress: Arc::default(),
+            validator_exit: Arc::default(),
+            authorized_voter_keypairs: Arc::default(),
+            tower_storage: Arc::new(NullTowerStorage::default()),
+            staked_nodes_overrides: Arc::default(),
+            minimal_snapshot_download_speed: minimal_snapshot_download_speed
+                .map(|speed| Arc::new(RwLock::new(speed))),
+            post_init: Arc::new(RwLock::new(Some(AdminRpcRequestMetadataPostInit {
+                cluster_info,
+                bank_forks,
+                vote_account: Pubkey::new_unique(),
+                geyser_plugin_manager: None,
+                accounts_background_request_sender: AbsRequestSender::default(),
+                adjustable_config,
+            }))),
+        }
+    }
+
+    fn get_validator_config(meta: &AdminRpcRequestMetadata) -> AdminRpcValidatorConfig {
+        AdminRpcImpl
+            .get_config(meta.clone())
+            .unwrap()
+            .validator
+            .unwrap()
+    }
+
+    #[test]
+    fn test_set_config() {
+        let meta = new_test_metadata(Some(10485760.), Some(DEFAULT_MIN_MAX_LEDGER_SHREDS));
+        let validator = Pubkey::new_unique();
+        AdminRpcImpl
+            .set_config(
+                meta.clone(),
+                vec![
+                    AdminRpcSetting::MinimalSnapshotDownloadSpeed(1024.),
+                    AdminRpcSetting::SendTransactionRetryRateMs(5_000),
+                    AdminRpcSetting::SendTransactionBatchSize(10),
+                    AdminRpcSetting::RepairValidators(Some(vec![validator.to_string()])),
+                    AdminRpcSetting::KnownValidators(Some(vec![validator.to_string()])),
+                    AdminRpcSetting::MaxLedgerShreds(2 * DEFAULT_MIN_MAX_LEDGER_SHREDS),
+                ],
+            )
+            .unwrap();
+
+        let config = AdminRpcImpl.get_config(meta.clone()).unwrap();
+        assert_eq!(config.minimal_snapshot_download_speed, Some(1024.));
+        let validator_config = config.validator.unwrap();
+        assert_eq!(
+            validator_config.send_transaction_service.retry_rate_ms,
+            5_000
+        );
+        assert_eq!(validator_config.send_transaction_service.batch_size, 10);
+        assert_eq!(
+            validator_config.repair_validators,
+            Some(vec![validator.to_string()])
+        );
+        assert_eq!(
+            validator_config.known_validators,
+            Some(vec![validator.to_string()])
+        );
+        assert_eq!(
+            validator_config.max_ledger_shreds,
+            Some(2 * DEFAULT_MIN_MAX_LEDGER_SHREDS)
+        );
+
+        // `null` goes back to repairing from and trusting all validators
+        AdminRpcImpl
+            .set_config(
+                meta.clone(),
+                vec![
+                    AdminRpcSetting::RepairValidators(None),
+                    AdminRpcSetting::KnownValidators(None),
+                ],
+            )
+            .unwrap();
+        let validator_config = get_validator_config(&meta);
+        assert_eq!(validator_config.repair_validators, None);
+        assert_eq!(validator_config.known_validators, None);
+    }
+
+    #[test]
+    fn test_set_config_invalid_values() {
+        let meta = new_test_metadata(Some(10485760.), Some(DEFAULT_MIN_MAX_LEDGER_SHREDS));
+        let initial_config = AdminRpcImpl.get_config(meta.clone()).unwrap();
+        for setting in [
+            AdminRpcSetting::MinimalSnapshotDownloadSpeed(-1.),
+            AdminRpcSetting::MinimalSnapshotDownloadSpeed(f32::NAN),
+            AdminRpcSetting::MinimalSnapshotDownloadSpeed(f32::INFINITY),
+            AdminRpcSetting::SendTransactionBatchSize(0),
+            AdminRpcSetting::SendTransactionBatchSize(MAX_TRANSACTION_BATCH_SIZE + 1),
+            AdminRpcSetting::SendTransactionBatchSendRateMs(0),
+            AdminRpcSetting::SendTransactionBatchSendRateMs(MAX_BATCH_SEND_RATE_MS as u64 + 1),
+            // The batch send rate must not exceed the retry rate
+            AdminRpcSetting::SendTransactionRetryRateMs(0),
+            AdminRpcSetting::RepairValidators(Some(vec!["not a pubkey".to_string()])),
+            AdminRpcSetting::KnownValidators(Some(vec!["not a pubkey".to_string()])),
+            AdminRpcSetting::MaxLedgerShreds(DEFAULT_MIN_MAX_LEDGER_SHREDS - 1),
+        ] {
+            // Nothing is applied if any of the settings is invalid
+            let settings = vec![AdminRpcSetting::MaxLedgerShreds(u64::MAX), setting];
+            assert!(AdminRpcImpl.set_config(meta.clone(), settings).is_err());
+            assert_eq!(
+                AdminRpcImpl.get_config(meta.clone()).unwrap(),
+                initial_config
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_config_unavailable_settings() {
+        // Settings of services the validator does not run
+        let meta = new_test_metadata(None, None);
+        assert!(AdminRpcImpl
+            .set_config(
+                meta.clone(),
+                vec![AdminRpcSetting::MinimalSnapshotDownloadSpeed(1024.)]
+            )
+            .is_err());
+        assert!(AdminRpcImpl
+            .set_config(
+                meta.clone(),
+                vec![AdminRpcSetting::MaxLedgerShreds(
+                    DEFAULT_MIN_MAX_LEDGER_SHREDS
+                )]
+            )
+            .is_err());
+        assert_eq!(get_validator_config(&meta).max_ledger_shreds, None);
+
+        // Settings of the validator services before they are started
+        *meta.post_init.write().unwrap() = None;
+        assert!(AdminRpcImpl
+            .set_config(
+                meta.clone(),
+                vec![AdminRpcSetting::SendTransactionBatchSize(10)]
+            )
+            .is_err());
+        assert!(AdminRpcImpl
+            .set_config(meta.clone(), vec![AdminRpcSetting::RepairValidators(None)])
+            .is_err());
+        assert_eq!(AdminRpcImpl.get_config(meta).unwrap().validator, None);
+    }
+
+    #[test]
+    fn test_repair_validators() {
+        let meta = new_test_metadata(None, None);
+        let validators: Vec<_> = (0..3).map(|_| Pubkey::new_unique().to_string()).collect();
+        let sorted = |mut validators: Vec<String>| {
+            validators.sort();
+            validators
+        };
+
+        // Repairing from all validators can't be narrowed by removing some
+        assert!(AdminRpcImpl
+            .remove_repair_validators(meta.clone(), validators[..1].to_vec())
+            .is_err());
+        assert_eq!(get_validator_config(&meta).repair_validators, None);
+
+        AdminRpcImpl
+            .add_repair_validators(meta.clone(), validators.clone())
+            .unwrap();
+        assert_eq!(
+            get_validator_config(&meta).repair_validators,
+            Some(sorted(validators.clone()))
+        );
+        AdminRpcImpl
+            .remove_repair_validators(meta.clone(), validators[..1].to_vec())
+            .unwrap();
+        assert_eq!(
+            get_validator_config(&meta).repair_validators,
+            Some(sorted(validators[1..].to_vec()))
+        );
+
+        // setConfig changes the same set
+        AdminRpcImpl
+            .set_config(meta.clone(), vec![AdminRpcSetting::RepairValidators(None)])
+            .unwrap();
+        assert_eq!(get_validator_config(&meta).repair_validators, None);
+
+        assert!(AdminRpcImpl
+            .add_repair_validators(meta.clone(), vec!["not a pubkey".to_string()])
+            .is_err());
+        assert_eq!(get_validator_config(&meta).repair_validators, None);
+    }
+
+    #[test]
+    fn test_repair_denylist() {
+        let meta = new_test_metadata(None, None);
+        let peers: Vec<_> = (0..3).map(|_| Pubkey::new_unique().to_string()).collect();
+        let mut sorted_peers = peers.clone();
+        sorted_peers.sort();
+        assert!(AdminRpcImpl
+            .list_repair_denylist(meta.clone())
+            .unwrap()
+            .is_empty());
+
+        AdminRpcImpl
+            .add_repair_denylist(meta.clone(), peers.clone())
+            .unwrap();
+        assert_eq!(
+            AdminRpcImpl.list_repair_denylist(meta.clone()).unwrap(),
+            sorted_peers
+        );
+        // The denylist is shared with serve repair
+        let denylist = meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .adjustable_config
+            .repair_denylist
+            .clone();
+        assert_eq!(denylist.read().unwrap().len(), 3);
+
+        AdminRpcImpl
+            .remove_repair_denylist(meta.clone(), peers[..2].to_vec())
+            .unwrap();
+        assert_eq!(
+            AdminRpcImpl.list_repair_denylist(meta.clone()).unwrap(),
+            peers[2..].to_vec()
+        );
+        assert!(AdminRpcImpl
+            .add_repair_denylist(meta.clone(), vec!["not a pubkey".to_string()])
+            .is_err());
+        assert_eq!(denylist.read().unwrap().len(), 1);
+    }
+}
@@ -715,6 +715,7 @@ fn main() {
             staked_nodes_overrides: genesis.staked_nodes_overrides.clone(),
             post_init: admin_service_post_init.clone(),
             tower_storage: tower_storage.clone(),
+            minimal_snapshot_download_speed: None,
         },
     );
     let dashboard = if output == Output::Dashboard {
@@ -740,6 +741,7 @@ fn main() {
                 String
             ),
             timeout: None,
+            ..RpcBigtableConfig::default()
         })
     } else {
         None
@@ -761,6 +763,7 @@ fn main() {
         })
         .pubsub_config(PubSubConfig {
             enable_vote_subscription,
+            enable_rpc_requests: true,
             ..PubSubConfig::default()
         })
         .bpf_jit(!matches.is_present("no_bpf_jit"))
@@ -858,6 +861,10 @@ fn main() {
                     bank_forks: test_validator.bank_forks(),
                     cluster_info: test_validator.cluster_info(),
                     vote_account: test_validator.vote_account_address(),
+                    geyser_plugin_manager: test_validator.geyser_plugin_manager(),
+                    accounts_background_request_sender: test_validator
+                        .accounts_background_request_sender(),
+                    adjustable_config: test_validator.adjustable_config(),
                 });
             if let Some(dashboard) = dashboard {
                 dashboard.run(Duration::from_millis(250));
@@ -363,7 +363,7 @@ pub fn attempt_download_genesis_and_snapshot(
     incremental_snapshot_archives_dir: &Path,
     maximum_local_snapshot_age: Slot,
     start_progress: &Arc<RwLock<ValidatorStartProgress>>,
-    minimal_snapshot_download_speed: f32,
+    minimal_snapshot_download_speed: &RwLock<f32>,
     maximum_snapshot_download_abort: u64,
     download_abort_count: &mut u64,
     snapshot_hash: Option<SnapshotHash>,
@@ -472,7 +472,7 @@ pub fn rpc_bootstrap(
     maximum_local_snapshot_age: Slot,
     should_check_duplicate_instance: bool,
     start_progress: &Arc<RwLock<ValidatorStartProgress>>,
-    minimal_snapshot_download_speed: f32,
+    minimal_snapshot_download_speed: &RwLock<f32>,
     maximum_snapshot_download_abort: u64,
     socket_addr_space: SocketAddrSpace,
 ) {
@@ -1140,7 +1140,7 @@ fn download_snapshots(
     use_progress_bar: bool,
     maximum_local_snapshot_age: Slot,
     start_progress: &Arc<RwLock<ValidatorStartProgress>>,
-    minimal_snapshot_download_speed: f32,
+    minimal_snapshot_download_speed: &RwLock<f32>,
     maximum_snapshot_download_abort: u64,
     download_abort_count: &mut u64,
     snapshot_hash: Option<SnapshotHash>,
@@ -1239,7 +1239,7 @@ fn download_snapshot(
     bootstrap_config: &RpcBootstrapConfig,
     use_progress_bar: bool,
     start_progress: &Arc<RwLock<ValidatorStartProgress>>,
-    minimal_snapshot_download_speed: f32,
+    minimal_snapshot_download_speed: &RwLock<f32>,
     maximum_snapshot_download_abort: u64,
     download_abort_count: &mut u64,
     rpc_contact_info: &ContactInfo,
@@ -1277,6 +1277,8 @@ fn download_snapshot(
         use_progress_bar,
         &mut Some(Box::new(|download_progress: &DownloadProgressRecord| {
             debug!("Download progress: {:?}", download_progress);
+            // May be changed over the admin RPC channel while the download is in progress
+            let minimal_snapshot_download_speed = *minimal_snapshot_download_speed.read().unwrap();
             if download_progress.last_throughput < minimal_snapshot_download_speed
                 && download_progress.notification_count <= 1
                 && download_progress.percentage_done <= 2_f32
@@ -36,18 +36,21 @@ use {
     solana_rpc::{
         rpc::{JsonRpcConfig, RpcBigtableConfig, MAX_REQUEST_BODY_SIZE},
         rpc_pubsub_service::PubSubConfig,
+        rpc_service::{RpcRateLimit, RpcRateLimitConfig},
     },
     solana_rpc_client::rpc_client::RpcClient,
     solana_rpc_client_api::{config::RpcLeaderScheduleConfig, request::MAX_MULTIPLE_ACCOUNTS},
     solana_runtime::{
+        accounts_background_service::OnDemandSnapshotKind,
         accounts_db::{
             AccountShrinkThreshold, AccountsDbConfig, FillerAccountsConfig,
             DEFAULT_ACCOUNTS_SHRINK_OPTIMIZE_TOTAL_SPACE, DEFAULT_ACCOUNTS_SHRINK_RATIO,
         },
         accounts_index::{
             AccountIndex, AccountSecondaryIndexes, AccountSecondaryIndexesIncludeExclude,
-            AccountsIndexConfig, IndexLimitMb,
+            AccountsIndexConfig, DataOffsetIndex, IndexLimitMb, MAX_DATA_OFFSET_INDEX_LENGTH,
         },
+        cold_storage::{ColdStorageConfig, DEFAULT_COLD_STORAGE_CACHE_LIMIT_BYTES},
         hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
         runtime_config::RuntimeConfig,
         snapshot_config::{SnapshotConfig, SnapshotUsage},
@@ -75,7 +78,10 @@ use {
     },
     solana_validator::{
         admin_rpc_service,
-        admin_rpc_service::{load_staked_nodes_overrides, StakedNodesOverrides},
+        admin_rpc_service::{
+            load_staked_nodes_overrides, AdminRpcSetting, AdminRpcSnapshotStatus,
+            StakedNodesOverrides,
+        },
         bootstrap,
         dashboard::Dashboard,
         ledger_lockfile, lock_ledger, new_spinner_progress_bar, println_name_value,
@@ -679,6 +685,18 @@ pub fn main() {
                 .takes_value(false)
                 .help("Upload new confirmed blocks into a BigTable instance"),
         )
+        .arg(
+            Arg::with_name("rpc_local_ledger_storage")
+                .long("rpc-local-ledger-storage")
+                .value_name("PATH")
+                .requires("enable_rpc_transaction_history")
+                .takes_value(true)
+                .help("Keep the historical ledger data served with \
+                       --enable-rpc-bigtable-ledger-storage, and uploaded with \
+                       --enable-bigtable-ledger-upload, in a local RocksDB \
+                       database at PATH instead of a BigTable instance. \
+                       Implies --enable-rpc-bigtable-ledger-storage"),
+        )
         .arg(
             Arg::with_name("enable_extended_tx_metadata_storage")
                 .long("enable-extended-tx-metadata-storage")
@@ -708,6 +726,26 @@ pub fn main() {
                        If no --known-validators are specified, the validator will always \
                        report itself to be healthy")
         )
+        .arg(
+            Arg::with_name("health_check_max_pubsub_backlog")
+                .long("health-check-max-pubsub-backlog")
+                .value_name("NOTIFICATIONS")
+                .takes_value(true)
+                .default_value("10000")
+                .help("Report this validator not ready on /health/ready if more than this \
+                       number of pubsub notifications are waiting to be processed")
+        )
+        .arg(
+            Arg::with_name("health_check_max_blockstore_write_lag")
+                .long("health-check-max-blockstore-write-lag")
+                .value_name("SLOTS")
+                .takes_value(true)
+                .default_value("64")
+                .help("If --enable-rpc-transaction-history is specified, report this \
+                       validator not ready on /health/ready if the transaction statuses \
+                       written to the blockstore trail the highest bank by more than this \
+                       number of slots")
+        )
         .arg(
             Arg::with_name("rpc_faucet_addr")
                 .long("rpc-faucet-address")
@@ -1312,12 +1350,35 @@ pub fn main() {
                 .takes_value(false)
                 .help("Enable the unstable RPC PubSub `blockSubscribe` subscription"),
         )
+        .arg(
+            Arg::with_name("rpc_pubsub_enable_transaction_subscription")
+                .long("rpc-pubsub-enable-transaction-subscription")
+                .requires("enable_rpc_transaction_history")
+                .takes_value(false)
+                .help("Enable the unstable RPC PubSub `transactionSubscribe` subscription"),
+        )
         .arg(
             Arg::with_name("rpc_pubsub_enable_vote_subscription")
                 .long("rpc-pubsub-enable-vote-subscription")
                 .takes_value(false)
                 .help("Enable the unstable RPC PubSub `voteSubscribe` subscription"),
         )
+        .arg(
+            Arg::with_name("rpc_pubsub_enable_rpc_requests")
+                .long("rpc-pubsub-enable-rpc-requests")
+                .takes_value(false)
+                .help("Also serve the JSON RPC API methods, such as `getAccountInfo` and \
+                       `sendTransaction`, on the RPC PubSub websocket"),
+        )
+        .arg(
+            Arg::with_name("rpc_pubsub_max_subscriptions_per_ip")
+                .long("rpc-pubsub-max-subscriptions-per-ip")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .help("The maximum number of active RPC PubSub subscriptions of the \
+                       connections from each IP address [default: unlimited]"),
+        )
         .arg(
             Arg::with_name("rpc_pubsub_max_connections")
                 .long("rpc-pubsub-max-connections")
@@ -1470,6 +1531,96 @@ pub fn main() {
                 .default_value(default_rpc_max_request_body_size)
                 .help("The maximum request body size accepted by rpc service"),
         )
+        .arg(
+            Arg::with_name("rpc_max_simulation_trace_frames")
+                .long("rpc-max-simulation-trace-frames")
+                .value_name("FRAMES")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .help("Enable execution traces in the simulateTransaction JSON RPC method, \
+                       recording at most this many instruction frames per transaction"),
+        )
+        .arg(
+            Arg::with_name("rpc_max_simulation_bpf_trace_length")
+                .long("rpc-max-simulation-bpf-trace-length")
+                .value_name("INSTRUCTIONS")
+                .takes_value(true)
+                .requires("rpc_max_simulation_trace_frames")
+                .validator(is_parsable::<usize>)
+                .help("Enable BPF instruction traces in simulateTransaction execution traces, \
+                       recording at most this many instructions per program invocation"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_ip_units_per_second")
+                .long("rpc-rate-limit-ip-units-per-second")
+                .value_name("UNITS")
+                .takes_value(true)
+                .requires("rpc_rate_limit_trust_forwarded_for")
+                .validator(is_parsable::<u64>)
+                .help("Rate limit the JSON RPC calls of each client IP address to this many \
+                       cost units per second. Most methods cost 1 unit, see \
+                       --rpc-rate-limit-method-cost"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_ip_burst_units")
+                .long("rpc-rate-limit-ip-burst-units")
+                .value_name("UNITS")
+                .takes_value(true)
+                .requires("rpc_rate_limit_ip_units_per_second")
+                .validator(is_parsable::<u64>)
+                .help("The most cost units a client IP address can spend in a burst \
+                       [default: --rpc-rate-limit-ip-units-per-second]"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_api_key")
+                .long("rpc-rate-limit-api-key")
+                .value_name("KEY")
+                .takes_value(true)
+                .multiple(true)
+                .help("Rate limit the HTTP JSON RPC calls presenting this key in the \
+                       X-Api-Key header per key instead of per IP address. \
+                       May be specified multiple times"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_api_key_units_per_second")
+                .long("rpc-rate-limit-api-key-units-per-second")
+                .value_name("UNITS")
+                .takes_value(true)
+                .requires("rpc_rate_limit_api_key")
+                .validator(is_parsable::<u64>)
+                .help("Rate limit the JSON RPC calls of each API key to this many cost units \
+                       per second [default: unlimited]"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_api_key_burst_units")
+                .long("rpc-rate-limit-api-key-burst-units")
+                .value_name("UNITS")
+                .takes_value(true)
+                .requires("rpc_rate_limit_api_key_units_per_second")
+                .validator(is_parsable::<u64>)
+                .help("The most cost units an API key can spend in a burst \
+                       [default: --rpc-rate-limit-api-key-units-per-second]"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_method_cost")
+                .long("rpc-rate-limit-method-cost")
+                .value_name("METHOD=UNITS")
+                .takes_value(true)
+                .multiple(true)
+                .validator(is_rpc_method_cost)
+                .help("Set the rate limit cost of a JSON RPC method. Methods which scan \
+                       accounts or read history cost up to 100 units by default. \
+                       May be specified multiple times"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_trust_forwarded_for")
+                .long("rpc-rate-limit-trust-forwarded-for")
+                .takes_value(false)
+                .help("Identify the IP address of HTTP JSON RPC clients by the last address \
+                       of the X-Forwarded-For header. Required by the IP address rate limit, \
+                       as the HTTP server does not expose the address of its clients. \
+                       Only enable behind a proxy which appends it"),
+        )
         .arg(
             Arg::with_name("enable_accountsdb_repl")
                 .long("enable-accountsdb-repl")
@@ -1514,6 +1665,15 @@ pub fn main() {
                 .multiple(true)
                 .help("Specify the configuration file for the Geyser plugin."),
         )
+        .arg(
+            Arg::with_name("geyser_plugin_always_enabled")
+                .long("geyser-plugin-always-enabled")
+                .takes_value(false)
+                .help("Start every Geyser notifier, even with no --geyser-plugin-config, so \
+                       any plugin can be loaded at runtime with the `plugin load` command. \
+                       This adds the cost of the notifiers to replay whether or not plugins \
+                       are loaded"),
+        )
         .arg(
             Arg::with_name("halt_on_known_validators_accounts_hash_mismatch")
                 .alias("halt-on-trusted-validators-accounts-hash-mismatch")
@@ -1597,7 +1757,12 @@ pub fn main() {
                 .long("account-index")
                 .takes_value(true)
                 .multiple(true)
-                .possible_values(&["program-id", "spl-token-owner", "spl-token-mint"])
+                .possible_values(&[
+                    "program-id",
+                    "spl-token-owner",
+                    "spl-token-mint",
+                    "spl-token-delegate",
+                ])
                 .value_name("INDEX")
                 .help("Enable an accounts index, indexed by the selected account field"),
         )
@@ -1620,6 +1785,17 @@ pub fn main() {
                 .value_name("KEY")
                 .help("When account indexes are enabled, only include specific keys in the index. This overrides --account-index-exclude-key."),
         )
+        .arg(
+            Arg::with_name("account_data_indexes")
+                .long("account-data-index")
+                .takes_value(true)
+                .multiple(true)
+                .validator(|value| parse_data_offset_index(&value).map(|_| ()))
+                .value_name("PROGRAM_ID:OFFSET:LENGTH")
+                .help("Enable an accounts index over the accounts owned by PROGRAM_ID, \
+                       indexed by LENGTH bytes of account data starting at OFFSET. \
+                       getProgramAccounts uses the index for memcmp filters at OFFSET"),
+        )
         .arg(
             Arg::with_name("no_accounts_db_caching")
                 .long("no-accounts-db-caching")
@@ -1653,6 +1829,24 @@ pub fn main() {
                 .help("AppendVecs that are older than (slots_per_epoch - SLOT-OFFSET) are squashed together.")
                 .hidden(true),
         )
+        .arg(
+            Arg::with_name("accounts_db_cold_storage")
+                .long("accounts-db-cold-storage")
+                .requires("accounts_db_ancient_append_vecs")
+                .help("Compress ancient AppendVecs into a cold storage tier that is decompressed on demand.")
+                .hidden(true),
+        )
+        .arg(
+            Arg::with_name("accounts_db_cold_storage_cache_limit_mb")
+                .long("accounts-db-cold-storage-cache-limit-mb")
+                .value_name("MEGABYTES")
+                .validator(is_parsable::<u64>)
+                .takes_value(true)
+                .requires("accounts_db_cold_storage")
+                .help("How much decompressed cold storage data to keep in memory. \
+                       Least recently read data is released first when this is exceeded.")
+                .hidden(true),
+        )
         .arg(
             Arg::with_name("accounts_db_cache_limit_mb")
                 .long("accounts-db-cache-limit-mb")
@@ -1709,6 +1903,22 @@ pub fn main() {
                        May be specified multiple times. \
                        [default: [ledger]/accounts_index]"),
          )
+        .arg(
+            Arg::with_name("persist_accounts_index")
+                .long("persist-accounts-index")
+                .help("Load the accounts index saved to [ledger]/persisted_accounts_index \
+                       by --save-accounts-index-on-exit instead of generating the index, if \
+                       the start loads the snapshot it was saved with."),
+        )
+        .arg(
+            Arg::with_name("save_accounts_index_on_exit")
+                .long("save-accounts-index-on-exit")
+                .requires("persist_accounts_index")
+                .help("Take a full snapshot of the root bank when the validator exits \
+                       cleanly, and save the accounts index with it. Delays the exit by the \
+                       time it takes to archive the snapshot. Requires snapshots to be \
+                       enabled."),
+        )
          .arg(Arg::with_name("accounts_filler_count")
             .long("accounts-filler-count")
             .value_name("COUNT")
@@ -1780,6 +1990,17 @@ pub fn main() {
                 .long("replay-slots-concurrently")
                 .help("Allow concurrent replay of slots on different forks")
         )
+        .arg(
+            Arg::with_name("program_stats_interval")
+                .long("program-stats-interval")
+                .takes_value(true)
+                .validator(|s| is_within_range(s, 1, u64::MAX))
+                .value_name("SECONDS")
+                .help("Periodically collect per-program account storage and access stats \
+                       from the root bank, at this interval. Access stats count the accesses \
+                       since the previous collection. The latest stats are available \
+                       through the `program-stats` command")
+        )
         .args(&get_deprecated_arguments())
         .after_help("The default subcommand is run")
         .subcommand(
@@ -1851,6 +2072,38 @@ pub fn main() {
                              currently running validator instance")
             )
         )
+        .subcommand(
+            SubCommand::with_name("config")
+            .about("View and change settings of the running validator")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .subcommand(
+                SubCommand::with_name("get")
+                .about("Display the settings that can be changed without a restart")
+            )
+            .subcommand(
+                SubCommand::with_name("set")
+                .about("Change a setting without a restart")
+                .arg(
+                    Arg::with_name("name")
+                        .index(1)
+                        .value_name("NAME")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Name of the setting, as listed by `config get`, \
+                               such as maxLedgerShreds"),
+                )
+                .arg(
+                    Arg::with_name("value")
+                        .index(2)
+                        .value_name("JSON")
+                        .takes_value(true)
+                        .required(true)
+                        .help("New value of the setting, as JSON"),
+                )
+                .after_help("Note: the new value only applies to the \
+                             currently running validator instance")
+            )
+        )
         .subcommand(
             SubCommand::with_name("contact-info")
             .about("Display the validator's contact info")
@@ -1863,6 +2116,23 @@ pub fn main() {
                     .help("Output display mode")
             )
         )
+        .subcommand(
+            SubCommand::with_name("create-snapshot")
+            .about("Snapshot the current root bank, outside of the snapshot intervals")
+            .arg(
+                Arg::with_name("incremental")
+                    .long("incremental")
+                    .takes_value(false)
+                    .help("Create an incremental snapshot on top of the latest full snapshot")
+            )
+            .arg(
+                Arg::with_name("no_wait")
+                    .long("no-wait")
+                    .takes_value(false)
+                    .help("Return once the snapshot is requested, instead of waiting \
+                           for its archive")
+            )
+        )
         .subcommand(
             SubCommand::with_name("init")
             .about("Initialize the ledger directory then exit")
@@ -1871,6 +2141,155 @@ pub fn main() {
             SubCommand::with_name("monitor")
             .about("Monitor the validator")
         )
+        .subcommand(
+            SubCommand::with_name("plugin")
+            .about("Manage and view Geyser plugins")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .setting(AppSettings::InferSubcommands)
+            .subcommand(
+                SubCommand::with_name("list")
+                .about("List all current running Geyser plugins")
+            )
+            .subcommand(
+                SubCommand::with_name("load")
+                .about("Load a new Geyser plugin")
+                .arg(
+                    Arg::with_name("config")
+                        .value_name("CONFIG")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to the config file of the Geyser plugin to load"),
+                )
+            )
+            .subcommand(
+                SubCommand::with_name("unload")
+                .about("Unload a running Geyser plugin")
+                .arg(
+                    Arg::with_name("name")
+                        .value_name("NAME")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Name of the Geyser plugin to unload"),
+                )
+            )
+            .subcommand(
+                SubCommand::with_name("reload")
+                .about("Reload a running Geyser plugin from its config file")
+                .arg(
+                    Arg::with_name("name")
+                        .value_name("NAME")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Name of the Geyser plugin to reload"),
+                )
+                .after_help("Note: the plugin is unloaded before it is loaded again. \
+                             If loading fails, the plugin stays unloaded")
+            )
+            .after_help("Note: plugins can only be managed at runtime if the validator was \
+                         started with --geyser-plugin-config or --geyser-plugin-always-enabled. \
+                         Without --geyser-plugin-always-enabled, only the notifications enabled \
+                         by the plugins given with --geyser-plugin-config at startup can be \
+                         delivered to plugins loaded at runtime")
+        )
+        .subcommand(
+            SubCommand::with_name("program-stats")
+            .about("Display the latest per-program account storage and access stats")
+            .arg(
+                Arg::with_name("limit")
+                    .long("limit")
+                    .takes_value(true)
+                    .value_name("NUM")
+                    .validator(is_parsable::<usize>)
+                    .help("Only display this many programs, largest first [default: all]")
+            )
+            .arg(
+                Arg::with_name("output")
+                    .long("output")
+                    .takes_value(true)
+                    .value_name("MODE")
+                    .possible_values(&["json", "json-compact"])
+                    .help("Output display mode")
+            )
+            .after_help("Note: stats are only collected when the validator is started \
+                         with --program-stats-interval")
+        )
+        .subcommand(
+            SubCommand::with_name("repair-denylist")
+            .about("Manage the validators whose repair requests are not served")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .setting(AppSettings::InferSubcommands)
+            .subcommand(
+                SubCommand::with_name("list")
+                .about("List the validators whose repair requests are not served")
+            )
+            .subcommand(
+                SubCommand::with_name("add")
+                .about("Add to the validators whose repair requests are not served")
+                .arg(
+                    Arg::with_name("validators")
+                        .value_name("VALIDATOR IDENTITY")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .help("Validators to add"),
+                )
+            )
+            .subcommand(
+                SubCommand::with_name("remove")
+                .about("Remove from the validators whose repair requests are not served")
+                .arg(
+                    Arg::with_name("validators")
+                        .value_name("VALIDATOR IDENTITY")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .help("Validators to remove"),
+                )
+            )
+            .after_help("Note: the changes only apply to the currently running validator instance")
+        )
+        .subcommand(
+            SubCommand::with_name("repair-validators")
+            .about("Manage the validators to request repairs from")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .setting(AppSettings::InferSubcommands)
+            .subcommand(
+                SubCommand::with_name("list")
+                .about("List the validators to request repairs from")
+            )
+            .subcommand(
+                SubCommand::with_name("add")
+                .about("Add to the validators to request repairs from")
+                .arg(
+                    Arg::with_name("validators")
+                        .value_name("VALIDATOR IDENTITY")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .help("Validators to add"),
+                )
+            )
+            .subcommand(
+                SubCommand::with_name("remove")
+                .about("Remove from the validators to request repairs from")
+                .arg(
+                    Arg::with_name("validators")
+                        .value_name("VALIDATOR IDENTITY")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .help("Validators to remove"),
+                )
+            )
+            .after_help("Note: adding validators while repairing from all validators restricts repair to the \
+                         added ones. Use `config set repairValidators null` to repair from all \
+                         validators again. The changes only apply to the currently running \
+                         validator instance")
+        )
         .subcommand(
             SubCommand::with_name("run")
             .about("Run the validator")
@@ -2045,6 +2464,47 @@ pub fn main() {
                 _ => unreachable!(),
             }
         }
+        ("config", Some(config_subcommand_matches)) => {
+            match config_subcommand_matches.subcommand() {
+                ("get", _) => {
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    let config = admin_rpc_service::runtime()
+                        .block_on(async move { admin_client.await?.get_config().await })
+                        .unwrap_or_else(|err| {
+                            eprintln!("getConfig request failed: {}", err);
+                            exit(1);
+                        });
+                    println!("{}", serde_json::to_string_pretty(&config).unwrap());
+                }
+                ("set", Some(subcommand_matches)) => {
+                    let name = value_t_or_exit!(subcommand_matches, "name", String);
+                    let value = value_t_or_exit!(subcommand_matches, "value", String);
+                    let setting = serde_json::from_str(&value)
+                        .and_then(|value: serde_json::Value| {
+                            serde_json::from_value::<AdminRpcSetting>(serde_json::json!({
+                                "name": name,
+                                "value": value,
+                            }))
+                        })
+                        .unwrap_or_else(|err| {
+                            eprintln!("Invalid setting {} = {}: {}", name, value, err);
+                            exit(1);
+                        });
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    admin_rpc_service::runtime()
+                        .block_on(
+                            async move { admin_client.await?.set_config(vec![setting]).await },
+                        )
+                        .unwrap_or_else(|err| {
+                            eprintln!("setConfig request failed: {}", err);
+                            exit(1);
+                        });
+                    println!("{} set to {}", name, value);
+                }
+                _ => unreachable!(),
+            }
+            return;
+        }
         ("contact-info", Some(subcommand_matches)) => {
             let output_mode = subcommand_matches.value_of("output");
             let admin_client = admin_rpc_service::connect(&ledger_path);
@@ -2065,7 +2525,114 @@ pub fn main() {
             }
             return;
         }
+        ("create-snapshot", Some(subcommand_matches)) => {
+            let kind = if subcommand_matches.is_present("incremental") {
+                OnDemandSnapshotKind::Incremental
+            } else {
+                OnDemandSnapshotKind::Full
+            };
+            let no_wait = subcommand_matches.is_present("no_wait");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move {
+                    let admin_client = admin_client.await?;
+                    let slot = admin_client.create_snapshot(kind).await?;
+                    println!("Requested a {:?} snapshot of slot {}", kind, slot);
+                    if no_wait {
+                        return Ok(());
+                    }
+
+                    let progress_bar = new_spinner_progress_bar();
+                    loop {
+                        let status = admin_client.snapshot_status(slot).await?;
+                        match status {
+                            AdminRpcSnapshotStatus::Pending | AdminRpcSnapshotStatus::Packaging => {
+                                progress_bar.set_message(format!("{}...", status));
+                                jsonrpc_server_utils::tokio::time::sleep(Duration::from_secs(1))
+                                    .await;
+                            }
+                            _ => {
+                                progress_bar.finish_with_message(status.to_string());
+                                return Ok(());
+                            }
+                        }
+                    }
+                })
+                .unwrap_or_else(|err: jsonrpc_core_client::RpcError| {
+                    println!("Snapshot request failed: {}", err);
+                    exit(1);
+                });
+            return;
+        }
         ("init", _) => Operation::Initialize,
+        ("plugin", Some(plugin_subcommand_matches)) => {
+            match plugin_subcommand_matches.subcommand() {
+                ("list", _) => {
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    let plugins = admin_rpc_service::runtime()
+                        .block_on(async move { admin_client.await?.list_plugins().await })
+                        .unwrap_or_else(|err| {
+                            println!("Failed to list plugins: {}", err);
+                            exit(1);
+                        });
+                    if !plugins.is_empty() {
+                        println!("Currently the following plugins are loaded:");
+                        for (plugin, i) in plugins.into_iter().zip(1..) {
+                            println!("  {}) {}", i, plugin);
+                        }
+                    } else {
+                        println!("There are currently no plugins loaded");
+                    }
+                    return;
+                }
+                ("load", Some(subcommand_matches)) => {
+                    let config = value_t_or_exit!(subcommand_matches, "config", String);
+                    let config = fs::canonicalize(&config).unwrap_or_else(|err| {
+                        println!("Unable to access path: {}: {:?}", config, err);
+                        exit(1);
+                    });
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    let name = admin_rpc_service::runtime()
+                        .block_on(async move {
+                            admin_client
+                                .await?
+                                .load_plugin(config.display().to_string())
+                                .await
+                        })
+                        .unwrap_or_else(|err| {
+                            println!("Failed to load plugin: {}", err);
+                            exit(1);
+                        });
+                    println!("Successfully loaded plugin: {}", name);
+                    return;
+                }
+                ("unload", Some(subcommand_matches)) => {
+                    let name = value_t_or_exit!(subcommand_matches, "name", String);
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    admin_rpc_service::runtime()
+                        .block_on(async { admin_client.await?.unload_plugin(name.clone()).await })
+                        .unwrap_or_else(|err| {
+                            println!("Failed to unload plugin {}: {}", name, err);
+                            exit(1);
+                        });
+                    println!("Successfully unloaded plugin: {}", name);
+                    return;
+                }
+                ("reload", Some(subcommand_matches)) => {
+                    let name = value_t_or_exit!(subcommand_matches, "name", String);
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    admin_rpc_service::runtime()
+                        .block_on(async { admin_client.await?.reload_plugin(name.clone()).await })
+                        .unwrap_or_else(|err| {
+                            println!("Failed to reload plugin {}: {}", name, err);
+                            exit(1);
+                        });
+                    println!("Successfully reloaded plugin: {}", name);
+                    return;
+                }
+                _ => unreachable!(),
+            }
+        }
         ("exit", Some(subcommand_matches)) => {
             let min_idle_time = value_t_or_exit!(subcommand_matches, "min_idle_time", usize);
             let force = subcommand_matches.is_present("force");
@@ -2130,6 +2697,138 @@ pub fn main() {
                 });
             return;
         }
+        ("program-stats", Some(subcommand_matches)) => {
+            let limit = value_t!(subcommand_matches, "limit", usize).ok();
+            let output_mode = subcommand_matches.value_of("output");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let program_stats = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.program_stats(limit).await })
+                .unwrap_or_else(|err| {
+                    eprintln!("Program stats query failed: {}", err);
+                    exit(1);
+                });
+            let program_stats = program_stats.unwrap_or_else(|| {
+                eprintln!(
+                    "No program stats available yet. Is the validator running with \
+                     --program-stats-interval?"
+                );
+                exit(1);
+            });
+            if let Some(mode) = output_mode {
+                match mode {
+                    "json" => println!("{}", serde_json::to_string_pretty(&program_stats).unwrap()),
+                    "json-compact" => print!("{}", serde_json::to_string(&program_stats).unwrap()),
+                    _ => unreachable!(),
+                }
+            } else {
+                print!("{}", program_stats);
+            }
+            return;
+        }
+        ("repair-denylist", Some(repair_denylist_subcommand_matches)) => {
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            match repair_denylist_subcommand_matches.subcommand() {
+                ("list", _) => {
+                    let peers = admin_rpc_service::runtime()
+                        .block_on(async move { admin_client.await?.list_repair_denylist().await })
+                        .unwrap_or_else(|err| {
+                            println!("Failed to list the repair denylist: {}", err);
+                            exit(1);
+                        });
+                    if peers.is_empty() {
+                        println!("Repair requests are served to all validators");
+                    } else {
+                        println!("Repair requests are not served to:");
+                        for peer in peers {
+                            println!("  {}", peer);
+                        }
+                    }
+                }
+                ("add", Some(subcommand_matches)) => {
+                    let peers = values_t_or_exit!(subcommand_matches, "validators", String);
+                    admin_rpc_service::runtime()
+                        .block_on(
+                            async move { admin_client.await?.add_repair_denylist(peers).await },
+                        )
+                        .unwrap_or_else(|err| {
+                            println!("Failed to add to the repair denylist: {}", err);
+                            exit(1);
+                        });
+                }
+                ("remove", Some(subcommand_matches)) => {
+                    let peers = values_t_or_exit!(subcommand_matches, "validators", String);
+                    admin_rpc_service::runtime()
+                        .block_on(
+                            async move { admin_client.await?.remove_repair_denylist(peers).await },
+                        )
+                        .unwrap_or_else(|err| {
+                            println!("Failed to remove from the repair denylist: {}", err);
+                            exit(1);
+                        });
+                }
+                _ => unreachable!(),
+            }
+            return;
+        }
+        ("repair-validators", Some(repair_validators_subcommand_matches)) => {
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            match repair_validators_subcommand_matches.subcommand() {
+                ("list", _) => {
+                    let config = admin_rpc_service::runtime()
+                        .block_on(async move { admin_client.await?.get_config().await })
+                        .unwrap_or_else(|err| {
+                            println!("Failed to list repair validators: {}", err);
+                            exit(1);
+                        });
+                    let validators = config
+                        .validator
+                        .unwrap_or_else(|| {
+                            println!("Retry once validator start up is complete");
+                            exit(1);
+                        })
+                        .repair_validators;
+                    match validators {
+                        None => println!("Repairing from all validators"),
+                        Some(validators) if validators.is_empty() => {
+                            println!("Repair is disabled, there are no repair validators")
+                        }
+                        Some(validators) => {
+                            println!("Repairing from:");
+                            for validator in validators {
+                                println!("  {}", validator);
+                            }
+                        }
+                    }
+                }
+                ("add", Some(subcommand_matches)) => {
+                    let validators = values_t_or_exit!(subcommand_matches, "validators", String);
+                    admin_rpc_service::runtime()
+                        .block_on(async move {
+                            admin_client.await?.add_repair_validators(validators).await
+                        })
+                        .unwrap_or_else(|err| {
+                            println!("Failed to add repair validators: {}", err);
+                            exit(1);
+                        });
+                }
+                ("remove", Some(subcommand_matches)) => {
+                    let validators = values_t_or_exit!(subcommand_matches, "validators", String);
+                    admin_rpc_service::runtime()
+                        .block_on(async move {
+                            admin_client
+                                .await?
+                                .remove_repair_validators(validators)
+                                .await
+                        })
+                        .unwrap_or_else(|err| {
+                            println!("Failed to remove repair validators: {}", err);
+                            exit(1);
+                        });
+                }
+                _ => unreachable!(),
+            }
+            return;
+        }
         ("set-identity", Some(subcommand_matches)) => {
             let require_tower = subcommand_matches.is_present("require_tower");
 
@@ -2478,6 +3177,10 @@ pub fn main() {
         }
         accounts_index_config.drives = Some(accounts_index_paths);
     }
+    if matches.is_present("persist_accounts_index") {
+        accounts_index_config.persisted_index_path =
+            Some(ledger_path.join("persisted_accounts_index"));
+    }
 
     const MB: usize = 1_024 * 1_024;
     accounts_index_config.scan_results_limit_bytes =
@@ -2500,6 +3203,19 @@ pub fn main() {
         skip_rewrites: matches.is_present("accounts_db_skip_rewrites"),
         ancient_append_vec_offset: value_t!(matches, "accounts_db_ancient_append_vecs", u64).ok(),
         exhaustively_verify_refcounts: matches.is_present("accounts_db_verify_refcounts"),
+        cold_storage: matches
+            .is_present("accounts_db_cold_storage")
+            .then(|| ColdStorageConfig {
+                cache_limit_bytes: value_t!(
+                    matches,
+                    "accounts_db_cold_storage_cache_limit_mb",
+                    u64
+                )
+                .map(|mb| mb * MB as u64)
+                .unwrap_or(DEFAULT_COLD_STORAGE_CACHE_LIMIT_BYTES),
+                ..ColdStorageConfig::default()
+            }),
+        program_access_stats: matches.is_present("program_stats_interval"),
         ..AccountsDbConfig::default()
     };
 
@@ -2521,6 +3237,7 @@ pub fn main() {
 
     let rpc_bigtable_config = if matches.is_present("enable_rpc_bigtable_ledger_storage")
         || matches.is_present("enable_bigtable_ledger_upload")
+        || matches.is_present("rpc_local_ledger_storage")
     {
         Some(RpcBigtableConfig {
             enable_bigtable_ledger_upload: matches.is_present("enable_bigtable_ledger_upload"),
@@ -2533,6 +3250,9 @@ pub fn main() {
             timeout: value_t!(matches, "rpc_bigtable_timeout", u64)
                 .ok()
                 .map(Duration::from_secs),
+            local_storage_path: matches
+                .value_of("rpc_local_ledger_storage")
+                .map(PathBuf::from),
         })
     } else {
         None
@@ -2596,6 +3316,16 @@ pub fn main() {
                 "health_check_slot_distance",
                 u64
             ),
+            health_check_max_pubsub_backlog: Some(value_t_or_exit!(
+                matches,
+                "health_check_max_pubsub_backlog",
+                usize
+            )),
+            health_check_max_blockstore_write_lag: Some(value_t_or_exit!(
+                matches,
+                "health_check_max_blockstore_write_lag",
+                u64
+            )),
             rpc_threads: value_t_or_exit!(matches, "rpc_threads", usize),
             rpc_niceness_adj: value_t_or_exit!(matches, "rpc_niceness_adj", i8),
             account_indexes: account_indexes.clone(),
@@ -2605,8 +3335,22 @@ pub fn main() {
                 "rpc_max_request_body_size",
                 usize
             )),
+            max_simulation_trace_frames: value_t!(
+                matches,
+                "rpc_max_simulation_trace_frames",
+                usize
+            )
+            .ok(),
+            max_simulation_bpf_trace_length: value_t!(
+                matches,
+                "rpc_max_simulation_bpf_trace_length",
+                usize
+            )
+            .ok(),
+            rate_limit: process_rpc_rate_limit_config(&matches),
         },
         geyser_plugin_config_files,
+        geyser_plugin_always_enabled: matches.is_present("geyser_plugin_always_enabled"),
         rpc_addrs: value_t!(matches, "rpc_port", u16).ok().map(|rpc_port| {
             (
                 SocketAddr::new(rpc_bind_address, rpc_port),
@@ -2618,6 +3362,8 @@ pub fn main() {
         }),
         pubsub_config: PubSubConfig {
             enable_block_subscription: matches.is_present("rpc_pubsub_enable_block_subscription"),
+            enable_transaction_subscription: matches
+                .is_present("rpc_pubsub_enable_transaction_subscription"),
             enable_vote_subscription: matches.is_present("rpc_pubsub_enable_vote_subscription"),
             max_active_subscriptions: value_t_or_exit!(
                 matches,
@@ -2640,6 +3386,13 @@ pub fn main() {
             } else {
                 Some(0)
             },
+            enable_rpc_requests: matches.is_present("rpc_pubsub_enable_rpc_requests"),
+            max_subscriptions_per_ip: value_t!(
+                matches,
+                "rpc_pubsub_max_subscriptions_per_ip",
+                usize
+            )
+            .ok(),
         },
         voting_disabled: matches.is_present("no_voting") || restricted_repair_only_mode,
         wait_for_supermajority: value_t!(matches, "wait_for_supermajority", Slot).ok(),
@@ -2696,6 +3449,10 @@ pub fn main() {
         },
         staked_nodes_overrides: staked_nodes_overrides.clone(),
         replay_slots_concurrently: matches.is_present("replay_slots_concurrently"),
+        program_stats_interval: value_t!(matches, "program_stats_interval", u64)
+            .ok()
+            .map(Duration::from_secs),
+        save_accounts_index_on_exit: matches.is_present("save_accounts_index_on_exit"),
         ..ValidatorConfig::default()
     };
 
@@ -2770,8 +3527,11 @@ pub fn main() {
         value_t_or_exit!(matches, "maximum_incremental_snapshots_to_retain", usize);
     let snapshot_packager_niceness_adj =
         value_t_or_exit!(matches, "snapshot_packager_niceness_adj", i8);
-    let minimal_snapshot_download_speed =
-        value_t_or_exit!(matches, "minimal_snapshot_download_speed", f32);
+    let minimal_snapshot_download_speed = Arc::new(RwLock::new(value_t_or_exit!(
+        matches,
+        "minimal_snapshot_download_speed",
+        f32
+    )));
     let maximum_snapshot_download_abort =
         value_t_or_exit!(matches, "maximum_snapshot_download_abort", u64);
 
@@ -2975,6 +3735,9 @@ pub fn main() {
             post_init: admin_service_post_init.clone(),
             tower_storage: validator_config.tower_storage.clone(),
             staked_nodes_overrides,
+            minimal_snapshot_download_speed: (!entrypoint_addrs.is_empty()
+                && !rpc_bootstrap_config.no_snapshot_fetch)
+                .then(|| minimal_snapshot_download_speed.clone()),
         },
     );
 
@@ -3104,7 +3867,7 @@ pub fn main() {
             maximum_local_snapshot_age,
             should_check_duplicate_instance,
             &start_progress,
-            minimal_snapshot_download_speed,
+            &minimal_snapshot_download_speed,
             maximum_snapshot_download_abort,
             socket_addr_space,
         );
@@ -3140,6 +3903,11 @@ pub fn main() {
             bank_forks: validator.bank_forks.clone(),
             cluster_info: validator.cluster_info.clone(),
             vote_account,
+            geyser_plugin_manager: validator.geyser_plugin_manager(),
+            accounts_background_request_sender: validator
+                .accounts_background_request_sender
+                .clone(),
+            adjustable_config: validator.adjustable_config.clone(),
         });
 
     if let Some(filename) = init_complete_file {
@@ -3154,13 +3922,14 @@ pub fn main() {
 }
 
 fn process_account_indexes(matches: &ArgMatches) -> AccountSecondaryIndexes {
-    let account_indexes: HashSet<AccountIndex> = matches
+    let mut account_indexes: HashSet<AccountIndex> = matches
         .values_of("account_indexes")
         .unwrap_or_default()
         .map(|value| match value {
             "program-id" => AccountIndex::ProgramId,
             "spl-token-mint" => AccountIndex::SplTokenMint,
             "spl-token-owner" => AccountIndex::SplTokenOwner,
+            "spl-token-delegate" => AccountIndex::SplTokenDelegate,
             _ => unreachable!(),
         })
         .collect();
@@ -3196,12 +3965,102 @@ fn process_account_indexes(matches: &ArgMatches) -> AccountSecondaryIndexes {
         None
     };
 
+    account_indexes.extend(
+        matches
+            .values_of("account_data_indexes")
+            .unwrap_or_default()
+            .map(|value| AccountIndex::DataOffset(parse_data_offset_index(value).unwrap())),
+    );
+
     AccountSecondaryIndexes {
         keys,
         indexes: account_indexes,
     }
 }
 
+fn parse_data_offset_index(value: &str) -> Result<DataOffsetIndex, String> {
+    let mut parts = value.split(':');
+    let (program_id, offset, length) =
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(program_id), Some(offset), Some(length), None) => (program_id, offset, length),
+            _ => return Err(format!("expected PROGRAM_ID:OFFSET:LENGTH, got {}", value)),
+        };
+    let program_id = program_id
+        .parse::<Pubkey>()
+        .map_err(|err| format!("invalid program id {}: {}", program_id, err))?;
+    let offset = offset
+        .parse::<usize>()
+        .map_err(|err| format!("invalid offset {}: {}", offset, err))?;
+    let length = length
+        .parse::<usize>()
+        .map_err(|err| format!("invalid length {}: {}", length, err))?;
+    if length == 0 || length > MAX_DATA_OFFSET_INDEX_LENGTH {
+        return Err(format!(
+            "length must be between 1 and {}, got {}",
+            MAX_DATA_OFFSET_INDEX_LENGTH, length
+        ));
+    }
+    Ok(DataOffsetIndex {
+        program_id,
+        offset,
+        length,
+    })
+}
+
+fn is_rpc_method_cost(value: String) -> Result<(), String> {
+    match value.split_once('=') {
+        Some((_method, cost)) => cost
+            .parse::<u64>()
+            .map(|_| ())
+            .map_err(|err| format!("invalid method cost {}: {}", cost, err)),
+        None => Err(format!("expected METHOD=UNITS, got {}", value)),
+    }
+}
+
+fn process_rpc_rate_limit_config(matches: &ArgMatches) -> Option<RpcRateLimitConfig> {
+    let rate_limit = |units_per_second: &str, burst_units: &str| {
+        value_t!(matches, units_per_second, u64)
+            .ok()
+            .map(|units_per_second| RpcRateLimit {
+                units_per_second,
+                burst_units: value_t!(matches, burst_units, u64).unwrap_or(units_per_second),
+            })
+    };
+    let ip_limit = rate_limit(
+        "rpc_rate_limit_ip_units_per_second",
+        "rpc_rate_limit_ip_burst_units",
+    );
+    let api_key_limit = rate_limit(
+        "rpc_rate_limit_api_key_units_per_second",
+        "rpc_rate_limit_api_key_burst_units",
+    );
+    if ip_limit.is_none() && api_key_limit.is_none() {
+        return None;
+    }
+
+    let mut config = RpcRateLimitConfig {
+        ip_limit,
+        api_key_limit,
+        api_keys: matches
+            .values_of("rpc_rate_limit_api_key")
+            .unwrap_or_default()
+            .map(str::to_string)
+            .collect(),
+        trust_forwarded_for: matches.is_present("rpc_rate_limit_trust_forwarded_for"),
+        ..RpcRateLimitConfig::default()
+    };
+    for value in matches
+        .values_of("rpc_rate_limit_method_cost")
+        .unwrap_or_default()
+    {
+        let (method, cost) = value.split_once('=').unwrap();
+        config
+            .method_costs
+            .insert(method.to_string(), cost.parse().unwrap());
+    }
+    Some(config)
+}
+
 // Helper to add arguments that are no longer used but are being kept around to
 // avoid breaking validator startup commands
 fn get_deprecated_arguments() -> Vec<Arg<'static, 'static>> {
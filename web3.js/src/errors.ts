@@ -27,6 +27,7 @@ export const SolanaJSONRPCErrorCode = {
   JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET: -32014,
   JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION: -32015,
   JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED: -32016,
+  JSON_RPC_SERVER_ERROR_RATE_LIMITED: -32017,
 } as const;
 export type SolanaJSONRPCErrorCodeEnum =
   typeof SolanaJSONRPCErrorCode[keyof typeof SolanaJSONRPCErrorCode];